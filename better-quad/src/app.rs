@@ -0,0 +1,62 @@
+//! Registering an app: implement [`StatefulGui`](crate::StatefulGui) and [`AppInfo`], then call
+//! [`register_app!`](crate::register_app) in its `main.rs` for the window, engine setup, and
+//! command line handling.
+//!
+//! Each app is still its own binary, so there's no registry of them: nothing lists apps in a
+//! launcher or settings screen, and the web page linking them (`docs/index.html`) is kept by hand.
+
+/// What an app is, for the command line (`--help`, `--version`).
+pub trait AppInfo {
+    /// Display name, e.g. "Mastermind".
+    const NAME: &'static str;
+    /// One line on what it is.
+    const DESCRIPTION: &'static str;
+}
+
+/// Generates `main` for an app that implements [`StatefulGui`](crate::StatefulGui) and
+/// [`AppInfo`]. Pass how to make the app if it isn't `Default`, which can `.await`:
+///
+/// ```ignore
+/// better_quad::register_app!(SnakeGameState);
+/// better_quad::register_app!(MastermindGame, MastermindGame::with_sounds().await);
+/// ```
+///
+/// The app's crate needs `macroquad` as a dependency, for `#[macroquad::main]`.
+#[macro_export]
+macro_rules! register_app {
+    ($app:ty) => {
+        $crate::register_app!($app, <$app as ::std::default::Default>::default());
+    };
+    ($app:ty, $new_app:expr) => {
+        // Runs before the window opens, so `--help` doesn't flash one
+        fn window_conf() -> $crate::mq::Conf {
+            $crate::handle_cli_args::<$app>(env!("CARGO_PKG_VERSION"));
+            <$app as $crate::StatefulGui>::main_conf()
+        }
+
+        #[macroquad::main(window_conf)]
+        async fn main() {
+            $crate::initialize_engine();
+            $crate::install_window_backends(
+                &<$app as $crate::StatefulGui>::main_conf().window_title,
+            );
+            $crate::run_gui($new_app).await
+        }
+    };
+}
+
+/// Prints the app's info and exits for `--help` or `--version`. Other args are left for the app.
+/// Does nothing on WASM, which has no args.
+pub fn handle_cli_args<T: AppInfo>(version: &str) {
+    match std::env::args().nth(1).as_deref() {
+        Some("-h" | "--help") => {
+            println!("{}\n\n{}", T::NAME, T::DESCRIPTION);
+            std::process::exit(0);
+        }
+        Some("-V" | "--version") => {
+            println!("{} {version}", T::NAME);
+            std::process::exit(0);
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,103 @@
+//! Keeping the window above others, e.g. so a turn timer floats over a rules PDF or a video call.
+//!
+//! miniquad has no way to do it, so it goes through an [`AlwaysOnTopBackend`]. There's one for X11
+//! (see `bq_x11`), installed by [`register_app!`](crate::register_app), and elsewhere apps built
+//! with a windowing library that can install one for it, and without one it's unsupported.
+//! Setting the `BQ_ALWAYS_ON_TOP` env var (to anything but `0`) asks for it from launch, as soon as
+//! a backend's installed.
+use crate::bq_backend::BackendSlot;
+use std::env;
+
+const ALWAYS_ON_TOP_ENV_VAR: &str = "BQ_ALWAYS_ON_TOP";
+
+/// Whether it's on, or without a backend yet, whether it should be once there's one.
+static ALWAYS_ON_TOP: BackendSlot<dyn AlwaysOnTopBackend, bool> = BackendSlot::new(false);
+
+/// Whatever can change the window's stacking.
+pub trait AlwaysOnTopBackend: Send {
+    /// Errors are shown to the user, e.g. "the window manager doesn't allow it".
+    fn set_always_on_top(&mut self, always_on_top: bool) -> Result<(), String>;
+}
+
+/// Replaces the installed backend, if any, and applies whatever was asked for so far.
+pub fn set_always_on_top_backend(backend: impl AlwaysOnTopBackend + 'static) {
+    let mut installed = ALWAYS_ON_TOP.lock();
+    let mut backend = Box::new(backend);
+    if installed.state {
+        if let Err(e) = backend.set_always_on_top(true) {
+            crate::mq::warn!("Couldn't keep the window on top: {e}");
+            installed.state = false;
+        }
+    }
+    installed.backend = Some(backend);
+}
+
+/// Whether there's a backend, e.g. to hide the toggle without one.
+pub fn is_always_on_top_supported() -> bool {
+    ALWAYS_ON_TOP.is_installed()
+}
+
+pub fn is_always_on_top() -> bool {
+    let installed = ALWAYS_ON_TOP.lock();
+    installed.state && installed.backend.is_some()
+}
+
+/// Errors without a backend, or if the backend couldn't, leaving it as it was.
+pub fn set_always_on_top(always_on_top: bool) -> Result<(), String> {
+    let mut installed = ALWAYS_ON_TOP.lock();
+    let Some(backend) = installed.backend.as_mut() else {
+        return Err("Always on top isn't supported here".to_string());
+    };
+    backend.set_always_on_top(always_on_top)?;
+    installed.state = always_on_top;
+    Ok(())
+}
+
+/// For a hotkey. Returns whether it's on now.
+pub fn toggle_always_on_top() -> Result<bool, String> {
+    let always_on_top = !is_always_on_top();
+    set_always_on_top(always_on_top)?;
+    Ok(always_on_top)
+}
+
+/// Called by `initialize_engine()`.
+pub(crate) fn init_from_env() {
+    if env::var_os(ALWAYS_ON_TOP_ENV_VAR).is_some_and(|value| value != "0") {
+        ALWAYS_ON_TOP.lock().state = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        is_always_on_top, set_always_on_top, set_always_on_top_backend, toggle_always_on_top,
+        AlwaysOnTopBackend,
+    };
+
+    /// Like a window manager that won't pin the window the first time.
+    struct StubbornBackend {
+        refused: bool,
+    }
+
+    impl AlwaysOnTopBackend for StubbornBackend {
+        fn set_always_on_top(&mut self, _always_on_top: bool) -> Result<(), String> {
+            if self.refused {
+                Ok(())
+            } else {
+                self.refused = true;
+                Err("not now".to_string())
+            }
+        }
+    }
+
+    #[test]
+    fn test_always_on_top() {
+        set_always_on_top_backend(StubbornBackend { refused: false });
+        assert_eq!(Err("not now".to_string()), toggle_always_on_top());
+        assert!(!is_always_on_top());
+        assert_eq!(Ok(true), toggle_always_on_top());
+        assert!(is_always_on_top());
+        assert_eq!(Ok(()), set_always_on_top(false));
+        assert!(!is_always_on_top());
+    }
+}
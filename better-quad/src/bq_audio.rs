@@ -0,0 +1,184 @@
+//! Sound effects, looked up by an app-defined key. Sounds can be loaded from files/bytes, or
+//! synthesized from a few notes with `synth_wav()` so small apps don't need to ship audio assets.
+//!
+//! Playback needs this crate's `audio` feature (which needs ALSA dev libs on Linux). Without it,
+//! sounds still "load" and `play()` is silently a no-op, so apps don't need cfgs of their own.
+use macroquad::audio;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::Duration;
+
+const SAMPLE_RATE: u32 = 22050;
+/// Fade in/out of each note, so notes don't click when they start and stop.
+const NOTE_FADE: Duration = Duration::from_millis(5);
+
+pub struct SoundBank<K> {
+    sounds: HashMap<K, audio::Sound>,
+    muted: bool,
+}
+
+impl<K: Eq + Hash + std::fmt::Debug> SoundBank<K> {
+    pub fn new() -> Self {
+        Self {
+            sounds: HashMap::new(),
+            muted: false,
+        }
+    }
+
+    /// WAV or OGG data. Failures are logged, and playing that key does nothing.
+    pub async fn load_bytes(&mut self, key: K, bytes: &[u8]) {
+        match audio::load_sound_from_bytes(bytes).await {
+            Ok(sound) => {
+                self.sounds.insert(key, sound);
+            }
+            Err(e) => crate::mq::warn!("Failed to load sound {key:?}: {e}"),
+        }
+    }
+
+    /// WAV or OGG file, relative to the assets dir on the web. Failures are logged, and playing
+    /// that key does nothing.
+    pub async fn load_file(&mut self, key: K, path: &str) {
+        match audio::load_sound(path).await {
+            Ok(sound) => {
+                self.sounds.insert(key, sound);
+            }
+            Err(e) => crate::mq::warn!("Failed to load sound {key:?} from {path}: {e}"),
+        }
+    }
+
+    pub fn play(&self, key: K) {
+        if self.muted {
+            return;
+        }
+        // The stub sound without the feature complains on every play.
+        #[cfg(feature = "audio")]
+        if let Some(sound) = self.sounds.get(&key) {
+            audio::play_sound_once(sound);
+        }
+        #[cfg(not(feature = "audio"))]
+        let _ = key;
+    }
+
+    pub fn is_muted(&self) -> bool {
+        self.muted
+    }
+
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+    }
+
+    /// Returns whether it's now muted.
+    pub fn toggle_muted(&mut self) -> bool {
+        self.muted = !self.muted;
+        self.muted
+    }
+}
+
+impl<K: Eq + Hash + std::fmt::Debug> Default for SoundBank<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Waveform {
+    /// Soft
+    Sine,
+    /// Buzzy
+    Square,
+    /// In between
+    Triangle,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Note {
+    /// 0 for a rest.
+    pub frequency: f32,
+    pub duration: Duration,
+}
+
+impl Note {
+    pub const fn new(frequency: f32, millis: u64) -> Self {
+        Self {
+            frequency,
+            duration: Duration::from_millis(millis),
+        }
+    }
+}
+
+/// Plays `notes` one after another, as a 16-bit mono WAV file. `volume` is from 0 to 1.
+pub fn synth_wav(notes: &[Note], waveform: Waveform, volume: f32) -> Vec<u8> {
+    let volume = volume.clamp(0.0, 1.0);
+    let fade_samples = (NOTE_FADE.as_secs_f32() * SAMPLE_RATE as f32) as usize;
+
+    let mut samples = Vec::<i16>::new();
+    for note in notes {
+        let num_samples = (note.duration.as_secs_f32() * SAMPLE_RATE as f32) as usize;
+        for n in 0..num_samples {
+            if note.frequency <= 0.0 {
+                samples.push(0);
+                continue;
+            }
+            let phase = (n as f32 * note.frequency / SAMPLE_RATE as f32).fract();
+            let value = match waveform {
+                Waveform::Sine => (phase * std::f32::consts::TAU).sin(),
+                Waveform::Square => {
+                    if phase < 0.5 {
+                        1.0
+                    } else {
+                        -1.0
+                    }
+                }
+                Waveform::Triangle => 1.0 - 4.0 * (phase - 0.5).abs(),
+            };
+            let samples_from_edge = n.min(num_samples - 1 - n);
+            let envelope = (samples_from_edge as f32 / fade_samples.max(1) as f32).min(1.0);
+            samples.push((value * envelope * volume * i16::MAX as f32) as i16);
+        }
+    }
+
+    let data_len = (samples.len() * 2) as u32;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes()); // fmt chunk size
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes()); // byte rate
+    wav.extend_from_slice(&2u16.to_le_bytes()); // block align
+    wav.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{synth_wav, Note, Waveform, SAMPLE_RATE};
+
+    #[test]
+    fn test_synth_wav() {
+        let notes = [Note::new(440.0, 100), Note::new(0.0, 100)];
+        let wav = synth_wav(&notes, Waveform::Square, 0.5);
+
+        let num_samples = 2 * SAMPLE_RATE as usize / 10;
+        assert_eq!(44 + num_samples * 2, wav.len());
+        assert_eq!(b"RIFF", &wav[..4]);
+        assert_eq!((wav.len() as u32 - 8).to_le_bytes(), wav[4..8]);
+
+        let samples = wav[44..]
+            .chunks(2)
+            .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+            .collect::<Vec<_>>();
+        // Faded in, half volume, then silent for the rest.
+        assert_eq!(0, samples[0]);
+        assert_eq!(i16::MAX / 2, samples[num_samples / 4].abs());
+        assert!(samples[num_samples / 2..].iter().all(|s| *s == 0));
+    }
+}
@@ -0,0 +1,96 @@
+//! When to save work in progress: every so often while it's going, and straight away after events
+//! worth not losing (e.g. a turn ending, or a level won), without saving every frame.
+//!
+//! Apps check [`Autosave::is_due`] once a frame, save if it is, then mark it [`Autosave::saved`].
+//! Events call [`Autosave::request`] wherever they happen, and the save goes in with the next
+//! check, so several events in one frame are one save.
+use crate::bq_timestamp::Timestamp;
+use std::time::Duration;
+
+/// Saving more often than this is just wear on the disk.
+pub const MIN_AUTOSAVE_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Copy, Clone, Debug)]
+pub struct Autosave {
+    interval: Duration,
+    /// None until the first save, which is due straight away.
+    last_save: Option<Timestamp>,
+    requested: bool,
+}
+
+impl Autosave {
+    /// Saving at least every `interval`, which is at least [`MIN_AUTOSAVE_INTERVAL`].
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval: interval.max(MIN_AUTOSAVE_INTERVAL),
+            last_save: None,
+            requested: false,
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval.max(MIN_AUTOSAVE_INTERVAL);
+    }
+
+    /// Saves at the next check, e.g. after an event worth not losing.
+    pub fn request(&mut self) {
+        self.requested = true;
+    }
+
+    /// Whether it's time to save: nothing's been saved yet, it was requested, or it's been the
+    /// interval since the last save. The clock going back counts too, so it's never stuck.
+    pub fn is_due(&self, now: Timestamp) -> bool {
+        self.requested
+            || self.last_save.is_none_or(|last_save| {
+                now.duration_since(last_save)
+                    .is_none_or(|since| since >= self.interval)
+            })
+    }
+
+    /// Once it's saved, e.g. after [`Self::is_due`], or when saving for some other reason.
+    pub fn saved(&mut self, now: Timestamp) {
+        self.last_save = Some(now);
+        self.requested = false;
+    }
+
+    /// Back to nothing saved, e.g. for a new game, so the next check is due.
+    pub fn reset(&mut self) {
+        self.last_save = None;
+        self.requested = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Autosave, MIN_AUTOSAVE_INTERVAL};
+    use crate::bq_timestamp::Timestamp;
+    use std::time::Duration;
+
+    #[test]
+    fn test_autosave() {
+        let start = Timestamp::now();
+        let minute = Duration::from_secs(60);
+        let mut autosave = Autosave::new(minute * 5);
+        assert!(autosave.is_due(start));
+        autosave.saved(start);
+        assert!(!autosave.is_due(start + minute));
+        assert!(autosave.is_due(start + minute * 5));
+
+        // Events don't wait for the interval
+        autosave.request();
+        assert!(autosave.is_due(start + minute));
+        autosave.saved(start + minute);
+        assert!(!autosave.is_due(start + minute * 2));
+        // Nor does the clock going back
+        assert!(autosave.is_due(start));
+
+        autosave.set_interval(Duration::ZERO);
+        assert_eq!(MIN_AUTOSAVE_INTERVAL, autosave.interval());
+        autosave.reset();
+        assert!(autosave.is_due(start));
+    }
+}
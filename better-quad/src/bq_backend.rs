@@ -0,0 +1,35 @@
+//! Where backends live, for what miniquad can't do itself: rumble (see `bq_rumble`), always on top
+//! (see `bq_always_on_top`) and the window title (see `bq_window_title`).
+use std::sync::{Mutex, MutexGuard};
+
+/// The installed backend, if any, and whatever the module keeps alongside it, e.g. what was asked
+/// for before there was a backend. It's a static so apps can call the module's functions from
+/// anywhere, which is why backends need `Send`, though they're only ever called from the main
+/// thread.
+pub(crate) struct BackendSlot<B: ?Sized, S = ()> {
+    installed: Mutex<Installed<B, S>>,
+}
+
+pub(crate) struct Installed<B: ?Sized, S> {
+    pub(crate) backend: Option<Box<B>>,
+    pub(crate) state: S,
+}
+
+impl<B: ?Sized, S> BackendSlot<B, S> {
+    pub(crate) const fn new(state: S) -> Self {
+        Self {
+            installed: Mutex::new(Installed {
+                backend: None,
+                state,
+            }),
+        }
+    }
+
+    pub(crate) fn lock(&self) -> MutexGuard<'_, Installed<B, S>> {
+        self.installed.lock().expect("backend lock poisoned")
+    }
+
+    pub(crate) fn is_installed(&self) -> bool {
+        self.lock().backend.is_some()
+    }
+}
@@ -0,0 +1,136 @@
+//! A button for the mouse or a finger, e.g. a big "next" button so an app can be used mouse-only or
+//! on a touchscreen. It lightens under the mouse and darkens while held down, so presses can be
+//! seen.
+//!
+//! Buttons don't keep any state, so like [`ResultsScreen`](crate::bq::ResultsScreen), build one
+//! from the same app state in both update (for [`Button::clicked`]) and draw. Buttons drawn with a
+//! camera, e.g. on a zoomed board, need [`Button::with_mouse_position`] in the camera's units.
+use crate::bq_color::contrasting_text_color;
+use crate::bq_text_fit::draw_text_fitted;
+use crate::mq;
+
+/// How far towards white under the mouse, and towards black while held down.
+const HOVER_LIGHTEN: f32 = 0.15;
+const PRESSED_DARKEN: f32 = 0.3;
+/// The label's biggest font size, unless capped, as a share of the button's height, and its padding
+/// at the sides.
+const LABEL_HEIGHT_FRACTION: f32 = 0.5;
+const LABEL_PADDING: f32 = 10.0;
+/// Past this, the label's cut short rather than shrunk.
+const MIN_LABEL_FONT_SIZE: u16 = 12;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ButtonState {
+    Idle,
+    Hovered,
+    Pressed,
+}
+
+pub struct Button {
+    rect: mq::Rect,
+    label: String,
+    color: mq::Color,
+    mouse_position: mq::Vec2,
+    max_font_size: Option<u16>,
+}
+
+impl Button {
+    /// The label's drawn as big as fits, in black or white, whichever reads better on `color`.
+    pub fn new(rect: mq::Rect, label: impl Into<String>, color: mq::Color) -> Self {
+        Self {
+            rect,
+            label: label.into(),
+            color,
+            mouse_position: mq::Vec2::from(mq::mouse_position()),
+            max_font_size: None,
+        }
+    }
+
+    /// Where the mouse is in the units `rect` is in, when that's not the window's.
+    pub fn with_mouse_position(mut self, mouse_position: mq::Vec2) -> Self {
+        self.mouse_position = mouse_position;
+        self
+    }
+
+    /// Caps the label's size, e.g. to match other text, instead of filling half the height.
+    pub fn max_font_size(mut self, font_size: u16) -> Self {
+        self.max_font_size = Some(font_size);
+        self
+    }
+
+    /// Whether it was clicked (or tapped) this frame. It counts as soon as it's pressed, rather
+    /// than let go, so it responds right away.
+    pub fn clicked(&self) -> bool {
+        mq::is_mouse_button_pressed(mq::MouseButton::Left) && self.is_under_mouse()
+    }
+
+    pub fn state(&self) -> ButtonState {
+        match (
+            self.is_under_mouse(),
+            mq::is_mouse_button_down(mq::MouseButton::Left),
+        ) {
+            (false, _) => ButtonState::Idle,
+            (true, false) => ButtonState::Hovered,
+            (true, true) => ButtonState::Pressed,
+        }
+    }
+
+    pub fn draw(&self) {
+        let color = state_color(self.color, self.state());
+        mq::draw_rectangle(self.rect.x, self.rect.y, self.rect.w, self.rect.h, color);
+        let label_rect = mq::Rect::new(
+            self.rect.x + LABEL_PADDING,
+            self.rect.y,
+            (self.rect.w - LABEL_PADDING * 2.0).max(0.0),
+            self.rect.h,
+        );
+        draw_text_fitted(
+            &self.label,
+            None,
+            MIN_LABEL_FONT_SIZE,
+            self.label_font_size(),
+            contrasting_text_color(color),
+            label_rect,
+        );
+    }
+
+    fn label_font_size(&self) -> u16 {
+        let fitting = (self.rect.h * LABEL_HEIGHT_FRACTION) as u16;
+        self.max_font_size.map_or(fitting, |max| fitting.min(max))
+    }
+
+    fn is_under_mouse(&self) -> bool {
+        self.rect.contains(self.mouse_position)
+    }
+}
+
+/// `color` lightened or darkened for `state`, keeping its alpha.
+fn state_color(color: mq::Color, state: ButtonState) -> mq::Color {
+    let (target, amount) = match state {
+        ButtonState::Idle => return color,
+        ButtonState::Hovered => (1.0, HOVER_LIGHTEN),
+        ButtonState::Pressed => (0.0, PRESSED_DARKEN),
+    };
+    let mix = |channel: f32| channel + (target - channel) * amount;
+    mq::Color::new(mix(color.r), mix(color.g), mix(color.b), color.a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{state_color, ButtonState, HOVER_LIGHTEN, PRESSED_DARKEN};
+    use crate::mq;
+
+    #[test]
+    fn test_state_color() {
+        let color = mq::Color::new(0.5, 0.0, 1.0, 0.8);
+        assert_eq!(color, state_color(color, ButtonState::Idle));
+        assert_eq!(
+            mq::Color::new(0.5 + 0.5 * HOVER_LIGHTEN, HOVER_LIGHTEN, 1.0, 0.8),
+            state_color(color, ButtonState::Hovered)
+        );
+        assert_eq!(
+            mq::Color::new(0.5 * (1.0 - PRESSED_DARKEN), 0.0, 1.0 - PRESSED_DARKEN, 0.8),
+            state_color(color, ButtonState::Pressed)
+        );
+    }
+}
@@ -0,0 +1,248 @@
+//! Instant replay: keep the last few seconds of frames in memory, and save them as a GIF on
+//! hotkey press. Apps opt in via `StatefulGui::frame_capture_conf()`, and `run_gui` does the rest.
+//!
+//! Frames are grabbed at a low frame rate and downscaled as they come in, so the ring buffer stays
+//! reasonably small. Encoding happens on a background thread since it takes a few seconds.
+use crate::bq_timestamp::Timestamp;
+use crate::mq;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::Duration;
+
+const ROOT_DIR_NAME: &str = "gui-playground";
+const CAPTURES_DIR_NAME: &str = "captures";
+
+#[derive(Copy, Clone, Debug)]
+pub struct FrameCaptureConf {
+    pub hotkey: mq::KeyCode,
+    /// How much history is kept.
+    pub duration: Duration,
+    pub fps: u32,
+    /// Frames are downscaled (by an integer factor) until neither side is bigger than this.
+    pub max_dimension: u32,
+}
+
+impl Default for FrameCaptureConf {
+    fn default() -> Self {
+        Self {
+            hotkey: mq::KeyCode::F9,
+            duration: Duration::from_secs(8),
+            fps: 10,
+            max_dimension: 320,
+        }
+    }
+}
+
+struct CapturedFrame {
+    width: u16,
+    height: u16,
+    rgba: Vec<u8>,
+}
+
+pub(crate) struct FrameRecorder {
+    conf: FrameCaptureConf,
+    app_name: String,
+    frames: VecDeque<CapturedFrame>,
+    last_capture_time: Option<Timestamp>,
+    save_results: Option<mpsc::Receiver<Result<PathBuf, String>>>,
+}
+
+impl FrameRecorder {
+    pub(crate) fn new(conf: FrameCaptureConf, app_name: &str) -> Self {
+        Self {
+            conf,
+            app_name: app_name.to_string(),
+            frames: VecDeque::new(),
+            last_capture_time: None,
+            save_results: None,
+        }
+    }
+
+    pub(crate) fn save_requested(&self) -> bool {
+        mq::is_key_pressed(self.conf.hotkey)
+    }
+
+    /// Call after the app has drawn its frame.
+    pub(crate) fn capture_frame_if_due(&mut self, now: Timestamp) {
+        let frame_interval = Duration::from_secs_f64(1.0 / self.conf.fps as f64);
+        let due = match self.last_capture_time {
+            None => true,
+            Some(last) => now
+                .duration_since(last)
+                .is_some_and(|since_last| since_last >= frame_interval),
+        };
+        if !due {
+            return;
+        }
+        self.last_capture_time = Some(now);
+
+        let max_frames = (self.conf.duration.as_secs_f64() * self.conf.fps as f64).ceil() as usize;
+        while self.frames.len() >= max_frames.max(1) {
+            self.frames.pop_front();
+        }
+        self.frames
+            .push_back(downscale(&mq::get_screen_data(), self.conf.max_dimension));
+    }
+
+    /// Encodes the buffered frames in the background. Poll `poll_save_result()` for the outcome.
+    pub(crate) fn start_save(&mut self) -> Result<(), String> {
+        if self.save_results.is_some() {
+            return Err("Still saving the last capture".to_string());
+        }
+        if self.frames.is_empty() {
+            return Err("Nothing captured yet".to_string());
+        }
+
+        let path = capture_path(&self.app_name)?;
+        let frames = self.frames.drain(..).collect::<Vec<_>>();
+        let frame_delay = (100 / self.conf.fps.max(1)) as u16;
+
+        let (sender, receiver) = mpsc::channel();
+        self.save_results = Some(receiver);
+        spawn_save(move || {
+            let result = encode_gif(&path, frames, frame_delay).map(|()| path);
+            // Receiver is only gone if the app is shutting down, so nobody cares.
+            let _ = sender.send(result);
+        });
+        Ok(())
+    }
+
+    pub(crate) fn poll_save_result(&mut self) -> Option<Result<PathBuf, String>> {
+        let result = self.save_results.as_ref()?.try_recv();
+        match result {
+            Ok(result) => {
+                self.save_results = None;
+                Some(result)
+            }
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.save_results = None;
+                Some(Err("Capture encoder crashed".to_string()))
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn spawn_save(save: impl FnOnce() + Send + 'static) {
+    std::thread::spawn(save);
+}
+
+/// No threads on the web, so the frame hitches while encoding. Saving a file there doesn't work
+/// anyway, but it fails with a proper error.
+#[cfg(target_arch = "wasm32")]
+fn spawn_save(save: impl FnOnce() + Send + 'static) {
+    save();
+}
+
+fn capture_path(app_name: &str) -> Result<PathBuf, String> {
+    let dir = dirs::picture_dir()
+        .or_else(dirs::data_dir)
+        .ok_or("No pictures or data dir on this platform")?
+        .join(ROOT_DIR_NAME)
+        .join(CAPTURES_DIR_NAME);
+    let file_name = format!(
+        "{}-{}.gif",
+        app_name.to_lowercase().replace(' ', "-"),
+        Timestamp::now().as_sec_f64() as u64
+    );
+    Ok(dir.join(file_name))
+}
+
+fn encode_gif(path: &PathBuf, frames: Vec<CapturedFrame>, frame_delay: u16) -> Result<(), String> {
+    let (width, height) = frames
+        .last()
+        .map(|frame| (frame.width, frame.height))
+        .unwrap_or_default();
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let file = std::fs::File::create(path).map_err(|e| e.to_string())?;
+    let mut encoder = gif::Encoder::new(file, width, height, &[]).map_err(|e| e.to_string())?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(|e| e.to_string())?;
+
+    for mut frame in frames {
+        // Window was resized mid-capture. Rare enough to just skip those frames.
+        if (frame.width, frame.height) != (width, height) {
+            continue;
+        }
+        // Speed 10 is gif's recommended trade-off between quality and encoding time.
+        let mut gif_frame = gif::Frame::from_rgba_speed(width, height, &mut frame.rgba, 10);
+        gif_frame.delay = frame_delay;
+        encoder.write_frame(&gif_frame).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Box-filter downscale, also flipping the image right side up (screen data is bottom row first).
+fn downscale(image: &mq::Image, max_dimension: u32) -> CapturedFrame {
+    let src_width = image.width as usize;
+    let src_height = image.height as usize;
+    let longest_side = src_width.max(src_height).max(1);
+    let factor = longest_side.div_ceil(max_dimension.max(1) as usize).max(1);
+    let width = (src_width / factor).max(1);
+    let height = (src_height / factor).max(1);
+
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = [0usize; 4];
+            for dy in 0..factor {
+                // Flip vertically while sampling.
+                let src_y = src_height - 1 - (y * factor + dy).min(src_height - 1);
+                for dx in 0..factor {
+                    let src_x = (x * factor + dx).min(src_width - 1);
+                    let i = (src_y * src_width + src_x) * 4;
+                    for (channel, total) in sum.iter_mut().enumerate() {
+                        *total += image.bytes[i + channel] as usize;
+                    }
+                }
+            }
+            let num_samples = factor * factor;
+            // GIF has no partial transparency, and the screen is opaque anyway.
+            rgba.extend_from_slice(&[
+                (sum[0] / num_samples) as u8,
+                (sum[1] / num_samples) as u8,
+                (sum[2] / num_samples) as u8,
+                255,
+            ]);
+        }
+    }
+
+    CapturedFrame {
+        width: width as u16,
+        height: height as u16,
+        rgba,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::downscale;
+    use crate::mq;
+
+    #[test]
+    fn test_downscale_averages_and_flips() {
+        // 4x2, bottom row first: bottom row is black, top row is white.
+        let mut bytes = vec![0; 4 * 4];
+        bytes.extend(vec![255; 4 * 4]);
+        let image = mq::Image {
+            bytes,
+            width: 4,
+            height: 2,
+        };
+
+        let frame = downscale(&image, 2);
+        assert_eq!((2, 1), (frame.width, frame.height));
+        assert_eq!(vec![127, 127, 127, 255, 127, 127, 127, 255], frame.rgba);
+
+        let frame = downscale(&image, 4);
+        assert_eq!((4, 2), (frame.width, frame.height));
+        assert_eq!([255, 255, 255, 255], frame.rgba[..4]);
+        assert_eq!([0, 0, 0, 255], frame.rgba[frame.rgba.len() - 4..]);
+    }
+}
@@ -0,0 +1,204 @@
+//! Small charts for comparing values rather than only their totals: spreads, e.g. how long each
+//! player's turns take, and trends, e.g. whether someone's turns are getting slower.
+use crate::bq_circle::{draw_circle, draw_circle_outline};
+use crate::bq_color::contrasting_text_color;
+use crate::mq;
+
+/// Whiskers are this share of the box's height.
+const WHISKER_END_HEIGHT_FRACTION: f32 = 0.5;
+const LINE_THICKNESS: f32 = 2.0;
+const MEDIAN_THICKNESS: f32 = 4.0;
+/// The mean's dot, as a share of the box's height.
+const MEAN_RADIUS_FRACTION: f32 = 0.15;
+const SPARKLINE_THICKNESS: f32 = 2.0;
+/// The latest value's dot, so a sparkline of one value still shows.
+const SPARKLINE_DOT_RADIUS: f32 = 3.0;
+
+/// The five-number summary of some values, plus their mean.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BoxPlotStats {
+    pub min: f32,
+    pub lower_quartile: f32,
+    pub median: f32,
+    pub upper_quartile: f32,
+    pub max: f32,
+    pub mean: f32,
+}
+
+impl BoxPlotStats {
+    /// None if there are no values. Quartiles are interpolated between values, like spreadsheets
+    /// do.
+    pub fn from_values(values: &[f32]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f32::total_cmp);
+        let quantile = |q: f32| {
+            let position = q * (sorted.len() - 1) as f32;
+            let below = sorted[position.floor() as usize];
+            let above = sorted[position.ceil() as usize];
+            below + (above - below) * position.fract()
+        };
+        Some(Self {
+            min: sorted[0],
+            lower_quartile: quantile(0.25),
+            median: quantile(0.5),
+            upper_quartile: quantile(0.75),
+            max: sorted[sorted.len() - 1],
+            mean: sorted.iter().sum::<f32>() / sorted.len() as f32,
+        })
+    }
+}
+
+/// Horizontal, filling `rect`'s height, with 0 at its left edge and `scale_max` at its right: the
+/// box from the lower to upper quartile with a line at the median, whiskers out to the min and max,
+/// and a dot at the mean.
+pub fn draw_box_plot(stats: &BoxPlotStats, scale_max: f32, rect: mq::Rect, color: mq::Color) {
+    let x = |value: f32| {
+        let fraction = if scale_max > 0.0 {
+            (value / scale_max).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        rect.x + rect.w * fraction
+    };
+    let center_y = rect.center().y;
+
+    let whisker_half_height = rect.h * WHISKER_END_HEIGHT_FRACTION / 2.0;
+    mq::draw_line(
+        x(stats.min),
+        center_y,
+        x(stats.max),
+        center_y,
+        LINE_THICKNESS,
+        color,
+    );
+    for end in [stats.min, stats.max] {
+        mq::draw_line(
+            x(end),
+            center_y - whisker_half_height,
+            x(end),
+            center_y + whisker_half_height,
+            LINE_THICKNESS,
+            color,
+        );
+    }
+
+    let box_x = x(stats.lower_quartile);
+    // At least a line wide, so a box of all the same value still shows
+    let box_w = (x(stats.upper_quartile) - box_x).max(LINE_THICKNESS);
+    mq::draw_rectangle(box_x, rect.y, box_w, rect.h, color);
+    let contrast = contrasting_text_color(color);
+    mq::draw_line(
+        x(stats.median),
+        rect.y,
+        x(stats.median),
+        rect.bottom(),
+        MEDIAN_THICKNESS,
+        contrast,
+    );
+
+    // Outlined, since it can be outside the box
+    let mean_radius = rect.h * MEAN_RADIUS_FRACTION;
+    draw_circle(x(stats.mean), center_y, mean_radius, contrast);
+    draw_circle_outline(x(stats.mean), center_y, mean_radius, LINE_THICKNESS, color);
+}
+
+/// A line through `values` in order, spread evenly across `rect`, with 0 at its bottom and
+/// `scale_max` at its top, and a dot on the last one.
+pub fn draw_sparkline(values: &[f32], scale_max: f32, rect: mq::Rect, color: mq::Color) {
+    let points = sparkline_points(values, scale_max, rect);
+    for pair in points.windows(2) {
+        mq::draw_line(
+            pair[0].x,
+            pair[0].y,
+            pair[1].x,
+            pair[1].y,
+            SPARKLINE_THICKNESS,
+            color,
+        );
+    }
+    if let Some(last) = points.last() {
+        draw_circle(last.x, last.y, SPARKLINE_DOT_RADIUS, color);
+    }
+}
+
+/// One value is at the right edge, like the latest of several would be.
+fn sparkline_points(values: &[f32], scale_max: f32, rect: mq::Rect) -> Vec<mq::Vec2> {
+    let step = if values.len() > 1 {
+        rect.w / (values.len() - 1) as f32
+    } else {
+        0.0
+    };
+    let start_x = if values.len() > 1 {
+        rect.x
+    } else {
+        rect.right()
+    };
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let fraction = if scale_max > 0.0 {
+                (value / scale_max).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            mq::vec2(start_x + step * i as f32, rect.bottom() - rect.h * fraction)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sparkline_points, BoxPlotStats};
+    use crate::mq;
+
+    #[test]
+    fn test_box_plot_stats() {
+        assert_eq!(None, BoxPlotStats::from_values(&[]));
+        assert_eq!(
+            Some(BoxPlotStats {
+                min: 5.0,
+                lower_quartile: 5.0,
+                median: 5.0,
+                upper_quartile: 5.0,
+                max: 5.0,
+                mean: 5.0,
+            }),
+            BoxPlotStats::from_values(&[5.0])
+        );
+        // Out of order, with the quartiles between values
+        assert_eq!(
+            Some(BoxPlotStats {
+                min: 1.0,
+                lower_quartile: 1.75,
+                median: 2.5,
+                upper_quartile: 4.75,
+                max: 10.0,
+                mean: 4.0,
+            }),
+            BoxPlotStats::from_values(&[10.0, 1.0, 3.0, 2.0])
+        );
+    }
+
+    #[test]
+    fn test_sparkline_points() {
+        let rect = mq::Rect::new(10.0, 100.0, 200.0, 50.0);
+        assert_eq!(
+            vec![
+                mq::vec2(10.0, 150.0),
+                mq::vec2(110.0, 125.0),
+                // Over the scale is cut off at the top
+                mq::vec2(210.0, 100.0),
+            ],
+            sparkline_points(&[0.0, 5.0, 20.0], 10.0, rect)
+        );
+        assert_eq!(
+            vec![mq::vec2(210.0, 100.0)],
+            sparkline_points(&[3.0], 3.0, rect)
+        );
+        assert!(sparkline_points(&[], 10.0, rect).is_empty());
+    }
+}
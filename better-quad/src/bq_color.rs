@@ -8,3 +8,65 @@ pub fn invert_color(color: mq::Color) -> mq::Color {
         a: color.a,
     }
 }
+
+/// Black or white, whichever reads better on `background`.
+pub fn contrasting_text_color(background: mq::Color) -> mq::Color {
+    // Perceived brightness, weighted the way the eye sees the channels
+    let luma = 0.299 * background.r + 0.587 * background.g + 0.114 * background.b;
+    if luma > 0.5 {
+        mq::BLACK
+    } else {
+        mq::WHITE
+    }
+}
+
+/// "#RRGGBB" or "#RGB", with or without the "#". Case-insensitive.
+pub fn parse_hex_color(text: &str) -> Option<mq::Color> {
+    let hex = text.trim();
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+    let (r, g, b) = match hex.len() {
+        6 => (
+            channel(&hex[0..2])?,
+            channel(&hex[2..4])?,
+            channel(&hex[4..6])?,
+        ),
+        // Each digit is doubled, e.g. "#f80" is "#ff8800"
+        3 => (
+            channel(&hex[0..1])? * 17,
+            channel(&hex[1..2])? * 17,
+            channel(&hex[2..3])? * 17,
+        ),
+        _ => return None,
+    };
+    Some(mq::Color::from_rgba(r, g, b, 255))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{contrasting_text_color, parse_hex_color};
+    use crate::mq;
+
+    #[test]
+    fn test_parse_hex_color() {
+        let orange = Some(mq::Color::from_rgba(255, 136, 0, 255));
+        assert_eq!(orange, parse_hex_color("#ff8800"));
+        assert_eq!(orange, parse_hex_color("FF8800"));
+        assert_eq!(orange, parse_hex_color(" #f80 "));
+        assert_eq!(None, parse_hex_color("#ff880"));
+        assert_eq!(None, parse_hex_color("#gg8800"));
+        assert_eq!(None, parse_hex_color("+f+f+f"));
+        assert_eq!(None, parse_hex_color(""));
+    }
+
+    #[test]
+    fn test_contrasting_text_color() {
+        assert_eq!(mq::BLACK, contrasting_text_color(mq::YELLOW));
+        assert_eq!(mq::BLACK, contrasting_text_color(mq::WHITE));
+        assert_eq!(mq::WHITE, contrasting_text_color(mq::BLUE));
+        assert_eq!(mq::WHITE, contrasting_text_color(mq::BLACK));
+    }
+}
@@ -0,0 +1,171 @@
+//! Load user-editable TOML config files (presets, rule sets, themes, etc).
+//!
+//! Each app has a folder per kind of config in the platform config dir, e.g.
+//! `~/.config/gui-playground/turn-time-tracker/presets/*.toml` on Linux. Apps compile in their
+//! built-in files, and user files are loaded after them, sorted by file name.
+//!
+//! Files that fail to parse or validate are skipped and returned as errors alongside whatever did
+//! load, so a typo in one file can be shown to the user (e.g. as a toast) instead of crashing.
+use crate::{bq_storage, mq};
+use serde::de::DeserializeOwned;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::PathBuf;
+
+const FILE_EXTENSION: &str = "toml";
+
+/// A kind of config file. Serde handles the syntax, `validate()` handles everything else.
+pub trait ConfigFile: DeserializeOwned {
+    /// Semantic checks that serde can't express, like numeric ranges.
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Handle to an app's config folder. Cheap to construct, so make it a const.
+#[derive(Copy, Clone, Debug)]
+pub struct AppConfigDir {
+    app_name: &'static str,
+}
+
+pub struct LoadedConfigs<T> {
+    pub configs: Vec<T>,
+    pub errors: Vec<ConfigLoadError>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigLoadError {
+    pub file_name: String,
+    pub message: String,
+}
+
+impl AppConfigDir {
+    pub const fn new(app_name: &'static str) -> Self {
+        Self { app_name }
+    }
+
+    /// The app's config folder, for one-off files that aren't a kind of config.
+    pub fn app_dir(&self) -> Option<PathBuf> {
+        bq_storage::root_dir(dirs::config_dir, "config").map(|dir| dir.join(self.app_name))
+    }
+
+    /// Folder for one kind of config, e.g. "presets".
+    pub fn kind_dir(&self, kind: &str) -> Option<PathBuf> {
+        self.app_dir().map(|dir| dir.join(kind))
+    }
+
+    /// `built_ins` are `(file name, contents)` pairs, typically from `include_str!()`.
+    pub fn load_all<T: ConfigFile>(
+        &self,
+        kind: &str,
+        built_ins: &[(&str, &str)],
+    ) -> LoadedConfigs<T> {
+        let mut loaded = LoadedConfigs {
+            configs: Vec::new(),
+            errors: Vec::new(),
+        };
+
+        for (file_name, contents) in built_ins {
+            loaded.push(file_name, parse_config(contents));
+        }
+
+        let Some(dir) = self.kind_dir(kind) else {
+            return loaded;
+        };
+        let Ok(dir_entries) = fs::read_dir(&dir) else {
+            // Not having any custom configs is the common case.
+            return loaded;
+        };
+        let mut paths = dir_entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == FILE_EXTENSION))
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        for path in paths {
+            let file_name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let parsed = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|contents| parse_config(&contents));
+            loaded.push(&file_name, parsed);
+        }
+
+        loaded
+    }
+}
+
+impl<T> LoadedConfigs<T> {
+    fn push(&mut self, file_name: &str, parsed: Result<T, String>) {
+        match parsed {
+            Ok(config) => self.configs.push(config),
+            Err(message) => {
+                let error = ConfigLoadError {
+                    file_name: file_name.to_string(),
+                    message,
+                };
+                mq::warn!("Skipping config {error}");
+                self.errors.push(error);
+            }
+        }
+    }
+}
+
+/// Parse and validate a single file's contents. Errors are one line, so they fit in a toast.
+pub fn parse_config<T: ConfigFile>(contents: &str) -> Result<T, String> {
+    let config = toml::from_str::<T>(contents).map_err(|e| {
+        let line_prefix = match e.span() {
+            Some(span) => format!("line {}: ", line_number(contents, span.start)),
+            None => "".to_string(),
+        };
+        format!("{line_prefix}{}", e.message().trim())
+    })?;
+    config.validate()?;
+    Ok(config)
+}
+
+fn line_number(contents: &str, byte_offset: usize) -> usize {
+    contents[..byte_offset.min(contents.len())]
+        .matches('\n')
+        .count()
+        + 1
+}
+
+impl Display for ConfigLoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.file_name, self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_config, ConfigFile};
+    use serde::Deserialize;
+
+    #[derive(Deserialize, Debug)]
+    struct TestConfig {
+        size: u32,
+    }
+
+    impl ConfigFile for TestConfig {
+        fn validate(&self) -> Result<(), String> {
+            if self.size == 0 {
+                return Err("size must be positive".to_string());
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_parse_config_errors() {
+        assert_eq!(3, parse_config::<TestConfig>("size = 3").unwrap().size);
+        assert_eq!(
+            "size must be positive",
+            parse_config::<TestConfig>("size = 0").unwrap_err()
+        );
+        let syntax_error = parse_config::<TestConfig>("\nsize = \"big\"").unwrap_err();
+        assert!(syntax_error.starts_with("line 2: "), "{syntax_error}");
+    }
+}
@@ -0,0 +1,34 @@
+//! Saving files for the user to open in other apps (reports, exported games, etc), as opposed to
+//! app data in [`bq_storage`](crate::bq_storage).
+//!
+//! They go in the documents folder, under `gui-playground/<app>/`, like captures go in pictures.
+//! There's no file dialog, so show the returned path (e.g. in a toast) so the user can find it.
+//!
+//! WASM has no filesystem, so saving returns an error there.
+use crate::bq_storage::root_dir;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+
+/// Writes `contents` to `file_name` in the app's exports folder, replacing any file already there.
+/// Returns where it went.
+pub fn save_export(app_name: &str, file_name: &str, contents: &str) -> Result<PathBuf, String> {
+    let dir = root_dir(|| dirs::document_dir().or_else(dirs::data_dir), "exports")
+        .ok_or("No documents or data dir on this platform")?
+        .join(app_name);
+    fs::create_dir_all(&dir).map_err(|e| format!("Couldn't create {}: {e}", dir.display()))?;
+    let path = dir.join(file_name);
+    fs::write(&path, contents).map_err(|e| format!("Couldn't write {}: {e}", path.display()))?;
+    Ok(path)
+}
+
+/// [`save_export`] with `value` as pretty-printed JSON.
+pub fn save_json_export<T: Serialize>(
+    app_name: &str,
+    file_name: &str,
+    value: &T,
+) -> Result<PathBuf, String> {
+    let contents =
+        serde_json::to_string_pretty(value).map_err(|e| format!("Couldn't write JSON: {e}"))?;
+    save_export(app_name, file_name, &contents)
+}
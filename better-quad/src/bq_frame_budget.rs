@@ -0,0 +1,201 @@
+//! Frame budget profiler: times each frame's `update()` and `draw()`, logs the frames that go over
+//! budget, and optionally warns about them in the top left corner, to help track down hitching.
+//! Apps opt in via `StatefulGui::frame_budget_conf()`, and `run_gui` does the rest. By default, any
+//! app can be profiled by setting the `BQ_FRAME_BUDGET_MS` env var, e.g. `16` for 60 FPS.
+//!
+//! `draw()` only queues up draw calls, so its time is the CPU side of drawing. The GPU's time isn't
+//! measured.
+use crate::bq::{TextAlignment, TextAnchorPoint};
+use crate::bq_text::TextBackground;
+use crate::bq_timestamp::Timestamp;
+use crate::{bq_safe_area, bq_text, mq};
+use std::env;
+use std::time::Duration;
+
+const FRAME_BUDGET_ENV_VAR: &str = "BQ_FRAME_BUDGET_MS";
+/// Slow frames are logged at most this often, with a count of the ones in between, so a slow
+/// stretch doesn't flood the log.
+const LOG_INTERVAL: Duration = Duration::from_secs(1);
+/// How long the warning stays up after a slow frame.
+const WARNING_DURATION: Duration = Duration::from_secs(2);
+const WARNING_FONT_SIZE: u16 = 18;
+const WARNING_PADDING: f32 = 6.0;
+const WARNING_MARGIN: f32 = 10.0;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FrameBudgetConf {
+    /// Frames whose update and draw take longer than this in total are slow.
+    pub budget: Duration,
+    /// Draw a warning over the app after a slow frame, rather than only logging it.
+    pub show_warning: bool,
+}
+
+impl FrameBudgetConf {
+    /// From the `BQ_FRAME_BUDGET_MS` env var, with the warning shown. None if it's unset or not a
+    /// number.
+    pub fn from_env() -> Option<Self> {
+        env::var(FRAME_BUDGET_ENV_VAR)
+            .ok()
+            .and_then(|text| parse_budget(&text))
+            .map(|budget| Self {
+                budget,
+                ..Self::default()
+            })
+    }
+}
+
+impl Default for FrameBudgetConf {
+    /// 60 FPS
+    fn default() -> Self {
+        Self {
+            budget: Duration::from_micros(16_667),
+            show_warning: true,
+        }
+    }
+}
+
+/// How long one frame's update and draw took.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FrameTimes {
+    pub update: Duration,
+    pub draw: Duration,
+}
+
+impl FrameTimes {
+    pub fn total(&self) -> Duration {
+        self.update + self.draw
+    }
+}
+
+pub(crate) struct FrameProfiler {
+    conf: FrameBudgetConf,
+    app_name: String,
+    /// The most recent, and when it ended.
+    last_slow_frame: Option<(FrameTimes, Timestamp)>,
+    last_log_time: Option<Timestamp>,
+    num_unlogged_slow_frames: u32,
+}
+
+impl FrameProfiler {
+    pub(crate) fn new(conf: FrameBudgetConf, app_name: &str) -> Self {
+        Self {
+            conf,
+            app_name: app_name.to_string(),
+            last_slow_frame: None,
+            last_log_time: None,
+            num_unlogged_slow_frames: 0,
+        }
+    }
+
+    /// Call once per frame, after it's been drawn.
+    pub(crate) fn record(&mut self, times: FrameTimes, now: Timestamp) {
+        if times.total() <= self.conf.budget {
+            return;
+        }
+        self.last_slow_frame = Some((times, now));
+        self.num_unlogged_slow_frames += 1;
+
+        let log_due = self.last_log_time.is_none_or(|last| {
+            now.duration_since(last)
+                .is_some_and(|since_last| since_last >= LOG_INTERVAL)
+        });
+        if log_due {
+            mq::warn!(
+                "{}: {} over the {} frame budget{}",
+                self.app_name,
+                describe_frame(times),
+                format_millis(self.conf.budget),
+                match self.num_unlogged_slow_frames {
+                    1 => String::new(),
+                    n => format!(" ({n} slow frames since the last warning)"),
+                }
+            );
+            self.last_log_time = Some(now);
+            self.num_unlogged_slow_frames = 0;
+        }
+    }
+
+    /// Call after the app has drawn, so the warning's on top.
+    pub(crate) fn draw_warning(&self, now: Timestamp) {
+        if !self.conf.show_warning {
+            return;
+        }
+        let Some((times, at)) = self.last_slow_frame else {
+            return;
+        };
+        if now
+            .duration_since(at)
+            .is_some_and(|since| since > WARNING_DURATION)
+        {
+            return;
+        }
+        let area = bq_safe_area::safe_area();
+        bq_text::draw_text(
+            format!(
+                "Slow frame: {} (budget {})",
+                describe_frame(times),
+                format_millis(self.conf.budget)
+            ),
+            TextAlignment::Left,
+            None,
+            WARNING_FONT_SIZE,
+            mq::WHITE,
+            TextAnchorPoint::TopLeft {
+                x: area.left() + WARNING_MARGIN,
+                y: area.top() + WARNING_MARGIN,
+            },
+            Some(TextBackground {
+                color: mq::Color::new(0.7, 0.1, 0.1, 0.9),
+                x_padding: WARNING_PADDING,
+                y_padding: WARNING_PADDING,
+            }),
+        );
+    }
+}
+
+fn describe_frame(times: FrameTimes) -> String {
+    format!(
+        "{} (update {}, draw {})",
+        format_millis(times.total()),
+        format_millis(times.update),
+        format_millis(times.draw)
+    )
+}
+
+fn format_millis(duration: Duration) -> String {
+    format!("{:.1} ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// Milliseconds, e.g. "16" or "33.3". None if it's not a positive number.
+fn parse_budget(text: &str) -> Option<Duration> {
+    text.trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|millis| millis.is_finite() && *millis > 0.0)
+        .map(|millis| Duration::from_micros((millis * 1000.0).round() as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_millis, parse_budget, FrameTimes};
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_budget() {
+        assert_eq!(Some(Duration::from_millis(16)), parse_budget("16"));
+        assert_eq!(Some(Duration::from_micros(33_300)), parse_budget(" 33.3 "));
+        assert_eq!(None, parse_budget("0"));
+        assert_eq!(None, parse_budget("-5"));
+        assert_eq!(None, parse_budget("fast"));
+    }
+
+    #[test]
+    fn test_frame_times() {
+        let times = FrameTimes {
+            update: Duration::from_micros(20_000),
+            draw: Duration::from_micros(3_100),
+        };
+        assert_eq!(Duration::from_micros(23_100), times.total());
+        assert_eq!("23.1 ms", format_millis(times.total()));
+    }
+}
@@ -0,0 +1,148 @@
+//! Splitting text into what reads as one character (grapheme clusters), so cutting text short or
+//! deleting the last character doesn't strip an accent off its letter or split up an emoji.
+//!
+//! It's a close approximation of the Unicode rules, without their tables: combining marks (Latin,
+//! Greek, Cyrillic, Hebrew, Arabic, Devanagari, Thai, and kana voicing marks), variation
+//! selectors, emoji skin tones, tags, and zero width joiner sequences, flag pairs, Hangul jamo,
+//! and CR LF. Precomposed text (e.g. most CJK, Hangul syllables, and "é") is one char per grapheme
+//! anyway.
+
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+
+/// Each grapheme of `text`, in order.
+pub fn graphemes(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        let len = first_grapheme_len(rest)?;
+        let (grapheme, after) = rest.split_at(len);
+        rest = after;
+        Some(grapheme)
+    })
+}
+
+pub fn grapheme_count(text: &str) -> usize {
+    graphemes(text).count()
+}
+
+/// Removes the last grapheme of `text`, e.g. for backspace.
+pub fn pop_grapheme(text: &mut String) {
+    let last_len = graphemes(text).last().map_or(0, str::len);
+    text.truncate(text.len() - last_len);
+}
+
+/// Byte length, None for empty text.
+fn first_grapheme_len(text: &str) -> Option<usize> {
+    let mut chars = text.char_indices();
+    let (_, first) = chars.next()?;
+    let mut previous = first;
+    let mut num_regional_indicators = usize::from(is_regional_indicator(first));
+    for (i, c) in chars {
+        let joins = is_extend(c)
+            || previous == ZERO_WIDTH_JOINER
+            || (previous == '\r' && c == '\n')
+            || (is_hangul_jamo(c) && (is_hangul_jamo_l(previous) || is_hangul_jamo(previous)))
+            || (is_regional_indicator(c) && num_regional_indicators == 1);
+        if !joins {
+            return Some(i);
+        }
+        if is_regional_indicator(c) {
+            num_regional_indicators += 1;
+        }
+        previous = c;
+    }
+    Some(text.len())
+}
+
+/// Goes with whatever's before it.
+fn is_extend(c: char) -> bool {
+    matches!(c,
+        // Combining diacritical marks, and their extended and supplement blocks
+        '\u{0300}'..='\u{036F}'
+        | '\u{1AB0}'..='\u{1AFF}'
+        | '\u{1DC0}'..='\u{1DFF}'
+        | '\u{20D0}'..='\u{20FF}'
+        | '\u{FE20}'..='\u{FE2F}'
+        // Cyrillic
+        | '\u{0483}'..='\u{0489}'
+        // Hebrew
+        | '\u{0591}'..='\u{05BD}'
+        | '\u{05BF}'
+        | '\u{05C1}'..='\u{05C2}'
+        | '\u{05C4}'..='\u{05C5}'
+        | '\u{05C7}'
+        // Arabic
+        | '\u{0610}'..='\u{061A}'
+        | '\u{064B}'..='\u{065F}'
+        | '\u{0670}'
+        // Devanagari
+        | '\u{0900}'..='\u{0903}'
+        | '\u{093A}'..='\u{093C}'
+        | '\u{093E}'..='\u{094F}'
+        | '\u{0951}'..='\u{0957}'
+        | '\u{0962}'..='\u{0963}'
+        // Thai
+        | '\u{0E31}'
+        | '\u{0E34}'..='\u{0E3A}'
+        | '\u{0E47}'..='\u{0E4E}'
+        // Kana voicing marks
+        | '\u{3099}'..='\u{309A}'
+        // Variation selectors, e.g. emoji presentation
+        | '\u{FE00}'..='\u{FE0F}'
+        | '\u{E0100}'..='\u{E01EF}'
+        // Emoji skin tones
+        | '\u{1F3FB}'..='\u{1F3FF}'
+        // Tags, e.g. in subdivision flags
+        | '\u{E0020}'..='\u{E007F}'
+        | ZERO_WIDTH_JOINER
+    )
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c, '\u{1F1E6}'..='\u{1F1FF}')
+}
+
+/// Leading consonants, which vowels and trailing consonants join onto.
+fn is_hangul_jamo_l(c: char) -> bool {
+    matches!(c, '\u{1100}'..='\u{115F}')
+}
+
+/// Vowels and trailing consonants, which also join onto each other.
+fn is_hangul_jamo(c: char) -> bool {
+    matches!(c, '\u{1160}'..='\u{11FF}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{grapheme_count, graphemes, pop_grapheme};
+
+    #[test]
+    fn test_graphemes() {
+        assert_eq!(
+            vec!["Z", "o", "e\u{308}"],
+            graphemes("Zoe\u{308}").collect::<Vec<_>>()
+        );
+        assert_eq!(3, grapheme_count("한국어"));
+        assert_eq!(4, grapheme_count("名前です"));
+        // Family, thumbs up with a skin tone, red heart
+        assert_eq!(3, grapheme_count("👨\u{200D}👩\u{200D}👧👍🏽❤\u{FE0F}"));
+        // Two flags
+        assert_eq!(vec!["🇯🇵", "🇰🇷"], graphemes("🇯🇵🇰🇷").collect::<Vec<_>>());
+        // Conjoining jamo, then a Devanagari syllable
+        assert_eq!(2, grapheme_count("\u{1100}\u{1161}\u{11A8}कि"));
+        assert_eq!(2, grapheme_count("a\r\n"));
+        assert_eq!(0, grapheme_count(""));
+    }
+
+    #[test]
+    fn test_pop_grapheme() {
+        let mut text = "Ame\u{301}lie 👩\u{200D}🚀".to_string();
+        pop_grapheme(&mut text);
+        assert_eq!("Ame\u{301}lie ", text);
+        let mut text = "Rene\u{301}".to_string();
+        pop_grapheme(&mut text);
+        assert_eq!("Ren", text);
+        let mut text = String::new();
+        pop_grapheme(&mut text);
+        assert_eq!("", text);
+    }
+}
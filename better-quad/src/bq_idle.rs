@@ -0,0 +1,51 @@
+//! How long it's been since the player last did anything, e.g. to pause a clock that's been left
+//! running overnight. Any key, mouse button, mouse movement, scroll, or touch counts.
+use crate::bq_monotonic::MonotonicTime;
+use crate::mq;
+use std::time::Duration;
+
+#[derive(Copy, Clone, Debug)]
+pub struct IdleTracker {
+    last_input: MonotonicTime,
+    last_mouse_position: (f32, f32),
+}
+
+impl IdleTracker {
+    /// Counting from `now`, as if there'd just been input.
+    pub fn new(now: MonotonicTime) -> Self {
+        Self {
+            last_input: now,
+            last_mouse_position: mq::mouse_position(),
+        }
+    }
+
+    /// Call every frame, before checking [`Self::idle_for`].
+    pub fn update(&mut self, now: MonotonicTime) {
+        let mouse_position = mq::mouse_position();
+        let mouse_moved = mouse_position != self.last_mouse_position;
+        self.last_mouse_position = mouse_position;
+        let any_input = mouse_moved
+            || !mq::get_keys_down().is_empty()
+            || [
+                mq::MouseButton::Left,
+                mq::MouseButton::Right,
+                mq::MouseButton::Middle,
+            ]
+            .into_iter()
+            .any(mq::is_mouse_button_down)
+            || mq::mouse_wheel() != (0.0, 0.0)
+            || !mq::touches().is_empty();
+        if any_input {
+            self.input_at(now);
+        }
+    }
+
+    /// Counts as input, e.g. when something other than the player starts things up again.
+    pub fn input_at(&mut self, now: MonotonicTime) {
+        self.last_input = now;
+    }
+
+    pub fn idle_for(&self, now: MonotonicTime) -> Duration {
+        now.duration_since(self.last_input)
+    }
+}
@@ -0,0 +1,350 @@
+//! Keys players can rebind, e.g. to put "next turn" on a foot pedal that sends Enter. Apps list
+//! their rebindable actions with [`InputAction`], and ask an [`InputMap`] whether an action's key
+//! was pressed instead of checking a hard-coded key.
+//!
+//! Only keys rebound away from their defaults are kept, and they're saved by name (see
+//! [`key_from_name`]), so it can go in an app's settings file. Actions added in later versions
+//! start on their defaults, and entries that don't make sense any more are skipped on load.
+use crate::bq::BetterKeyCode;
+use crate::mq;
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use std::collections::BTreeMap;
+
+/// An app's rebindable actions, usually a fieldless enum.
+pub trait InputAction: Copy + PartialEq + 'static {
+    /// Every action, in the order they're listed for rebinding.
+    const ALL: &'static [Self];
+    /// Keys the app uses for something else, which can't be bound to any action.
+    const RESERVED_KEYS: &'static [mq::KeyCode] = &[];
+
+    /// Saved in place of the action, so it shouldn't change, e.g. "next_player".
+    fn name(self) -> &'static str;
+    /// Shown when rebinding, e.g. "Next player".
+    fn label(self) -> &'static str;
+    fn default_key(self) -> mq::KeyCode;
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct InputMap<A> {
+    /// Only actions that aren't on their default key.
+    overrides: Vec<(A, mq::KeyCode)>,
+}
+
+impl<A: InputAction> Default for InputMap<A> {
+    fn default() -> Self {
+        Self {
+            overrides: Vec::new(),
+        }
+    }
+}
+
+impl<A: InputAction> InputMap<A> {
+    pub fn key(&self, action: A) -> mq::KeyCode {
+        self.overrides
+            .iter()
+            .find(|(overridden, _)| *overridden == action)
+            .map_or_else(|| action.default_key(), |(_, key)| *key)
+    }
+
+    pub fn is_pressed(&self, action: A) -> bool {
+        mq::is_key_pressed(self.key(action))
+    }
+
+    pub fn is_down(&self, action: A) -> bool {
+        mq::is_key_down(self.key(action))
+    }
+
+    pub fn is_default(&self, action: A) -> bool {
+        self.key(action) == action.default_key()
+    }
+
+    /// The action on `key`, if any.
+    pub fn action(&self, key: mq::KeyCode) -> Option<A> {
+        A::ALL
+            .iter()
+            .copied()
+            .find(|action| self.key(*action) == key)
+    }
+
+    /// Puts `action` on `key`. If another action was on it, they swap keys, and that action is
+    /// returned so the player can be told. Errs for a reserved key.
+    pub fn rebind(&mut self, action: A, key: mq::KeyCode) -> Result<Option<A>, String> {
+        if A::RESERVED_KEYS.contains(&key) {
+            return Err(format!(
+                "[{}] is used for something else",
+                key.to_lowercase()
+            ));
+        }
+        Ok(self.swap_onto(action, key))
+    }
+
+    /// Back to its default key. Whatever else was on it swaps over, like [`Self::rebind`].
+    pub fn reset(&mut self, action: A) -> Option<A> {
+        self.swap_onto(action, action.default_key())
+    }
+
+    fn swap_onto(&mut self, action: A, key: mq::KeyCode) -> Option<A> {
+        let old_key = self.key(action);
+        let swapped = self.action(key).filter(|other| *other != action);
+        if let Some(other) = swapped {
+            self.set(other, old_key);
+        }
+        self.set(action, key);
+        swapped
+    }
+
+    fn set(&mut self, action: A, key: mq::KeyCode) {
+        self.overrides
+            .retain(|(overridden, _)| *overridden != action);
+        if key != action.default_key() {
+            self.overrides.push((action, key));
+        }
+    }
+}
+
+/// Saved as action names to key names, e.g. `{"next_player": "enter"}`.
+impl<A: InputAction> Serialize for InputMap<A> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            self.overrides
+                .iter()
+                .map(|(action, key)| (action.name(), key.to_lowercase())),
+        )
+    }
+}
+
+impl<'de, A: InputAction> Deserialize<'de> for InputMap<A> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let saved = BTreeMap::<String, String>::deserialize(deserializer)?;
+        let mut input_map = Self::default();
+        for (action_name, key_name) in saved {
+            let action = A::ALL
+                .iter()
+                .copied()
+                .find(|action| action.name() == action_name);
+            match (action, key_from_name(&key_name)) {
+                (Some(action), Some(key)) => {
+                    // Hand-edited files can have two actions on one key, or a reserved one
+                    if let Err(e) = input_map.rebind(action, key) {
+                        mq::warn!("Skipping key binding for {action_name}: {e}");
+                    }
+                }
+                _ => mq::warn!("Skipping unknown key binding {action_name} = {key_name}"),
+            }
+        }
+        Ok(input_map)
+    }
+}
+
+/// The key named like [`BetterKeyCode::to_lowercase`], e.g. "space" or "f8".
+pub fn key_from_name(name: &str) -> Option<mq::KeyCode> {
+    let name = name.trim().to_lowercase();
+    ALL_KEYS
+        .iter()
+        .copied()
+        .find(|key| key.to_lowercase() == name)
+}
+
+/// Every key but `Unknown`.
+const ALL_KEYS: [mq::KeyCode; 121] = {
+    use mq::KeyCode::*;
+    [
+        Space,
+        Apostrophe,
+        Comma,
+        Minus,
+        Period,
+        Slash,
+        Key0,
+        Key1,
+        Key2,
+        Key3,
+        Key4,
+        Key5,
+        Key6,
+        Key7,
+        Key8,
+        Key9,
+        Semicolon,
+        Equal,
+        A,
+        B,
+        C,
+        D,
+        E,
+        F,
+        G,
+        H,
+        I,
+        J,
+        K,
+        L,
+        M,
+        N,
+        O,
+        P,
+        Q,
+        R,
+        S,
+        T,
+        U,
+        V,
+        W,
+        X,
+        Y,
+        Z,
+        LeftBracket,
+        Backslash,
+        RightBracket,
+        GraveAccent,
+        World1,
+        World2,
+        Escape,
+        Enter,
+        Tab,
+        Backspace,
+        Insert,
+        Delete,
+        Right,
+        Left,
+        Down,
+        Up,
+        PageUp,
+        PageDown,
+        Home,
+        End,
+        CapsLock,
+        ScrollLock,
+        NumLock,
+        PrintScreen,
+        Pause,
+        F1,
+        F2,
+        F3,
+        F4,
+        F5,
+        F6,
+        F7,
+        F8,
+        F9,
+        F10,
+        F11,
+        F12,
+        F13,
+        F14,
+        F15,
+        F16,
+        F17,
+        F18,
+        F19,
+        F20,
+        F21,
+        F22,
+        F23,
+        F24,
+        F25,
+        Kp0,
+        Kp1,
+        Kp2,
+        Kp3,
+        Kp4,
+        Kp5,
+        Kp6,
+        Kp7,
+        Kp8,
+        Kp9,
+        KpDecimal,
+        KpDivide,
+        KpMultiply,
+        KpSubtract,
+        KpAdd,
+        KpEnter,
+        KpEqual,
+        LeftShift,
+        LeftControl,
+        LeftAlt,
+        LeftSuper,
+        RightShift,
+        RightControl,
+        RightAlt,
+        RightSuper,
+        Menu,
+        Back,
+    ]
+};
+
+#[cfg(test)]
+mod tests {
+    use super::{key_from_name, InputAction, InputMap};
+    use crate::mq::KeyCode;
+
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    enum Action {
+        Jump,
+        Duck,
+    }
+
+    impl InputAction for Action {
+        const ALL: &'static [Self] = &[Action::Jump, Action::Duck];
+        const RESERVED_KEYS: &'static [KeyCode] = &[KeyCode::Escape];
+
+        fn name(self) -> &'static str {
+            match self {
+                Action::Jump => "jump",
+                Action::Duck => "duck",
+            }
+        }
+
+        fn label(self) -> &'static str {
+            match self {
+                Action::Jump => "Jump",
+                Action::Duck => "Duck",
+            }
+        }
+
+        fn default_key(self) -> KeyCode {
+            match self {
+                Action::Jump => KeyCode::Space,
+                Action::Duck => KeyCode::Down,
+            }
+        }
+    }
+
+    #[test]
+    fn test_rebind() {
+        let mut input_map = InputMap::<Action>::default();
+        assert_eq!(KeyCode::Space, input_map.key(Action::Jump));
+        assert_eq!(Ok(None), input_map.rebind(Action::Jump, KeyCode::Enter));
+        assert_eq!(KeyCode::Enter, input_map.key(Action::Jump));
+        assert!(!input_map.is_default(Action::Jump));
+
+        // Taking another action's key swaps them
+        assert_eq!(
+            Ok(Some(Action::Jump)),
+            input_map.rebind(Action::Duck, KeyCode::Enter)
+        );
+        assert_eq!(KeyCode::Down, input_map.key(Action::Jump));
+        assert_eq!(Some(Action::Duck), input_map.action(KeyCode::Enter));
+        assert!(input_map.rebind(Action::Duck, KeyCode::Escape).is_err());
+
+        assert_eq!(Some(Action::Jump), input_map.reset(Action::Duck));
+        assert_eq!(None, input_map.reset(Action::Jump));
+        assert_eq!(InputMap::default(), input_map);
+    }
+
+    #[test]
+    fn test_saved() {
+        let mut input_map = InputMap::<Action>::default();
+        input_map.rebind(Action::Jump, KeyCode::F8).unwrap();
+        let json = serde_json::to_string(&input_map).unwrap();
+        assert_eq!(r#"{"jump":"f8"}"#, json);
+        assert_eq!(input_map, serde_json::from_str(&json).unwrap());
+
+        // Anything that doesn't make sense is skipped
+        let read: InputMap<Action> =
+            serde_json::from_str(r#"{"fly":"f","duck":"nope","jump":"escape"}"#).unwrap();
+        assert_eq!(InputMap::default(), read);
+        assert_eq!(Some(KeyCode::PageUp), key_from_name(" PageUp"));
+    }
+}
@@ -9,3 +9,16 @@ impl BetterKeyCode for mq::KeyCode {
         format!("{self:?}").to_lowercase()
     }
 }
+
+/// All chars typed since the last call, oldest first.
+///
+/// mq's char queue is never cleared on its own and pops newest first, so call
+/// `mq::clear_input_queue()` when you start listening to avoid picking up stale chars.
+pub fn drain_chars_pressed() -> Vec<char> {
+    let mut chars = Vec::new();
+    while let Some(c) = mq::get_char_pressed() {
+        chars.push(c);
+    }
+    chars.reverse();
+    chars
+}
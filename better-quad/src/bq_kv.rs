@@ -0,0 +1,140 @@
+//! Per-app key-value store for small flags and values (e.g. "tutorial_completed"), so apps don't
+//! need to define a save format for each one.
+//!
+//! Everything lives in one `kv.json` in the app's storage folder (see `bq_storage`), and is saved
+//! on every set. Storage is best-effort like the rest of `AppStorage`: without it (e.g. on WASM),
+//! values only last until the app closes.
+use crate::bq_storage::AppStorage;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const KV_FILE_NAME: &str = "kv";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(untagged)]
+pub enum KvValue {
+    Bool(bool),
+    Num(f64),
+    String(String),
+}
+
+pub struct KvStore {
+    // None for in-memory only
+    storage: Option<AppStorage>,
+    values: BTreeMap<String, KvValue>,
+}
+
+impl KvStore {
+    pub fn load(storage: AppStorage) -> Self {
+        Self {
+            storage: Some(storage),
+            values: storage.load_or_default(KV_FILE_NAME),
+        }
+    }
+
+    /// Never saved. Handy for tests.
+    pub fn in_memory() -> Self {
+        Self {
+            storage: None,
+            values: BTreeMap::new(),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&KvValue> {
+        self.values.get(key)
+    }
+
+    /// None if missing or not a string.
+    pub fn get_string(&self, key: &str) -> Option<&str> {
+        match self.get(key)? {
+            KvValue::String(value) => Some(value),
+            KvValue::Bool(_) | KvValue::Num(_) => None,
+        }
+    }
+
+    /// None if missing or not a number.
+    pub fn get_num(&self, key: &str) -> Option<f64> {
+        match self.get(key)? {
+            KvValue::Num(value) => Some(*value),
+            KvValue::Bool(_) | KvValue::String(_) => None,
+        }
+    }
+
+    /// None if missing or not a bool.
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        match self.get(key)? {
+            KvValue::Bool(value) => Some(*value),
+            KvValue::Num(_) | KvValue::String(_) => None,
+        }
+    }
+
+    pub fn set(&mut self, key: &str, value: KvValue) {
+        if self.values.get(key) == Some(&value) {
+            return;
+        }
+        self.values.insert(key.to_string(), value);
+        self.save();
+    }
+
+    pub fn set_string(&mut self, key: &str, value: impl Into<String>) {
+        self.set(key, KvValue::String(value.into()));
+    }
+
+    pub fn set_num(&mut self, key: &str, value: f64) {
+        self.set(key, KvValue::Num(value));
+    }
+
+    pub fn set_bool(&mut self, key: &str, value: bool) {
+        self.set(key, KvValue::Bool(value));
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        if self.values.remove(key).is_some() {
+            self.save();
+        }
+    }
+
+    fn save(&self) {
+        if let Some(storage) = self.storage {
+            storage.save_or_log(KV_FILE_NAME, &self.values);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KvStore, KvValue};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_typed_get_and_set() {
+        let mut kv = KvStore::in_memory();
+        kv.set_bool("tutorial_completed", true);
+        kv.set_num("volume", 0.5);
+        kv.set_string("last_difficulty", "hard");
+
+        assert_eq!(Some(true), kv.get_bool("tutorial_completed"));
+        assert_eq!(Some(0.5), kv.get_num("volume"));
+        assert_eq!(Some("hard"), kv.get_string("last_difficulty"));
+        // Wrong type or missing
+        assert_eq!(None, kv.get_num("tutorial_completed"));
+        assert_eq!(None, kv.get_bool("nope"));
+
+        kv.set_num("last_difficulty", 3.0);
+        assert_eq!(Some(3.0), kv.get_num("last_difficulty"));
+        kv.remove("last_difficulty");
+        assert_eq!(None, kv.get("last_difficulty"));
+    }
+
+    #[test]
+    fn test_values_round_trip_through_json() {
+        let values = BTreeMap::from([
+            ("a".to_string(), KvValue::Bool(false)),
+            ("b".to_string(), KvValue::Num(2.0)),
+            ("c".to_string(), KvValue::String("true".to_string())),
+        ]);
+        let json = serde_json::to_string(&values).unwrap();
+        assert_eq!(r#"{"a":false,"b":2.0,"c":"true"}"#, json);
+        assert_eq!(values, serde_json::from_str(&json).unwrap());
+    }
+}
@@ -0,0 +1,115 @@
+//! Time that only goes forward, for counting up time on a clock frame by frame. [`Timestamp`]
+//! follows the wall clock, which can jump either way when it's synced or changed, so it's for
+//! dates, not timing.
+//!
+//! A [`TickClock`] also notices when frames stop coming for a while, e.g. while the computer was
+//! asleep, so apps can decide what to do with the gap instead of counting it as play.
+//!
+//! [`Timestamp`]: crate::bq_timestamp::Timestamp
+use std::ops::Add;
+use std::time::Duration;
+
+#[derive(Copy, Clone, PartialEq, PartialOrd, Debug)]
+pub struct MonotonicTime {
+    /// Since some point at startup.
+    seconds: f64,
+}
+
+impl MonotonicTime {
+    pub fn now() -> Self {
+        Self {
+            seconds: monotonic_seconds(),
+        }
+    }
+
+    /// Zero if `earlier` is actually later.
+    pub fn duration_since(&self, earlier: MonotonicTime) -> Duration {
+        Duration::from_secs_f64((self.seconds - earlier.seconds).max(0.0))
+    }
+}
+
+impl Add<Duration> for MonotonicTime {
+    type Output = MonotonicTime;
+
+    fn add(self, rhs: Duration) -> Self::Output {
+        Self {
+            seconds: self.seconds + rhs.as_secs_f64(),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn monotonic_seconds() -> f64 {
+    use once_cell::sync::Lazy;
+    use std::time::Instant;
+
+    static START: Lazy<Instant> = Lazy::new(Instant::now);
+    START.elapsed().as_secs_f64()
+}
+
+/// There's no `Instant` on WASM, so it's the wall clock, held still whenever it goes back.
+#[cfg(target_arch = "wasm32")]
+fn monotonic_seconds() -> f64 {
+    use std::sync::Mutex;
+
+    static LATEST: Mutex<f64> = Mutex::new(0.0);
+    let mut latest = LATEST.lock().expect("monotonic lock poisoned");
+    *latest = latest.max(crate::mq::miniquad::date::now());
+    *latest
+}
+
+/// What happened since the last [`TickClock::tick`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Tick {
+    Elapsed(Duration),
+    /// Longer than the clock's max gap, e.g. asleep. The next tick counts from here again.
+    Gap(Duration),
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TickClock {
+    last_tick: MonotonicTime,
+    max_gap: Duration,
+}
+
+impl TickClock {
+    /// Counting from `now`. Ticks further apart than `max_gap` are gaps, so it should be well over
+    /// a frame, and over any hitch.
+    pub fn new(now: MonotonicTime, max_gap: Duration) -> Self {
+        Self {
+            last_tick: now,
+            max_gap,
+        }
+    }
+
+    pub fn tick(&mut self, now: MonotonicTime) -> Tick {
+        let elapsed = now.duration_since(self.last_tick);
+        self.last_tick = now;
+        if elapsed > self.max_gap {
+            Tick::Gap(elapsed)
+        } else {
+            Tick::Elapsed(elapsed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MonotonicTime, Tick, TickClock};
+    use std::time::Duration;
+
+    #[test]
+    fn test_tick_clock() {
+        let start = MonotonicTime::now();
+        let second = Duration::from_secs(1);
+        let mut clock = TickClock::new(start, second * 60);
+        assert_eq!(Tick::Elapsed(second), clock.tick(start + second));
+        assert_eq!(Tick::Elapsed(second * 60), clock.tick(start + second * 61));
+        assert_eq!(Tick::Gap(second * 600), clock.tick(start + second * 661));
+        // Counting from the end of the gap
+        assert_eq!(Tick::Elapsed(second), clock.tick(start + second * 662));
+        // Never backwards
+        assert_eq!(Tick::Elapsed(Duration::ZERO), clock.tick(start));
+        assert!(MonotonicTime::now() >= start);
+    }
+}
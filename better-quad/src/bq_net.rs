@@ -0,0 +1,271 @@
+//! Messaging between two instances of an app over TCP, e.g. for LAN play: one listens, the other
+//! connects, and they swap serde messages as lines of JSON.
+//!
+//! Nothing blocks except connecting, so poll from `update()` each frame. Connecting can take a
+//! while to time out, so do it in the background:
+//! `bq::spawn_task(move || NetConnection::connect(&address))`.
+//!
+//! WASM has no raw sockets, so everything returns an error there.
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+use std::io::{self, Read, Write};
+use std::marker::PhantomData;
+use std::net::{IpAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const READ_CHUNK_SIZE: usize = 4096;
+/// Messages are tiny, so a longer line means the other end isn't this app, and buffering it could
+/// take any amount of memory.
+pub const MAX_LINE_LENGTH: usize = 8 * 1024;
+
+/// Waits for others to connect, on every network interface.
+pub struct NetListener {
+    listener: TcpListener,
+}
+
+/// One end of a connection, sending and receiving `M`s. Both ends usually share one message enum.
+pub struct NetConnection<M> {
+    stream: TcpStream,
+    /// Received bytes that aren't a full line yet
+    unread: Vec<u8>,
+    /// Hit after messages that were returned first, for the next poll.
+    pending_error: Option<NetError>,
+    message_type: PhantomData<fn() -> M>,
+}
+
+#[derive(Debug)]
+pub enum NetError {
+    /// The other end hung up.
+    Closed,
+    Io(io::Error),
+    /// The other end sent something that isn't an `M`, e.g. it's a different version of the app.
+    BadMessage(serde_json::Error),
+    /// The other end sent a line over [`MAX_LINE_LENGTH`].
+    TooLong,
+}
+
+impl Display for NetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Closed => write!(f, "connection closed"),
+            Self::Io(e) => write!(f, "{e}"),
+            Self::BadMessage(e) => write!(f, "bad message: {e}"),
+            Self::TooLong => write!(f, "message over {MAX_LINE_LENGTH} bytes"),
+        }
+    }
+}
+
+impl NetListener {
+    /// Port 0 picks any free port, see [`Self::port`].
+    pub fn bind(port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        listener.set_nonblocking(true)?;
+        Ok(Self { listener })
+    }
+
+    pub fn port(&self) -> io::Result<u16> {
+        Ok(self.listener.local_addr()?.port())
+    }
+
+    /// The next connection, if anyone's connected since the last call.
+    pub fn poll_accept<M>(&self) -> io::Result<Option<NetConnection<M>>> {
+        match self.listener.accept() {
+            Ok((stream, _)) => NetConnection::from_stream(stream).map(Some),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl<M: Serialize + DeserializeOwned> NetConnection<M> {
+    /// `address` is a host and port, e.g. `192.168.1.5:7878`. Blocks until connected or timed out.
+    pub fn connect(address: &str) -> io::Result<Self> {
+        let mut last_error = io::Error::new(io::ErrorKind::NotFound, "address not found");
+        for socket_address in address.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&socket_address, CONNECT_TIMEOUT) {
+                Ok(stream) => return Self::from_stream(stream),
+                Err(e) => last_error = e,
+            }
+        }
+        Err(last_error)
+    }
+
+    /// Messages are small enough to fit in the socket's buffer, so this doesn't wait.
+    pub fn send(&mut self, message: &M) -> Result<(), NetError> {
+        let mut line = serde_json::to_vec(message).map_err(NetError::BadMessage)?;
+        line.push(b'\n');
+        self.stream.write_all(&line).map_err(NetError::Io)
+    }
+
+    /// Everything received since the last call, oldest first. Messages received before an error
+    /// are returned first, and the error on the next call.
+    pub fn poll(&mut self) -> Result<Vec<M>, NetError> {
+        if let Some(error) = self.pending_error.take() {
+            return Err(error);
+        }
+        let read_result = self.read_available();
+
+        let mut messages = Vec::new();
+        let mut error = None;
+        while let Some(line_end) = self.unread.iter().position(|byte| *byte == b'\n') {
+            let line = self.unread.drain(..=line_end).collect::<Vec<_>>();
+            match serde_json::from_slice(&line) {
+                Ok(message) => messages.push(message),
+                Err(e) => {
+                    error = Some(NetError::BadMessage(e));
+                    break;
+                }
+            }
+        }
+        match error.or(read_result.err()) {
+            Some(error) if messages.is_empty() => Err(error),
+            error => {
+                self.pending_error = error;
+                Ok(messages)
+            }
+        }
+    }
+
+    /// Into `unread`, until there's nothing more to read for now.
+    fn read_available(&mut self) -> Result<(), NetError> {
+        let mut chunk = [0; READ_CHUNK_SIZE];
+        loop {
+            match self.stream.read(&mut chunk) {
+                Ok(0) => return Err(NetError::Closed),
+                Ok(num_read) => {
+                    self.unread.extend_from_slice(&chunk[..num_read]);
+                    let partial_line_length = self
+                        .unread
+                        .iter()
+                        .rev()
+                        .position(|byte| *byte == b'\n')
+                        .unwrap_or(self.unread.len());
+                    if partial_line_length > MAX_LINE_LENGTH {
+                        return Err(NetError::TooLong);
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(NetError::Io(e)),
+            }
+        }
+    }
+}
+
+impl<M> NetConnection<M> {
+    fn from_stream(stream: TcpStream) -> io::Result<Self> {
+        stream.set_nonblocking(true)?;
+        // Messages are tiny and latency matters more than throughput
+        stream.set_nodelay(true)?;
+        Ok(Self {
+            stream,
+            unread: Vec::new(),
+            pending_error: None,
+            message_type: PhantomData,
+        })
+    }
+}
+
+/// This computer's address on the local network, to tell others where to connect. None if there's
+/// no network.
+pub fn local_ip() -> Option<IpAddr> {
+    // Connecting a UDP socket sends nothing, but picks the interface that would be used
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NetConnection, NetError, NetListener, MAX_LINE_LENGTH};
+    use serde::{Deserialize, Serialize};
+    use std::io::Write;
+    use std::net::TcpStream;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Message {
+        Ping(u32),
+        Text(String),
+    }
+
+    /// Polls until there's anything to report.
+    fn poll_until_some<T>(mut poll: impl FnMut() -> Option<T>) -> T {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            if let Some(result) = poll() {
+                return result;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        panic!("timed out");
+    }
+
+    #[test]
+    fn test_send_and_receive() {
+        let listener = NetListener::bind(0).unwrap();
+        let address = format!("127.0.0.1:{}", listener.port().unwrap());
+        let mut client = NetConnection::<Message>::connect(&address).unwrap();
+        let mut server = poll_until_some(|| listener.poll_accept::<Message>().unwrap());
+
+        client.send(&Message::Ping(1)).unwrap();
+        client
+            .send(&Message::Text("two\nlines".to_string()))
+            .unwrap();
+        let mut received = Vec::new();
+        let received = poll_until_some(|| {
+            received.extend(server.poll().unwrap());
+            (received.len() == 2).then(|| std::mem::take(&mut received))
+        });
+        assert_eq!(
+            vec![Message::Ping(1), Message::Text("two\nlines".to_string())],
+            received
+        );
+
+        server.send(&Message::Ping(3)).unwrap();
+        let received = poll_until_some(|| Some(client.poll().unwrap()).filter(|m| !m.is_empty()));
+        assert_eq!(vec![Message::Ping(3)], received);
+
+        drop(server);
+        let result = poll_until_some(|| client.poll().err());
+        assert!(matches!(result, NetError::Closed));
+    }
+
+    /// Everything polled until an error, and the error.
+    fn poll_until_error(server: &mut NetConnection<Message>) -> (Vec<Message>, NetError) {
+        let mut received = Vec::new();
+        let error = poll_until_some(|| match server.poll() {
+            Ok(messages) => {
+                received.extend(messages);
+                None
+            }
+            Err(e) => Some(e),
+        });
+        (received, error)
+    }
+
+    #[test]
+    fn test_bad_lines() {
+        let listener = NetListener::bind(0).unwrap();
+        let address = format!("127.0.0.1:{}", listener.port().unwrap());
+
+        // The message before a bad one still comes through
+        let mut client = TcpStream::connect(&address).unwrap();
+        let mut server = poll_until_some(|| listener.poll_accept::<Message>().unwrap());
+        client.write_all(b"{\"Ping\":1}\nnot json\n").unwrap();
+        let (received, error) = poll_until_error(&mut server);
+        assert_eq!(vec![Message::Ping(1)], received);
+        assert!(matches!(error, NetError::BadMessage(_)), "{error}");
+
+        // A line that never ends isn't buffered forever
+        let mut client = TcpStream::connect(&address).unwrap();
+        let mut server = poll_until_some(|| listener.poll_accept::<Message>().unwrap());
+        client.write_all(&[b'a'; MAX_LINE_LENGTH + 1]).unwrap();
+        let (received, error) = poll_until_error(&mut server);
+        assert!(received.is_empty());
+        assert!(matches!(error, NetError::TooLong), "{error}");
+    }
+}
@@ -0,0 +1,151 @@
+//! Particle effects for celebrating, e.g. confetti when a game or session ends.
+//!
+//! Each particle's path is worked out from how long ago the effect started, so there's nothing to
+//! update every frame, only draw.
+use crate::bq_rand::SeededRng;
+use crate::bq_timestamp::Timestamp;
+use crate::mq;
+use std::f32::consts::PI;
+use std::time::Duration;
+
+/// How long a burst lasts. Pieces fade out over the last [`CONFETTI_FADE`] of it.
+pub const CONFETTI_DURATION: Duration = Duration::from_secs(4);
+const CONFETTI_FADE: Duration = Duration::from_secs(1);
+/// Pixels per second squared
+const GRAVITY: f32 = 900.0;
+/// How quickly the air slows pieces down, per second. Also caps how fast they fall.
+const DRAG: f32 = 1.5;
+const MIN_SPEED: f32 = 300.0;
+const MAX_SPEED: f32 = 1000.0;
+/// Up, give or take this much either way.
+const MAX_ANGLE_FROM_UP: f32 = PI / 3.0;
+const PIECE_WIDTH: f32 = 10.0;
+const PIECE_HEIGHT: f32 = 6.0;
+/// Radians per second
+const MAX_SPIN: f32 = 4.0 * PI;
+
+/// A burst of little colored rectangles that fly up and out from a point, then flutter down.
+pub struct Confetti {
+    origin: mq::Vec2,
+    started: Timestamp,
+    pieces: Vec<ConfettiPiece>,
+}
+
+struct ConfettiPiece {
+    velocity: mq::Vec2,
+    spin: f32,
+    color: mq::Color,
+}
+
+impl Confetti {
+    /// `num_pieces` from (`x`, `y`) in window coordinates, taking turns with `colors` (e.g. the
+    /// players' colors).
+    pub fn burst(
+        x: f32,
+        y: f32,
+        colors: &[mq::Color],
+        num_pieces: usize,
+        now: Timestamp,
+        rng: &SeededRng,
+    ) -> Self {
+        let pieces = (0..num_pieces)
+            .map(|i| {
+                let angle = -PI / 2.0 + rng.gen_range(-MAX_ANGLE_FROM_UP, MAX_ANGLE_FROM_UP);
+                let speed = rng.gen_range(MIN_SPEED, MAX_SPEED);
+                ConfettiPiece {
+                    velocity: mq::Vec2::from_angle(angle) * speed,
+                    spin: rng.gen_range(-MAX_SPIN, MAX_SPIN),
+                    color: colors
+                        .get(i % colors.len().max(1))
+                        .copied()
+                        .unwrap_or(mq::WHITE),
+                }
+            })
+            .collect();
+        Self {
+            origin: mq::vec2(x, y),
+            started: now,
+            pieces,
+        }
+    }
+
+    pub fn is_finished(&self, now: Timestamp) -> bool {
+        self.elapsed(now) >= CONFETTI_DURATION
+    }
+
+    /// With the default camera.
+    pub fn draw(&self, now: Timestamp) {
+        let elapsed = self.elapsed(now);
+        if elapsed >= CONFETTI_DURATION {
+            return;
+        }
+        let fade_left = CONFETTI_DURATION - elapsed;
+        let alpha = (fade_left.as_secs_f32() / CONFETTI_FADE.as_secs_f32()).min(1.0);
+        let t = elapsed.as_secs_f32();
+        for piece in &self.pieces {
+            let position = self.origin + piece.offset(t);
+            // Flipping over, seen edge on every half turn
+            let flip = (piece.spin * t).cos().abs();
+            mq::draw_rectangle_ex(
+                position.x,
+                position.y,
+                PIECE_WIDTH,
+                PIECE_HEIGHT * flip.max(0.1),
+                mq::DrawRectangleParams {
+                    offset: mq::vec2(0.5, 0.5),
+                    rotation: piece.spin * t / 2.0,
+                    color: mq::Color {
+                        a: piece.color.a * alpha,
+                        ..piece.color
+                    },
+                },
+            );
+        }
+    }
+
+    fn elapsed(&self, now: Timestamp) -> Duration {
+        now.duration_since(self.started).unwrap_or_default()
+    }
+}
+
+impl ConfettiPiece {
+    /// From the burst's origin, `t` seconds in. Drag slows the throw down and gravity wins out.
+    fn offset(&self, t: f32) -> mq::Vec2 {
+        let slowed = (1.0 - (-DRAG * t).exp()) / DRAG;
+        let fallen = GRAVITY / DRAG * (t - slowed);
+        self.velocity * slowed + mq::vec2(0.0, fallen)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Confetti, CONFETTI_DURATION};
+    use crate::bq_rand::SeededRng;
+    use crate::bq_timestamp::Timestamp;
+    use crate::mq;
+
+    #[test]
+    fn test_confetti() {
+        let now = Timestamp::now();
+        let confetti = Confetti::burst(
+            100.0,
+            500.0,
+            &[mq::RED, mq::BLUE],
+            20,
+            now,
+            &SeededRng::new(1),
+        );
+        assert_eq!(mq::RED, confetti.pieces[2].color);
+        assert_eq!(mq::BLUE, confetti.pieces[3].color);
+
+        for piece in &confetti.pieces {
+            assert_eq!(mq::Vec2::ZERO, piece.offset(0.0));
+            // Thrown up, then down past where it started by the end
+            assert!(piece.offset(0.1).y < 0.0, "{:?}", piece.velocity);
+            assert!(piece.offset(CONFETTI_DURATION.as_secs_f32()).y > 0.0);
+        }
+
+        assert!(!confetti.is_finished(now));
+        assert!(confetti.is_finished(now + CONFETTI_DURATION));
+    }
+}
@@ -0,0 +1,82 @@
+//! Whether the player is using a mouse or a touchscreen, for things that only make sense with one
+//! of them (custom cursors, hover tooltips, on-screen buttons, etc).
+//!
+//! Macroquad turns taps into left clicks, so apps don't need to handle touches themselves to be
+//! playable on a touchscreen.
+use crate::mq;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum PointerKind {
+    #[default]
+    Mouse,
+    Touch,
+}
+
+/// Switches to touch on any touch, and back to mouse when the mouse moves without one.
+#[derive(Default)]
+pub struct PointerTracker {
+    kind: PointerKind,
+    last_mouse_position: (f32, f32),
+}
+
+impl PointerTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call every frame, before checking [`PointerTracker::kind`].
+    pub fn update(&mut self) {
+        let mouse_position = mq::mouse_position();
+        self.kind = next_kind(
+            self.kind,
+            !mq::touches().is_empty(),
+            mouse_position != self.last_mouse_position,
+        );
+        self.last_mouse_position = mouse_position;
+    }
+
+    pub fn kind(&self) -> PointerKind {
+        self.kind
+    }
+
+    pub fn is_touch(&self) -> bool {
+        self.kind == PointerKind::Touch
+    }
+}
+
+/// Taps move the (simulated) mouse too, so the mouse only counts when there's no touch.
+fn next_kind(kind: PointerKind, any_touches: bool, mouse_moved: bool) -> PointerKind {
+    if any_touches {
+        PointerKind::Touch
+    } else if mouse_moved {
+        PointerKind::Mouse
+    } else {
+        kind
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{next_kind, PointerKind};
+
+    #[test]
+    fn test_next_kind() {
+        assert_eq!(
+            PointerKind::Touch,
+            next_kind(PointerKind::Mouse, true, true)
+        );
+        // Lifting the finger leaves the mouse where it was
+        assert_eq!(
+            PointerKind::Touch,
+            next_kind(PointerKind::Touch, false, false)
+        );
+        assert_eq!(
+            PointerKind::Mouse,
+            next_kind(PointerKind::Touch, false, true)
+        );
+        assert_eq!(
+            PointerKind::Mouse,
+            next_kind(PointerKind::Mouse, false, false)
+        );
+    }
+}
@@ -21,14 +21,19 @@ struct RandState {
 
 /// Use this when you want to set any new seed and you don't care what it's set to.
 pub fn randomize_rand_seed() {
+    set_rand_seed(random_seed());
+}
+
+/// A new seed that you don't care about the value of.
+pub fn random_seed() -> u64 {
     // Hash current timestamp to use as rand seed. We could just use timestamp itself, but this makes
     // it visually appear that the seeds are non-sequential. That wouldn't affect randomness quality
     // in a PRNG, it's just visually satisfying when displaying the seed.
+    //
+    // Use sub-second precision so seeds taken in quick succession still differ.
     let mut hasher = std::hash::DefaultHasher::new();
-    hasher.write_u64(Timestamp::now().as_sec_f64() as u64);
-    let seed = hasher.finish();
-
-    set_rand_seed(seed);
+    hasher.write_u64((Timestamp::now().as_sec_f64() * 1000.0) as u64);
+    hasher.finish()
 }
 
 pub fn set_rand_seed(seed: u64) {
@@ -41,3 +46,81 @@ pub fn set_rand_seed(seed: u64) {
 pub fn get_last_set_rand_seed() -> u64 {
     GLOBAL_STATE.last_set_seed.load(atomic::Ordering::Relaxed)
 }
+
+/// RNG with its own state, independent of the global `mq::rand` one. Use this when the same seed
+/// must reproduce the same results (e.g. sharing a seed with a friend), regardless of whatever
+/// else is pulling from the global RNG in the meantime.
+///
+/// Uses the same algorithm as the global RNG, so `SeededRng::new(seed)` produces the same
+/// sequence as `set_rand_seed(seed)` followed by `mq::rand::gen_range()` calls.
+pub struct SeededRng {
+    seed: u64,
+    generator: mq::rand::RandGenerator,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        let generator = mq::rand::RandGenerator::new();
+        generator.srand(seed);
+        Self { seed, generator }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// `[low, high)`, same as `mq::rand::gen_range()`.
+    pub fn gen_range<T: mq::rand::RandomRange>(&self, low: T, high: T) -> T {
+        self.generator.gen_range(low, high)
+    }
+}
+
+const SEED_CODE_DIGITS: &[u8; 36] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Short, human-typeable form of a seed (base 36), for sharing.
+pub fn seed_to_code(mut seed: u64) -> String {
+    let mut code = Vec::new();
+    loop {
+        code.push(SEED_CODE_DIGITS[(seed % 36) as usize]);
+        seed /= 36;
+        if seed == 0 {
+            break;
+        }
+    }
+    code.reverse();
+    String::from_utf8(code).expect("digits are ascii")
+}
+
+/// Inverse of `seed_to_code()`. Case insensitive and ignores surrounding whitespace.
+pub fn seed_from_code(code: &str) -> Option<u64> {
+    let code = code.trim();
+    if code.is_empty() {
+        return None;
+    }
+    u64::from_str_radix(code, 36).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{seed_from_code, seed_to_code, SeededRng};
+
+    #[test]
+    fn test_seed_code_round_trip() {
+        for seed in [0, 35, 36, 1_234_567, u64::MAX] {
+            assert_eq!(Some(seed), seed_from_code(&seed_to_code(seed)), "{seed}");
+        }
+        assert_eq!("ZZ", seed_to_code(36 * 36 - 1));
+        assert_eq!(Some(36 * 36 - 1), seed_from_code(" zz\n"));
+        assert_eq!(None, seed_from_code(""));
+        assert_eq!(None, seed_from_code("not a seed"));
+    }
+
+    #[test]
+    fn test_seeded_rng_is_deterministic() {
+        let a = SeededRng::new(42);
+        let b = SeededRng::new(42);
+        for _ in 0..10 {
+            assert_eq!(a.gen_range(0, 1000), b.gen_range(0, 1000));
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! End of game results screen: a title, stat rows, an optional message, and buttons, centered in
+//! the window's safe area and styled the same in every app.
+//!
+//! Each button has a key, so apps handle a click on it the same as the key press. Build the screen
+//! in both update (for [`ResultsScreen::clicked_key`]) and draw, from the same state.
+use crate::bq::{BetterKeyCode, TextAlignment, TextAnchorPoint};
+use crate::{bq_safe_area, bq_text, mq};
+
+const TITLE_FONT_SIZE: u16 = 32;
+const BODY_FONT_SIZE: u16 = 24;
+const BUTTON_FONT_SIZE: u16 = 22;
+const PADDING: f32 = 15.0;
+/// Between the title, stats, message, and buttons.
+const SECTION_SPACING: f32 = 12.0;
+/// Between a stat's label and value.
+const STAT_COLUMN_SPACING: f32 = 30.0;
+const BUTTON_PADDING: f32 = 6.0;
+const BUTTON_SPACING: f32 = 8.0;
+/// Space kept between the screen and the window edges, so buttons wrap before they'd spill out.
+const WINDOW_MARGIN: f32 = 10.0;
+
+const BACKGROUND_COLOR: mq::Color = mq::Color::new(0.78, 0.78, 0.78, 0.9);
+const TEXT_COLOR: mq::Color = mq::BLACK;
+const BUTTON_COLOR: mq::Color = mq::Color::new(0.25, 0.25, 0.3, 1.0);
+const BUTTON_HOVER_COLOR: mq::Color = mq::Color::new(0.35, 0.35, 0.45, 1.0);
+const BUTTON_TEXT_COLOR: mq::Color = mq::WHITE;
+
+pub struct ResultsScreen {
+    title: String,
+    title_color: mq::Color,
+    /// Label, value
+    stats: Vec<(String, String)>,
+    message: String,
+    buttons: Vec<ResultsButton>,
+}
+
+struct ResultsButton {
+    key: mq::KeyCode,
+    label: String,
+}
+
+/// Where everything goes, in window coordinates.
+struct ResultsLayout {
+    rect: mq::Rect,
+    title_y: f32,
+    stats_y: f32,
+    message_y: f32,
+    button_rects: Vec<mq::Rect>,
+}
+
+impl ResultsScreen {
+    /// `title_color` is for how it went, e.g. green for a win.
+    pub fn new(title: impl Into<String>, title_color: mq::Color) -> Self {
+        Self {
+            title: title.into(),
+            title_color,
+            stats: Vec::new(),
+            message: String::new(),
+            buttons: Vec::new(),
+        }
+    }
+
+    /// A row with the label on the left and the value on the right.
+    pub fn stat(mut self, label: impl Into<String>, value: impl Into<String>) -> Self {
+        self.stats.push((label.into(), value.into()));
+        self
+    }
+
+    /// Text under the stats, for anything that isn't a stat. Can have multiple lines. Each call
+    /// adds a paragraph, and empty ones are skipped.
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        let message = message.into();
+        if !message.is_empty() {
+            if !self.message.is_empty() {
+                self.message.push_str("\n\n");
+            }
+            self.message.push_str(&message);
+        }
+        self
+    }
+
+    /// e.g. `button(mq::KeyCode::R, "Restart")`, shown as "[r] Restart".
+    pub fn button(mut self, key: mq::KeyCode, label: impl Into<String>) -> Self {
+        self.buttons.push(ResultsButton {
+            key,
+            label: label.into(),
+        });
+        self
+    }
+
+    /// The key of the button clicked this frame, if any.
+    pub fn clicked_key(&self) -> Option<mq::KeyCode> {
+        if !mq::is_mouse_button_pressed(mq::MouseButton::Left) {
+            return None;
+        }
+        let mouse_position = mq::Vec2::from(mq::mouse_position());
+        self.layout()
+            .button_rects
+            .iter()
+            .zip(&self.buttons)
+            .find(|(rect, _)| rect.contains(mouse_position))
+            .map(|(_, button)| button.key)
+    }
+
+    /// Draws it centered in the window, with the default camera. Returns its rect, for placing
+    /// anything else around it.
+    pub fn draw(&self) -> mq::Rect {
+        let layout = self.layout();
+        let content_x = layout.rect.x + PADDING;
+        let content_right = layout.rect.right() - PADDING;
+        mq::draw_rectangle(
+            layout.rect.x,
+            layout.rect.y,
+            layout.rect.w,
+            layout.rect.h,
+            BACKGROUND_COLOR,
+        );
+
+        bq_text::draw_text(
+            &self.title,
+            TextAlignment::Center,
+            None,
+            TITLE_FONT_SIZE,
+            self.title_color,
+            TextAnchorPoint::TopCenter {
+                x: layout.rect.center().x,
+                y: layout.title_y,
+            },
+            None,
+        );
+
+        for (i, (label, value)) in self.stats.iter().enumerate() {
+            let y = layout.stats_y + BODY_FONT_SIZE as f32 * i as f32;
+            draw_line(label, BODY_FONT_SIZE, TEXT_COLOR, content_x, y);
+            bq_text::draw_text(
+                value,
+                TextAlignment::Right,
+                None,
+                BODY_FONT_SIZE,
+                TEXT_COLOR,
+                TextAnchorPoint::TopRight {
+                    x: content_right,
+                    y,
+                },
+                None,
+            );
+        }
+
+        if !self.message.is_empty() {
+            bq_text::draw_text(
+                &self.message,
+                TextAlignment::Left,
+                None,
+                BODY_FONT_SIZE,
+                TEXT_COLOR,
+                TextAnchorPoint::TopLeft {
+                    x: content_x,
+                    y: layout.message_y,
+                },
+                None,
+            );
+        }
+
+        let mouse_position = mq::Vec2::from(mq::mouse_position());
+        for (rect, button) in layout.button_rects.iter().zip(&self.buttons) {
+            let color = if rect.contains(mouse_position) {
+                BUTTON_HOVER_COLOR
+            } else {
+                BUTTON_COLOR
+            };
+            mq::draw_rectangle(rect.x, rect.y, rect.w, rect.h, color);
+            draw_line(
+                &button.text(),
+                BUTTON_FONT_SIZE,
+                BUTTON_TEXT_COLOR,
+                rect.x + BUTTON_PADDING,
+                rect.y + BUTTON_PADDING,
+            );
+        }
+
+        layout.rect
+    }
+
+    fn layout(&self) -> ResultsLayout {
+        let title_width = measure_width(&self.title, TITLE_FONT_SIZE);
+        let max_label_width = max_width(self.stats.iter().map(|(label, _)| label), BODY_FONT_SIZE);
+        let max_value_width = max_width(self.stats.iter().map(|(_, value)| value), BODY_FONT_SIZE);
+        let stats_width = if self.stats.is_empty() {
+            0.0
+        } else {
+            max_label_width + STAT_COLUMN_SPACING + max_value_width
+        };
+        let message_width = max_width(self.message.lines(), BODY_FONT_SIZE);
+        let button_widths = self
+            .buttons
+            .iter()
+            .map(|button| measure_width(&button.text(), BUTTON_FONT_SIZE) + BUTTON_PADDING * 2.0)
+            .collect::<Vec<_>>();
+        let widest_button = button_widths.iter().copied().fold(0.0, f32::max);
+
+        // Buttons wrap to the width of everything else, or the window if that's narrower
+        let area = bq_safe_area::safe_area();
+        let max_content_width = area.w - (WINDOW_MARGIN + PADDING) * 2.0;
+        let content_width = title_width
+            .max(stats_width)
+            .max(message_width)
+            .min(max_content_width)
+            .max(widest_button);
+        let button_positions = flow_buttons(&button_widths, content_width, BUTTON_SPACING);
+        let content_width = button_positions
+            .iter()
+            .zip(&button_widths)
+            .map(|((x, _), width)| x + width)
+            .fold(content_width, f32::max);
+        let button_height = BUTTON_FONT_SIZE as f32 + BUTTON_PADDING * 2.0;
+        let num_button_rows = button_positions.last().map_or(0, |(_, row)| row + 1);
+
+        let title_y = PADDING;
+        let mut y = title_y + TITLE_FONT_SIZE as f32;
+        let stats_y = y + SECTION_SPACING;
+        if !self.stats.is_empty() {
+            y = stats_y + BODY_FONT_SIZE as f32 * self.stats.len() as f32;
+        }
+        let message_y = y + SECTION_SPACING;
+        if !self.message.is_empty() {
+            y = message_y + BODY_FONT_SIZE as f32 * self.message.lines().count() as f32;
+        }
+        let buttons_y = y + SECTION_SPACING;
+        if num_button_rows > 0 {
+            y = buttons_y + (button_height + BUTTON_SPACING) * num_button_rows as f32
+                - BUTTON_SPACING;
+        }
+
+        let width = content_width + PADDING * 2.0;
+        let height = y + PADDING;
+        let x = area.x + (area.w - width) / 2.0;
+        let top = area.y + (area.h - height) / 2.0;
+        ResultsLayout {
+            rect: mq::Rect::new(x, top, width, height),
+            title_y: top + title_y,
+            stats_y: top + stats_y,
+            message_y: top + message_y,
+            button_rects: button_positions
+                .into_iter()
+                .zip(button_widths)
+                .map(|((button_x, row), button_width)| {
+                    mq::Rect::new(
+                        x + PADDING + button_x,
+                        top + buttons_y + (button_height + BUTTON_SPACING) * row as f32,
+                        button_width,
+                        button_height,
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl ResultsButton {
+    fn text(&self) -> String {
+        format!("[{}] {}", self.key.to_lowercase(), self.label)
+    }
+}
+
+fn measure_width(text: &str, font_size: u16) -> f32 {
+    mq::measure_text(text, None, font_size, 1.0).width
+}
+
+fn max_width(lines: impl Iterator<Item = impl AsRef<str>>, font_size: u16) -> f32 {
+    lines
+        .map(|line| measure_width(line.as_ref(), font_size))
+        .fold(0.0, f32::max)
+}
+
+fn draw_line(text: &str, font_size: u16, color: mq::Color, x: f32, y: f32) {
+    bq_text::draw_text(
+        text,
+        TextAlignment::Left,
+        None,
+        font_size,
+        color,
+        TextAnchorPoint::TopLeft { x, y },
+        None,
+    );
+}
+
+/// Lays buttons out left to right, wrapping to a new row when the next one wouldn't fit in
+/// `max_width`. Returns each button's x and row. A button wider than `max_width` gets a row of its
+/// own.
+fn flow_buttons(widths: &[f32], max_width: f32, spacing: f32) -> Vec<(f32, usize)> {
+    let mut positions = Vec::with_capacity(widths.len());
+    let mut x = 0.0;
+    let mut row = 0;
+    for &width in widths {
+        if x > 0.0 && x + width > max_width {
+            x = 0.0;
+            row += 1;
+        }
+        positions.push((x, row));
+        x += width + spacing;
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::flow_buttons;
+
+    #[test]
+    fn test_flow_buttons() {
+        assert_eq!(Vec::<(f32, usize)>::new(), flow_buttons(&[], 100.0, 10.0));
+        assert_eq!(
+            vec![(0.0, 0), (40.0, 0), (0.0, 1), (0.0, 2), (0.0, 3)],
+            flow_buttons(&[30.0, 50.0, 60.0, 200.0, 10.0], 100.0, 10.0)
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Controller feedback hooks: rumble pulses and LED colors, for games to fire on events like a win
+//! or a clock running low, so couch players feel them without watching the screen.
+//!
+//! Hooks only: macroquad doesn't talk to controllers and there's no controller backend here, so
+//! they're no-ops until an app built with a controller library installs a [`RumbleBackend`] for
+//! it. Setting the `BQ_RUMBLE_LOG` env var (to anything but `0`) installs a backend that logs them
+//! instead, to check when they'd fire.
+use crate::bq_backend::BackendSlot;
+use crate::mq;
+use std::env;
+use std::time::Duration;
+
+const RUMBLE_LOG_ENV_VAR: &str = "BQ_RUMBLE_LOG";
+/// Longer pulses are cut short, so a mistake can't leave a controller buzzing.
+pub const MAX_RUMBLE_DURATION: Duration = Duration::from_secs(2);
+
+static RUMBLE: BackendSlot<dyn RumbleBackend> = BackendSlot::new(());
+
+/// One pulse of rumble.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Rumble {
+    /// From 0 (off) to 1 (as strong as the controller goes).
+    pub strength: f32,
+    pub duration: Duration,
+}
+
+impl Rumble {
+    /// A quick nudge, e.g. for a warning.
+    pub const TAP: Self = Self::new_unchecked(0.35, Duration::from_millis(120));
+    /// For something happening, e.g. running out of time.
+    pub const PULSE: Self = Self::new_unchecked(0.7, Duration::from_millis(350));
+    /// For the big moments, e.g. winning.
+    pub const CELEBRATE: Self = Self::new_unchecked(1.0, Duration::from_millis(800));
+
+    /// Clamped to [0, 1] and [`MAX_RUMBLE_DURATION`].
+    pub fn new(strength: f32, duration: Duration) -> Self {
+        Self {
+            strength: if strength.is_nan() {
+                0.0
+            } else {
+                strength.clamp(0.0, 1.0)
+            },
+            duration: duration.min(MAX_RUMBLE_DURATION),
+        }
+    }
+
+    const fn new_unchecked(strength: f32, duration: Duration) -> Self {
+        Self { strength, duration }
+    }
+}
+
+/// Whatever drives the controllers.
+pub trait RumbleBackend: Send {
+    /// Every connected controller, since couch games don't know whose it is.
+    fn rumble(&mut self, rumble: Rumble);
+
+    /// For controllers with a light bar. None turns it back to the controller's default.
+    fn set_led(&mut self, _color: Option<mq::Color>) {}
+}
+
+/// Replaces the installed backend, if any.
+pub fn set_rumble_backend(backend: impl RumbleBackend + 'static) {
+    RUMBLE.lock().backend = Some(Box::new(backend));
+}
+
+/// Whether there's anything to rumble, e.g. to hide a rumble setting without one.
+pub fn is_rumble_available() -> bool {
+    RUMBLE.is_installed()
+}
+
+/// A no-op without a backend.
+pub fn rumble(rumble: Rumble) {
+    if let Some(backend) = RUMBLE.lock().backend.as_mut() {
+        backend.rumble(rumble);
+    }
+}
+
+/// A no-op without a backend, or for controllers without a light.
+pub fn set_controller_led(color: Option<mq::Color>) {
+    if let Some(backend) = RUMBLE.lock().backend.as_mut() {
+        backend.set_led(color);
+    }
+}
+
+/// Logs instead of rumbling (see `BQ_RUMBLE_LOG`).
+struct LogRumbleBackend;
+
+impl RumbleBackend for LogRumbleBackend {
+    fn rumble(&mut self, rumble: Rumble) {
+        mq::info!(
+            "Rumble: {:.0}% for {} ms",
+            rumble.strength * 100.0,
+            rumble.duration.as_millis()
+        );
+    }
+
+    fn set_led(&mut self, color: Option<mq::Color>) {
+        match color {
+            Some(color) => {
+                let rgba: [u8; 4] = color.into();
+                mq::info!("Controller LED: {rgba:?}");
+            }
+            None => mq::info!("Controller LED: default"),
+        }
+    }
+}
+
+/// Called by `initialize_engine()`.
+pub(crate) fn init_from_env() {
+    if env::var_os(RUMBLE_LOG_ENV_VAR).is_some_and(|value| value != "0") {
+        set_rumble_backend(LogRumbleBackend);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Rumble, MAX_RUMBLE_DURATION};
+    use std::time::Duration;
+
+    #[test]
+    fn test_rumble_clamped() {
+        let rumble = Rumble::new(1.5, Duration::from_secs(10));
+        assert_eq!(1.0, rumble.strength);
+        assert_eq!(MAX_RUMBLE_DURATION, rumble.duration);
+        assert_eq!(0.0, Rumble::new(-1.0, Duration::ZERO).strength);
+        assert_eq!(0.0, Rumble::new(f32::NAN, Duration::ZERO).strength);
+        assert_eq!(Rumble::PULSE, Rumble::new(0.7, Duration::from_millis(350)));
+    }
+}
@@ -0,0 +1,134 @@
+//! Safe area: how much of the window's edges to keep clear, for TVs whose overscan crops them (e.g.
+//! a laptop mirrored to a TV over HDMI). The framework's own drawing (window anchors, toasts,
+//! results screens, tutorials) stays inside it, and apps can lay out in [`safe_area`] rather than
+//! the whole window.
+//!
+//! Starts from the `BQ_SAFE_AREA_PERCENT` env var, e.g. `5` for 5% off every edge, or `5,3` for 5%
+//! off the sides and 3% off the top and bottom. Apps can change it from there.
+use crate::mq;
+use std::env;
+use std::sync::Mutex;
+
+const SAFE_AREA_ENV_VAR: &str = "BQ_SAFE_AREA_PERCENT";
+/// Per edge, so at most 40% of the window is given up each way.
+pub const MAX_SAFE_AREA_MARGIN: f32 = 0.2;
+
+static SAFE_AREA_MARGINS: Mutex<SafeAreaMargins> = Mutex::new(SafeAreaMargins::NONE);
+
+/// Fractions of the window's width and height to keep clear, on each edge.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct SafeAreaMargins {
+    /// Left and right
+    pub horizontal: f32,
+    /// Top and bottom
+    pub vertical: f32,
+}
+
+impl SafeAreaMargins {
+    pub const NONE: Self = Self::uniform(0.0);
+
+    pub const fn uniform(margin: f32) -> Self {
+        Self {
+            horizontal: margin,
+            vertical: margin,
+        }
+    }
+
+    fn clamped(self) -> Self {
+        Self {
+            horizontal: self.horizontal.clamp(0.0, MAX_SAFE_AREA_MARGIN),
+            vertical: self.vertical.clamp(0.0, MAX_SAFE_AREA_MARGIN),
+        }
+    }
+}
+
+pub fn safe_area_margins() -> SafeAreaMargins {
+    *SAFE_AREA_MARGINS.lock().expect("safe area lock poisoned")
+}
+
+/// Clamped to [`MAX_SAFE_AREA_MARGIN`].
+pub fn set_safe_area_margins(margins: SafeAreaMargins) {
+    *SAFE_AREA_MARGINS.lock().expect("safe area lock poisoned") = margins.clamped();
+}
+
+/// The part of the window inside the margins, with the default camera.
+pub fn safe_area() -> mq::Rect {
+    inset_window(mq::screen_width(), mq::screen_height(), safe_area_margins())
+}
+
+fn inset_window(width: f32, height: f32, margins: SafeAreaMargins) -> mq::Rect {
+    let x = width * margins.horizontal;
+    let y = height * margins.vertical;
+    mq::Rect::new(x, y, width - x * 2.0, height - y * 2.0)
+}
+
+/// Percents, e.g. "5" or "5,3". None if they're not numbers.
+fn parse_margins(text: &str) -> Option<SafeAreaMargins> {
+    let parse_percent = |percent: &str| {
+        percent
+            .trim()
+            .parse::<f32>()
+            .ok()
+            .filter(|percent| percent.is_finite())
+            .map(|percent| percent / 100.0)
+    };
+    let margins = match text.split_once(',') {
+        None => SafeAreaMargins::uniform(parse_percent(text)?),
+        Some((horizontal, vertical)) => SafeAreaMargins {
+            horizontal: parse_percent(horizontal)?,
+            vertical: parse_percent(vertical)?,
+        },
+    };
+    Some(margins.clamped())
+}
+
+/// Called by `initialize_engine()`.
+pub(crate) fn init_from_env() {
+    let margins = env::var(SAFE_AREA_ENV_VAR)
+        .ok()
+        .and_then(|text| parse_margins(&text))
+        .unwrap_or(SafeAreaMargins::NONE);
+    set_safe_area_margins(margins);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{inset_window, parse_margins, SafeAreaMargins};
+    use crate::mq;
+
+    #[test]
+    fn test_parse_margins() {
+        assert_eq!(Some(SafeAreaMargins::uniform(0.05)), parse_margins("5"));
+        assert_eq!(
+            Some(SafeAreaMargins {
+                horizontal: 0.05,
+                vertical: 0.03,
+            }),
+            parse_margins(" 5, 3 ")
+        );
+        // Too much is capped, and too little is none
+        assert_eq!(Some(SafeAreaMargins::uniform(0.2)), parse_margins("50"));
+        assert_eq!(Some(SafeAreaMargins::NONE), parse_margins("-5"));
+        assert_eq!(None, parse_margins("lots"));
+        assert_eq!(None, parse_margins("5,"));
+    }
+
+    #[test]
+    fn test_inset_window() {
+        assert_eq!(
+            mq::Rect::new(0.0, 0.0, 800.0, 600.0),
+            inset_window(800.0, 600.0, SafeAreaMargins::NONE)
+        );
+        assert_eq!(
+            mq::Rect::new(40.0, 18.0, 720.0, 564.0),
+            inset_window(
+                800.0,
+                600.0,
+                SafeAreaMargins {
+                    horizontal: 0.05,
+                    vertical: 0.03,
+                }
+            )
+        );
+    }
+}
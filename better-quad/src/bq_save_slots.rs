@@ -0,0 +1,455 @@
+//! Named saves to pick between, e.g. a campaign per person on a shared computer, or a few game
+//! sessions on the go at once. Each slot is a file in the app's storage, `<kind>-slot-<id>.json`,
+//! with its name and when it was saved next to the data, so slots can be listed without knowing
+//! what's in them.
+//!
+//! [`SaveSlotBrowser`] lists an app's slots of one kind to load, rename, or delete, and looks the
+//! same in every app.
+use crate::bq::{
+    BetterKeyCode, TextAlignment, TextAnchorPoint, TextContainer, TextInput, TextInputEvent,
+    Timestamp,
+};
+use crate::bq_storage::{AppStorage, StorageError};
+use crate::{bq_text, bq_text_fit, mq};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io;
+
+const MAX_SLOT_NAME_LEN: usize = 40;
+
+const KEY_SELECT_PREVIOUS: mq::KeyCode = mq::KeyCode::Up;
+const KEY_SELECT_NEXT: mq::KeyCode = mq::KeyCode::Down;
+const KEY_LOAD: mq::KeyCode = mq::KeyCode::Enter;
+const KEY_RENAME: mq::KeyCode = mq::KeyCode::R;
+const KEY_DELETE: mq::KeyCode = mq::KeyCode::Delete;
+const KEY_CLOSE: mq::KeyCode = mq::KeyCode::Escape;
+
+const BROWSER_FONT_SIZE: u16 = 28;
+const BROWSER_X: f32 = 20.0;
+const BROWSER_Y: f32 = 20.0;
+const ROW_HEIGHT: f32 = 40.0;
+/// Only this many rows are drawn, scrolling to keep the selected one in view.
+const MAX_ROWS_SHOWN: usize = 10;
+const SAVED_AT_COLUMN_X: f32 = 420.0;
+const SELECTED_OUTLINE_THICKNESS: f32 = 3.0;
+const BACKGROUND_COLOR: mq::Color = mq::Color::new(0.2, 0.2, 0.25, 0.97);
+const CONFIRM_COLOR: mq::Color = mq::ORANGE;
+const ERROR_COLOR: mq::Color = mq::Color::new(1.0, 0.45, 0.45, 1.0);
+
+/// An app's slots of one kind. Cheap to construct, so make it a const.
+#[derive(Copy, Clone, Debug)]
+pub struct SaveSlots {
+    storage: AppStorage,
+    /// What's saved in them, e.g. "campaign". Part of the file names, so it shouldn't change.
+    kind: &'static str,
+}
+
+/// A slot as listed, without its data.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SlotInfo {
+    pub id: u32,
+    pub name: String,
+    pub saved_at: Timestamp,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SlotFile<T> {
+    name: String,
+    saved_at_secs: f64,
+    data: T,
+}
+
+/// Everything but the data, which is skipped when listing.
+#[derive(Deserialize)]
+struct SlotHeader {
+    name: String,
+    saved_at_secs: f64,
+}
+
+impl SaveSlots {
+    pub const fn new(storage: AppStorage, kind: &'static str) -> Self {
+        Self { storage, kind }
+    }
+
+    /// Most recently saved first. Files that can't be read are logged and left out.
+    pub fn list(&self) -> Result<Vec<SlotInfo>, StorageError> {
+        let mut slots = Vec::new();
+        for name in self.storage.names()? {
+            let Some(id) = parse_slot_id(self.kind, &name) else {
+                continue;
+            };
+            match self.storage.load::<SlotHeader>(&name) {
+                Ok(Some(header)) => slots.push(SlotInfo {
+                    id,
+                    name: header.name,
+                    saved_at: Timestamp::from_sec_f64(header.saved_at_secs),
+                }),
+                Ok(None) => {}
+                Err(e) => mq::warn!("Skipping unreadable save slot {name}: {e}"),
+            }
+        }
+        slots.sort_by(|a, b| b.saved_at.as_sec_f64().total_cmp(&a.saved_at.as_sec_f64()));
+        Ok(slots)
+    }
+
+    /// `Ok(None)` if there's no such slot, e.g. it's been deleted.
+    pub fn load<T: DeserializeOwned>(&self, id: u32) -> Result<Option<T>, StorageError> {
+        let file = self
+            .storage
+            .load::<SlotFile<T>>(&slot_file_name(self.kind, id))?;
+        Ok(file.map(|file| file.data))
+    }
+
+    /// In a new slot, returning its id.
+    pub fn save_new<T: Serialize>(
+        &self,
+        name: &str,
+        data: &T,
+        now: Timestamp,
+    ) -> Result<u32, StorageError> {
+        let id = self
+            .list()?
+            .iter()
+            .map(|slot| slot.id + 1)
+            .max()
+            .unwrap_or(0);
+        self.write(id, name, data, now)?;
+        Ok(id)
+    }
+
+    /// Over what's in slot `id`, keeping its name, which is returned. `Ok(None)` if there's no such
+    /// slot any more, so it's saved nowhere and the app can make a new one.
+    pub fn overwrite<T: Serialize>(
+        &self,
+        id: u32,
+        data: &T,
+        now: Timestamp,
+    ) -> Result<Option<String>, StorageError> {
+        let file_name = slot_file_name(self.kind, id);
+        let Some(header) = self.storage.load::<SlotHeader>(&file_name)? else {
+            return Ok(None);
+        };
+        self.write(id, &header.name, data, now)?;
+        Ok(Some(header.name))
+    }
+
+    /// Keeps when it was saved, so it doesn't jump to the top of the list.
+    pub fn rename(&self, id: u32, name: &str) -> Result<(), StorageError> {
+        let file_name = slot_file_name(self.kind, id);
+        let Some(mut file) = self
+            .storage
+            .load::<SlotFile<serde_json::Value>>(&file_name)?
+        else {
+            return Err(StorageError::Io(io::ErrorKind::NotFound.into()));
+        };
+        file.name = name.to_string();
+        self.storage.save(&file_name, &file)
+    }
+
+    pub fn delete(&self, id: u32) -> Result<(), StorageError> {
+        self.storage.remove(&slot_file_name(self.kind, id))
+    }
+
+    fn write<T: Serialize>(
+        &self,
+        id: u32,
+        name: &str,
+        data: &T,
+        now: Timestamp,
+    ) -> Result<(), StorageError> {
+        let file = SlotFile {
+            name: name.to_string(),
+            saved_at_secs: now.as_sec_f64(),
+            data,
+        };
+        self.storage.save(&slot_file_name(self.kind, id), &file)
+    }
+}
+
+fn slot_file_name(kind: &str, id: u32) -> String {
+    format!("{kind}-slot-{id}")
+}
+
+/// The opposite of [`slot_file_name`]. None for other files, including other kinds' slots.
+fn parse_slot_id(kind: &str, file_name: &str) -> Option<u32> {
+    file_name
+        .strip_prefix(kind)?
+        .strip_prefix("-slot-")?
+        .parse()
+        .ok()
+}
+
+/// e.g. "2025-01-17 18:05 UTC". There's no local timezone on every platform.
+pub fn format_saved_at(saved_at: Timestamp) -> String {
+    let minute_of_day = (saved_at.as_sec_f64().rem_euclid(86_400.0) / 60.0) as u32;
+    format!(
+        "{} {:02}:{:02} UTC",
+        saved_at.utc_date(),
+        minute_of_day / 60,
+        minute_of_day % 60
+    )
+}
+
+/// Lists [`SaveSlots`] over the whole window, to pick one to load. Renaming and deleting happen in
+/// here, so apps only handle loading.
+pub struct SaveSlotBrowser {
+    slots: SaveSlots,
+    title: String,
+    entries: Vec<SlotInfo>,
+    selected: usize,
+    /// Some while the selected slot's being renamed.
+    renaming: Option<TextInput>,
+    /// Whether delete's been pressed once on the selected slot. Pressing it again deletes it.
+    confirming_delete: bool,
+    /// The last thing that went wrong, until the next key press.
+    error: Option<String>,
+}
+
+pub enum SlotBrowserAction {
+    Load(SlotInfo),
+    Close,
+}
+
+impl SaveSlotBrowser {
+    /// e.g. "Saved campaigns" for `title`.
+    pub fn new(slots: SaveSlots, title: impl Into<String>) -> Self {
+        let mut browser = Self {
+            slots,
+            title: title.into(),
+            entries: Vec::new(),
+            selected: 0,
+            renaming: None,
+            confirming_delete: false,
+            error: None,
+        };
+        browser.refresh();
+        browser
+    }
+
+    fn refresh(&mut self) {
+        match self.slots.list() {
+            Ok(entries) => self.entries = entries,
+            Err(e) => {
+                self.entries.clear();
+                self.error = Some(format!("Couldn't list saves: {e}"));
+            }
+        }
+        self.selected = self.selected.min(self.entries.len().saturating_sub(1));
+    }
+
+    /// Call every frame while it's open, instead of the rest of the app's input.
+    pub fn update(&mut self) -> Option<SlotBrowserAction> {
+        if let Some(renaming) = &mut self.renaming {
+            match renaming.update()? {
+                TextInputEvent::Submit(name) if !name.trim().is_empty() => {
+                    let id = self.entries[self.selected].id;
+                    if let Err(e) = self.slots.rename(id, name.trim()) {
+                        self.error = Some(format!("Couldn't rename it: {e}"));
+                    }
+                    self.refresh();
+                }
+                TextInputEvent::Submit(_) | TextInputEvent::Cancel => {}
+            }
+            self.renaming = None;
+            return None;
+        }
+
+        if !mq::get_keys_pressed().is_empty() {
+            self.error = None;
+        }
+        if mq::is_key_pressed(KEY_CLOSE) {
+            return Some(SlotBrowserAction::Close);
+        }
+        let confirming_delete = std::mem::take(&mut self.confirming_delete);
+        if self.entries.is_empty() {
+            return None;
+        }
+        if mq::is_key_pressed(KEY_SELECT_PREVIOUS) {
+            self.selected = self.selected.saturating_sub(1);
+        } else if mq::is_key_pressed(KEY_SELECT_NEXT) {
+            self.selected = (self.selected + 1).min(self.entries.len() - 1);
+        } else if mq::is_key_pressed(KEY_LOAD) {
+            return Some(SlotBrowserAction::Load(self.entries[self.selected].clone()));
+        } else if mq::is_key_pressed(KEY_RENAME) {
+            let name = &self.entries[self.selected].name;
+            self.renaming = Some(TextInput::new(MAX_SLOT_NAME_LEN, |_| true).with_text(name));
+        } else if mq::is_key_pressed(KEY_DELETE) {
+            if confirming_delete {
+                if let Err(e) = self.slots.delete(self.entries[self.selected].id) {
+                    self.error = Some(format!("Couldn't delete it: {e}"));
+                }
+                self.refresh();
+            } else {
+                self.confirming_delete = true;
+            }
+        } else if mq::is_mouse_button_pressed(mq::MouseButton::Left) {
+            // Clicking a row loads it
+            let mouse_position = mq::Vec2::from(mq::mouse_position());
+            let clicked = self
+                .shown_rows()
+                .find(|(row, _)| row_rect(*row).contains(mouse_position));
+            if let Some((_, index)) = clicked {
+                return Some(SlotBrowserAction::Load(self.entries[index].clone()));
+            }
+        } else {
+            // Anything else, e.g. the mouse moving, keeps waiting for the second press
+            self.confirming_delete = confirming_delete;
+        }
+        None
+    }
+
+    /// (row on screen, index into the entries), scrolled so the selected one's shown.
+    fn shown_rows(&self) -> impl Iterator<Item = (usize, usize)> {
+        let first_shown = (self.selected + 1).saturating_sub(MAX_ROWS_SHOWN);
+        let last_shown = (first_shown + MAX_ROWS_SHOWN).min(self.entries.len());
+        (first_shown..last_shown).enumerate()
+    }
+
+    /// Covers the whole window, with the default camera.
+    pub fn draw(&self) {
+        mq::draw_rectangle(
+            0.0,
+            0.0,
+            mq::screen_width(),
+            mq::screen_height(),
+            BACKGROUND_COLOR,
+        );
+        draw_browser_text(&self.title, mq::WHITE, BROWSER_X, BROWSER_Y);
+
+        if self.entries.is_empty() && self.error.is_none() {
+            draw_browser_text("Nothing saved yet", mq::LIGHTGRAY, BROWSER_X, row_y(0));
+        }
+        for (row, index) in self.shown_rows() {
+            let slot = &self.entries[index];
+            let rect = row_rect(row);
+            let text_y = rect.y + (ROW_HEIGHT - BROWSER_FONT_SIZE as f32) / 2.0;
+            if index == self.selected {
+                let outline_color = if self.confirming_delete {
+                    CONFIRM_COLOR
+                } else {
+                    mq::WHITE
+                };
+                mq::draw_rectangle_lines(
+                    rect.x,
+                    rect.y,
+                    rect.w,
+                    rect.h,
+                    SELECTED_OUTLINE_THICKNESS,
+                    outline_color,
+                );
+                if let Some(renaming) = &self.renaming {
+                    renaming.draw(
+                        "Name: ",
+                        BROWSER_FONT_SIZE,
+                        TextAnchorPoint::TopLeft {
+                            x: rect.x + BROWSER_X / 2.0,
+                            y: text_y,
+                        },
+                    );
+                    continue;
+                }
+            }
+            let name = bq_text_fit::truncate_with_ellipsis(
+                &slot.name,
+                None,
+                BROWSER_FONT_SIZE,
+                SAVED_AT_COLUMN_X - BROWSER_X,
+            );
+            draw_browser_text(&name, mq::WHITE, rect.x + BROWSER_X / 2.0, text_y);
+            draw_browser_text(
+                &format_saved_at(slot.saved_at),
+                mq::LIGHTGRAY,
+                rect.x + SAVED_AT_COLUMN_X,
+                text_y,
+            );
+        }
+
+        let help_text = if self.renaming.is_some() {
+            format!(
+                "[{}] done, [{}] cancel",
+                KEY_LOAD.to_lowercase(),
+                KEY_CLOSE.to_lowercase()
+            )
+        } else if self.confirming_delete {
+            format!(
+                "Press [{}] again to delete it for good",
+                KEY_DELETE.to_lowercase()
+            )
+        } else if self.entries.is_empty() {
+            format!("[{}] back", KEY_CLOSE.to_lowercase())
+        } else {
+            format!(
+                "[{}] / [{}] select\n[{}] or click to load\n[{}] rename\n[{}] delete\n[{}] back",
+                KEY_SELECT_PREVIOUS.to_lowercase(),
+                KEY_SELECT_NEXT.to_lowercase(),
+                KEY_LOAD.to_lowercase(),
+                KEY_RENAME.to_lowercase(),
+                KEY_DELETE.to_lowercase(),
+                KEY_CLOSE.to_lowercase(),
+            )
+        };
+        let num_rows = self.entries.len().clamp(1, MAX_ROWS_SHOWN);
+        let below_rows = row_y(num_rows) + ROW_HEIGHT / 2.0;
+        let help_color = if self.confirming_delete {
+            CONFIRM_COLOR
+        } else {
+            mq::WHITE
+        };
+        let help_rect = draw_browser_text(&help_text, help_color, BROWSER_X, below_rows);
+        if let Some(error) = &self.error {
+            draw_browser_text(
+                error,
+                ERROR_COLOR,
+                BROWSER_X,
+                help_rect.rect_y + help_rect.rect_height + ROW_HEIGHT / 2.0,
+            );
+        }
+    }
+}
+
+fn draw_browser_text(text: &str, color: mq::Color, x: f32, y: f32) -> TextContainer {
+    bq_text::draw_text(
+        text,
+        TextAlignment::Left,
+        None,
+        BROWSER_FONT_SIZE,
+        color,
+        TextAnchorPoint::TopLeft { x, y },
+        None,
+    )
+}
+
+fn row_y(row: usize) -> f32 {
+    BROWSER_Y + ROW_HEIGHT * (row as f32 + 1.0)
+}
+
+fn row_rect(row: usize) -> mq::Rect {
+    mq::Rect::new(
+        BROWSER_X,
+        row_y(row),
+        mq::screen_width() - BROWSER_X * 2.0,
+        ROW_HEIGHT,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_saved_at, parse_slot_id, slot_file_name};
+    use crate::bq::Timestamp;
+
+    #[test]
+    fn test_slot_file_names() {
+        assert_eq!("campaign-slot-3", slot_file_name("campaign", 3));
+        assert_eq!(Some(3), parse_slot_id("campaign", "campaign-slot-3"));
+        assert_eq!(None, parse_slot_id("session", "campaign-slot-3"));
+        assert_eq!(None, parse_slot_id("campaign", "campaign"));
+        assert_eq!(None, parse_slot_id("campaign", "campaign-slot-x"));
+    }
+
+    #[test]
+    fn test_format_saved_at() {
+        // 2025-01-17, 18:05:59
+        let saved_at = Timestamp::from_sec_f64(20_105.0 * 86_400.0 + 18.0 * 3600.0 + 359.0);
+        assert_eq!("2025-01-17 18:05 UTC", format_saved_at(saved_at));
+    }
+}
@@ -0,0 +1,306 @@
+//! Golden-image snapshot tests: draw an app's frame, and compare it against a PNG checked into the
+//! repo, to catch layout regressions that unit tests can't see.
+//!
+//! Snapshot tests need a real window, since miniquad can't make a GL context without one, so there's
+//! no offscreen rendering. They run as a test target with `harness = false` that calls
+//! [`run_snapshot_tests`] from `main`. On Linux CI without a display, run them under a virtual one
+//! (`xvfb-run cargo test`). Without any display, each one's reported as skipped rather than failed.
+//!
+//! Only `draw()` is called, never `update()`, so there's no input or time passing between frames
+//! (e.g. FPS counters stay at 0). Storage and config are redirected to an empty temp dir, so saved
+//! settings and stats don't leak into the frames.
+//!
+//! Set `BQ_UPDATE_SNAPSHOTS=1` to write the current frames as the new goldens. A snapshot with no
+//! golden fails, with its frame written as `<name>.actual.png` to look over. On a mismatch, the
+//! actual frame and a diff (mismatched pixels in red) are written next to the golden.
+use crate::stateful_gui::StatefulGui;
+use crate::{bq_rand, bq_storage, mq};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const UPDATE_SNAPSHOTS_ENV_VAR: &str = "BQ_UPDATE_SNAPSHOTS";
+const RAND_SEED: u64 = 0;
+/// Frames drawn before the captured one, for anything that settles after the first frame (e.g.
+/// the window reaching its requested size).
+const WARM_UP_FRAMES: usize = 2;
+
+/// How different a frame can be from its golden and still pass. Rasterization varies a little
+/// between GPUs and drivers, mostly along anti-aliased edges.
+#[derive(Copy, Clone, Debug)]
+pub struct SnapshotTolerance {
+    /// Pixels with every RGB channel within this of the golden's count as matching.
+    pub max_channel_diff: u8,
+    /// Fraction of pixels (0 to 1) that are allowed to not match.
+    pub max_mismatched_fraction: f32,
+}
+
+impl Default for SnapshotTolerance {
+    fn default() -> Self {
+        Self {
+            max_channel_diff: 16,
+            max_mismatched_fraction: 0.002,
+        }
+    }
+}
+
+/// One frame to compare against `<snapshot dir>/<name>.png`.
+pub struct Snapshot {
+    name: String,
+    make_draw: Box<dyn FnOnce() -> Box<dyn Fn()>>,
+}
+
+impl Snapshot {
+    /// `make_gui` is called once the window is up, since most apps need it to construct.
+    pub fn new<T: StatefulGui + 'static>(
+        name: &str,
+        make_gui: impl FnOnce() -> T + 'static,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            make_draw: Box::new(|| {
+                let gui = make_gui();
+                Box::new(move || gui.draw())
+            }),
+        }
+    }
+}
+
+/// Opens a window per `conf`, draws each snapshot, and compares it to its golden in
+/// `snapshot_dir`. Exits the process with an error code if any don't match.
+pub fn run_snapshot_tests(
+    conf: mq::Conf,
+    snapshot_dir: impl Into<PathBuf>,
+    tolerance: SnapshotTolerance,
+    snapshots: Vec<Snapshot>,
+) {
+    if !has_display() {
+        for snapshot in &snapshots {
+            eprintln!(
+                "snapshot {} ... skipped: no display (on Linux CI, run under xvfb-run)",
+                snapshot.name
+            );
+        }
+        print_summary(0, 0, snapshots.len());
+        return;
+    }
+
+    let snapshot_dir = snapshot_dir.into();
+    let update = env::var_os(UPDATE_SNAPSHOTS_ENV_VAR).is_some_and(|value| value != "0");
+    bq_storage::override_root_dir(
+        env::temp_dir().join(format!("bq-snapshots-{}", std::process::id())),
+    );
+    bq_rand::set_rand_seed(RAND_SEED);
+
+    let conf = mq::Conf {
+        window_resizable: false,
+        high_dpi: false,
+        sample_count: 1,
+        ..conf
+    };
+    macroquad::Window::from_config(conf, async move {
+        let (mut num_passed, mut num_failed) = (0, 0);
+        for snapshot in snapshots {
+            let draw = (snapshot.make_draw)();
+            for _ in 0..WARM_UP_FRAMES {
+                draw();
+                mq::next_frame().await;
+            }
+            draw();
+            let frame = capture_screen();
+            mq::next_frame().await;
+
+            let result = if update {
+                save_png(&frame, &snapshot_dir.join(format!("{}.png", snapshot.name)))
+            } else {
+                check_snapshot(&snapshot_dir, &snapshot.name, &frame, tolerance)
+            };
+            match result {
+                Ok(()) => {
+                    eprintln!("snapshot {} ... ok", snapshot.name);
+                    num_passed += 1;
+                }
+                Err(e) => {
+                    eprintln!("snapshot {} ... FAILED: {e}", snapshot.name);
+                    num_failed += 1;
+                }
+            }
+        }
+        print_summary(num_passed, num_failed, 0);
+        // The event loop doesn't hand control back, so this is the only way to report failure.
+        std::process::exit(if num_failed == 0 { 0 } else { 1 });
+    });
+}
+
+/// Like the test harness's, so skips aren't missed in the output.
+fn print_summary(num_passed: usize, num_failed: usize, num_skipped: usize) {
+    let result = if num_failed == 0 { "ok" } else { "FAILED" };
+    eprintln!(
+        "snapshot result: {result}. {num_passed} passed; {num_failed} failed; {num_skipped} skipped"
+    );
+}
+
+fn has_display() -> bool {
+    if cfg!(target_os = "linux") {
+        env::var_os("DISPLAY").is_some() || env::var_os("WAYLAND_DISPLAY").is_some()
+    } else {
+        true
+    }
+}
+
+/// What's been drawn so far this frame, top row first.
+pub fn capture_screen() -> mq::Image {
+    // Screen data comes bottom row first
+    flipped_vertically(&mq::get_screen_data())
+}
+
+/// Compares `frame` to `<dir>/<name>.png`, writing the actual frame and a diff next to it if they
+/// don't match.
+fn check_snapshot(
+    dir: &Path,
+    name: &str,
+    frame: &mq::Image,
+    tolerance: SnapshotTolerance,
+) -> Result<(), String> {
+    let golden_path = dir.join(format!("{name}.png"));
+    let actual_path = dir.join(format!("{name}.actual.png"));
+    let golden = match fs::read(&golden_path) {
+        Ok(bytes) => mq::Image::from_file_with_format(&bytes, Some(mq::ImageFormat::Png))
+            .map_err(|e| format!("can't read {}: {e}", golden_path.display()))?,
+        Err(e) => {
+            save_png(frame, &actual_path)?;
+            return Err(format!(
+                "no golden at {} ({e}). Run with {UPDATE_SNAPSHOTS_ENV_VAR}=1 to create it",
+                golden_path.display()
+            ));
+        }
+    };
+
+    let diff = compare_images(frame, &golden, tolerance.max_channel_diff)?;
+    if diff.mismatched_fraction() <= tolerance.max_mismatched_fraction {
+        return Ok(());
+    }
+    save_png(frame, &actual_path)?;
+    save_png(&diff.image, &dir.join(format!("{name}.diff.png")))?;
+    Err(format!(
+        "{} of {} pixels differ, see {}",
+        diff.num_mismatched,
+        diff.num_pixels(),
+        actual_path.display()
+    ))
+}
+
+struct ImageDiff {
+    num_mismatched: usize,
+    /// Same size as the compared images, with mismatched pixels in red.
+    image: mq::Image,
+}
+
+impl ImageDiff {
+    fn num_pixels(&self) -> usize {
+        self.image.width as usize * self.image.height as usize
+    }
+
+    fn mismatched_fraction(&self) -> f32 {
+        self.num_mismatched as f32 / self.num_pixels().max(1) as f32
+    }
+}
+
+/// Alpha is ignored, since whether the window has any depends on the platform.
+fn compare_images(
+    actual: &mq::Image,
+    expected: &mq::Image,
+    max_channel_diff: u8,
+) -> Result<ImageDiff, String> {
+    if (actual.width, actual.height) != (expected.width, expected.height) {
+        return Err(format!(
+            "size is {}x{}, expected {}x{}",
+            actual.width, actual.height, expected.width, expected.height
+        ));
+    }
+
+    let mut diff = ImageDiff {
+        num_mismatched: 0,
+        image: mq::Image::gen_image_color(actual.width, actual.height, mq::BLACK),
+    };
+    let pixels = actual
+        .get_image_data()
+        .iter()
+        .zip(expected.get_image_data());
+    for (i, (actual_pixel, expected_pixel)) in pixels.enumerate() {
+        let matches = actual_pixel[..3]
+            .iter()
+            .zip(&expected_pixel[..3])
+            .all(|(a, e)| a.abs_diff(*e) <= max_channel_diff);
+        if !matches {
+            diff.num_mismatched += 1;
+            diff.image.get_image_data_mut()[i] = [255, 0, 0, 255];
+        }
+    }
+    Ok(diff)
+}
+
+fn flipped_vertically(image: &mq::Image) -> mq::Image {
+    let row_len = image.width as usize * 4;
+    mq::Image {
+        bytes: image
+            .bytes
+            .chunks_exact(row_len)
+            .rev()
+            .flatten()
+            .copied()
+            .collect(),
+        width: image.width,
+        height: image.height,
+    }
+}
+
+/// `image` is top row first.
+fn save_png(image: &mq::Image, path: &Path) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("can't create {}: {e}", dir.display()))?;
+    }
+    // `export_png` expects the bottom row first, like screen data
+    flipped_vertically(image).export_png(&path.to_string_lossy());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare_images, flipped_vertically};
+    use crate::mq;
+
+    #[test]
+    fn test_compare_images() {
+        let expected = mq::Image::gen_image_color(4, 2, mq::BLACK);
+        let mut actual = mq::Image::gen_image_color(4, 2, mq::BLACK);
+        actual.set_pixel(1, 0, mq::Color::from_rgba(10, 0, 0, 255));
+        actual.set_pixel(2, 1, mq::WHITE);
+        // Alpha doesn't count
+        actual.set_pixel(3, 1, mq::Color::from_rgba(0, 0, 0, 0));
+
+        let diff = compare_images(&actual, &expected, 10).unwrap();
+        assert_eq!(1, diff.num_mismatched);
+        assert_eq!(1.0 / 8.0, diff.mismatched_fraction());
+        assert_eq!(
+            mq::Color::new(1.0, 0.0, 0.0, 1.0),
+            diff.image.get_pixel(2, 1)
+        );
+        assert_eq!(mq::BLACK, diff.image.get_pixel(1, 0));
+
+        let diff = compare_images(&actual, &expected, 0).unwrap();
+        assert_eq!(2, diff.num_mismatched);
+
+        let smaller = mq::Image::gen_image_color(4, 1, mq::BLACK);
+        assert!(compare_images(&smaller, &expected, 10).is_err());
+    }
+
+    #[test]
+    fn test_flipped_vertically() {
+        let mut image = mq::Image::gen_image_color(2, 3, mq::BLACK);
+        image.set_pixel(1, 0, mq::WHITE);
+        let flipped = flipped_vertically(&image);
+        assert_eq!(mq::WHITE, flipped.get_pixel(1, 2));
+        assert_eq!(mq::BLACK, flipped.get_pixel(1, 0));
+    }
+}
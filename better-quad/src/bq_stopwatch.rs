@@ -0,0 +1,83 @@
+//! Game time, as opposed to wall time: a [`Stopwatch`] only counts while it's running, so pausing a
+//! game doesn't eat into its clock.
+use crate::bq_timestamp::Timestamp;
+use std::time::Duration;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Stopwatch {
+    /// From before the last pause.
+    accumulated: Duration,
+    /// None while paused.
+    running_since: Option<Timestamp>,
+}
+
+impl Stopwatch {
+    /// Running from zero.
+    pub fn started(now: Timestamp) -> Self {
+        Self::resumed_from(Duration::ZERO, now)
+    }
+
+    /// Running, with `elapsed` already on the clock, e.g. to pick a game back up.
+    pub fn resumed_from(elapsed: Duration, now: Timestamp) -> Self {
+        Self {
+            accumulated: elapsed,
+            running_since: Some(now),
+        }
+    }
+
+    pub fn elapsed(&self, now: Timestamp) -> Duration {
+        let running_for = self
+            .running_since
+            .and_then(|since| now.duration_since(since))
+            .unwrap_or_default();
+        self.accumulated + running_for
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.running_since.is_none()
+    }
+
+    /// Does nothing if already paused.
+    pub fn pause(&mut self, now: Timestamp) {
+        if !self.is_paused() {
+            self.accumulated = self.elapsed(now);
+            self.running_since = None;
+        }
+    }
+
+    /// Does nothing if already running.
+    pub fn resume(&mut self, now: Timestamp) {
+        if self.is_paused() {
+            self.running_since = Some(now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stopwatch;
+    use crate::bq_timestamp::Timestamp;
+    use std::time::Duration;
+
+    #[test]
+    fn test_stopwatch() {
+        let start = Timestamp::now();
+        let second = Duration::from_secs(1);
+        let mut stopwatch = Stopwatch::started(start);
+        assert_eq!(second * 3, stopwatch.elapsed(start + second * 3));
+
+        // Time while paused doesn't count
+        stopwatch.pause(start + second * 3);
+        assert!(stopwatch.is_paused());
+        assert_eq!(second * 3, stopwatch.elapsed(start + second * 60));
+        stopwatch.resume(start + second * 60);
+        assert_eq!(second * 5, stopwatch.elapsed(start + second * 62));
+
+        // Resuming while running doesn't restart the clock
+        stopwatch.resume(start + second * 62);
+        assert_eq!(second * 6, stopwatch.elapsed(start + second * 63));
+
+        let picked_up = Stopwatch::resumed_from(second * 10, start);
+        assert_eq!(second * 12, picked_up.elapsed(start + second * 2));
+    }
+}
@@ -0,0 +1,269 @@
+//! Persist small app data (stats, settings, etc) between runs.
+//!
+//! Each app gets its own folder in the platform data dir, and each saved value is a pretty-printed
+//! JSON file in that folder, e.g. `~/.local/share/gui-playground/mastermind/stats.json` on Linux.
+//!
+//! WASM has no filesystem, so everything returns [`StorageError::Unavailable`] there. Apps should
+//! treat storage as best-effort and keep running with defaults.
+//!
+//! Files that leave the app (e.g. to carry to another machine, which might have an older version of
+//! it) are better off versioned, with [`to_versioned_json`] and [`from_versioned_json`].
+use crate::mq;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::{fs, io};
+
+const ROOT_DIR_NAME: &str = "gui-playground";
+
+static ROOT_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Handle to an app's storage folder. Cheap to construct, so make it a const.
+#[derive(Copy, Clone, Debug)]
+pub struct AppStorage {
+    app_name: &'static str,
+}
+
+#[derive(Debug)]
+pub enum StorageError {
+    /// No data dir on this platform (e.g. WASM).
+    Unavailable,
+    Io(io::Error),
+    Serde(serde_json::Error),
+    /// A versioned file of some other kind.
+    WrongFormat {
+        expected: String,
+        found: String,
+    },
+    /// A versioned file from a newer version of the app than this one understands.
+    TooNew {
+        version: u32,
+        max_version: u32,
+    },
+}
+
+/// What versioned JSON looks like. Only the header's read first, so a file that's too new is caught
+/// before its data fails to parse.
+#[derive(Serialize, Deserialize)]
+struct VersionedFile<T> {
+    format: String,
+    version: u32,
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct VersionedHeader {
+    format: String,
+    version: u32,
+}
+
+/// `value` as pretty-printed JSON, labeled with what `format` it is and its `version`. Bump the
+/// version whenever older apps couldn't read it any more, e.g. a new field without a default.
+pub fn to_versioned_json<T: Serialize>(
+    format: &str,
+    version: u32,
+    value: &T,
+) -> Result<String, StorageError> {
+    serde_json::to_string_pretty(&VersionedFile {
+        format: format.to_string(),
+        version,
+        data: value,
+    })
+    .map_err(StorageError::Serde)
+}
+
+/// The opposite of [`to_versioned_json`]. Older versions are read as the current one, so new fields
+/// need defaults.
+pub fn from_versioned_json<T: DeserializeOwned>(
+    contents: &str,
+    format: &str,
+    max_version: u32,
+) -> Result<T, StorageError> {
+    let header: VersionedHeader = serde_json::from_str(contents).map_err(StorageError::Serde)?;
+    if header.format != format {
+        return Err(StorageError::WrongFormat {
+            expected: format.to_string(),
+            found: header.format,
+        });
+    }
+    if header.version > max_version {
+        return Err(StorageError::TooNew {
+            version: header.version,
+            max_version,
+        });
+    }
+    let file: VersionedFile<T> = serde_json::from_str(contents).map_err(StorageError::Serde)?;
+    Ok(file.data)
+}
+
+/// Keeps all apps' storage and config under `dir` instead of the platform dirs, for the rest of the
+/// process. For tests that should start from a blank slate rather than whatever the user has saved.
+/// Only the first call has an effect.
+pub fn override_root_dir(dir: PathBuf) {
+    let _ = ROOT_DIR_OVERRIDE.set(dir);
+}
+
+/// `gui-playground` in the platform dir, or `override_subdir` in the override if there is one.
+pub(crate) fn root_dir(
+    platform_dir: fn() -> Option<PathBuf>,
+    override_subdir: &str,
+) -> Option<PathBuf> {
+    match ROOT_DIR_OVERRIDE.get() {
+        Some(dir) => Some(dir.join(override_subdir)),
+        None => platform_dir().map(|dir| dir.join(ROOT_DIR_NAME)),
+    }
+}
+
+impl AppStorage {
+    pub const fn new(app_name: &'static str) -> Self {
+        Self { app_name }
+    }
+
+    pub fn app_dir(&self) -> Option<PathBuf> {
+        root_dir(dirs::data_dir, "data").map(|dir| dir.join(self.app_name))
+    }
+
+    pub fn file_path(&self, name: &str) -> Option<PathBuf> {
+        self.app_dir().map(|dir| dir.join(format!("{name}.json")))
+    }
+
+    /// `Ok(None)` if nothing has been saved yet.
+    pub fn load<T: DeserializeOwned>(&self, name: &str) -> Result<Option<T>, StorageError> {
+        let path = self.file_path(name).ok_or(StorageError::Unavailable)?;
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(StorageError::Io(e)),
+        };
+        let value = serde_json::from_str(&contents).map_err(StorageError::Serde)?;
+        Ok(Some(value))
+    }
+
+    /// Best-effort load. Falls back to default (and logs) if anything goes wrong, so a corrupt or
+    /// outdated file never prevents the app from starting.
+    pub fn load_or_default<T: DeserializeOwned + Default>(&self, name: &str) -> T {
+        match self.load(name) {
+            Ok(Some(value)) => value,
+            Ok(None) | Err(StorageError::Unavailable) => T::default(),
+            Err(e) => {
+                mq::warn!(
+                    "Failed to load {}/{name}, using default: {e}",
+                    self.app_name
+                );
+                T::default()
+            }
+        }
+    }
+
+    pub fn save<T: Serialize>(&self, name: &str, value: &T) -> Result<(), StorageError> {
+        let dir = self.app_dir().ok_or(StorageError::Unavailable)?;
+        fs::create_dir_all(&dir).map_err(StorageError::Io)?;
+
+        let contents = serde_json::to_string_pretty(value).map_err(StorageError::Serde)?;
+        // Write then rename so a crash mid-write can't leave a truncated file behind.
+        let path = dir.join(format!("{name}.json"));
+        let tmp_path = dir.join(format!("{name}.json.tmp"));
+        fs::write(&tmp_path, contents).map_err(StorageError::Io)?;
+        fs::rename(tmp_path, path).map_err(StorageError::Io)
+    }
+
+    /// Best-effort save. Logs instead of returning the error.
+    pub fn save_or_log<T: Serialize>(&self, name: &str, value: &T) {
+        match self.save(name, value) {
+            Ok(()) | Err(StorageError::Unavailable) => {}
+            Err(e) => mq::warn!("Failed to save {}/{name}: {e}", self.app_name),
+        }
+    }
+
+    /// Names of everything saved, in no particular order. Empty if nothing has been saved yet.
+    pub fn names(&self) -> Result<Vec<String>, StorageError> {
+        let dir = self.app_dir().ok_or(StorageError::Unavailable)?;
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(StorageError::Io(e)),
+        };
+        let mut names = Vec::new();
+        for entry in entries {
+            let file_name = entry.map_err(StorageError::Io)?.file_name();
+            // Skips leftover temp files too, which end in .json.tmp
+            if let Some(name) = file_name.to_str().and_then(|f| f.strip_suffix(".json")) {
+                names.push(name.to_string());
+            }
+        }
+        Ok(names)
+    }
+
+    /// Deletes a saved value. Fine if nothing was saved.
+    pub fn remove(&self, name: &str) -> Result<(), StorageError> {
+        let path = self.file_path(name).ok_or(StorageError::Unavailable)?;
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(StorageError::Io(e)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Best-effort remove. Logs instead of returning the error.
+    pub fn remove_or_log(&self, name: &str) {
+        match self.remove(name) {
+            Ok(()) | Err(StorageError::Unavailable) => {}
+            Err(e) => mq::warn!("Failed to remove {}/{name}: {e}", self.app_name),
+        }
+    }
+}
+
+impl Display for StorageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageError::Unavailable => write!(f, "storage unavailable on this platform"),
+            StorageError::Io(e) => write!(f, "io error: {e}"),
+            StorageError::Serde(e) => write!(f, "serialization error: {e}"),
+            StorageError::WrongFormat { expected, found } => {
+                write!(f, "expected a {expected} file, not a {found} file")
+            }
+            StorageError::TooNew {
+                version,
+                max_version,
+            } => write!(
+                f,
+                "made by a newer version of the app (version {version}, this one reads up to \
+                {max_version})"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_versioned_json, to_versioned_json, StorageError};
+
+    #[test]
+    fn test_versioned_json() {
+        let json = to_versioned_json("scores", 2, &vec![1, 2, 3]).unwrap();
+        assert_eq!(
+            vec![1, 2, 3],
+            from_versioned_json::<Vec<u32>>(&json, "scores", 2).unwrap()
+        );
+        // Newer apps read older files
+        assert!(from_versioned_json::<Vec<u32>>(&json, "scores", 3).is_ok());
+        assert!(matches!(
+            from_versioned_json::<Vec<u32>>(&json, "scores", 1),
+            Err(StorageError::TooNew {
+                version: 2,
+                max_version: 1
+            })
+        ));
+        assert!(matches!(
+            from_versioned_json::<Vec<u32>>(&json, "settings", 2),
+            Err(StorageError::WrongFormat { .. })
+        ));
+        assert!(matches!(
+            from_versioned_json::<Vec<u32>>("[1, 2, 3]", "scores", 2),
+            Err(StorageError::Serde(_))
+        ));
+    }
+}
@@ -0,0 +1,25 @@
+//! Streamer mode: a process-wide flag for apps to hide what an audience shouldn't see yet (e.g.
+//! a guess before it's submitted, so viewers can guess along).
+//!
+//! Starts on if the `BQ_STREAMER_MODE` env var is set (to anything but `0`), so it can be turned on
+//! from a stream launcher, and apps can toggle it from there.
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const STREAMER_MODE_ENV_VAR: &str = "BQ_STREAMER_MODE";
+
+static STREAMER_MODE: AtomicBool = AtomicBool::new(false);
+
+pub fn is_streamer_mode() -> bool {
+    STREAMER_MODE.load(Ordering::Relaxed)
+}
+
+pub fn set_streamer_mode(on: bool) {
+    STREAMER_MODE.store(on, Ordering::Relaxed);
+}
+
+/// Called by `initialize_engine()`.
+pub(crate) fn init_from_env() {
+    let on = env::var_os(STREAMER_MODE_ENV_VAR).is_some_and(|value| value != "0");
+    set_streamer_mode(on);
+}
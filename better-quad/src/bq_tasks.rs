@@ -0,0 +1,167 @@
+//! Background work that would otherwise hitch the render loop (solvers, file exports, network
+//! calls, etc).
+//!
+//! Spawn a task, keep the [`Task`] handle, and [`Task::poll`] it from `update()` until it's done:
+//! ```ignore
+//! self.export = Some(bq::spawn_task(move || write_export(&path, &rows)));
+//! // Later, in update()
+//! if let Some(export) = &self.export {
+//!     match export.poll() {
+//!         TaskPoll::Pending => {}
+//!         TaskPoll::Done(result) => { self.export = None; /* show a toast */ }
+//!         TaskPoll::Failed => { self.export = None; /* show an error toast */ }
+//!     }
+//! }
+//! ```
+//!
+//! On native, tasks run on a small shared thread pool. WASM has no threads, so there they run on
+//! the main thread between frames, as macroquad coroutines: the frame that spawns them doesn't
+//! wait, but the one they run after does, so split big work into smaller tasks there.
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+
+/// A running piece of background work, with its result once it's done.
+pub struct Task<T> {
+    receiver: Receiver<T>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TaskPoll<T> {
+    Pending,
+    /// Only reported once, so drop the task after this.
+    Done(T),
+    /// The work panicked.
+    Failed,
+}
+
+impl<T> Task<T> {
+    /// Never blocks, so it's fine to call every frame.
+    pub fn poll(&self) -> TaskPoll<T> {
+        match self.receiver.try_recv() {
+            Ok(result) => TaskPoll::Done(result),
+            Err(TryRecvError::Empty) => TaskPoll::Pending,
+            Err(TryRecvError::Disconnected) => TaskPoll::Failed,
+        }
+    }
+}
+
+/// Runs `work` in the background. See the module docs for where.
+pub fn spawn_task<T: Send + 'static>(work: impl FnOnce() -> T + Send + 'static) -> Task<T> {
+    let (sender, receiver) = mpsc::channel();
+    let job = move || {
+        // A panic drops `sender` without sending, which the task reports as failed
+        if let Ok(result) = panic::catch_unwind(AssertUnwindSafe(work)) {
+            // Nobody's listening if the task was dropped
+            let _ = sender.send(result);
+        }
+    };
+    run_job(Box::new(job));
+    Task { receiver }
+}
+
+/// Runs `future` alongside the render loop, as a macroquad coroutine, on every platform. For work
+/// that spends its time waiting (e.g. on the network) rather than computing. Needs the engine to
+/// be running.
+pub fn spawn_async_task<T: Send + 'static>(
+    future: impl std::future::Future<Output = T> + Send + 'static,
+) -> Task<T> {
+    let (sender, receiver) = mpsc::channel();
+    macroquad::experimental::coroutines::start_coroutine(async move {
+        let _ = sender.send(future.await);
+    });
+    Task { receiver }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+#[cfg(not(target_arch = "wasm32"))]
+fn run_job(job: Job) {
+    thread_pool::sender()
+        .send(job)
+        .expect("task workers never stop");
+}
+
+#[cfg(target_arch = "wasm32")]
+fn run_job(job: Job) {
+    macroquad::experimental::coroutines::start_coroutine(async move { job() });
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod thread_pool {
+    use super::Job;
+    use std::sync::mpsc::{self, Sender};
+    use std::sync::{Arc, Mutex, OnceLock};
+    use std::thread;
+
+    /// Leaves the rest of the cores for the render loop and other apps.
+    const MAX_WORKERS: usize = 4;
+
+    static SENDER: OnceLock<Sender<Job>> = OnceLock::new();
+
+    /// Starts the workers on first use.
+    pub(super) fn sender() -> &'static Sender<Job> {
+        SENDER.get_or_init(|| {
+            let (sender, receiver) = mpsc::channel::<Job>();
+            let receiver = Arc::new(Mutex::new(receiver));
+            let num_workers = thread::available_parallelism()
+                .map_or(1, |n| n.get() - 1)
+                .clamp(1, MAX_WORKERS);
+            for i in 0..num_workers {
+                let receiver = Arc::clone(&receiver);
+                thread::Builder::new()
+                    .name(format!("bq-task-{i}"))
+                    .spawn(move || loop {
+                        // Only locked while waiting, not while working
+                        let job = receiver.lock().unwrap().recv();
+                        match job {
+                            Ok(job) => job(),
+                            Err(_) => break,
+                        }
+                    })
+                    .expect("can't start task worker");
+            }
+            sender
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{spawn_task, Task, TaskPoll};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    fn wait_for<T>(task: &Task<T>) -> TaskPoll<T> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            match task.poll() {
+                TaskPoll::Pending => thread::sleep(Duration::from_millis(1)),
+                done_or_failed => return done_or_failed,
+            }
+        }
+        panic!("task didn't finish");
+    }
+
+    #[test]
+    fn test_spawn_task() {
+        let tasks = (0..10u64)
+            .map(|i| spawn_task(move || (0..=i).sum::<u64>()))
+            .collect::<Vec<_>>();
+        for (i, task) in tasks.iter().enumerate() {
+            assert_eq!(
+                TaskPoll::Done(i as u64 * (i as u64 + 1) / 2),
+                wait_for(task)
+            );
+        }
+    }
+
+    #[test]
+    fn test_panicking_task_fails() {
+        let task = spawn_task(|| -> u32 { panic!("expected by the test") });
+        assert_eq!(TaskPoll::Failed, wait_for(&task));
+
+        // Workers keep going after a panic
+        let task = spawn_task(|| 7);
+        assert_eq!(TaskPoll::Done(7), wait_for(&task));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::mq;
+use crate::{bq_safe_area, mq};
 
 const FONT_SCALE: f32 = 1.0;
 
@@ -67,38 +67,45 @@ pub enum TextAlignment {
 #[derive(Copy, Clone)]
 pub enum TextAnchorPoint {
     TopLeft { x: f32, y: f32 },
+    TopCenter { x: f32, y: f32 },
     TopRight { x: f32, y: f32 },
     Center { x: f32, y: f32 },
     BottomLeft { x: f32, y: f32 },
+    BottomCenter { x: f32, y: f32 },
     BottomRight { x: f32, y: f32 },
 }
 
+/// The `window_` anchors are inside the safe area (see `bq_safe_area`).
 impl TextAnchorPoint {
     pub fn window_centered() -> Self {
+        let area = bq_safe_area::safe_area();
         Self::Center {
-            x: mq::screen_width() / 2.0,
-            y: mq::screen_height() / 2.0,
+            x: area.center().x,
+            y: area.center().y,
         }
     }
 
     pub fn window_bottom_left() -> Self {
+        let area = bq_safe_area::safe_area();
         Self::BottomLeft {
-            x: 0.0,
-            y: mq::screen_height(),
+            x: area.left(),
+            y: area.bottom(),
         }
     }
 
     pub fn window_bottom_right() -> Self {
+        let area = bq_safe_area::safe_area();
         Self::BottomRight {
-            x: mq::screen_width(),
-            y: mq::screen_height(),
+            x: area.right(),
+            y: area.bottom(),
         }
     }
 
     pub fn window_top_right() -> Self {
+        let area = bq_safe_area::safe_area();
         Self::TopRight {
-            x: mq::screen_width(),
-            y: 0.0,
+            x: area.right(),
+            y: area.top(),
         }
     }
 }
@@ -175,9 +182,11 @@ impl TextContainer {
 
         let (x, y) = match text_anchor_point {
             TextAnchorPoint::TopLeft { x, y } => (x, y),
+            TextAnchorPoint::TopCenter { x, y } => (x - (total_width / 2.0), y),
             TextAnchorPoint::TopRight { x, y } => (x - total_width, y),
             TextAnchorPoint::Center { x, y } => (x - (total_width / 2.0), y - (total_height / 2.0)),
             TextAnchorPoint::BottomLeft { x, y } => (x, y - total_height),
+            TextAnchorPoint::BottomCenter { x, y } => (x - (total_width / 2.0), y - total_height),
             TextAnchorPoint::BottomRight { x, y } => (x - total_width, y - total_height),
         };
 
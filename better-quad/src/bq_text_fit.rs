@@ -0,0 +1,205 @@
+//! Fitting a line of text into a rect: shrink the font until it fits, or cut it off with an ellipsis
+//! when it would get too small to read. For things like long player names and big-screen layouts,
+//! where the text isn't known ahead of time.
+use crate::bq_graphemes;
+use crate::mq;
+use std::borrow::Cow;
+
+const FONT_SCALE: f32 = 1.0;
+/// Text is first measured at this size, and the fitted size is estimated from there.
+const MEASURE_FONT_SIZE: u16 = 100;
+const ELLIPSIS: &str = "...";
+
+/// Largest font size, up to `max_font_size`, that fits `text` in `width` x `height`. 0 if nothing
+/// does.
+///
+/// The height bound is the font size itself rather than this text's height, since font size is
+/// roughly the height of the tallest glyphs. That keeps a row of different texts the same size.
+pub fn fit_font_size(
+    text: &str,
+    font: Option<&mq::Font>,
+    max_font_size: u16,
+    width: f32,
+    height: f32,
+) -> u16 {
+    fit_font_size_by(
+        |text, font_size| mq::measure_text(text, font, font_size, FONT_SCALE).width,
+        text,
+        max_font_size,
+        width,
+        height,
+    )
+}
+
+/// `text` as is if it fits in `width`, otherwise cut short with an ellipsis. Just the ellipsis if
+/// not even one char fits. It's only cut between graphemes, so accents and emoji stay whole.
+pub fn truncate_with_ellipsis<'a>(
+    text: &'a str,
+    font: Option<&mq::Font>,
+    font_size: u16,
+    width: f32,
+) -> Cow<'a, str> {
+    truncate_with_ellipsis_by(
+        |text| mq::measure_text(text, font, font_size, FONT_SCALE).width,
+        text,
+        width,
+    )
+}
+
+/// Draws one line of `text` centered in `rect`, as big as fits up to `max_font_size`. Below
+/// `min_font_size`, it's drawn at `min_font_size` and cut off with an ellipsis instead. Returns the
+/// font size used.
+pub fn draw_text_fitted(
+    text: &str,
+    font: Option<&mq::Font>,
+    min_font_size: u16,
+    max_font_size: u16,
+    color: mq::Color,
+    rect: mq::Rect,
+) -> u16 {
+    let fitted_font_size = fit_font_size(text, font, max_font_size, rect.w, rect.h);
+    let (text, font_size) = if fitted_font_size >= min_font_size {
+        (Cow::Borrowed(text), fitted_font_size)
+    } else {
+        (
+            truncate_with_ellipsis(text, font, min_font_size, rect.w),
+            min_font_size,
+        )
+    };
+    if font_size == 0 {
+        return 0;
+    }
+
+    let dimensions = mq::measure_text(&text, font, font_size, FONT_SCALE);
+    mq::draw_text_ex(
+        &text,
+        rect.x + (rect.w - dimensions.width) / 2.0,
+        rect.y + (rect.h - dimensions.height) / 2.0 + dimensions.offset_y,
+        mq::TextParams {
+            font,
+            font_size,
+            color,
+            ..Default::default()
+        },
+    );
+    font_size
+}
+
+/// `measure_width(text, font_size)` is the text's drawn width.
+fn fit_font_size_by(
+    measure_width: impl Fn(&str, u16) -> f32,
+    text: &str,
+    max_font_size: u16,
+    width: f32,
+    height: f32,
+) -> u16 {
+    let height_bound = height.clamp(0.0, max_font_size as f32) as u16;
+    let measured_width = measure_width(text, MEASURE_FONT_SIZE);
+    if measured_width <= 0.0 {
+        return height_bound;
+    }
+
+    // Width is close to proportional to font size, but not exactly since glyphs are rasterized
+    // per size. Start from the estimate and step down until it really fits.
+    let estimate = (MEASURE_FONT_SIZE as f32 * width / measured_width).max(0.0);
+    let mut font_size = estimate.min(height_bound as f32) as u16;
+    while font_size > 0 && measure_width(text, font_size) > width {
+        font_size -= 1;
+    }
+    font_size
+}
+
+/// `measure_width(text)` is the text's drawn width.
+fn truncate_with_ellipsis_by(
+    measure_width: impl Fn(&str) -> f32,
+    text: &str,
+    width: f32,
+) -> Cow<'_, str> {
+    if measure_width(text) <= width {
+        return Cow::Borrowed(text);
+    }
+
+    // Byte index where each prefix ends, by number of graphemes kept
+    let prefix_ends = [0]
+        .into_iter()
+        .chain(bq_graphemes::graphemes(text).scan(0, |end, grapheme| {
+            *end += grapheme.len();
+            Some(*end)
+        }))
+        .collect::<Vec<_>>();
+    let truncated = |num_graphemes: usize| {
+        format!(
+            "{}{ELLIPSIS}",
+            text[..prefix_ends[num_graphemes]].trim_end()
+        )
+    };
+
+    // Binary search for the most graphemes that fit, since measuring isn't free
+    let (mut low, mut high) = (0, prefix_ends.len() - 1);
+    while low < high {
+        let mid = (low + high).div_ceil(2);
+        if measure_width(&truncated(mid)) <= width {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    Cow::Owned(truncated(low))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fit_font_size_by, truncate_with_ellipsis_by};
+
+    // Every char is half as wide as the font size
+    fn measure_width(text: &str, font_size: u16) -> f32 {
+        text.chars().count() as f32 * font_size as f32 / 2.0
+    }
+
+    #[test]
+    fn test_fit_font_size() {
+        // Width bound: 10 chars * 20 / 2 = 100
+        assert_eq!(
+            20,
+            fit_font_size_by(measure_width, "0123456789", 50, 100.0, 40.0)
+        );
+        // Height bound
+        assert_eq!(30, fit_font_size_by(measure_width, "hi", 50, 100.0, 30.0));
+        // Max bound
+        assert_eq!(25, fit_font_size_by(measure_width, "hi", 25, 100.0, 30.0));
+        assert_eq!(0, fit_font_size_by(measure_width, "hi", 25, 0.0, 30.0));
+        assert_eq!(25, fit_font_size_by(measure_width, "", 25, 0.0, 30.0));
+    }
+
+    #[test]
+    fn test_truncate_with_ellipsis() {
+        let measure_width = |text: &str| measure_width(text, 2);
+        assert_eq!(
+            "Alice",
+            truncate_with_ellipsis_by(measure_width, "Alice", 5.0)
+        );
+        assert_eq!(
+            "Bartho...",
+            truncate_with_ellipsis_by(measure_width, "Bartholomew", 9.0)
+        );
+        // No dangling space before the ellipsis
+        assert_eq!(
+            "Dr...",
+            truncate_with_ellipsis_by(measure_width, "Dr Zoidberg", 6.0)
+        );
+        assert_eq!(
+            "名前...",
+            truncate_with_ellipsis_by(measure_width, "名前がとても長い", 5.0)
+        );
+        // Accents and emoji aren't split off
+        assert_eq!(
+            "Zoe\u{308}...",
+            truncate_with_ellipsis_by(measure_width, "Zoe\u{308} Quinn", 7.0)
+        );
+        assert_eq!(
+            "👩\u{200D}🚀...",
+            truncate_with_ellipsis_by(measure_width, "👩\u{200D}🚀👩\u{200D}🚀👩\u{200D}🚀", 6.0)
+        );
+        assert_eq!("...", truncate_with_ellipsis_by(measure_width, "Bob", 1.0));
+    }
+}
@@ -0,0 +1,107 @@
+//! Minimal single-line text input: typing, backspace, paste, enter to submit, escape to cancel.
+use crate::bq::{TextAlignment, TextAnchorPoint, TextBackground, TextContainer};
+use crate::{bq_graphemes, bq_keys, bq_text, mq};
+
+pub struct TextInput {
+    text: String,
+    max_len: usize,
+    accept_char: fn(char) -> bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextInputEvent {
+    Submit(String),
+    Cancel,
+}
+
+impl TextInput {
+    /// Only chars passing `accept_char` are kept, both typed and pasted. `max_len` is in graphemes,
+    /// so an accented letter or an emoji counts as one (see `bq_graphemes`).
+    pub fn new(max_len: usize, accept_char: fn(char) -> bool) -> Self {
+        // Don't pick up whatever was typed before the input was opened.
+        mq::clear_input_queue();
+        Self {
+            text: String::new(),
+            max_len,
+            accept_char,
+        }
+    }
+
+    /// Starts with `text` already typed, e.g. to edit an existing value. Filtered like typed text.
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.push_chars(text.chars());
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Call once per frame while the input is open. Other hotkeys should be ignored meanwhile,
+    /// since letters typed here also register as key presses.
+    pub fn update(&mut self) -> Option<TextInputEvent> {
+        let ctrl_down = mq::is_key_down(mq::KeyCode::LeftControl)
+            || mq::is_key_down(mq::KeyCode::RightControl)
+            || mq::is_key_down(mq::KeyCode::LeftSuper)
+            || mq::is_key_down(mq::KeyCode::RightSuper);
+
+        let typed_chars = bq_keys::drain_chars_pressed();
+        if ctrl_down {
+            if mq::is_key_pressed(mq::KeyCode::V) {
+                if let Some(pasted) = mq::miniquad::window::clipboard_get() {
+                    self.push_chars(pasted.chars());
+                }
+            }
+        } else {
+            self.push_chars(typed_chars.into_iter());
+        }
+
+        if mq::is_key_pressed(mq::KeyCode::Backspace) {
+            bq_graphemes::pop_grapheme(&mut self.text);
+        }
+        if mq::is_key_pressed(mq::KeyCode::Enter) || mq::is_key_pressed(mq::KeyCode::KpEnter) {
+            return Some(TextInputEvent::Submit(self.text.clone()));
+        }
+        if mq::is_key_pressed(mq::KeyCode::Escape) {
+            return Some(TextInputEvent::Cancel);
+        }
+
+        None
+    }
+
+    fn push_chars(&mut self, chars: impl Iterator<Item = char>) {
+        for c in chars {
+            if c.is_control() || !(self.accept_char)(c) {
+                continue;
+            }
+            // Marks and joiners can still go onto the last grapheme once it's full
+            self.text.push(c);
+            if bq_graphemes::grapheme_count(&self.text) > self.max_len {
+                self.text.pop();
+                return;
+            }
+        }
+    }
+
+    /// Draws `prompt` followed by the text so far and a cursor.
+    pub fn draw(
+        &self,
+        prompt: &str,
+        font_size: u16,
+        anchor_point: TextAnchorPoint,
+    ) -> TextContainer {
+        bq_text::draw_text(
+            format!("{prompt}{}_", self.text),
+            TextAlignment::Left,
+            None,
+            font_size,
+            mq::BLACK,
+            anchor_point,
+            Some(TextBackground {
+                color: mq::WHITE,
+                x_padding: 10.0,
+                y_padding: 10.0,
+            }),
+        )
+    }
+}
@@ -1,7 +1,10 @@
 use crate::mq;
-use std::ops::Sub;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Sub};
 use std::time::Duration;
 
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
 /// Wrapper of time so we can have a non-primitive type for time to disambiguate UOM.
 ///
 /// Why not SystemTime/etc? Those don't exist on WASM.
@@ -17,6 +20,11 @@ impl Timestamp {
         }
     }
 
+    /// The opposite of [`Self::as_sec_f64`], e.g. for a saved time.
+    pub fn from_sec_f64(seconds: f64) -> Self {
+        Self { seconds }
+    }
+
     pub fn as_sec_f64(&self) -> f64 {
         self.seconds
     }
@@ -30,9 +38,39 @@ impl Timestamp {
         }
     }
 
+    /// Whole days since the unix epoch, in UTC. Same for everyone on the same day, regardless of
+    /// timezone.
+    pub fn utc_day_number(&self) -> u64 {
+        (self.seconds / SECONDS_PER_DAY) as u64
+    }
+
+    pub fn utc_date(&self) -> UtcDate {
+        UtcDate::from_day_number(self.utc_day_number())
+    }
+
     // note: Shouldn't need `elapsed` as their should always be the provided `now` to do math with.
 }
 
+impl Add<Duration> for Timestamp {
+    type Output = Timestamp;
+
+    fn add(self, rhs: Duration) -> Self::Output {
+        Self {
+            seconds: self.seconds + rhs.as_secs_f64(),
+        }
+    }
+}
+
+impl Sub<Duration> for Timestamp {
+    type Output = Timestamp;
+
+    fn sub(self, rhs: Duration) -> Self::Output {
+        Self {
+            seconds: self.seconds - rhs.as_secs_f64(),
+        }
+    }
+}
+
 impl Sub for Timestamp {
     type Output = Duration;
 
@@ -40,3 +78,56 @@ impl Sub for Timestamp {
         self.duration_since(rhs).unwrap()
     }
 }
+
+/// Calendar date in UTC. Only meant for display, so it's intentionally bare-bones.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct UtcDate {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl UtcDate {
+    /// Inverse of "days from civil" from http://howardhinnant.github.io/date_algorithms.html
+    pub fn from_day_number(day_number: u64) -> Self {
+        let z = day_number as i64 + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        Self { year, month, day }
+    }
+}
+
+impl Display for UtcDate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UtcDate;
+
+    #[test]
+    fn test_utc_date_from_day_number() {
+        let test_cases = [
+            // (day number, expected date)
+            (0, "1970-01-01"),
+            (59, "1970-03-01"),
+            (11_016, "2000-02-29"),
+            (20_105, "2025-01-17"),
+        ];
+
+        for (day_number, expected) in test_cases {
+            let actual = UtcDate::from_day_number(day_number).to_string();
+            assert_eq!(expected, actual, "Day number: {day_number}");
+        }
+    }
+}
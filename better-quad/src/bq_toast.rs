@@ -0,0 +1,78 @@
+//! Short-lived messages stacked at the top of the window. Handy for surfacing non-fatal problems
+//! (like a broken config file) without interrupting the app.
+use crate::bq::{TextAlignment, TextAnchorPoint, TextBackground};
+use crate::bq_timestamp::Timestamp;
+use crate::{bq_safe_area, bq_text, mq};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+const TOAST_DURATION: Duration = Duration::from_secs(6);
+const MAX_VISIBLE_TOASTS: usize = 4;
+const TOAST_FONT_SIZE: u16 = 20;
+const TOAST_PADDING: f32 = 6.0;
+const TOAST_MARGIN: f32 = 6.0;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ToastKind {
+    Info,
+    Error,
+}
+
+struct Toast {
+    kind: ToastKind,
+    message: String,
+    expires_at: Timestamp,
+}
+
+/// Queue of toasts. Apps own one, `tick()` it in update, and `draw()` it last so it's on top.
+#[derive(Default)]
+pub struct Toasts {
+    toasts: VecDeque<Toast>,
+}
+
+impl Toasts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, kind: ToastKind, message: impl Into<String>) {
+        self.toasts.push_back(Toast {
+            kind,
+            message: message.into(),
+            expires_at: Timestamp::now() + TOAST_DURATION,
+        });
+    }
+
+    pub fn tick(&mut self, now: Timestamp) {
+        self.toasts
+            .retain(|toast| toast.expires_at.duration_since(now).is_some());
+    }
+
+    pub fn draw(&self) {
+        let area = bq_safe_area::safe_area();
+        let mut y = area.top() + TOAST_MARGIN;
+        for toast in self.toasts.iter().take(MAX_VISIBLE_TOASTS) {
+            let (text_color, background_color) = match toast.kind {
+                ToastKind::Info => (mq::BLACK, mq::Color::new(1.0, 1.0, 1.0, 0.9)),
+                ToastKind::Error => (mq::WHITE, mq::Color::new(0.7, 0.1, 0.1, 0.9)),
+            };
+            let container = bq_text::draw_text(
+                &toast.message,
+                TextAlignment::Left,
+                None,
+                TOAST_FONT_SIZE,
+                text_color,
+                TextAnchorPoint::TopCenter {
+                    x: area.center().x,
+                    y,
+                },
+                Some(TextBackground {
+                    color: background_color,
+                    x_padding: TOAST_PADDING,
+                    y_padding: TOAST_PADDING,
+                }),
+            );
+            y += container.rect_height + TOAST_MARGIN;
+        }
+    }
+}
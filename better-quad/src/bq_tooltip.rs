@@ -0,0 +1,128 @@
+//! Tooltips: a small box of text down and to the right of the mouse. [`HoverTimer`] waits until the
+//! mouse has rested on something for a moment, so tooltips don't flicker up while just passing
+//! over things.
+use crate::bq::{TextAlignment, TextAnchorPoint, TextBackground};
+use crate::bq_timestamp::Timestamp;
+use crate::{bq_text, mq};
+use std::time::Duration;
+
+/// How long the mouse has to rest on something before its tooltip shows.
+pub const TOOLTIP_DELAY: Duration = Duration::from_millis(500);
+const TOOLTIP_FONT_SIZE: u16 = 22;
+const TOOLTIP_OFFSET: f32 = 16.0;
+const TOOLTIP_PADDING: f32 = 5.0;
+
+/// What's under the mouse, and since when. `K` is whatever identifies a hover target in the app.
+pub struct HoverTimer<K> {
+    hovered: Option<(K, Timestamp)>,
+    is_ready: bool,
+}
+
+impl<K: PartialEq> HoverTimer<K> {
+    pub fn new() -> Self {
+        Self {
+            hovered: None,
+            is_ready: false,
+        }
+    }
+
+    /// Call every frame with what's under the mouse, if anything. Moving onto something else
+    /// starts the wait over.
+    pub fn update(&mut self, hovered: Option<K>, now: Timestamp) {
+        let since = match (self.hovered.take(), hovered) {
+            (Some((previous, since)), Some(hovered)) if previous == hovered => {
+                Some((hovered, since))
+            }
+            (_, hovered) => hovered.map(|hovered| (hovered, now)),
+        };
+        self.is_ready = since.as_ref().is_some_and(|(_, since)| {
+            now.duration_since(*since)
+                .is_some_and(|hovered_for| hovered_for >= TOOLTIP_DELAY)
+        });
+        self.hovered = since;
+    }
+
+    /// What's been hovered for at least [`TOOLTIP_DELAY`], for drawing its tooltip.
+    pub fn ready(&self) -> Option<&K> {
+        self.hovered
+            .as_ref()
+            .filter(|_| self.is_ready)
+            .map(|(hovered, _)| hovered)
+    }
+}
+
+impl<K: PartialEq> Default for HoverTimer<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Draws `text` down and to the right of (`x`, `y`), usually the mouse. Flips to the other side of
+/// it where it'd go off the window.
+pub fn draw_tooltip(text: &str, x: f32, y: f32, background_color: mq::Color) {
+    let width = text
+        .lines()
+        .map(|line| mq::measure_text(line, None, TOOLTIP_FONT_SIZE, 1.0).width)
+        .fold(0.0, f32::max)
+        + TOOLTIP_PADDING * 2.0;
+    let height = text.lines().count() as f32 * TOOLTIP_FONT_SIZE as f32 + TOOLTIP_PADDING * 2.0;
+    let tooltip_x = if x + TOOLTIP_OFFSET + width > mq::screen_width() {
+        x - TOOLTIP_OFFSET - width
+    } else {
+        x + TOOLTIP_OFFSET
+    };
+    let tooltip_y = if y + TOOLTIP_OFFSET + height > mq::screen_height() {
+        y - TOOLTIP_OFFSET - height
+    } else {
+        y + TOOLTIP_OFFSET
+    };
+    bq_text::draw_text(
+        text,
+        TextAlignment::Left,
+        None,
+        TOOLTIP_FONT_SIZE,
+        mq::BLACK,
+        TextAnchorPoint::TopLeft {
+            x: tooltip_x,
+            y: tooltip_y,
+        },
+        Some(TextBackground {
+            color: background_color,
+            x_padding: TOOLTIP_PADDING,
+            y_padding: TOOLTIP_PADDING,
+        }),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HoverTimer, TOOLTIP_DELAY};
+    use crate::bq_timestamp::Timestamp;
+    use std::time::Duration;
+
+    #[test]
+    fn test_hover_timer() {
+        let start = Timestamp::now();
+        let mut hover_timer = HoverTimer::new();
+        hover_timer.update(Some("red"), start);
+        assert_eq!(None, hover_timer.ready());
+        hover_timer.update(Some("red"), start + TOOLTIP_DELAY);
+        assert_eq!(Some(&"red"), hover_timer.ready());
+
+        // Something else starts the wait over
+        let later = start + TOOLTIP_DELAY * 2;
+        hover_timer.update(Some("blue"), later);
+        assert_eq!(None, hover_timer.ready());
+        hover_timer.update(Some("blue"), later + TOOLTIP_DELAY);
+        assert_eq!(Some(&"blue"), hover_timer.ready());
+
+        // So does leaving for a frame
+        hover_timer.update(None, later + TOOLTIP_DELAY * 2);
+        assert_eq!(None, hover_timer.ready());
+        hover_timer.update(
+            Some("blue"),
+            later + TOOLTIP_DELAY * 2 + Duration::from_millis(1),
+        );
+        assert_eq!(None, hover_timer.ready());
+    }
+}
@@ -0,0 +1,265 @@
+//! Step by step tutorials, scripted in TOML rather than code: each step has a prompt to show and
+//! the input that moves on to the next step.
+//!
+//! ```toml
+//! [[steps]]
+//! prompt = "Press {submit} to check your guess."
+//! expect = { action = "submit_guess" }
+//! ```
+//!
+//! Steps can expect a key (named like [`BetterKeyCode::to_lowercase`], e.g. "space"), a click or
+//! tap, any key or click at all, or an action. Actions are whatever the app says happened this
+//! frame (e.g. "place_peg"), for things that can be done with different inputs. `{name}`s in
+//! prompts are filled in when drawing, e.g. with the app's key bindings.
+use crate::bq::{BetterKeyCode, TextAlignment, TextAnchorPoint, TextBackground};
+use crate::bq_config::ConfigFile;
+use crate::{bq_safe_area, bq_text, mq};
+use serde::Deserialize;
+
+const PROMPT_FONT_SIZE: u16 = 26;
+const PROMPT_PADDING: f32 = 12.0;
+const PROMPT_MARGIN_BOTTOM: f32 = 20.0;
+const PROMPT_BACKGROUND_COLOR: mq::Color = mq::Color::new(0.1, 0.1, 0.3, 0.9);
+const PROMPT_TEXT_COLOR: mq::Color = mq::WHITE;
+
+/// Raw tutorial file format.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TutorialScript {
+    pub steps: Vec<TutorialStep>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TutorialStep {
+    pub prompt: String,
+    pub expect: Expect,
+}
+
+/// What moves a step on.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Expect {
+    /// Any of these keys.
+    Keys(Vec<String>),
+    /// A left click, or a tap.
+    Click,
+    /// Any key or click, for steps that only explain something.
+    Continue,
+    /// Something the app reports, see [`Tutorial::update`].
+    Action(String),
+}
+
+/// This frame's input, as far as tutorials care.
+#[derive(Default, Debug)]
+pub struct TutorialInput<'a> {
+    /// Named like [`BetterKeyCode::to_lowercase`].
+    pub keys_pressed: Vec<String>,
+    pub clicked: bool,
+    pub actions: &'a [&'a str],
+}
+
+/// A running tutorial.
+pub struct Tutorial {
+    steps: Vec<TutorialStep>,
+    current_step: usize,
+}
+
+impl ConfigFile for TutorialScript {
+    fn validate(&self) -> Result<(), String> {
+        if self.steps.is_empty() {
+            return Err("steps must not be empty".to_string());
+        }
+        for (i, step) in self.steps.iter().enumerate() {
+            if step.prompt.trim().is_empty() {
+                return Err(format!("step {} has no prompt", i + 1));
+            }
+            if matches!(&step.expect, Expect::Keys(keys) if keys.is_empty()) {
+                return Err(format!("step {} expects no keys", i + 1));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Expect {
+    fn is_met_by(&self, input: &TutorialInput) -> bool {
+        match self {
+            Self::Keys(keys) => input.keys_pressed.iter().any(|key| keys.contains(key)),
+            Self::Click => input.clicked,
+            Self::Continue => input.clicked || !input.keys_pressed.is_empty(),
+            Self::Action(action) => input.actions.contains(&action.as_str()),
+        }
+    }
+}
+
+impl Tutorial {
+    pub fn new(script: TutorialScript) -> Self {
+        Self {
+            steps: script.steps,
+            current_step: 0,
+        }
+    }
+
+    /// Call every frame with what the app says happened this frame. Moves on at most one step per
+    /// frame, so one press doesn't skip through several. Returns whether it moved on.
+    pub fn update(&mut self, actions: &[&str]) -> bool {
+        let input = TutorialInput {
+            keys_pressed: mq::get_keys_pressed()
+                .iter()
+                .map(BetterKeyCode::to_lowercase)
+                .collect(),
+            clicked: mq::is_mouse_button_pressed(mq::MouseButton::Left),
+            actions,
+        };
+        self.advance(&input)
+    }
+
+    /// [`Tutorial::update`] with the input given, rather than read from macroquad.
+    pub fn advance(&mut self, input: &TutorialInput) -> bool {
+        let is_met = self
+            .steps
+            .get(self.current_step)
+            .is_some_and(|step| step.expect.is_met_by(input));
+        if is_met {
+            self.current_step += 1;
+        }
+        is_met
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current_step >= self.steps.len()
+    }
+
+    /// The current step's prompt, with `{name}`s replaced by their values.
+    pub fn prompt(&self, replacements: &[(&str, &str)]) -> Option<String> {
+        let step = self.steps.get(self.current_step)?;
+        let mut prompt = step.prompt.clone();
+        for (name, value) in replacements {
+            prompt = prompt.replace(&format!("{{{name}}}"), value);
+        }
+        Some(prompt)
+    }
+
+    /// Draws the prompt along the bottom of the window's safe area, with the default camera.
+    /// `footer` goes under it, e.g. how to skip the tutorial.
+    pub fn draw(&self, replacements: &[(&str, &str)], footer: &str) {
+        let Some(prompt) = self.prompt(replacements) else {
+            return;
+        };
+        let area = bq_safe_area::safe_area();
+        bq_text::draw_text(
+            format!(
+                "{prompt}\n\n({}/{}) {footer}",
+                self.current_step + 1,
+                self.steps.len()
+            ),
+            TextAlignment::Center,
+            None,
+            PROMPT_FONT_SIZE,
+            PROMPT_TEXT_COLOR,
+            TextAnchorPoint::BottomCenter {
+                x: area.center().x,
+                y: area.bottom() - PROMPT_MARGIN_BOTTOM,
+            },
+            Some(TextBackground {
+                color: PROMPT_BACKGROUND_COLOR,
+                x_padding: PROMPT_PADDING,
+                y_padding: PROMPT_PADDING,
+            }),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Expect, Tutorial, TutorialInput, TutorialScript, TutorialStep};
+    use crate::bq_config::parse_config;
+
+    #[test]
+    fn test_parse_script() {
+        let script = parse_config::<TutorialScript>(
+            "[[steps]]\n\
+            prompt = \"Welcome!\"\n\
+            expect = \"continue\"\n\
+            [[steps]]\n\
+            prompt = \"Press {submit}\"\n\
+            expect = { keys = [\"space\", \"enter\"] }\n\
+            [[steps]]\n\
+            prompt = \"Place a peg\"\n\
+            expect = { action = \"place_peg\" }\n",
+        );
+        assert_eq!(
+            Ok(TutorialScript {
+                steps: vec![
+                    TutorialStep {
+                        prompt: "Welcome!".to_string(),
+                        expect: Expect::Continue,
+                    },
+                    TutorialStep {
+                        prompt: "Press {submit}".to_string(),
+                        expect: Expect::Keys(vec!["space".to_string(), "enter".to_string()]),
+                    },
+                    TutorialStep {
+                        prompt: "Place a peg".to_string(),
+                        expect: Expect::Action("place_peg".to_string()),
+                    },
+                ],
+            }),
+            script
+        );
+
+        assert!(parse_config::<TutorialScript>("steps = []").is_err());
+    }
+
+    #[test]
+    fn test_advance() {
+        let mut tutorial = Tutorial::new(TutorialScript {
+            steps: vec![
+                TutorialStep {
+                    prompt: "Welcome!".to_string(),
+                    expect: Expect::Continue,
+                },
+                TutorialStep {
+                    prompt: "Press {submit}".to_string(),
+                    expect: Expect::Keys(vec!["space".to_string()]),
+                },
+                TutorialStep {
+                    prompt: "Place a peg".to_string(),
+                    expect: Expect::Action("place_peg".to_string()),
+                },
+            ],
+        });
+        assert!(!tutorial.advance(&TutorialInput::default()));
+        assert!(tutorial.advance(&TutorialInput {
+            clicked: true,
+            ..TutorialInput::default()
+        }));
+        assert_eq!(
+            Some("Press space".to_string()),
+            tutorial.prompt(&[("submit", "space")])
+        );
+
+        // Only the expected key
+        assert!(!tutorial.advance(&TutorialInput {
+            keys_pressed: vec!["enter".to_string()],
+            ..TutorialInput::default()
+        }));
+        assert!(tutorial.advance(&TutorialInput {
+            keys_pressed: vec!["space".to_string()],
+            ..TutorialInput::default()
+        }));
+
+        // Actions come from the app
+        assert!(!tutorial.advance(&TutorialInput {
+            actions: &["select_color"],
+            ..TutorialInput::default()
+        }));
+        assert!(tutorial.advance(&TutorialInput {
+            actions: &["select_color", "place_peg"],
+            ..TutorialInput::default()
+        }));
+        assert!(tutorial.is_finished());
+        assert_eq!(None, tutorial.prompt(&[]));
+    }
+}
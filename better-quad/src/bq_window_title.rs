@@ -0,0 +1,132 @@
+//! Changing the window's title while running, e.g. to show whose turn it is in the taskbar while
+//! the window's behind others.
+//!
+//! miniquad only sets the title when the window opens, so like always on top (see
+//! `bq_always_on_top`), it goes through a [`WindowTitleBackend`]. There's one for X11 (see
+//! `bq_x11`) and one for the page's title on WASM, installed by
+//! [`register_app!`](crate::register_app), and elsewhere apps built with a windowing library that
+//! can change it install one, and without one titles are only remembered.
+use crate::bq_backend::BackendSlot;
+
+static WINDOW_TITLE: BackendSlot<dyn WindowTitleBackend, WindowTitle> =
+    BackendSlot::new(WindowTitle {
+        title: None,
+        shown: false,
+    });
+
+struct WindowTitle {
+    /// The latest one asked for, if any.
+    title: Option<String>,
+    /// Whether the backend's showing it.
+    shown: bool,
+}
+
+/// Whatever can retitle the window.
+pub trait WindowTitleBackend: Send {
+    /// Errors, e.g. when the window can't be found yet, mean it's given the title again on the next
+    /// call.
+    fn set_window_title(&mut self, title: &str) -> Result<(), String>;
+}
+
+/// Replaces the installed backend, if any, and gives it the latest title asked for.
+pub fn set_window_title_backend(backend: impl WindowTitleBackend + 'static) {
+    let mut installed = WINDOW_TITLE.lock();
+    let mut backend = Box::new(backend);
+    if let Some(title) = &installed.state.title {
+        installed.state.shown = backend.set_window_title(title).is_ok();
+    }
+    installed.backend = Some(backend);
+}
+
+/// Only passed on to the backend when it's changed, or it couldn't show it last time, so it's fine
+/// to call every frame.
+pub fn set_window_title(title: &str) {
+    let mut installed = WINDOW_TITLE.lock();
+    if installed.state.title.as_deref() == Some(title) && installed.state.shown {
+        return;
+    }
+    installed.state.title = Some(title.to_string());
+    installed.state.shown = match installed.backend.as_mut() {
+        Some(backend) => backend.set_window_title(title).is_ok(),
+        None => false,
+    };
+}
+
+/// The latest one asked for, whether or not there's a backend to show it.
+pub fn window_title() -> Option<String> {
+    WINDOW_TITLE.lock().state.title.clone()
+}
+
+/// Called by `install_window_backends()`.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn install_document_title_backend() {
+    set_window_title_backend(DocumentTitleBackend);
+}
+
+/// Sets the page's `document.title`, through the `bq` plugin (`docs/bq.js`) that each app's page
+/// loads.
+#[cfg(target_arch = "wasm32")]
+struct DocumentTitleBackend;
+
+#[cfg(target_arch = "wasm32")]
+extern "C" {
+    fn bq_set_document_title(title: *const u8, len: usize);
+}
+
+/// miniquad's loader checks it against the `bq` plugin's version, and logs a mismatch.
+#[cfg(target_arch = "wasm32")]
+#[no_mangle]
+extern "C" fn bq_crate_version() -> u32 {
+    1
+}
+
+#[cfg(target_arch = "wasm32")]
+impl WindowTitleBackend for DocumentTitleBackend {
+    fn set_window_title(&mut self, title: &str) -> Result<(), String> {
+        unsafe { bq_set_document_title(title.as_ptr(), title.len()) };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{set_window_title, set_window_title_backend, window_title, WindowTitleBackend};
+    use std::sync::{Arc, Mutex};
+
+    /// Keeps every title it's given, and like a window that hasn't opened yet, fails the first.
+    struct RecordingBackend {
+        titles: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl WindowTitleBackend for RecordingBackend {
+        fn set_window_title(&mut self, title: &str) -> Result<(), String> {
+            let mut titles = self.titles.lock().unwrap();
+            titles.push(title.to_string());
+            if titles.len() == 1 {
+                Err("no window yet".to_string())
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[test]
+    fn test_window_title() {
+        set_window_title("Timer");
+        let titles = Arc::new(Mutex::new(Vec::new()));
+        set_window_title_backend(RecordingBackend {
+            titles: titles.clone(),
+        });
+        set_window_title("Timer");
+        set_window_title("Timer");
+        set_window_title("Timer - Leo 00:01");
+        set_window_title("Timer - Leo 00:01");
+        set_window_title("Timer - Leo 00:02");
+        assert_eq!(Some("Timer - Leo 00:02".to_string()), window_title());
+        // The one from before the backend, again after it failed, then only changes
+        assert_eq!(
+            vec!["Timer", "Timer", "Timer - Leo 00:01", "Timer - Leo 00:02"],
+            *titles.lock().unwrap()
+        );
+    }
+}
@@ -0,0 +1,292 @@
+//! The app's own window on X11, for what miniquad can't do with it: keeping it on top (see
+//! `bq_always_on_top`) and changing its title (see `bq_window_title`).
+//!
+//! miniquad doesn't hand out its window, so it's found through a second connection to the X
+//! server, by its title among the window manager's windows (`_NET_CLIENT_LIST`). With several
+//! matches, e.g. two copies of the same app, the focused one wins, and otherwise the newest. Window
+//! managers without the EWMH hints (most have them) can't be asked, so there's no backend there.
+use crate::bq_always_on_top::{self, AlwaysOnTopBackend};
+use crate::bq_window_title::{self, WindowTitleBackend};
+use macroquad::miniquad::native::linux_x11::libx11::{
+    self, _XPrivDisplay, Atom, ClientMessageData, Display, LibX11, Window, XClientMessageEvent,
+    XErrorEvent,
+};
+use std::ffi::{c_char, c_int, c_long, c_uchar, c_ulong, c_void, CString};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// `_NET_WM_STATE` actions.
+const NET_WM_STATE_REMOVE: c_long = 0;
+const NET_WM_STATE_ADD: c_long = 1;
+/// Says a state change comes from the app itself, rather than a pager.
+const SOURCE_APPLICATION: c_long = 1;
+/// Most of a property read, in items, e.g. windows in the client list.
+const MAX_PROPERTY_ITEMS: c_long = 4096;
+/// The window manager lists the window shortly after it opens, so the first lookup waits for it.
+const FIRST_LOOKUP_ATTEMPTS: usize = 25;
+const FIRST_LOOKUP_INTERVAL: Duration = Duration::from_millis(20);
+
+struct X11Window {
+    libx11: LibX11,
+    display: *mut Display,
+    root: Window,
+    /// The one it opened with, to find it by.
+    initial_title: String,
+    window: Option<Window>,
+    looked_up: bool,
+}
+
+// Only used from the main thread, behind the backends' locks.
+unsafe impl Send for X11Window {}
+
+/// Installs the backends, if there's an X server with a window manager that can take them.
+pub(crate) fn install_backends(initial_title: &str) {
+    let Some(window) = X11Window::open(initial_title) else {
+        return;
+    };
+    if !window.supports("_NET_CLIENT_LIST") {
+        return;
+    }
+    let supports_above = window.supports("_NET_WM_STATE_ABOVE");
+    let window = Arc::new(Mutex::new(window));
+    bq_window_title::set_window_title_backend(X11WindowTitle(window.clone()));
+    if supports_above {
+        bq_always_on_top::set_always_on_top_backend(X11AlwaysOnTop(window));
+    }
+}
+
+struct X11WindowTitle(Arc<Mutex<X11Window>>);
+
+impl WindowTitleBackend for X11WindowTitle {
+    fn set_window_title(&mut self, title: &str) -> Result<(), String> {
+        self.0
+            .lock()
+            .expect("X11 window lock poisoned")
+            .set_title(title)
+    }
+}
+
+struct X11AlwaysOnTop(Arc<Mutex<X11Window>>);
+
+impl AlwaysOnTopBackend for X11AlwaysOnTop {
+    fn set_always_on_top(&mut self, always_on_top: bool) -> Result<(), String> {
+        self.0
+            .lock()
+            .expect("X11 window lock poisoned")
+            .set_above(always_on_top)
+    }
+}
+
+impl X11Window {
+    /// None without libX11 or an X server, e.g. on Wayland without XWayland.
+    fn open(initial_title: &str) -> Option<Self> {
+        let libx11 = LibX11::try_load().ok()?;
+        let display = unsafe { (libx11.XOpenDisplay)(std::ptr::null()) };
+        if display.is_null() {
+            return None;
+        }
+        // What the DefaultRootWindow macro does
+        let root = unsafe {
+            let private = display as _XPrivDisplay;
+            (*(*private)
+                .screens
+                .offset((*private).default_screen as isize))
+            .root
+        };
+        Some(Self {
+            libx11,
+            display,
+            root,
+            initial_title: initial_title.to_string(),
+            window: None,
+            looked_up: false,
+        })
+    }
+
+    /// Whether the window manager says it handles `atom_name`, e.g. a window state.
+    fn supports(&self, atom_name: &str) -> bool {
+        let atom = self.atom(atom_name);
+        self.property::<c_ulong>(self.root, "_NET_SUPPORTED")
+            .is_some_and(|supported| supported.contains(&atom))
+    }
+
+    fn set_above(&mut self, above: bool) -> Result<(), String> {
+        let window = self.window()?;
+        let action = if above {
+            NET_WM_STATE_ADD
+        } else {
+            NET_WM_STATE_REMOVE
+        };
+        let mut event = XClientMessageEvent {
+            type_0: libx11::ClientMessage,
+            serial: 0,
+            send_event: 1,
+            display: self.display,
+            window,
+            message_type: self.atom("_NET_WM_STATE"),
+            format: 32,
+            data: ClientMessageData {
+                l: [
+                    action,
+                    self.atom("_NET_WM_STATE_ABOVE") as c_long,
+                    0,
+                    SOURCE_APPLICATION,
+                    0,
+                ],
+            },
+        };
+        self.ignoring_errors(|libx11, display| unsafe {
+            (libx11.XSendEvent)(
+                display,
+                self.root,
+                0,
+                libx11::SubstructureNotifyMask | libx11::SubstructureRedirectMask,
+                &mut event as *mut XClientMessageEvent as *mut _,
+            );
+        });
+        Ok(())
+    }
+
+    /// Like miniquad does when the window opens: the old-style name for the title bar and taskbar
+    /// (ICCCM), and the UTF-8 one most window managers show instead (EWMH).
+    fn set_title(&mut self, title: &str) -> Result<(), String> {
+        let window = self.window()?;
+        // A title with a nul byte would be cut short anyway
+        let title = CString::new(title.replace('\0', "")).expect("nul bytes removed");
+        let utf8_string = self.atom("UTF8_STRING");
+        let net_wm_name = self.atom("_NET_WM_NAME");
+        let net_wm_icon_name = self.atom("_NET_WM_ICON_NAME");
+        self.ignoring_errors(|libx11, display| unsafe {
+            (libx11.Xutf8SetWMProperties)(
+                display,
+                window,
+                title.as_ptr(),
+                title.as_ptr(),
+                std::ptr::null_mut::<*mut c_char>(),
+                0,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            );
+            for property in [net_wm_name, net_wm_icon_name] {
+                (libx11.XChangeProperty)(
+                    display,
+                    window,
+                    property,
+                    utf8_string,
+                    8,
+                    libx11::PropModeReplace,
+                    title.as_ptr() as *const c_uchar,
+                    title.as_bytes().len() as c_int,
+                );
+            }
+        });
+        Ok(())
+    }
+
+    fn window(&mut self) -> Result<Window, String> {
+        if let Some(window) = self.window {
+            return Ok(window);
+        }
+        let attempts = if self.looked_up {
+            1
+        } else {
+            FIRST_LOOKUP_ATTEMPTS
+        };
+        self.looked_up = true;
+        for attempt in 0..attempts {
+            if attempt > 0 {
+                thread::sleep(FIRST_LOOKUP_INTERVAL);
+            }
+            if let Some(window) = self.find_window() {
+                self.window = Some(window);
+                return Ok(window);
+            }
+        }
+        Err("Couldn't find the window to change".to_string())
+    }
+
+    fn find_window(&self) -> Option<Window> {
+        let clients = self.property::<c_ulong>(self.root, "_NET_CLIENT_LIST")?;
+        let matching = clients
+            .into_iter()
+            .filter(|client| {
+                self.property::<u8>(*client, "_NET_WM_NAME").as_deref()
+                    == Some(self.initial_title.as_bytes())
+            })
+            .collect::<Vec<_>>();
+        let active = self
+            .property::<c_ulong>(self.root, "_NET_ACTIVE_WINDOW")
+            .and_then(|active| active.first().copied());
+        active
+            .filter(|active| matching.contains(active))
+            .or(matching.last().copied())
+    }
+
+    fn atom(&self, name: &str) -> Atom {
+        let name = CString::new(name).expect("atom names have no nul bytes");
+        unsafe { (self.libx11.XInternAtom)(self.display, name.as_ptr(), 0) }
+    }
+
+    /// Its items, as bytes for strings, or `c_ulong`s for lists of windows or atoms (Xlib widens
+    /// 32-bit items to longs). None if it isn't set, or has a different item size.
+    fn property<T: Copy>(&self, window: Window, name: &str) -> Option<Vec<T>> {
+        let atom = self.atom(name);
+        let expected_format = match size_of::<T>() {
+            1 => 8,
+            _ => 32,
+        };
+        self.ignoring_errors(|libx11, display| unsafe {
+            let mut actual_type: Atom = 0;
+            let mut format: c_int = 0;
+            let mut num_items: c_ulong = 0;
+            let mut bytes_after: c_ulong = 0;
+            let mut data: *mut c_uchar = std::ptr::null_mut();
+            let status = (libx11.XGetWindowProperty)(
+                display,
+                window,
+                atom,
+                0,
+                MAX_PROPERTY_ITEMS,
+                0,
+                libx11::AnyPropertyType,
+                &mut actual_type,
+                &mut format,
+                &mut num_items,
+                &mut bytes_after,
+                &mut data,
+            );
+            if status != libx11::Success || data.is_null() {
+                return None;
+            }
+            let items = (format == expected_format)
+                .then(|| std::slice::from_raw_parts(data as *const T, num_items as usize).to_vec());
+            (libx11.XFree)(data as *mut c_void);
+            items
+        })
+    }
+
+    /// Runs `f` with X errors (e.g. a window closing while it's read) ignored rather than quitting
+    /// the app, which is Xlib's default.
+    fn ignoring_errors<R>(&self, f: impl FnOnce(&LibX11, *mut Display) -> R) -> R {
+        unsafe extern "C" fn ignore_error(_: *mut Display, _: *mut XErrorEvent) -> c_int {
+            0
+        }
+        unsafe {
+            let previous = (self.libx11.XSetErrorHandler)(Some(ignore_error));
+            let result = f(&self.libx11, self.display);
+            (self.libx11.XSync)(self.display, 0);
+            (self.libx11.XSetErrorHandler)(previous);
+            result
+        }
+    }
+}
+
+impl Drop for X11Window {
+    fn drop(&mut self) {
+        unsafe {
+            (self.libx11.XCloseDisplay)(self.display);
+        }
+    }
+}
@@ -1,22 +1,42 @@
-use crate::bq_rand;
+use crate::{bq_always_on_top, bq_rand, bq_rumble, bq_safe_area, bq_streamer};
 use once_cell::sync::Lazy;
 
 static INIT: Lazy<()> = Lazy::new(do_init);
 
 fn do_init() {
     bq_rand::randomize_rand_seed();
+    bq_streamer::init_from_env();
+    bq_safe_area::init_from_env();
+    bq_rumble::init_from_env();
+    bq_always_on_top::init_from_env();
 }
 
 /// Must call before starting game engine.
 ///
 /// # Why?
 ///
-/// This currently only initializes the seeded RNG, needed because macroquad has an objectively bad
-/// API for rand. See https://github.com/not-fl3/macroquad/issues/369
+/// This initializes the seeded RNG, needed because macroquad has an objectively bad API for rand.
+/// See https://github.com/not-fl3/macroquad/issues/369
+///
+/// It also picks up streamer mode, the safe area, rumble logging, and always on top from the
+/// environment (see `bq_streamer`, `bq_safe_area`, `bq_rumble`, and `bq_always_on_top`).
 pub fn initialize_engine() {
     Lazy::force(&INIT);
 }
 
+/// Installs the framework's own backends for the open window where there are any: always on top
+/// and the title with X11 on Linux (see `bq_x11`), and the page's title on WASM. `window_title` is
+/// the one it opened with, to find it by. Called by [`register_app!`](crate::register_app) before
+/// making the app, so it can use them straight away.
+pub fn install_window_backends(window_title: &str) {
+    #[cfg(target_os = "linux")]
+    crate::bq_x11::install_backends(window_title);
+    #[cfg(target_arch = "wasm32")]
+    crate::bq_window_title::install_document_title_backend();
+    #[cfg(not(target_os = "linux"))]
+    let _ = window_title;
+}
+
 pub(crate) fn is_initialized() -> bool {
     Lazy::get(&INIT).is_some()
 }
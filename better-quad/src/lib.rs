@@ -1,11 +1,47 @@
 //! Better abstractions and utilities than macroquad ("mq").
+pub(crate) mod app;
+pub(crate) mod bq_always_on_top;
+pub(crate) mod bq_audio;
+pub(crate) mod bq_autosave;
+pub(crate) mod bq_backend;
+pub(crate) mod bq_button;
+pub(crate) mod bq_capture;
+pub(crate) mod bq_chart;
 pub(crate) mod bq_circle;
 pub(crate) mod bq_color;
+pub(crate) mod bq_config;
+pub(crate) mod bq_export;
 pub(crate) mod bq_fps;
+pub(crate) mod bq_frame_budget;
+pub(crate) mod bq_graphemes;
+pub(crate) mod bq_idle;
+pub(crate) mod bq_input_map;
 pub(crate) mod bq_keys;
+pub(crate) mod bq_kv;
+pub(crate) mod bq_monotonic;
+pub(crate) mod bq_net;
+pub(crate) mod bq_particles;
+pub(crate) mod bq_pointer;
 pub(crate) mod bq_rand;
+pub(crate) mod bq_results;
+pub(crate) mod bq_rumble;
+pub(crate) mod bq_safe_area;
+pub(crate) mod bq_save_slots;
+pub(crate) mod bq_snapshot;
+pub(crate) mod bq_stopwatch;
+pub(crate) mod bq_storage;
+pub(crate) mod bq_streamer;
+pub(crate) mod bq_tasks;
 pub(crate) mod bq_text;
+pub(crate) mod bq_text_fit;
+pub(crate) mod bq_text_input;
 pub(crate) mod bq_timestamp;
+pub(crate) mod bq_toast;
+pub(crate) mod bq_tooltip;
+pub(crate) mod bq_tutorial;
+pub(crate) mod bq_window_title;
+#[cfg(target_os = "linux")]
+pub(crate) mod bq_x11;
 pub(crate) mod init;
 pub(crate) mod stateful_gui;
 
@@ -13,16 +49,49 @@ pub mod utils;
 
 pub use macroquad::prelude as mq;
 
-pub use init::initialize_engine;
+pub use app::{handle_cli_args, AppInfo};
+pub use init::{initialize_engine, install_window_backends};
 pub use stateful_gui::{run_gui, run_gui_default, StatefulGui};
 
 pub mod bq {
     //! prelude
+    pub use crate::bq_always_on_top::*;
+    pub use crate::bq_audio::*;
+    pub use crate::bq_autosave::*;
+    pub use crate::bq_button::*;
+    pub use crate::bq_capture::FrameCaptureConf;
+    pub use crate::bq_chart::*;
     pub use crate::bq_circle::*;
     pub use crate::bq_color::*;
+    pub use crate::bq_config::*;
+    pub use crate::bq_export::*;
     pub use crate::bq_fps::*;
+    pub use crate::bq_frame_budget::*;
+    pub use crate::bq_graphemes::*;
+    pub use crate::bq_idle::*;
+    pub use crate::bq_input_map::*;
     pub use crate::bq_keys::*;
+    pub use crate::bq_kv::*;
+    pub use crate::bq_monotonic::*;
+    pub use crate::bq_net::*;
+    pub use crate::bq_particles::*;
+    pub use crate::bq_pointer::*;
     pub use crate::bq_rand::*;
+    pub use crate::bq_results::*;
+    pub use crate::bq_rumble::*;
+    pub use crate::bq_safe_area::*;
+    pub use crate::bq_save_slots::*;
+    pub use crate::bq_snapshot::*;
+    pub use crate::bq_stopwatch::*;
+    pub use crate::bq_storage::*;
+    pub use crate::bq_streamer::*;
+    pub use crate::bq_tasks::*;
     pub use crate::bq_text::*;
+    pub use crate::bq_text_fit::*;
+    pub use crate::bq_text_input::*;
     pub use crate::bq_timestamp::*;
+    pub use crate::bq_toast::*;
+    pub use crate::bq_tooltip::*;
+    pub use crate::bq_tutorial::*;
+    pub use crate::bq_window_title::*;
 }
@@ -1,4 +1,7 @@
+use crate::bq_capture::{FrameCaptureConf, FrameRecorder};
+use crate::bq_frame_budget::{FrameBudgetConf, FrameProfiler, FrameTimes};
 use crate::bq_timestamp::Timestamp;
+use crate::bq_toast::{ToastKind, Toasts};
 use crate::init;
 use crate::mq;
 
@@ -9,9 +12,24 @@ pub trait StatefulGui {
         mq::Conf::default()
     }
 
+    /// Opt in to saving the last few seconds as a GIF on hotkey press. See `bq_capture`.
+    fn frame_capture_conf() -> Option<FrameCaptureConf> {
+        None
+    }
+
+    /// Opt in to timing every frame's `update()` and `draw()`, and warning about slow ones. See
+    /// `bq_frame_budget`. Off unless the `BQ_FRAME_BUDGET_MS` env var is set.
+    fn frame_budget_conf() -> Option<FrameBudgetConf> {
+        FrameBudgetConf::from_env()
+    }
+
     // Both called once per frame.
     fn update(&mut self, now: Timestamp);
     fn draw(&self);
+
+    /// Called once when the window is closed, e.g. to save anything unsaved. The app quits right
+    /// after.
+    fn on_exit(&mut self) {}
 }
 
 pub async fn run_gui<T: StatefulGui>(mut gui: T) {
@@ -20,13 +38,62 @@ pub async fn run_gui<T: StatefulGui>(mut gui: T) {
         "Must call initialize_engine() before running any app"
     );
 
+    let mut frame_recorder =
+        T::frame_capture_conf().map(|conf| FrameRecorder::new(conf, &T::main_conf().window_title));
+    let mut frame_profiler =
+        T::frame_budget_conf().map(|conf| FrameProfiler::new(conf, &T::main_conf().window_title));
+    // For the framework's own messages, drawn over the app.
+    let mut toasts = Toasts::new();
+    // So `on_exit()` gets a chance to run
+    mq::prevent_quit();
+
     loop {
-        gui.update(Timestamp::now());
+        if mq::is_quit_requested() {
+            gui.on_exit();
+            return;
+        }
+        let now = Timestamp::now();
+        gui.update(now);
+        let updated_at = Timestamp::now();
+        toasts.tick(now);
         gui.draw();
+        if let Some(frame_profiler) = &mut frame_profiler {
+            let drawn_at = Timestamp::now();
+            let times = FrameTimes {
+                update: updated_at.duration_since(now).unwrap_or_default(),
+                draw: drawn_at.duration_since(updated_at).unwrap_or_default(),
+            };
+            frame_profiler.record(times, drawn_at);
+        }
+
+        if let Some(frame_recorder) = &mut frame_recorder {
+            // Capture before drawing toasts, so the "saving..." toast doesn't end up in the GIF.
+            frame_recorder.capture_frame_if_due(now);
+            update_frame_recorder(frame_recorder, &mut toasts);
+        }
+        toasts.draw();
+        if let Some(frame_profiler) = &frame_profiler {
+            frame_profiler.draw_warning(now);
+        }
+
         mq::next_frame().await;
     }
 }
 
+fn update_frame_recorder(frame_recorder: &mut FrameRecorder, toasts: &mut Toasts) {
+    if frame_recorder.save_requested() {
+        match frame_recorder.start_save() {
+            Ok(()) => toasts.push(ToastKind::Info, "Saving capture..."),
+            Err(e) => toasts.push(ToastKind::Error, format!("Can't save capture: {e}")),
+        }
+    }
+    match frame_recorder.poll_save_result() {
+        Some(Ok(path)) => toasts.push(ToastKind::Info, format!("Saved {}", path.display())),
+        Some(Err(e)) => toasts.push(ToastKind::Error, format!("Failed to save capture: {e}")),
+        None => {}
+    }
+}
+
 pub async fn run_gui_default<T: StatefulGui + Default>() {
     run_gui(T::default()).await
 }
@@ -96,14 +96,54 @@ impl SmoothColorAnimation {
         let transition_percent = self.animation_ticker.animation_percent();
 
         let transition = self.color_transitions.current();
-        let start = transition.start;
-        let end = transition.end;
-
-        mq::Color {
-            r: start.r + transition_percent * (end.r - start.r),
-            g: start.g + transition_percent * (end.g - start.g),
-            b: start.b + transition_percent * (end.b - start.b),
-            a: start.a + transition_percent * (end.a - start.a),
-        }
+        lerp_color(transition.start, transition.end, transition_percent)
+    }
+}
+
+/// Linear interpolation between 2 colors. `percent` is `[0, 1]`.
+pub fn lerp_color(start: mq::Color, end: mq::Color, percent: f32) -> mq::Color {
+    mq::Color {
+        r: start.r + percent * (end.r - start.r),
+        g: start.g + percent * (end.g - start.g),
+        b: start.b + percent * (end.b - start.b),
+        a: start.a + percent * (end.a - start.a),
+    }
+}
+
+/// Color at `percent` along evenly spaced color stops, like a CSS linear-gradient. `percent` is
+/// clamped to `[0, 1]`, where 0 is the first stop and 1 is the last.
+pub fn gradient_color(stops: &[mq::Color], percent: f32) -> mq::Color {
+    assert!(!stops.is_empty(), "gradient needs at least 1 color");
+    if stops.len() == 1 {
+        return stops[0];
+    }
+
+    let num_segments = stops.len() - 1;
+    let position = percent.clamp(0.0, 1.0) * num_segments as f32;
+    let segment = (position as usize).min(num_segments - 1);
+    lerp_color(
+        stops[segment],
+        stops[segment + 1],
+        position - segment as f32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::gradient_color;
+    use crate::mq;
+
+    #[test]
+    fn test_gradient_color() {
+        let stops = [mq::RED, mq::YELLOW, mq::GREEN];
+        assert_eq!(mq::RED, gradient_color(&stops, 0.0));
+        assert_eq!(mq::RED, gradient_color(&stops, -1.0));
+        assert_eq!(mq::YELLOW, gradient_color(&stops, 0.5));
+        assert_eq!(mq::GREEN, gradient_color(&stops, 1.0));
+        assert_eq!(mq::GREEN, gradient_color(&stops, 2.0));
+
+        let quarter = gradient_color(&stops, 0.25);
+        let expected_green = (mq::RED.g + mq::YELLOW.g) / 2.0;
+        assert!((quarter.g - expected_green).abs() < 1e-6, "{quarter:?}");
     }
 }
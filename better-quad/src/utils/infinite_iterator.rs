@@ -1,4 +1,8 @@
-/// Infinite repeating iterator around a Vec<T>. Immutable.
+use std::collections::BTreeSet;
+
+/// Infinite repeating iterator around a Vec<T>. Items can be added, removed, and moved around, but
+/// it's never empty. It can go either way round, e.g. for games where the turn order reverses.
+/// Other items can be active at the same time as the current one, e.g. for simultaneous turns.
 ///
 /// # Panic
 ///
@@ -7,6 +11,10 @@ pub struct InfiniteIterator<T> {
     items: Vec<T>,
     // Invariant: `current_index` is always a valid index into `items`.
     current_index: usize,
+    // Invariant: valid indices into `items`, never including `current_index`.
+    also_active: BTreeSet<usize>,
+    // Whether `advance` goes towards the start instead of the end
+    reversed: bool,
 }
 
 impl<T> InfiniteIterator<T> {
@@ -18,6 +26,8 @@ impl<T> InfiniteIterator<T> {
         Self {
             items,
             current_index: 0,
+            also_active: BTreeSet::new(),
+            reversed: false,
         }
     }
 
@@ -29,13 +39,218 @@ impl<T> InfiniteIterator<T> {
         &mut self.items[self.current_index]
     }
 
-    pub fn advance(&mut self) {
-        self.current_index = (self.current_index + 1) % self.items.len();
+    /// Returns whether it came back round to the start, e.g. to count rounds of a game. That's
+    /// going from the last item to the first, or the first to the last while reversed.
+    pub fn advance(&mut self) -> bool {
+        let from = self.current_index;
+        self.move_current((self.current_index + self.forward_step()) % self.items.len());
+        self.wrapped_from(from)
+    }
+
+    /// The opposite of [`Self::advance`].
+    pub fn go_back(&mut self) {
+        self.move_current((self.current_index + self.backward_step()) % self.items.len());
+    }
+
+    /// Flips which way [`Self::advance`] goes. The items stay where they are.
+    pub fn reverse(&mut self) {
+        self.reversed = !self.reversed;
+    }
+
+    pub fn is_reversed(&self) -> bool {
+        self.reversed
+    }
+
+    /// Steps are added, so going backwards is going most of the way round.
+    fn forward_step(&self) -> usize {
+        if self.reversed {
+            self.items.len() - 1
+        } else {
+            1
+        }
+    }
+
+    fn backward_step(&self) -> usize {
+        self.items.len() - self.forward_step()
+    }
+
+    /// Like [`Self::advance`], but past any items `skip` is true for (e.g. players sitting out).
+    /// Comes back around to the current item if every other one is skipped, which goes past the
+    /// start too.
+    pub fn advance_skipping(&mut self, skip: impl Fn(&T) -> bool) -> bool {
+        let from = self.current_index;
+        self.step_skipping(self.forward_step(), skip);
+        self.wrapped_from(from)
+    }
+
+    /// Whether a step forwards from `from` to the current index went past the start.
+    fn wrapped_from(&self, from: usize) -> bool {
+        if self.reversed {
+            self.current_index >= from
+        } else {
+            self.current_index <= from
+        }
+    }
+
+    /// Like [`Self::go_back`], but past any items `skip` is true for.
+    pub fn go_back_skipping(&mut self, skip: impl Fn(&T) -> bool) {
+        self.step_skipping(self.backward_step(), skip);
+    }
+
+    fn step_skipping(&mut self, step: usize, skip: impl Fn(&T) -> bool) {
+        let len = self.items.len();
+        let index = (1..=len)
+            .map(|i| (self.current_index + step * i) % len)
+            .find(|index| !skip(&self.items[*index]))
+            .unwrap_or(self.current_index);
+        self.move_current(index);
+    }
+
+    /// Makes the item at `index` current, e.g. when play jumps out of order. Does nothing if it's
+    /// out of bounds.
+    pub fn set_current(&mut self, index: usize) {
+        if index < self.items.len() {
+            self.move_current(index);
+        }
+    }
+
+    /// The item that was current is no longer active, but any others active alongside it stay
+    /// active.
+    fn move_current(&mut self, index: usize) {
+        self.current_index = index;
+        self.also_active.remove(&index);
+    }
+
+    /// Whether the item at `index` is current, or active alongside it.
+    pub fn is_active(&self, index: usize) -> bool {
+        index == self.current_index || self.also_active.contains(&index)
+    }
+
+    /// Makes the item at `index` active alongside the current one, or not. The current item is
+    /// always active, so does nothing for it, or if `index` is out of bounds.
+    pub fn set_active(&mut self, index: usize, active: bool) {
+        if index == self.current_index || index >= self.items.len() {
+            return;
+        }
+        if active {
+            self.also_active.insert(index);
+        } else {
+            self.also_active.remove(&index);
+        }
+    }
+
+    /// Every active item's index in order, the current one included.
+    pub fn active_indices(&self) -> Vec<usize> {
+        (0..self.items.len())
+            .filter(|index| self.is_active(*index))
+            .collect()
+    }
+
+    /// The indices of the items active alongside the current one, in order.
+    pub fn also_active_indices(&self) -> Vec<usize> {
+        self.also_active.iter().copied().collect()
+    }
+
+    /// Leaves only the current item active.
+    pub fn clear_also_active(&mut self) {
+        self.also_active.clear();
+    }
+
+    /// Moves every index in `also_active` to `map` of it, dropping those it gives None for (or
+    /// that end up current), for when items move around.
+    fn remap_also_active(&mut self, map: impl Fn(usize) -> Option<usize>) {
+        self.also_active = self
+            .also_active
+            .iter()
+            .filter_map(|index| map(*index))
+            .filter(|index| *index != self.current_index)
+            .collect();
     }
 
     pub fn raw(&self) -> (&Vec<T>, usize) {
         (&self.items, self.current_index)
     }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.items.get_mut(index)
+    }
+
+    /// Adds to the end, i.e. just before the first item comes around again.
+    pub fn push(&mut self, item: T) {
+        self.items.push(item);
+    }
+
+    /// Inserts before `index`, or at the end if it's out of bounds. The current item stays current.
+    pub fn insert(&mut self, index: usize, item: T) {
+        let index = index.min(self.items.len());
+        self.items.insert(index, item);
+        let shifted = |i: usize| if index <= i { i + 1 } else { i };
+        self.current_index = shifted(self.current_index);
+        self.remap_also_active(|i| Some(shifted(i)));
+    }
+
+    /// Moves the item at `from` to `to`, shifting the ones in between along. The current item stays
+    /// current, and active ones active, wherever they end up. Does nothing if either index is out
+    /// of bounds.
+    pub fn move_item(&mut self, from: usize, to: usize) {
+        if from >= self.items.len() || to >= self.items.len() {
+            return;
+        }
+        let item = self.items.remove(from);
+        self.items.insert(to, item);
+        let moved = |i: usize| {
+            if i == from {
+                to
+            } else if from < i && i <= to {
+                i - 1
+            } else if to <= i && i < from {
+                i + 1
+            } else {
+                i
+            }
+        };
+        self.current_index = moved(self.current_index);
+        self.remap_also_active(|i| Some(moved(i)));
+    }
+
+    /// Swaps two items. The current item stays current, and active ones active, wherever they end
+    /// up. Does nothing if either index is out of bounds.
+    pub fn swap(&mut self, a: usize, b: usize) {
+        if a >= self.items.len() || b >= self.items.len() {
+            return;
+        }
+        self.items.swap(a, b);
+        let swapped = |i: usize| {
+            if i == a {
+                b
+            } else if i == b {
+                a
+            } else {
+                i
+            }
+        };
+        self.current_index = swapped(self.current_index);
+        self.remap_also_active(|i| Some(swapped(i)));
+    }
+
+    /// None if `index` is out of bounds, or it's the only item left. Removing the current item
+    /// makes the one after it current.
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.items.len() || self.items.len() == 1 {
+            return None;
+        }
+        let item = self.items.remove(index);
+        if index < self.current_index {
+            self.current_index -= 1;
+        }
+        self.current_index %= self.items.len();
+        self.remap_also_active(|i| match i.cmp(&index) {
+            std::cmp::Ordering::Less => Some(i),
+            std::cmp::Ordering::Equal => None,
+            std::cmp::Ordering::Greater => Some(i - 1),
+        });
+        Some(item)
+    }
 }
 
 impl<T> From<Vec<T>> for InfiniteIterator<T> {
@@ -49,3 +264,176 @@ impl<T: Clone> From<&[T]> for InfiniteIterator<T> {
         Self::from_vec(value.to_vec())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::InfiniteIterator;
+
+    #[test]
+    fn test_remove() {
+        let mut iterator = InfiniteIterator::from(vec!['a', 'b', 'c', 'd']);
+        iterator.advance();
+        iterator.advance();
+        assert_eq!(None, iterator.remove(4));
+
+        // Before the current item
+        assert_eq!(Some('a'), iterator.remove(0));
+        assert_eq!('c', *iterator.current());
+        // The current item, so the next one's up
+        assert_eq!(Some('c'), iterator.remove(1));
+        assert_eq!('d', *iterator.current());
+        // The current last item, so it wraps around
+        assert_eq!(Some('d'), iterator.remove(1));
+        assert_eq!('b', *iterator.current());
+
+        assert_eq!(None, iterator.remove(0));
+        iterator.push('e');
+        assert_eq!((&vec!['b', 'e'], 0), iterator.raw());
+    }
+
+    #[test]
+    fn test_swap() {
+        let mut iterator = InfiniteIterator::from(vec!['a', 'b', 'c']);
+        iterator.advance();
+        iterator.swap(1, 2);
+        assert_eq!((&vec!['a', 'c', 'b'], 2), iterator.raw());
+        // Others around it don't change who's current
+        iterator.swap(0, 1);
+        assert_eq!((&vec!['c', 'a', 'b'], 2), iterator.raw());
+        iterator.swap(0, 3);
+        assert_eq!('b', *iterator.current());
+    }
+
+    #[test]
+    fn test_insert_and_move() {
+        let mut iterator = InfiniteIterator::from(vec!['a', 'b', 'c']);
+        iterator.advance();
+        iterator.insert(0, 'z');
+        assert_eq!((&vec!['z', 'a', 'b', 'c'], 2), iterator.raw());
+        // After the current item, and out of bounds
+        iterator.insert(3, 'y');
+        iterator.insert(10, 'x');
+        assert_eq!((&vec!['z', 'a', 'b', 'y', 'c', 'x'], 2), iterator.raw());
+
+        // The current item moving, and others moving past it both ways
+        iterator.move_item(2, 0);
+        assert_eq!((&vec!['b', 'z', 'a', 'y', 'c', 'x'], 0), iterator.raw());
+        iterator.move_item(5, 0);
+        assert_eq!((&vec!['x', 'b', 'z', 'a', 'y', 'c'], 1), iterator.raw());
+        iterator.move_item(0, 5);
+        assert_eq!((&vec!['b', 'z', 'a', 'y', 'c', 'x'], 0), iterator.raw());
+        iterator.move_item(0, 6);
+        assert_eq!('b', *iterator.current());
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mut iterator = InfiniteIterator::from(vec!['a', 'b', 'c']);
+        iterator.advance();
+        iterator.reverse();
+        assert!(iterator.is_reversed());
+        assert_eq!('b', *iterator.current());
+        iterator.advance();
+        assert_eq!('a', *iterator.current());
+        iterator.advance();
+        assert_eq!('c', *iterator.current());
+        iterator.go_back();
+        assert_eq!('a', *iterator.current());
+        iterator.advance_skipping(|c| *c == 'c');
+        assert_eq!('b', *iterator.current());
+        iterator.reverse();
+        iterator.advance();
+        assert_eq!('c', *iterator.current());
+    }
+
+    #[test]
+    fn test_skipping() {
+        let mut iterator = InfiniteIterator::from(vec!['a', 'b', 'c', 'd']);
+        let vowels = |c: &char| "aeiou".contains(*c);
+        iterator.advance_skipping(|c| *c == 'b');
+        assert_eq!('c', *iterator.current());
+        iterator.go_back_skipping(|c| *c == 'b');
+        assert_eq!('a', *iterator.current());
+        iterator.go_back_skipping(vowels);
+        assert_eq!('d', *iterator.current());
+
+        // Everyone else is skipped, so it comes back around
+        iterator.advance_skipping(|c| *c != 'd');
+        assert_eq!('d', *iterator.current());
+        iterator.advance_skipping(|_| true);
+        assert_eq!('d', *iterator.current());
+    }
+
+    #[test]
+    fn test_wrap_around() {
+        let mut iterator = InfiniteIterator::from(vec!['a', 'b', 'c']);
+        assert!(!iterator.advance());
+        assert!(!iterator.advance());
+        assert!(iterator.advance());
+        assert_eq!('a', *iterator.current());
+        // Skipping past the start still wraps
+        iterator.advance();
+        assert!(iterator.advance_skipping(|c| *c == 'c'));
+        assert_eq!('a', *iterator.current());
+        // The other way round, it's from the first to the last
+        iterator.reverse();
+        assert!(iterator.advance());
+        assert_eq!('c', *iterator.current());
+        assert!(!iterator.advance_skipping(|c| *c == 'b'));
+        // Nobody else to go to is a whole round
+        assert!(iterator.advance_skipping(|c| *c != 'a'));
+    }
+
+    #[test]
+    fn test_set_current() {
+        let mut iterator = InfiniteIterator::from(vec!['a', 'b', 'c']);
+        iterator.set_current(2);
+        assert_eq!('c', *iterator.current());
+        iterator.set_current(3);
+        assert_eq!('c', *iterator.current());
+        iterator.advance();
+        assert_eq!('a', *iterator.current());
+    }
+
+    #[test]
+    fn test_also_active() {
+        let mut iterator = InfiniteIterator::from(vec!['a', 'b', 'c', 'd', 'e']);
+        iterator.set_active(2, true);
+        iterator.set_active(3, true);
+        iterator.set_active(0, false);
+        iterator.set_active(5, true);
+        assert_eq!(vec![0, 2, 3], iterator.active_indices());
+        assert_eq!(vec![2, 3], iterator.also_active_indices());
+
+        // They follow their items around
+        iterator.insert(0, 'z');
+        assert_eq!(vec![1, 3, 4], iterator.active_indices());
+        iterator.swap(3, 5);
+        assert_eq!(vec![1, 4, 5], iterator.active_indices());
+        iterator.move_item(5, 0);
+        assert_eq!(vec!['c', 'z', 'a', 'b', 'e', 'd'], *iterator.raw().0);
+        assert_eq!(vec![0, 2, 5], iterator.active_indices());
+        assert_eq!(Some('d'), iterator.remove(5));
+        assert_eq!(vec![0, 2], iterator.active_indices());
+
+        // Moving on to one that's active leaves the rest, but not the one that was current
+        iterator.set_current(0);
+        assert_eq!('c', *iterator.current());
+        assert_eq!(vec![0], iterator.active_indices());
+        iterator.set_active(3, true);
+        iterator.clear_also_active();
+        assert!(!iterator.is_active(3));
+        assert!(iterator.is_active(0));
+    }
+
+    #[test]
+    fn test_go_back() {
+        let mut iterator = InfiniteIterator::from(vec!['a', 'b', 'c']);
+        iterator.go_back();
+        assert_eq!('c', *iterator.current());
+        iterator.advance();
+        iterator.advance();
+        iterator.go_back();
+        assert_eq!('a', *iterator.current());
+    }
+}
@@ -1,22 +1,19 @@
 use better_quad::bq::{TextAlignment, TextAnchorPoint};
 use better_quad::{
     bq::{self, TextBackground, Timestamp},
-    mq, StatefulGui,
+    mq, AppInfo, StatefulGui,
 };
 
-fn window_conf() -> mq::Conf {
-    BugRepro::main_conf()
-}
-
-#[macroquad::main(window_conf)]
-async fn main() {
-    better_quad::initialize_engine();
-    better_quad::run_gui_default::<BugRepro>().await
-}
+better_quad::register_app!(BugRepro);
 
 #[derive(Default)]
 struct BugRepro;
 
+impl AppInfo for BugRepro {
+    const NAME: &'static str = "bq-text-debug";
+    const DESCRIPTION: &'static str = "Text drawing examples, for debugging better-quad's text.";
+}
+
 impl StatefulGui for BugRepro {
     fn main_conf() -> mq::Conf {
         mq::Conf {
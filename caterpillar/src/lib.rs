@@ -2,7 +2,7 @@ use better_quad::bq::TextAlignment;
 use better_quad::{bq, mq};
 use better_quad::{
     bq::{FpsCounter, Timestamp},
-    StatefulGui,
+    AppInfo, StatefulGui,
 };
 use std::collections::LinkedList;
 use std::ops::Add;
@@ -80,6 +80,11 @@ impl Default for SnakeGameState {
     }
 }
 
+impl AppInfo for SnakeGameState {
+    const NAME: &'static str = "Caterpillar";
+    const DESCRIPTION: &'static str = "Modified snake example.";
+}
+
 impl StatefulGui for SnakeGameState {
     fn update(&mut self, now: Timestamp) {
         evaluate_game(self, now);
@@ -106,59 +111,43 @@ fn evaluate_game(state: &mut SnakeGameState, now: Timestamp) {
     if !state.snake.next_dir_locked {
         // check for change direction
         match dir_key_down {
-            Some(Direction::Right) => {
-                if state.snake.next_dir != Direction::Left {
-                    state.snake.next_dir = Direction::Right;
-                    state.snake.next_dir_locked = true;
-                }
+            Some(Direction::Right) if state.snake.next_dir != Direction::Left => {
+                state.snake.next_dir = Direction::Right;
+                state.snake.next_dir_locked = true;
             }
-            Some(Direction::Left) => {
-                if state.snake.next_dir != Direction::Right {
-                    state.snake.next_dir = Direction::Left;
-                    state.snake.next_dir_locked = true;
-                }
+            Some(Direction::Left) if state.snake.next_dir != Direction::Right => {
+                state.snake.next_dir = Direction::Left;
+                state.snake.next_dir_locked = true;
             }
-            Some(Direction::Up) => {
-                if state.snake.next_dir != Direction::Down {
-                    state.snake.next_dir = Direction::Up;
-                    state.snake.next_dir_locked = true;
-                }
+            Some(Direction::Up) if state.snake.next_dir != Direction::Down => {
+                state.snake.next_dir = Direction::Up;
+                state.snake.next_dir_locked = true;
             }
-            Some(Direction::Down) => {
-                if state.snake.next_dir != Direction::Up {
-                    state.snake.next_dir = Direction::Down;
-                    state.snake.next_dir_locked = true;
-                }
+            Some(Direction::Down) if state.snake.next_dir != Direction::Up => {
+                state.snake.next_dir = Direction::Down;
+                state.snake.next_dir_locked = true;
             }
-            None => {}
+            _ => {}
         }
     }
 
     // Store the queued direction if the next move is already locked in
     if state.snake.next_dir_locked {
         match dir_key_down {
-            Some(Direction::Right) => {
+            Some(Direction::Right) if state.snake.next_dir != Direction::Left => {
                 // TODO refactor both this and above to not allow re-sending the same input (QoL)
-                if state.snake.next_dir != Direction::Left {
-                    state.snake.queued_dir = Some(Direction::Right);
-                }
+                state.snake.queued_dir = Some(Direction::Right);
             }
-            Some(Direction::Left) => {
-                if state.snake.next_dir != Direction::Right {
-                    state.snake.queued_dir = Some(Direction::Left);
-                }
+            Some(Direction::Left) if state.snake.next_dir != Direction::Right => {
+                state.snake.queued_dir = Some(Direction::Left);
             }
-            Some(Direction::Up) => {
-                if state.snake.next_dir != Direction::Down {
-                    state.snake.queued_dir = Some(Direction::Up);
-                }
+            Some(Direction::Up) if state.snake.next_dir != Direction::Down => {
+                state.snake.queued_dir = Some(Direction::Up);
             }
-            Some(Direction::Down) => {
-                if state.snake.next_dir != Direction::Up {
-                    state.snake.queued_dir = Some(Direction::Down);
-                }
+            Some(Direction::Down) if state.snake.next_dir != Direction::Up => {
+                state.snake.queued_dir = Some(Direction::Down);
             }
-            None => {}
+            _ => {}
         }
     }
 
@@ -0,0 +1,12 @@
+// better-quad's plugin for miniquad's loader: what apps need from the page. Load it after
+// mq_js_bundle.js and before load().
+miniquad_add_plugin({
+    name: "bq",
+    version: 1,
+    register_plugin: function (importObject) {
+        // For bq::set_window_title()
+        importObject.env.bq_set_document_title = function (ptr, len) {
+            document.title = UTF8ToString(ptr, len);
+        };
+    },
+});
@@ -0,0 +1,60 @@
+//! How long listing candidate passwords takes, from the classic rules up to the biggest ones that
+//! can be listed. Run with `cargo bench -p mastermind --features benchmarks`.
+//!
+//! A frame is about 16ms, so anything listed while the game is running should stay well under
+//! that: either all at once for small rule sets, or a chunk at a time (see `list_chunk`).
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use mastermind::benchmarks::CandidateSearch;
+
+/// (slots, colors), from the classic rules to 9 colors, the biggest palette.
+const BOARDS: [(usize, usize); 4] = [(4, 6), (5, 8), (6, 8), (6, 9)];
+const NUM_GUESSES: [usize; 2] = [0, 3];
+/// Passwords listed per frame to compare.
+const CHUNK_SIZES: [usize; 3] = [10_000, 50_000, 100_000];
+
+fn list_all(c: &mut Criterion) {
+    let mut group = c.benchmark_group("list_all");
+    group.sample_size(10);
+    for (num_slots, num_colors) in BOARDS {
+        for num_guesses in NUM_GUESSES {
+            let search = CandidateSearch::new(num_slots, num_colors, num_guesses);
+            group.bench_with_input(
+                BenchmarkId::new(format!("{num_slots}x{num_colors}"), num_guesses),
+                &search,
+                |b, search| b.iter(|| search.list_all()),
+            );
+        }
+    }
+    group.finish();
+}
+
+fn list_chunk(c: &mut Criterion) {
+    let mut group = c.benchmark_group("list_chunk");
+    for (num_slots, num_colors) in BOARDS {
+        let search = CandidateSearch::new(num_slots, num_colors, 3);
+        for chunk_size in CHUNK_SIZES {
+            group.bench_with_input(
+                BenchmarkId::new(format!("{num_slots}x{num_colors}"), chunk_size),
+                &chunk_size,
+                |b, chunk_size| b.iter(|| search.list_chunk(*chunk_size)),
+            );
+        }
+    }
+    group.finish();
+}
+
+fn narrow_adversarially(c: &mut Criterion) {
+    let mut group = c.benchmark_group("narrow_adversarially");
+    group.sample_size(10);
+    // Psychic mode only lists everything at once, which the bigger boards are too big for.
+    for (num_slots, num_colors) in [(4, 6), (5, 8)] {
+        let search = CandidateSearch::new(num_slots, num_colors, 0);
+        group.bench_function(format!("{num_slots}x{num_colors}"), |b| {
+            b.iter(|| search.narrow_adversarially())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, list_all, list_chunk, narrow_adversarially);
+criterion_main!(benches);
@@ -0,0 +1,223 @@
+//! One-off milestones, like winning in 3 guesses, unlocked at the end of a game and persisted
+//! between runs. Only games that count towards stats can unlock them.
+use crate::rules;
+use crate::stats::GameResult;
+use crate::{CompleteRow, STORAGE};
+use better_quad::bq::{self, TextAlignment, TextAnchorPoint, TextBackground, UtcDate};
+use better_quad::mq;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const ACHIEVEMENTS_FILE_NAME: &str = "achievements";
+const ACHIEVEMENTS_FONT_SIZE: u16 = 22;
+const ACHIEVEMENTS_PADDING: f32 = 10.0;
+const QUICK_WIN_MAX_GUESSES: usize = 3;
+const LONG_STREAK: u32 = 10;
+const MANY_GAMES: u32 = 100;
+
+/// In the order they're listed.
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub(crate) enum Achievement {
+    FirstWin,
+    QuickWin,
+    NoRepeatsWin,
+    DailyWin,
+    HardModeWin,
+    TimedWin,
+    LongStreak,
+    ManyGames,
+}
+
+const ALL_ACHIEVEMENTS: [Achievement; 8] = [
+    Achievement::FirstWin,
+    Achievement::QuickWin,
+    Achievement::NoRepeatsWin,
+    Achievement::DailyWin,
+    Achievement::HardModeWin,
+    Achievement::TimedWin,
+    Achievement::LongStreak,
+    Achievement::ManyGames,
+];
+
+/// What's needed to know about a game that just ended, after it's been recorded in the stats.
+pub(crate) struct FinishedGame<'a> {
+    pub(crate) result: GameResult,
+    pub(crate) history: &'a [CompleteRow],
+    pub(crate) is_daily: bool,
+    pub(crate) hard_mode: bool,
+    pub(crate) timed: bool,
+    pub(crate) games_played: u32,
+    pub(crate) current_streak: u32,
+}
+
+impl Achievement {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::FirstWin => "Cracked it",
+            Self::QuickWin => "Mind reader",
+            Self::NoRepeatsWin => "Variety pack",
+            Self::DailyWin => "Daily driver",
+            Self::HardModeWin => "No shortcuts",
+            Self::TimedWin => "Beat the clock",
+            Self::LongStreak => "On a roll",
+            Self::ManyGames => "Regular",
+        }
+    }
+
+    fn description(self) -> String {
+        match self {
+            Self::FirstWin => "Win a game".to_string(),
+            Self::QuickWin => format!("Win in {QUICK_WIN_MAX_GUESSES} guesses or fewer"),
+            Self::NoRepeatsWin => "Win without repeating a color in any guess".to_string(),
+            Self::DailyWin => "Solve a daily puzzle".to_string(),
+            Self::HardModeWin => "Win in hard mode".to_string(),
+            Self::TimedWin => "Win with a time limit".to_string(),
+            Self::LongStreak => format!("Win {LONG_STREAK} games in a row"),
+            Self::ManyGames => format!("Play {MANY_GAMES} games"),
+        }
+    }
+
+    fn is_earned_by(self, game: &FinishedGame) -> bool {
+        let won = matches!(game.result, GameResult::Won { .. });
+        match self {
+            Self::FirstWin => won,
+            Self::QuickWin => game
+                .result
+                .num_guesses_to_win()
+                .is_some_and(|num_guesses| num_guesses <= QUICK_WIN_MAX_GUESSES),
+            Self::NoRepeatsWin => {
+                won && !game
+                    .history
+                    .iter()
+                    .any(|row| rules::has_duplicates(&row.guess))
+            }
+            Self::DailyWin => won && game.is_daily,
+            Self::HardModeWin => won && game.hard_mode,
+            Self::TimedWin => won && game.timed,
+            Self::LongStreak => game.current_streak >= LONG_STREAK,
+            Self::ManyGames => game.games_played >= MANY_GAMES,
+        }
+    }
+}
+
+/// Unlocked achievements, persisted between runs.
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub(crate) struct Achievements {
+    // UTC day number each was unlocked on
+    unlocked: BTreeMap<Achievement, u64>,
+}
+
+impl Achievements {
+    pub(crate) fn load() -> Self {
+        STORAGE.load_or_default(ACHIEVEMENTS_FILE_NAME)
+    }
+
+    pub(crate) fn save(&self) {
+        STORAGE.save_or_log(ACHIEVEMENTS_FILE_NAME, self);
+    }
+
+    /// Returns the ones unlocked for the first time.
+    pub(crate) fn record_game(&mut self, game: &FinishedGame, day_number: u64) -> Vec<Achievement> {
+        let newly_unlocked = self.unlock(game, day_number);
+        if !newly_unlocked.is_empty() {
+            self.save();
+        }
+        newly_unlocked
+    }
+
+    fn unlock(&mut self, game: &FinishedGame, day_number: u64) -> Vec<Achievement> {
+        let newly_unlocked = ALL_ACHIEVEMENTS
+            .into_iter()
+            .filter(|achievement| !self.unlocked.contains_key(achievement))
+            .filter(|achievement| achievement.is_earned_by(game))
+            .collect::<Vec<_>>();
+        for achievement in &newly_unlocked {
+            self.unlocked.insert(*achievement, day_number);
+        }
+        newly_unlocked
+    }
+
+    /// Window centered overlay, with locked ones greyed out.
+    pub(crate) fn draw(&self, footer: &str) {
+        let mut text = format!(
+            "Achievements ({} of {})\n\n",
+            self.unlocked.len(),
+            ALL_ACHIEVEMENTS.len()
+        );
+        for achievement in ALL_ACHIEVEMENTS {
+            let status = match self.unlocked.get(&achievement) {
+                Some(day_number) => format!("unlocked {}", UtcDate::from_day_number(*day_number)),
+                None => "locked".to_string(),
+            };
+            text.push_str(&format!(
+                "{} ({status})\n  {}\n",
+                achievement.name(),
+                achievement.description()
+            ));
+        }
+        text.push('\n');
+        text.push_str(footer);
+
+        bq::draw_text(
+            text,
+            TextAlignment::Left,
+            None,
+            ACHIEVEMENTS_FONT_SIZE,
+            mq::WHITE,
+            TextAnchorPoint::window_centered(),
+            Some(TextBackground {
+                color: mq::Color::new(0.0, 0.0, 0.0, 0.85),
+                x_padding: ACHIEVEMENTS_PADDING,
+                y_padding: ACHIEVEMENTS_PADDING,
+            }),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Achievement, Achievements, FinishedGame};
+    use crate::stats::GameResult;
+    use crate::{evaluate_guess, Color};
+
+    #[test]
+    fn test_unlocks() {
+        let password = [Color::Red, Color::Red, Color::Green, Color::Blue];
+        let history = vec![
+            evaluate_guess(
+                vec![Color::Red, Color::Orange, Color::Yellow, Color::Green],
+                &password,
+            ),
+            evaluate_guess(password.to_vec(), &password),
+        ];
+        let game = FinishedGame {
+            result: GameResult::Won { num_guesses: 2 },
+            history: &history,
+            is_daily: false,
+            hard_mode: false,
+            timed: false,
+            games_played: 1,
+            current_streak: 1,
+        };
+        let mut achievements = Achievements::default();
+        // The winning guess repeats red
+        assert_eq!(
+            vec![Achievement::FirstWin, Achievement::QuickWin],
+            achievements.unlock(&game, 1)
+        );
+        // Only once
+        assert_eq!(Vec::<Achievement>::new(), achievements.unlock(&game, 2));
+
+        let game = FinishedGame {
+            result: GameResult::Lost,
+            is_daily: true,
+            hard_mode: true,
+            games_played: 100,
+            current_streak: 0,
+            ..game
+        };
+        assert_eq!(vec![Achievement::ManyGames], achievements.unlock(&game, 3));
+        assert_eq!(Some(&1), achievements.unlocked.get(&Achievement::FirstWin));
+    }
+}
@@ -0,0 +1,443 @@
+//! Campaign: a ladder of levels, from 3 slots and 4 colors up to 6 slots and all 9, with up to 3
+//! stars per level for how few guesses it took. Each level unlocks once the one before it is won,
+//! and the best stars are persisted between runs. Levels are picked on a map.
+//!
+//! Progress can also be saved to named slots from the map, e.g. one per person on a shared
+//! computer, and loaded back over the current progress.
+use crate::rules::RuleSet;
+use crate::STORAGE;
+use better_quad::bq::{self, SaveSlots, TextAlignment, TextAnchorPoint, TextBackground};
+use better_quad::mq;
+use serde::{Deserialize, Serialize};
+
+const CAMPAIGN_FILE_NAME: &str = "campaign";
+pub(crate) const CAMPAIGN_SLOTS: SaveSlots = SaveSlots::new(STORAGE, "campaign");
+pub(crate) const KEY_SAVE_SLOT: mq::KeyCode = mq::KeyCode::S;
+pub(crate) const KEY_BROWSE_SLOTS: mq::KeyCode = mq::KeyCode::L;
+pub(crate) const MAX_STARS: u8 = 3;
+
+const MAP_DIM_COLOR: mq::Color = mq::Color::new(0.0, 0.0, 0.0, 0.85);
+const MAP_COLUMNS: usize = 4;
+const MAP_NODE_RADIUS: f32 = 26.0;
+const MAP_NODE_SPACING: f32 = 110.0;
+const MAP_PATH_THICKNESS: f32 = 6.0;
+const MAP_SELECTION_THICKNESS: f32 = 4.0;
+const MAP_STAR_RADIUS: f32 = 8.0;
+const MAP_FONT_SIZE: u16 = 24;
+const MAP_TITLE_FONT_SIZE: u16 = 32;
+const MAP_PADDING: f32 = 10.0;
+const MAP_UNLOCKED_COLOR: mq::Color = mq::Color::new(0.2, 0.45, 0.8, 1.0);
+const MAP_CLEARED_COLOR: mq::Color = mq::Color::new(0.15, 0.6, 0.3, 1.0);
+const MAP_LOCKED_COLOR: mq::Color = mq::Color::new(0.3, 0.3, 0.3, 1.0);
+const STAR_EMPTY_COLOR: mq::Color = mq::Color::new(0.4, 0.4, 0.4, 1.0);
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) struct CampaignLevel {
+    pub(crate) num_slots: usize,
+    pub(crate) num_colors: usize,
+    pub(crate) num_guesses: usize,
+}
+
+/// Easiest first. Each step adds a color or a slot, or takes away a guess.
+pub(crate) const CAMPAIGN_LEVELS: [CampaignLevel; 12] = [
+    CampaignLevel::new(3, 4, 8),
+    CampaignLevel::new(3, 5, 8),
+    CampaignLevel::new(4, 4, 8),
+    CampaignLevel::new(4, 5, 8),
+    CampaignLevel::new(4, 6, 8),
+    CampaignLevel::new(4, 6, 7),
+    CampaignLevel::new(5, 6, 10),
+    CampaignLevel::new(5, 7, 10),
+    CampaignLevel::new(5, 8, 10),
+    CampaignLevel::new(6, 8, 12),
+    CampaignLevel::new(6, 9, 12),
+    CampaignLevel::new(6, 9, 10),
+];
+
+impl CampaignLevel {
+    const fn new(num_slots: usize, num_colors: usize, num_guesses: usize) -> Self {
+        Self {
+            num_slots,
+            num_colors,
+            num_guesses,
+        }
+    }
+
+    /// `index` is from 0, but levels are named from 1.
+    pub(crate) fn rules(self, index: usize) -> RuleSet {
+        RuleSet {
+            name: level_name(index),
+            num_slots: self.num_slots,
+            num_colors: self.num_colors,
+            num_guesses: self.num_guesses,
+            time_limit_secs: None,
+            blanks: false,
+        }
+    }
+
+    /// For a win in `num_guesses`: 3 within one guess of the number of slots, 2 within three, and
+    /// 1 for any win.
+    pub(crate) fn stars(self, num_guesses: usize) -> u8 {
+        if num_guesses <= self.num_slots + 1 {
+            3
+        } else if num_guesses <= self.num_slots + 3 {
+            2
+        } else {
+            1
+        }
+    }
+
+    pub(crate) fn description(self) -> String {
+        format!(
+            "{} slots, {} colors, {} guesses",
+            self.num_slots, self.num_colors, self.num_guesses
+        )
+    }
+}
+
+pub(crate) fn level_name(index: usize) -> String {
+    format!("Level {}", index + 1)
+}
+
+/// Best stars per level, persisted between runs. 0 (or missing) is not won yet.
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub(crate) struct CampaignProgress {
+    best_stars: Vec<u8>,
+}
+
+impl CampaignProgress {
+    pub(crate) fn load() -> Self {
+        STORAGE.load_or_default(CAMPAIGN_FILE_NAME)
+    }
+
+    pub(crate) fn stars(&self, index: usize) -> u8 {
+        self.best_stars.get(index).copied().unwrap_or(0)
+    }
+
+    pub(crate) fn total_stars(&self) -> u32 {
+        self.best_stars.iter().map(|stars| u32::from(*stars)).sum()
+    }
+
+    /// The first level, and any after a won one.
+    pub(crate) fn is_unlocked(&self, index: usize) -> bool {
+        index < CAMPAIGN_LEVELS.len() && (index == 0 || self.stars(index - 1) > 0)
+    }
+
+    /// The furthest unlocked level, where the map starts.
+    pub(crate) fn furthest_unlocked(&self) -> usize {
+        (0..CAMPAIGN_LEVELS.len())
+            .rev()
+            .find(|index| self.is_unlocked(*index))
+            .unwrap_or(0)
+    }
+
+    /// Keeps the best, and saves if it's better. Returns whether it is.
+    pub(crate) fn record_win(&mut self, index: usize, stars: u8) -> bool {
+        if stars <= self.stars(index) {
+            return false;
+        }
+        if self.best_stars.len() <= index {
+            self.best_stars.resize(index + 1, 0);
+        }
+        self.best_stars[index] = stars;
+        self.save();
+        true
+    }
+
+    /// As the current progress, e.g. after loading a slot over it.
+    pub(crate) fn save(&self) {
+        STORAGE.save_or_log(CAMPAIGN_FILE_NAME, self);
+    }
+}
+
+/// The level select map, open over the board.
+pub(crate) struct CampaignMap {
+    pub(crate) selected: usize,
+}
+
+pub(crate) enum MapAction {
+    Play(usize),
+    SaveSlot,
+    BrowseSlots,
+    Close,
+}
+
+impl CampaignMap {
+    pub(crate) fn new(progress: &CampaignProgress) -> Self {
+        Self {
+            selected: progress.furthest_unlocked(),
+        }
+    }
+
+    /// Arrow keys move along the path, enter or a click plays an unlocked level.
+    pub(crate) fn update(&mut self, progress: &CampaignProgress) -> Option<MapAction> {
+        if mq::is_key_pressed(mq::KeyCode::Escape) {
+            return Some(MapAction::Close);
+        }
+        if mq::is_key_pressed(KEY_SAVE_SLOT) {
+            return Some(MapAction::SaveSlot);
+        }
+        if mq::is_key_pressed(KEY_BROWSE_SLOTS) {
+            return Some(MapAction::BrowseSlots);
+        }
+        for (key, step) in [
+            (mq::KeyCode::Right, 1),
+            (mq::KeyCode::Down, 1),
+            (mq::KeyCode::Left, -1),
+            (mq::KeyCode::Up, -1),
+        ] {
+            if mq::is_key_pressed(key) {
+                self.selected = step_selection(self.selected, step);
+            }
+        }
+        if mq::is_key_pressed(mq::KeyCode::Enter) && progress.is_unlocked(self.selected) {
+            return Some(MapAction::Play(self.selected));
+        }
+        if mq::is_mouse_button_pressed(mq::MouseButton::Left) {
+            let (mouse_x, mouse_y) = mq::mouse_position();
+            let origin = map_origin();
+            let clicked = (0..CAMPAIGN_LEVELS.len()).find(|index| {
+                let (x, y) = node_center(*index, origin);
+                mq::vec2(x - mouse_x, y - mouse_y).length() <= MAP_NODE_RADIUS
+            });
+            if let Some(index) = clicked {
+                self.selected = index;
+                if progress.is_unlocked(index) {
+                    return Some(MapAction::Play(index));
+                }
+            }
+        }
+        None
+    }
+
+    pub(crate) fn draw(&self, progress: &CampaignProgress, footer: &str) {
+        mq::draw_rectangle(
+            0.0,
+            0.0,
+            mq::screen_width(),
+            mq::screen_height(),
+            MAP_DIM_COLOR,
+        );
+        let origin = map_origin();
+
+        bq::draw_text(
+            format!(
+                "Campaign ({} of {} stars)",
+                progress.total_stars(),
+                CAMPAIGN_LEVELS.len() as u32 * u32::from(MAX_STARS)
+            ),
+            TextAlignment::Left,
+            None,
+            MAP_TITLE_FONT_SIZE,
+            mq::WHITE,
+            TextAnchorPoint::BottomCenter {
+                x: origin.0 + map_width() / 2.0,
+                y: origin.1 - MAP_NODE_RADIUS - MAP_PADDING * 2.0,
+            },
+            None,
+        );
+
+        // The path first, so the nodes cover its ends
+        for index in 1..CAMPAIGN_LEVELS.len() {
+            let (x1, y1) = node_center(index - 1, origin);
+            let (x2, y2) = node_center(index, origin);
+            let color = if progress.is_unlocked(index) {
+                mq::WHITE
+            } else {
+                MAP_LOCKED_COLOR
+            };
+            mq::draw_line(x1, y1, x2, y2, MAP_PATH_THICKNESS, color);
+        }
+
+        for index in 0..CAMPAIGN_LEVELS.len() {
+            let (x, y) = node_center(index, origin);
+            let stars = progress.stars(index);
+            let (color, label) = if !progress.is_unlocked(index) {
+                (MAP_LOCKED_COLOR, "-".to_string())
+            } else if stars > 0 {
+                (MAP_CLEARED_COLOR, (index + 1).to_string())
+            } else {
+                (MAP_UNLOCKED_COLOR, (index + 1).to_string())
+            };
+            bq::draw_circle(x, y, MAP_NODE_RADIUS, color);
+            if index == self.selected {
+                bq::draw_circle_outline(
+                    x,
+                    y,
+                    MAP_NODE_RADIUS + MAP_SELECTION_THICKNESS,
+                    MAP_SELECTION_THICKNESS,
+                    mq::GOLD,
+                );
+            }
+            bq::draw_text(
+                label,
+                TextAlignment::Left,
+                None,
+                MAP_FONT_SIZE,
+                mq::WHITE,
+                TextAnchorPoint::Center { x, y },
+                None,
+            );
+            for star in 0..MAX_STARS {
+                let star_x = x + (f32::from(star) - 1.0) * MAP_STAR_RADIUS * 2.2;
+                let star_color = if star < stars {
+                    mq::GOLD
+                } else {
+                    STAR_EMPTY_COLOR
+                };
+                draw_star(
+                    star_x,
+                    y + MAP_NODE_RADIUS + MAP_STAR_RADIUS + 4.0,
+                    MAP_STAR_RADIUS,
+                    star_color,
+                );
+            }
+        }
+
+        let selected = CAMPAIGN_LEVELS[self.selected];
+        let status = if progress.is_unlocked(self.selected) {
+            selected.description()
+        } else {
+            format!("Win {} to unlock", level_name(self.selected - 1))
+        };
+        let (_, last_y) = node_center(CAMPAIGN_LEVELS.len() - 1, origin);
+        bq::draw_text(
+            format!("{}: {status}\n\n{footer}", level_name(self.selected)),
+            TextAlignment::Center,
+            None,
+            MAP_FONT_SIZE,
+            mq::WHITE,
+            TextAnchorPoint::TopCenter {
+                x: origin.0 + map_width() / 2.0,
+                y: last_y + MAP_NODE_RADIUS + MAP_STAR_RADIUS * 2.0 + MAP_PADDING * 3.0,
+            },
+            Some(TextBackground {
+                color: mq::BLACK,
+                x_padding: MAP_PADDING,
+                y_padding: MAP_PADDING,
+            }),
+        );
+    }
+}
+
+/// Along the path, stopping at either end.
+fn step_selection(selected: usize, step: isize) -> usize {
+    selected
+        .saturating_add_signed(step)
+        .min(CAMPAIGN_LEVELS.len() - 1)
+}
+
+fn map_width() -> f32 {
+    MAP_NODE_SPACING * (MAP_COLUMNS - 1) as f32
+}
+
+/// Center of the first node, so the map's centered in the safe area.
+fn map_origin() -> (f32, f32) {
+    let area = bq::safe_area();
+    let num_rows = CAMPAIGN_LEVELS.len().div_ceil(MAP_COLUMNS);
+    let height = MAP_NODE_SPACING * (num_rows - 1) as f32;
+    (
+        area.center().x - map_width() / 2.0,
+        area.center().y - height / 2.0,
+    )
+}
+
+/// Rows of `MAP_COLUMNS`, snaking back and forth so the path never jumps.
+fn node_center(index: usize, origin: (f32, f32)) -> (f32, f32) {
+    let row = index / MAP_COLUMNS;
+    let column = if row.is_multiple_of(2) {
+        index % MAP_COLUMNS
+    } else {
+        MAP_COLUMNS - 1 - index % MAP_COLUMNS
+    };
+    (
+        origin.0 + MAP_NODE_SPACING * column as f32,
+        origin.1 + MAP_NODE_SPACING * row as f32,
+    )
+}
+
+/// Five pointed, point up.
+pub(crate) fn draw_star(x: f32, y: f32, radius: f32, color: mq::Color) {
+    let center = mq::vec2(x, y);
+    let point = |i: usize| {
+        let point_radius = if i.is_multiple_of(2) {
+            radius
+        } else {
+            radius * 0.45
+        };
+        let angle = (-90.0 + 36.0 * i as f32).to_radians();
+        center + mq::vec2(angle.cos(), angle.sin()) * point_radius
+    };
+    for i in 0..10 {
+        mq::draw_triangle(center, point(i), point(i + 1), color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{node_center, step_selection, CampaignProgress, CAMPAIGN_LEVELS, MAP_COLUMNS};
+    use crate::rules::NUM_SLOTS_RANGE;
+    use crate::ALL_COLORS;
+
+    #[test]
+    fn test_levels_ramp_up() {
+        assert_eq!(
+            (3, 4),
+            (CAMPAIGN_LEVELS[0].num_slots, CAMPAIGN_LEVELS[0].num_colors)
+        );
+        for pair in CAMPAIGN_LEVELS.windows(2) {
+            let (easier, harder) = (pair[0], pair[1]);
+            assert!(harder.num_slots >= easier.num_slots, "{harder:?}");
+            assert!(
+                harder.num_slots > easier.num_slots
+                    || harder.num_colors > easier.num_colors
+                    || harder.num_guesses < easier.num_guesses,
+                "{harder:?} isn't harder than {easier:?}"
+            );
+        }
+        for level in CAMPAIGN_LEVELS {
+            assert!(NUM_SLOTS_RANGE.contains(&level.num_slots));
+            assert!(level.num_colors <= ALL_COLORS.len());
+        }
+    }
+
+    #[test]
+    fn test_stars() {
+        let level = CAMPAIGN_LEVELS[0];
+        assert_eq!(3, level.stars(1));
+        assert_eq!(3, level.stars(4));
+        assert_eq!(2, level.stars(6));
+        assert_eq!(1, level.stars(7));
+    }
+
+    #[test]
+    fn test_unlocking() {
+        let mut progress = CampaignProgress::default();
+        assert!(progress.is_unlocked(0));
+        assert!(!progress.is_unlocked(1));
+        assert_eq!(0, progress.furthest_unlocked());
+
+        progress.best_stars = vec![2, 0, 1];
+        assert!(progress.is_unlocked(1));
+        assert!(!progress.is_unlocked(2));
+        assert!(progress.is_unlocked(3));
+        assert_eq!(3, progress.furthest_unlocked());
+        assert_eq!(3, progress.total_stars());
+        assert!(!progress.is_unlocked(CAMPAIGN_LEVELS.len()));
+    }
+
+    #[test]
+    fn test_map_path() {
+        assert_eq!(0, step_selection(0, -1));
+        assert_eq!(
+            CAMPAIGN_LEVELS.len() - 1,
+            step_selection(CAMPAIGN_LEVELS.len() - 1, 1)
+        );
+        // The end of one row is right above the start of the next
+        let end_of_row = node_center(MAP_COLUMNS - 1, (0.0, 0.0));
+        let start_of_next = node_center(MAP_COLUMNS, (0.0, 0.0));
+        assert_eq!(end_of_row.0, start_of_next.0);
+        assert!(start_of_next.1 > end_of_row.1);
+    }
+}
@@ -0,0 +1,123 @@
+//! Optional count of the passwords still possible given the feedback so far.
+//!
+//! The candidates are kept between guesses, so each guess only filters what's left instead of
+//! starting over from every password. Big rule sets are listed a chunk per frame, so the count
+//! climbs for a moment at the start of a game.
+use crate::candidates::Candidates;
+use crate::rules::{DuplicateColors, RuleSet};
+use crate::CompleteRow;
+
+/// A few milliseconds per frame on a desktop, see the benchmarks.
+const CHUNK_SIZE: usize = 50_000;
+
+pub(crate) struct CandidateCounter {
+    rules: RuleSet,
+    duplicate_colors: DuplicateColors,
+    /// None if there are too many passwords to list.
+    candidates: Option<Candidates>,
+    /// How many rows of history the candidates have been filtered by.
+    num_rows: usize,
+}
+
+impl CandidateCounter {
+    pub(crate) fn new(rules: &RuleSet, duplicate_colors: DuplicateColors) -> Self {
+        Self {
+            rules: rules.clone(),
+            duplicate_colors,
+            candidates: Candidates::unlisted(rules, duplicate_colors),
+            num_rows: 0,
+        }
+    }
+
+    /// Call every frame. Filters by any new guesses, and lists another chunk. Starts over if the
+    /// rules changed, or guesses went away (a new game, or an undo).
+    pub(crate) fn update(
+        &mut self,
+        rules: &RuleSet,
+        duplicate_colors: DuplicateColors,
+        history: &[CompleteRow],
+    ) {
+        if *rules != self.rules
+            || duplicate_colors != self.duplicate_colors
+            || history.len() < self.num_rows
+        {
+            *self = Self::new(rules, duplicate_colors);
+        }
+        if let Some(candidates) = &mut self.candidates {
+            for row in &history[self.num_rows..] {
+                candidates.retain_consistent(row);
+            }
+            candidates.list_more(CHUNK_SIZE);
+        }
+        self.num_rows = history.len();
+    }
+
+    /// None until every password's been checked.
+    fn count(&self) -> Option<usize> {
+        self.candidates
+            .as_ref()
+            .filter(|candidates| candidates.is_fully_listed())
+            .map(Candidates::len)
+    }
+
+    pub(crate) fn text(&self) -> String {
+        match (&self.candidates, self.count()) {
+            (None, _) => "Passwords left: too many to count".to_string(),
+            (Some(_), Some(1)) => "Passwords left: 1, you've got it!".to_string(),
+            (Some(_), Some(count)) => format!("Passwords left: {count}"),
+            (Some(candidates), None) => {
+                format!("Passwords left: {}+ (counting...)", candidates.len())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CandidateCounter;
+    use crate::rules::{DuplicateColors, RuleSet};
+    use crate::{evaluate_guess, Color};
+
+    #[test]
+    fn test_counts_as_guesses_come_in() {
+        let rules = RuleSet::classic();
+        let password = [Color::Red, Color::Orange, Color::Yellow, Color::Green];
+        let mut history = Vec::new();
+        let mut counter = CandidateCounter::new(&rules, DuplicateColors::Allowed);
+        counter.update(&rules, DuplicateColors::Allowed, &history);
+        assert_eq!(Some(6usize.pow(4)), counter.count());
+
+        // No blue, then no purple either
+        history.push(evaluate_guess(vec![Color::Blue; 4], &password));
+        counter.update(&rules, DuplicateColors::Allowed, &history);
+        assert_eq!(Some(5usize.pow(4)), counter.count());
+        history.push(evaluate_guess(vec![Color::Purple; 4], &password));
+        counter.update(&rules, DuplicateColors::Allowed, &history);
+        assert_eq!(Some(4usize.pow(4)), counter.count());
+
+        // Taking a guess back starts over
+        history.pop();
+        counter.update(&rules, DuplicateColors::Allowed, &history);
+        assert_eq!(Some(5usize.pow(4)), counter.count());
+        // So do new rules
+        counter.update(&rules, DuplicateColors::Forbidden, &history);
+        assert_eq!(Some(5 * 4 * 3 * 2), counter.count());
+    }
+
+    #[test]
+    fn test_big_rule_sets_are_counted_over_frames() {
+        let rules = RuleSet {
+            num_slots: 6,
+            num_colors: 9,
+            ..RuleSet::classic()
+        };
+        let mut counter = CandidateCounter::new(&rules, DuplicateColors::Allowed);
+        counter.update(&rules, DuplicateColors::Allowed, &[]);
+        assert_eq!(None, counter.count());
+        assert!(counter.text().contains("counting"));
+        while counter.count().is_none() {
+            counter.update(&rules, DuplicateColors::Allowed, &[]);
+        }
+        assert_eq!(Some(9usize.pow(6)), counter.count());
+    }
+}
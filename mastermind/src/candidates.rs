@@ -0,0 +1,321 @@
+//! The passwords still possible given the guesses so far.
+//!
+//! Big rule sets have too many passwords to list in one frame, so they can be listed a chunk at a
+//! time with [`Candidates::list_more`]. Feedback can be added at any point: passwords already
+//! listed are filtered right away, and the rest as they're listed.
+use crate::rules::{DuplicateColors, RuleSet};
+use crate::{count_hits, Color, CompleteRow};
+use std::collections::HashMap;
+
+/// Listing every password at once for anything bigger would take more than a frame.
+const MAX_CANDIDATES_AT_ONCE: usize = 100_000;
+/// Listing every password for the biggest rule sets would take too long even a chunk at a time,
+/// and too much memory (8 slots of 9 colors is 43 million passwords). 6 slots of 9 colors fits.
+const MAX_CANDIDATES: usize = 600_000;
+
+pub(crate) struct Candidates {
+    palette: &'static [Color],
+    num_slots: usize,
+    duplicate_colors: DuplicateColors,
+    /// Passwords listed so far that fit `history`, back to back.
+    listed: Vec<Color>,
+    /// Every password has an index: count up in base `palette.len()`, with each digit picking a
+    /// color. Passwords from this index on haven't been listed yet.
+    next_index: usize,
+    num_passwords: usize,
+    history: Vec<CompleteRow>,
+}
+
+impl Candidates {
+    /// Every password the rules allow, with none listed yet. None if there are too many to list.
+    pub(crate) fn unlisted(rules: &RuleSet, duplicate_colors: DuplicateColors) -> Option<Self> {
+        let palette = rules.palette();
+        let num_passwords = palette
+            .len()
+            .checked_pow(rules.num_slots as u32)
+            .filter(|num_passwords| *num_passwords <= MAX_CANDIDATES)?;
+        Some(Self {
+            palette,
+            num_slots: rules.num_slots,
+            duplicate_colors: duplicate_colors.effective_for(rules),
+            listed: Vec::new(),
+            next_index: 0,
+            num_passwords,
+            history: Vec::new(),
+        })
+    }
+
+    /// Every password the rules allow. None if there are too many to list at once.
+    pub(crate) fn all(rules: &RuleSet, duplicate_colors: DuplicateColors) -> Option<Self> {
+        Self::consistent_with(rules, duplicate_colors, &[])
+    }
+
+    /// Passwords that would have given the feedback in `history`. None if there are too many to
+    /// list at once.
+    pub(crate) fn consistent_with(
+        rules: &RuleSet,
+        duplicate_colors: DuplicateColors,
+        history: &[CompleteRow],
+    ) -> Option<Self> {
+        let mut candidates = Self::unlisted(rules, duplicate_colors)
+            .filter(|candidates| candidates.num_passwords <= MAX_CANDIDATES_AT_ONCE)?;
+        for row in history {
+            candidates.retain_consistent(row);
+        }
+        candidates.list_more(usize::MAX);
+        Some(candidates)
+    }
+
+    /// Lists up to `max_passwords` more passwords (counting ones that don't fit the feedback).
+    /// Returns whether they're all listed now.
+    pub(crate) fn list_more(&mut self, max_passwords: usize) -> bool {
+        let end = self
+            .next_index
+            .saturating_add(max_passwords)
+            .min(self.num_passwords);
+        let mut password = Vec::with_capacity(self.num_slots);
+        for index in self.next_index..end {
+            password.clear();
+            let mut n = index;
+            for _ in 0..self.num_slots {
+                password.push(self.palette[n % self.palette.len()]);
+                n /= self.palette.len();
+            }
+            if self.duplicate_colors.allows(&password)
+                && self.history.iter().all(|row| is_consistent(&password, row))
+            {
+                self.listed.extend_from_slice(&password);
+            }
+        }
+        self.next_index = end;
+        self.is_fully_listed()
+    }
+
+    pub(crate) fn is_fully_listed(&self) -> bool {
+        self.next_index == self.num_passwords
+    }
+
+    /// How many passwords are left out of those listed so far.
+    pub(crate) fn len(&self) -> usize {
+        self.listed.len() / self.num_slots
+    }
+
+    pub(crate) fn first(&self) -> Option<&[Color]> {
+        self.passwords().next()
+    }
+
+    fn passwords(&self) -> impl Iterator<Item = &[Color]> {
+        self.listed.chunks_exact(self.num_slots)
+    }
+
+    /// Keep only passwords that would have given `row`'s feedback, including ones listed later.
+    pub(crate) fn retain_consistent(&mut self, row: &CompleteRow) {
+        self.retain(|password| is_consistent(password, row));
+        self.history.push(row.clone());
+    }
+
+    /// Absurdle style: picks the feedback for `guess` that keeps the most passwords possible, and
+    /// narrows down to those. Ties go to the least helpful feedback (fewest hits), so a correct
+    /// guess only wins once it's the last password standing. Only makes sense once every password
+    /// is listed.
+    pub(crate) fn narrow_adversarially(&mut self, guess: &[Color]) {
+        debug_assert!(self.is_fully_listed());
+        let mut bucket_sizes = HashMap::<(usize, usize), usize>::new();
+        for password in self.passwords() {
+            *bucket_sizes.entry(count_hits(guess, password)).or_default() += 1;
+        }
+        let Some(feedback) = bucket_sizes
+            .into_iter()
+            .max_by(|(feedback_a, size_a), (feedback_b, size_b)| {
+                size_a.cmp(size_b).then(feedback_b.cmp(feedback_a))
+            })
+            .map(|(feedback, _)| feedback)
+        else {
+            return;
+        };
+        self.retain(|password| count_hits(guess, password) == feedback);
+    }
+
+    /// Like `Vec::retain`, but for whole passwords.
+    fn retain(&mut self, mut keep: impl FnMut(&[Color]) -> bool) {
+        let mut num_kept = 0;
+        for i in 0..self.len() {
+            let range = i * self.num_slots..(i + 1) * self.num_slots;
+            if keep(&self.listed[range.clone()]) {
+                self.listed.copy_within(range, num_kept * self.num_slots);
+                num_kept += 1;
+            }
+        }
+        self.listed.truncate(num_kept * self.num_slots);
+    }
+}
+
+/// Whether `password` would have given `row`'s feedback.
+fn is_consistent(password: &[Color], row: &CompleteRow) -> bool {
+    count_hits(&row.guess, password) == (row.num_correct_hits, row.num_misplaced_hits)
+}
+
+/// For hard mode: the first row of `history` that rules out `password`, if any, and the feedback
+/// that row would have gotten if `password` were the real one.
+pub(crate) fn find_contradiction<'a>(
+    password: &[Color],
+    history: &'a [CompleteRow],
+) -> Option<(usize, &'a CompleteRow, (usize, usize))> {
+    history
+        .iter()
+        .enumerate()
+        .find(|(_, row)| !is_consistent(password, row))
+        .map(|(i, row)| (i, row, count_hits(&row.guess, password)))
+}
+
+/// Checks the consistency filter against the feedback `guesses` get for `password`: the password
+/// is never ruled out by its own feedback, and every password left would have gotten the same
+/// feedback. Panics otherwise. Shared by the property tests and the fuzz target.
+#[cfg(any(test, feature = "fuzzing"))]
+pub(crate) fn check_consistency_filter(
+    rules: &RuleSet,
+    password: &[Color],
+    guesses: &[Vec<Color>],
+) {
+    let history = guesses
+        .iter()
+        .map(|guess| crate::evaluate_guess(guess.clone(), password))
+        .collect::<Vec<_>>();
+    assert!(find_contradiction(password, &history).is_none());
+
+    let candidates = Candidates::consistent_with(rules, DuplicateColors::Allowed, &history)
+        .expect("rules small enough to list");
+    assert!(candidates
+        .passwords()
+        .any(|candidate| candidate == password));
+    for candidate in candidates.passwords() {
+        for row in &history {
+            assert_eq!(
+                count_hits(&row.guess, candidate),
+                count_hits(&row.guess, password),
+                "{candidate:?} left in for {password:?}"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_consistency_filter, find_contradiction, Candidates};
+    use crate::rules::{DuplicateColors, RuleSet};
+    use crate::{count_hits, evaluate_guess, Color};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_narrow_adversarially() {
+        let rules = RuleSet::classic();
+        let mut candidates = Candidates::all(&rules, DuplicateColors::Allowed).unwrap();
+        assert_eq!(6usize.pow(4), candidates.len());
+        let no_duplicates = Candidates::all(&rules, DuplicateColors::Forbidden).unwrap();
+        assert_eq!(6 * 5 * 4 * 3, no_duplicates.len());
+
+        // Worst case for a "1122" opener is that neither color is in the password: 4^4 left.
+        let guess = [Color::Red, Color::Red, Color::Orange, Color::Orange];
+        candidates.narrow_adversarially(&guess);
+        assert_eq!(4usize.pow(4), candidates.len());
+        assert_eq!((0, 0), count_hits(&guess, candidates.first().unwrap()));
+
+        // Down to one password, so guessing it finally wins.
+        let password = vec![Color::Blue; 4];
+        let history = vec![evaluate_guess(vec![Color::Blue; 4], &password)];
+        let mut candidates =
+            Candidates::consistent_with(&rules, DuplicateColors::Allowed, &history).unwrap();
+        candidates.narrow_adversarially(&password);
+        assert_eq!(Some(password.as_slice()), candidates.first());
+        assert_eq!(1, candidates.len());
+    }
+
+    #[test]
+    fn test_find_contradiction() {
+        let password = [Color::Red, Color::Orange, Color::Yellow, Color::Green];
+        let history = vec![
+            evaluate_guess(vec![Color::Red; 4], &password),
+            evaluate_guess(vec![Color::Blue; 4], &password),
+        ];
+
+        assert!(find_contradiction(&password, &history).is_none());
+        // No red, but the first guess found one.
+        let guess = [Color::Orange, Color::Orange, Color::Yellow, Color::Green];
+        let (i, _, would_have_gotten) = find_contradiction(&guess, &history).unwrap();
+        assert_eq!((0, (0, 0)), (i, would_have_gotten));
+        // Has a blue.
+        let guess = [Color::Red, Color::Orange, Color::Yellow, Color::Blue];
+        assert_eq!(1, find_contradiction(&guess, &history).unwrap().0);
+    }
+
+    proptest! {
+        #[test]
+        fn test_consistency_filter(
+            (num_colors, password, guesses) in (2..=6usize, 2..=4usize).prop_flat_map(
+                |(num_colors, num_slots)| {
+                    let colors = || vec(0..num_colors, num_slots);
+                    (Just(num_colors), colors(), vec(colors(), 0..=4))
+                }
+            )
+        ) {
+            let rules = RuleSet {
+                num_slots: password.len(),
+                num_colors,
+                ..RuleSet::classic()
+            };
+            let to_colors = |indices: &Vec<usize>| {
+                indices.iter().map(|i| rules.palette()[*i]).collect::<Vec<_>>()
+            };
+            check_consistency_filter(
+                &rules,
+                &to_colors(&password),
+                &guesses.iter().map(to_colors).collect::<Vec<_>>(),
+            );
+        }
+    }
+
+    #[test]
+    fn test_list_a_chunk_at_a_time() {
+        let rules = RuleSet::classic();
+        let password = [Color::Red, Color::Orange, Color::Yellow, Color::Green];
+        let history = [
+            evaluate_guess(
+                vec![Color::Red, Color::Red, Color::Blue, Color::Blue],
+                &password,
+            ),
+            evaluate_guess(vec![Color::Green; 4], &password),
+        ];
+        let all_at_once =
+            Candidates::consistent_with(&rules, DuplicateColors::Forbidden, &history).unwrap();
+
+        let mut chunked = Candidates::unlisted(&rules, DuplicateColors::Forbidden).unwrap();
+        chunked.retain_consistent(&history[0]);
+        assert!(!chunked.list_more(500));
+        assert!(!chunked.is_fully_listed());
+        // Feedback added partway through applies to passwords listed before and after
+        chunked.retain_consistent(&history[1]);
+        while !chunked.list_more(100) {}
+        assert!(chunked.passwords().eq(all_at_once.passwords()));
+    }
+
+    #[test]
+    fn test_too_many_candidates() {
+        let rules = RuleSet {
+            num_slots: 8,
+            num_colors: 9,
+            ..RuleSet::classic()
+        };
+        assert!(Candidates::all(&rules, DuplicateColors::Allowed).is_none());
+        assert!(Candidates::unlisted(&rules, DuplicateColors::Allowed).is_none());
+
+        // Too many to list at once, but fine a chunk at a time
+        let rules = RuleSet {
+            num_slots: 6,
+            num_colors: 9,
+            ..RuleSet::classic()
+        };
+        assert!(Candidates::all(&rules, DuplicateColors::Allowed).is_none());
+        assert!(Candidates::unlisted(&rules, DuplicateColors::Allowed).is_some());
+    }
+}
@@ -0,0 +1,71 @@
+//! Daily puzzle: everyone gets the same password each (UTC) day, and you only get one attempt.
+use crate::STORAGE;
+use better_quad::bq::UtcDate;
+use serde::{Deserialize, Serialize};
+
+const DAILY_FILE_NAME: &str = "daily";
+
+/// Day number of the first daily puzzle, so puzzle numbers start at #1 instead of #20000.
+const FIRST_DAILY_DAY_NUMBER: u64 = 20_300; // 2025-07-31
+
+/// Completion badge for the most recently completed daily puzzle, persisted between runs.
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub(crate) struct DailyRecord {
+    last_completion: Option<DailyCompletion>,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug)]
+pub(crate) struct DailyCompletion {
+    pub(crate) day_number: u64,
+    /// None if the puzzle was lost.
+    pub(crate) num_guesses_to_win: Option<usize>,
+}
+
+impl DailyRecord {
+    pub(crate) fn load() -> Self {
+        STORAGE.load_or_default(DAILY_FILE_NAME)
+    }
+
+    pub(crate) fn record_completion(&mut self, day_number: u64, num_guesses_to_win: Option<usize>) {
+        self.last_completion = Some(DailyCompletion {
+            day_number,
+            num_guesses_to_win,
+        });
+        STORAGE.save_or_log(DAILY_FILE_NAME, self);
+    }
+
+    pub(crate) fn completion_for_day(&self, day_number: u64) -> Option<DailyCompletion> {
+        self.last_completion
+            .filter(|completion| completion.day_number == day_number)
+    }
+}
+
+impl DailyCompletion {
+    pub(crate) fn badge_text(&self) -> String {
+        match self.num_guesses_to_win {
+            Some(num_guesses) => {
+                format!("{} solved in {num_guesses}!", puzzle_name(self.day_number))
+            }
+            None => format!("{} unsolved :(", puzzle_name(self.day_number)),
+        }
+    }
+}
+
+pub(crate) fn puzzle_name(day_number: u64) -> String {
+    format!(
+        "Daily #{} ({})",
+        day_number.saturating_sub(FIRST_DAILY_DAY_NUMBER) + 1,
+        UtcDate::from_day_number(day_number)
+    )
+}
+
+/// Deterministic seed for a day. Intentionally not using std hashers since their algorithm isn't
+/// guaranteed to be stable across Rust versions, and every build needs to agree on the password.
+pub(crate) fn seed_for_day(day_number: u64) -> u64 {
+    // splitmix64 finalizer
+    let mut z = day_number.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,97 @@
+//! Exporting a finished game as JSON, for sharing or analyzing it elsewhere: the rules, the
+//! password, and every guess with its feedback and when it was made.
+use crate::rules::{DuplicateColors, RuleSet};
+use crate::{Color, CompleteRow};
+use better_quad::bq::UtcDate;
+use serde::Serialize;
+use std::time::Duration;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+#[derive(Serialize, Debug)]
+pub(crate) struct GameExport<'a> {
+    rules: &'a RuleSet,
+    duplicate_colors: DuplicateColors,
+    /// Seed code for a random password, so the game can be replayed.
+    seed: Option<String>,
+    outcome: GameOutcome,
+    total_secs: Option<f64>,
+    password: &'a [Color],
+    guesses: Vec<GuessExport<'a>>,
+}
+
+#[derive(Serialize, Copy, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum GameOutcome {
+    Won,
+    Lost,
+    OutOfTime,
+    GaveUp,
+}
+
+#[derive(Serialize, Debug)]
+struct GuessExport<'a> {
+    colors: &'a [Color],
+    num_correct_hits: usize,
+    num_misplaced_hits: usize,
+    /// Seconds into the game it was submitted.
+    elapsed_secs: Option<f64>,
+    note: Option<&'a str>,
+}
+
+impl<'a> GameExport<'a> {
+    pub(crate) fn new(
+        rules: &'a RuleSet,
+        duplicate_colors: DuplicateColors,
+        seed: Option<String>,
+        outcome: GameOutcome,
+        total_time: Option<Duration>,
+        password: &'a [Color],
+        history: &'a [CompleteRow],
+    ) -> Self {
+        Self {
+            rules,
+            duplicate_colors,
+            seed,
+            outcome,
+            total_secs: total_time.map(|total_time| total_time.as_secs_f64()),
+            password,
+            guesses: history
+                .iter()
+                .map(|row| GuessExport {
+                    colors: &row.guess,
+                    num_correct_hits: row.num_correct_hits,
+                    num_misplaced_hits: row.num_misplaced_hits,
+                    elapsed_secs: row.elapsed.map(|elapsed| elapsed.as_secs_f64()),
+                    note: row.note.as_deref(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// e.g. "mastermind-2025-08-10-143005.json", with the UTC time so games exported the same day
+/// don't replace each other.
+pub(crate) fn file_name(unix_secs: u64) -> String {
+    let seconds_of_day = unix_secs % SECONDS_PER_DAY;
+    format!(
+        "mastermind-{}-{:02}{:02}{:02}.json",
+        UtcDate::from_day_number(unix_secs / SECONDS_PER_DAY),
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::file_name;
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(
+            "mastermind-2025-08-10-143005.json",
+            file_name(20_310 * 86_400 + 52_205)
+        );
+    }
+}
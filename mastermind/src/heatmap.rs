@@ -0,0 +1,114 @@
+//! Just-for-fun heatmap of where you click on the board over a session (not saved between runs).
+//!
+//! Clicks are drawn as soft blobs into a render target as they come in, so showing the heatmap is
+//! a single texture draw no matter how many clicks there are.
+use better_quad::mq;
+
+const BLOB_RADIUS: f32 = 22.0;
+const BLOB_LAYERS: usize = 5;
+const BLOB_LAYER_ALPHA: f32 = 0.06;
+
+pub(crate) struct ClickHeatmap {
+    area: mq::Rect,
+    // Relative to the area's top left
+    clicks: Vec<mq::Vec2>,
+    render_target: Option<mq::RenderTarget>,
+    num_rendered_clicks: usize,
+}
+
+impl ClickHeatmap {
+    /// Only clicks within `area` are tracked.
+    pub(crate) fn new(area: mq::Rect) -> Self {
+        Self {
+            area,
+            clicks: Vec::new(),
+            render_target: None,
+            num_rendered_clicks: 0,
+        }
+    }
+
+    pub(crate) fn record_click(&mut self, x: f32, y: f32) {
+        let point = mq::vec2(x, y);
+        if self.area.contains(point) {
+            self.clicks.push(point - self.area.point());
+        }
+    }
+
+    pub(crate) fn num_clicks(&self) -> usize {
+        self.clicks.len()
+    }
+
+    /// Draws clicks recorded since the last call into the texture. Call from update, before
+    /// `draw()`, since it changes the camera while drawing.
+    pub(crate) fn render_new_clicks(&mut self) {
+        if self.num_rendered_clicks == self.clicks.len() && self.render_target.is_some() {
+            return;
+        }
+
+        let render_target = self.render_target.get_or_insert_with(|| {
+            let render_target = mq::render_target(self.area.w as u32, self.area.h as u32);
+            render_target.texture.set_filter(mq::FilterMode::Linear);
+            render_target
+        });
+        let mut camera =
+            mq::Camera2D::from_display_rect(mq::Rect::new(0.0, 0.0, self.area.w, self.area.h));
+        camera.render_target = Some(render_target.clone());
+        mq::set_camera(&camera);
+
+        if self.num_rendered_clicks == 0 {
+            mq::clear_background(mq::Color::new(0.0, 0.0, 0.0, 0.0));
+        }
+        for click in &self.clicks[self.num_rendered_clicks..] {
+            // Smaller layers on top, so the center of each blob is the hottest.
+            for layer in 0..BLOB_LAYERS {
+                let layer_percent = layer as f32 / BLOB_LAYERS as f32;
+                mq::draw_circle(
+                    click.x,
+                    click.y,
+                    BLOB_RADIUS * (1.0 - layer_percent),
+                    mq::Color::new(1.0, 0.2 + 0.6 * layer_percent, 0.0, BLOB_LAYER_ALPHA),
+                );
+            }
+        }
+
+        mq::set_default_camera();
+        self.num_rendered_clicks = self.clicks.len();
+    }
+
+    /// Draws whatever has been rendered so far over the tracked area.
+    pub(crate) fn draw(&self) {
+        let Some(render_target) = &self.render_target else {
+            return;
+        };
+        mq::draw_texture_ex(
+            &render_target.texture,
+            self.area.x,
+            self.area.y,
+            mq::WHITE,
+            mq::DrawTextureParams {
+                dest_size: Some(self.area.size()),
+                // Render targets come out upside down with a screen-style (y down) camera.
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ClickHeatmap;
+    use better_quad::mq;
+
+    #[test]
+    fn test_only_clicks_in_area_are_recorded() {
+        let mut heatmap = ClickHeatmap::new(mq::Rect::new(20.0, 20.0, 100.0, 50.0));
+        heatmap.record_click(25.0, 30.0);
+        heatmap.record_click(119.0, 69.0);
+        heatmap.record_click(10.0, 30.0);
+        heatmap.record_click(25.0, 71.0);
+
+        assert_eq!(2, heatmap.num_clicks());
+        assert_eq!(mq::vec2(5.0, 10.0), heatmap.clicks[0]);
+    }
+}
@@ -0,0 +1,100 @@
+//! Overlay explaining the controls and feedback pegs, drawn over a dimmed board. Shown on first
+//! launch, since nothing else tells you space submits.
+use crate::theme::Theme;
+use better_quad::bq::{self, TextAlignment, TextAnchorPoint, TextBackground};
+use better_quad::mq;
+
+const HELP_FONT_SIZE: u16 = 25;
+const HELP_PADDING: f32 = 15.0;
+const DIM_COLOR: mq::Color = mq::Color::new(0.0, 0.0, 0.0, 0.6);
+/// Lines of `help_text()` that the example feedback pegs are drawn at the start of.
+const CORRECT_KEY_LINE: usize = 9;
+const MISPLACED_KEY_LINE: usize = CORRECT_KEY_LINE + 1;
+// Leaves room for the example peg
+const KEY_LINE_INDENT: &str = "      ";
+
+pub(crate) struct HelpKeys {
+    pub(crate) colors: String,
+    pub(crate) submit: String,
+    pub(crate) toggle_help: String,
+    pub(crate) start_tutorial: String,
+    /// Which mouse button, e.g. "right".
+    pub(crate) clear_button: String,
+}
+
+fn help_text(num_slots: usize, placing_hint: &str, keys: &HelpKeys) -> String {
+    format!(
+        "How to play\n\
+        Guess the secret code of {num_slots} colors.\n\
+        \n\
+        Pick colors with [{}] or the pegs\n\
+        below the board. {placing_hint}\n\
+        [{}] submits a full row.\n\
+        Clear a slot with {}-click.\n\
+        \n\
+        Each guess gets feedback pegs:\n\
+        {KEY_LINE_INDENT}right color, right spot\n\
+        {KEY_LINE_INDENT}right color, wrong spot\n\
+        They're in no particular order, so they\n\
+        don't say which of your pegs they mean.\n\
+        \n\
+        [{}] to close, or to show this again.\n\
+        [{}] for a step by step tutorial.",
+        keys.colors, keys.submit, keys.clear_button, keys.toggle_help, keys.start_tutorial,
+    )
+}
+
+/// `placing_hint` explains the input mode.
+pub(crate) fn draw(num_slots: usize, placing_hint: &str, keys: &HelpKeys, theme: &Theme) {
+    mq::draw_rectangle(0.0, 0.0, mq::screen_width(), mq::screen_height(), DIM_COLOR);
+
+    let container = bq::draw_text(
+        help_text(num_slots, placing_hint, keys),
+        TextAlignment::Left,
+        None,
+        HELP_FONT_SIZE,
+        theme.text,
+        TextAnchorPoint::window_centered(),
+        Some(TextBackground {
+            color: mq::Color {
+                a: 1.0,
+                ..theme.text_background
+            },
+            x_padding: HELP_PADDING,
+            y_padding: HELP_PADDING,
+        }),
+    );
+
+    // Example feedback pegs, on the board's color so they look like the real ones
+    let line_height = HELP_FONT_SIZE as f32;
+    let radius = line_height / 2.0 - 2.0;
+    let x = container.rect_x + container.text_padding_x + line_height / 2.0;
+    for (line, color) in [
+        (CORRECT_KEY_LINE, theme.correct_key),
+        (MISPLACED_KEY_LINE, theme.misplaced_key),
+    ] {
+        let y = container.rect_y + container.text_padding_y + line_height * (line as f32 + 0.5);
+        bq::draw_circle(x, y, line_height / 2.0, theme.board);
+        bq::draw_circle(x, y, radius, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{help_text, HelpKeys, CORRECT_KEY_LINE, MISPLACED_KEY_LINE};
+
+    #[test]
+    fn test_key_lines_line_up() {
+        let keys = HelpKeys {
+            colors: "1-6".to_string(),
+            submit: "space".to_string(),
+            toggle_help: "f1".to_string(),
+            start_tutorial: "t".to_string(),
+            clear_button: "right".to_string(),
+        };
+        let text = help_text(4, "Then click slots.", &keys);
+        let lines = text.lines().collect::<Vec<_>>();
+        assert!(lines[CORRECT_KEY_LINE].ends_with("right spot"));
+        assert!(lines[MISPLACED_KEY_LINE].ends_with("wrong spot"));
+    }
+}
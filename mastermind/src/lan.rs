@@ -0,0 +1,530 @@
+//! LAN games: one instance hosts, and another joins. They take turns as the codemaker, with a
+//! random password, and the codebreaker. Guesses go to the codemaker, which sends back the
+//! feedback, and reveals the password once the game's over. The codebreaker then makes the next
+//! password. The host starts as the codemaker.
+//!
+//! The codemaker's board shows the password and the guesses as they come in, so it's fun to
+//! watch. Codemakers score like a hotseat match (see `pvp`), for as long as both stay connected.
+use crate::pvp;
+use crate::rules::RuleSet;
+use crate::{Color, CompleteRow};
+use better_quad::bq::{self, ConfigFile, NetConnection, NetError, NetListener, Task, TaskPoll};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::net::{Ipv6Addr, SocketAddr};
+
+pub(crate) const LAN_PORT: u16 = 7878;
+/// Bump when messages change, so mismatched versions refuse to play instead of misbehaving.
+const PROTOCOL_VERSION: u32 = 2;
+pub(crate) const ADDRESS_INPUT_MAX_LEN: usize = 64;
+
+/// Colors are sent as indices into the palette.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub(crate) enum LanMessage {
+    /// Joiner to host, on connecting
+    Hello { version: u32 },
+    /// Host to joiner, instead of a game
+    Rejected { reason: String },
+    /// Codemaker to codebreaker: a new password is ready
+    NewGame { rules: RuleSet },
+    /// Codebreaker to codemaker
+    Guess { colors: Vec<usize> },
+    /// Codemaker to codebreaker, for the last guess
+    Feedback {
+        num_correct_hits: usize,
+        num_misplaced_hits: usize,
+    },
+    /// Codebreaker to codemaker: gave up or ran out of time
+    GameOver,
+    /// Codemaker to codebreaker, once the game's over
+    Reveal { password: Vec<usize> },
+}
+
+pub(crate) struct LanSession {
+    link: LanLink,
+    role: LanRole,
+    /// Yours, then theirs
+    scores: [usize; 2],
+}
+
+enum LanLink {
+    Hosting {
+        listener: NetListener,
+        /// Where to join, shown while waiting. Looked up once, since it takes a socket. None
+        /// without a network.
+        address: Option<String>,
+        // One at a time. Someone else can join after they leave.
+        peer: Option<NetConnection<LanMessage>>,
+    },
+    Connecting {
+        address: String,
+        task: Task<io::Result<NetConnection<LanMessage>>>,
+    },
+    Joined {
+        address: String,
+        host: NetConnection<LanMessage>,
+    },
+}
+
+enum LanRole {
+    Codemaker {
+        game_over: bool,
+    },
+    Codebreaker {
+        // Sent, waiting for feedback
+        pending_guess: Option<Vec<Color>>,
+        // Feedback for the pending guess, waiting to be put on the board
+        feedback: Option<CompleteRow>,
+        // Once the password's revealed, it's your turn to make one
+        revealed: bool,
+    },
+}
+
+impl LanRole {
+    fn codebreaker() -> Self {
+        Self::Codebreaker {
+            pending_guess: None,
+            feedback: None,
+            revealed: false,
+        }
+    }
+}
+
+impl LanSession {
+    pub(crate) fn host(port: u16) -> io::Result<Self> {
+        let listener = NetListener::bind(port)?;
+        let address = bq::local_ip()
+            .zip(listener.port().ok())
+            .map(|(ip, port)| format!("{ip}:{port}"));
+        Ok(Self {
+            link: LanLink::Hosting {
+                listener,
+                address,
+                peer: None,
+            },
+            role: LanRole::Codemaker { game_over: false },
+            scores: [0, 0],
+        })
+    }
+
+    /// Connects in the background. See [`Self::poll_connecting`].
+    pub(crate) fn join(address: &str) -> Self {
+        let address = address_with_port(address);
+        let task = {
+            let address = address.clone();
+            bq::spawn_task(move || NetConnection::connect(&address))
+        };
+        Self {
+            link: LanLink::Connecting { address, task },
+            role: LanRole::codebreaker(),
+            scores: [0, 0],
+        }
+    }
+
+    pub(crate) fn is_host(&self) -> bool {
+        matches!(self.link, LanLink::Hosting { .. })
+    }
+
+    pub(crate) fn is_codemaker(&self) -> bool {
+        matches!(self.role, LanRole::Codemaker { .. })
+    }
+
+    pub(crate) fn is_codebreaker(&self) -> bool {
+        matches!(self.role, LanRole::Codebreaker { .. })
+    }
+
+    /// Codemakers and players still connecting don't play on their own board.
+    pub(crate) fn takes_over_input(&self) -> bool {
+        self.is_codemaker() || matches!(self.link, LanLink::Connecting { .. })
+    }
+
+    /// Some once connecting is done, with why it failed if it did.
+    pub(crate) fn poll_connecting(&mut self) -> Option<Result<(), String>> {
+        let LanLink::Connecting { address, task } = &mut self.link else {
+            return None;
+        };
+        let mut host = match task.poll() {
+            TaskPoll::Pending => return None,
+            TaskPoll::Done(Ok(host)) => host,
+            TaskPoll::Done(Err(e)) => return Some(Err(format!("Couldn't join {address}: {e}"))),
+            TaskPoll::Failed => return Some(Err(format!("Couldn't join {address}"))),
+        };
+        let hello = LanMessage::Hello {
+            version: PROTOCOL_VERSION,
+        };
+        if let Err(e) = host.send(&hello) {
+            return Some(Err(format!("Couldn't join {address}: {e}")));
+        }
+        self.link = LanLink::Joined {
+            address: address.clone(),
+            host,
+        };
+        Some(Ok(()))
+    }
+
+    /// Messages from the other end since the last call. The host also lets someone new in here if
+    /// nobody's joined, and goes back to waiting if they leave.
+    pub(crate) fn poll(&mut self) -> Result<Vec<LanMessage>, NetError> {
+        let result = match &mut self.link {
+            LanLink::Hosting { listener, peer, .. } => {
+                if peer.is_none() {
+                    *peer = listener.poll_accept().map_err(NetError::Io)?;
+                }
+                let Some(connection) = peer else {
+                    return Ok(Vec::new());
+                };
+                connection.poll()
+            }
+            LanLink::Connecting { .. } => Ok(Vec::new()),
+            LanLink::Joined { host, .. } => host.poll(),
+        };
+        if result.is_err() {
+            self.drop_peer();
+        }
+        result
+    }
+
+    /// Does nothing if nobody's connected yet. The host drops the other player if it fails.
+    pub(crate) fn send(&mut self, message: &LanMessage) -> Result<(), NetError> {
+        let result = match &mut self.link {
+            LanLink::Hosting { peer, .. } => {
+                let Some(connection) = peer else {
+                    return Ok(());
+                };
+                connection.send(message)
+            }
+            LanLink::Connecting { .. } => Ok(()),
+            LanLink::Joined { host, .. } => host.send(message),
+        };
+        if result.is_err() {
+            self.drop_peer();
+        }
+        result
+    }
+
+    /// The host only rejects the joiner, not the whole session.
+    pub(crate) fn reject(&mut self, reason: String) {
+        if let LanLink::Hosting { peer, .. } = &mut self.link {
+            if let Some(mut connection) = peer.take() {
+                let _ = connection.send(&LanMessage::Rejected { reason });
+            }
+        }
+    }
+
+    /// Hosts go back to waiting for someone to join, as the codemaker, with a fresh score.
+    fn drop_peer(&mut self) {
+        if let LanLink::Hosting { peer, .. } = &mut self.link {
+            *peer = None;
+            self.role = LanRole::Codemaker { game_over: false };
+            self.scores = [0, 0];
+        }
+    }
+
+    pub(crate) fn check_version(version: u32) -> Result<(), String> {
+        if version == PROTOCOL_VERSION {
+            Ok(())
+        } else {
+            Err(format!(
+                "The host has version {PROTOCOL_VERSION} of LAN games, but you have version \
+                {version}"
+            ))
+        }
+    }
+
+    /// Only for the codemaker.
+    pub(crate) fn is_game_over(&self) -> bool {
+        matches!(self.role, LanRole::Codemaker { game_over: true })
+    }
+
+    /// Makes you the codemaker, for a new game.
+    pub(crate) fn start_as_codemaker(&mut self) {
+        self.role = LanRole::Codemaker { game_over: false };
+    }
+
+    /// Makes you the codebreaker, for a new game.
+    pub(crate) fn start_as_codebreaker(&mut self) {
+        self.role = LanRole::codebreaker();
+    }
+
+    /// For the codemaker, once the password's been broken, or the codebreaker's out of guesses.
+    pub(crate) fn finish_as_codemaker(&mut self, num_guesses: usize, broken: bool) {
+        if let LanRole::Codemaker { game_over } = &mut self.role {
+            *game_over = true;
+            self.scores[0] += pvp::codemaker_points(num_guesses, broken);
+        }
+    }
+
+    /// For the codebreaker, once the game's over on their end.
+    pub(crate) fn finish_as_codebreaker(&mut self, num_guesses: usize, broken: bool) {
+        if self.is_codebreaker() {
+            self.scores[1] += pvp::codemaker_points(num_guesses, broken);
+        }
+    }
+
+    /// For the codebreaker, once the codemaker reveals the password.
+    pub(crate) fn receive_reveal(&mut self) {
+        if let LanRole::Codebreaker { revealed, .. } = &mut self.role {
+            *revealed = true;
+        }
+    }
+
+    /// Whether it's your turn to make a password.
+    pub(crate) fn can_start_next_game(&self) -> bool {
+        matches!(self.role, LanRole::Codebreaker { revealed: true, .. })
+    }
+
+    /// Sends `guess` to the codemaker for feedback. Ok(false) if there's already one waiting for it.
+    pub(crate) fn send_guess(&mut self, guess: Vec<Color>) -> Result<bool, NetError> {
+        let LanRole::Codebreaker { pending_guess, .. } = &self.role else {
+            return Ok(false);
+        };
+        if pending_guess.is_some() {
+            return Ok(false);
+        }
+        self.send(&LanMessage::Guess {
+            colors: encode_colors(&guess),
+        })?;
+        if let LanRole::Codebreaker { pending_guess, .. } = &mut self.role {
+            *pending_guess = Some(guess);
+        }
+        Ok(true)
+    }
+
+    /// Ignores feedback that doesn't make sense for the pending guess.
+    pub(crate) fn receive_feedback(&mut self, num_correct_hits: usize, num_misplaced_hits: usize) {
+        let LanRole::Codebreaker {
+            pending_guess,
+            feedback,
+            ..
+        } = &mut self.role
+        else {
+            return;
+        };
+        let Some(guess) = pending_guess.take() else {
+            return;
+        };
+        if num_correct_hits + num_misplaced_hits <= guess.len() {
+            *feedback = Some(CompleteRow {
+                guess,
+                num_correct_hits,
+                num_misplaced_hits,
+                note: None,
+                elapsed: None,
+            });
+        }
+    }
+
+    /// The pending guess, scored, once the codemaker's feedback comes in.
+    pub(crate) fn take_feedback(&mut self) -> Option<CompleteRow> {
+        match &mut self.role {
+            LanRole::Codebreaker { feedback, .. } => feedback.take(),
+            LanRole::Codemaker { .. } => None,
+        }
+    }
+
+    /// e.g. "Score: you 5, them 9"
+    fn score_text(&self) -> String {
+        format!("Score: you {}, them {}", self.scores[0], self.scores[1])
+    }
+
+    fn leave_text(&self) -> &'static str {
+        if self.is_host() {
+            "[esc] to stop hosting"
+        } else {
+            "[esc] to leave"
+        }
+    }
+
+    /// Shown beside the board, in place of how to play. `result` is how the last game went, for
+    /// the codemaker.
+    pub(crate) fn status_text(
+        &self,
+        guess_number: usize,
+        num_guesses: usize,
+        result: &str,
+    ) -> String {
+        let leave_text = self.leave_text();
+        let score_text = self.score_text();
+        match (&self.link, &self.role) {
+            (
+                LanLink::Hosting {
+                    peer: None,
+                    address,
+                    ..
+                },
+                _,
+            ) => {
+                let address = address.as_deref().unwrap_or("this computer's address");
+                format!(
+                    "Hosting a LAN game.\n\
+                    Waiting for someone to\n\
+                    join {address}\n\
+                    \n\
+                    {leave_text}"
+                )
+            }
+            (LanLink::Connecting { address, .. }, _) => format!(
+                "Joining {address}...\n\
+                \n\
+                [esc] to cancel"
+            ),
+            (_, LanRole::Codemaker { game_over: false }) => format!(
+                "You're the codemaker!\n\
+                The password is up top.\n\
+                The codebreaker is on\n\
+                guess {guess_number} of {num_guesses}.\n\
+                \n\
+                {score_text}\n\
+                {leave_text}"
+            ),
+            (_, LanRole::Codemaker { game_over: true }) => format!(
+                "{result}\n\
+                \n\
+                Their turn to make a password.\n\
+                \n\
+                {score_text}\n\
+                {leave_text}"
+            ),
+            (_, LanRole::Codebreaker { pending_guess, .. }) => {
+                let against = match &self.link {
+                    LanLink::Joined { address, .. } => address.as_str(),
+                    _ => "the joiner",
+                };
+                let waiting = if pending_guess.is_some() {
+                    "Waiting for feedback..."
+                } else {
+                    "Break their password!"
+                };
+                format!(
+                    "Playing against\n\
+                    {against}\n\
+                    {waiting}\n\
+                    \n\
+                    {score_text}\n\
+                    {leave_text}"
+                )
+            }
+        }
+    }
+
+    /// Shown after the game, for the codebreaker.
+    pub(crate) fn next_game_text(&self, new_game_key: &str) -> String {
+        let next = if self.can_start_next_game() {
+            format!("Your turn to make a password!\nPress [{new_game_key}] to start the next game.")
+        } else {
+            "Waiting for the password...".to_string()
+        };
+        format!("{next}\n{}\nPress [esc] to leave.", self.score_text())
+    }
+}
+
+pub(crate) fn encode_colors(colors: &[Color]) -> Vec<usize> {
+    // Same for a color whatever the palette, blank included
+    colors.iter().map(|color| *color as usize).collect()
+}
+
+/// None unless it's a full row of colors from `rules`'s palette.
+pub(crate) fn decode_colors(indices: &[usize], rules: &RuleSet) -> Option<Vec<Color>> {
+    if indices.len() != rules.num_slots {
+        return None;
+    }
+    indices
+        .iter()
+        .map(|i| {
+            rules
+                .palette()
+                .iter()
+                .find(|color| **color as usize == *i)
+                .copied()
+        })
+        .collect()
+}
+
+/// The host's rules, if they're sane.
+pub(crate) fn validate_rules(rules: RuleSet) -> Result<RuleSet, String> {
+    rules.validate().map(|()| rules)
+}
+
+/// For the address input.
+pub(crate) fn is_address_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '.' | ':' | '-' | '[' | ']')
+}
+
+/// Adds the default port if there isn't one.
+fn address_with_port(address: &str) -> String {
+    let address = address.trim();
+    if address.parse::<SocketAddr>().is_ok() {
+        address.to_string()
+    } else if address.parse::<Ipv6Addr>().is_ok() {
+        format!("[{address}]:{LAN_PORT}")
+    } else if address.contains(':') {
+        address.to_string()
+    } else {
+        format!("{address}:{LAN_PORT}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{address_with_port, decode_colors, encode_colors, LanSession};
+    use crate::rules::RuleSet;
+    use crate::Color;
+
+    #[test]
+    fn test_colors_round_trip() {
+        let rules = RuleSet::classic();
+        let guess = vec![Color::Red, Color::Purple, Color::Green, Color::Red];
+        assert_eq!(
+            Some(guess.clone()),
+            decode_colors(&encode_colors(&guess), &rules)
+        );
+
+        // Off the palette
+        assert_eq!(None, decode_colors(&[0, 1, 2, 8], &rules));
+        // Wrong length
+        assert_eq!(None, decode_colors(&[0, 1, 2], &rules));
+
+        let blanks = RuleSet {
+            blanks: true,
+            ..RuleSet::classic()
+        };
+        let guess = vec![Color::Red, Color::Blank, Color::Blank, Color::Purple];
+        assert_eq!(
+            Some(guess.clone()),
+            decode_colors(&encode_colors(&guess), &blanks)
+        );
+        assert_eq!(None, decode_colors(&encode_colors(&guess), &rules));
+    }
+
+    #[test]
+    fn test_address_with_port() {
+        assert_eq!("192.168.1.5:7878", address_with_port(" 192.168.1.5 "));
+        assert_eq!("192.168.1.5:9000", address_with_port("192.168.1.5:9000"));
+        assert_eq!("[::1]:7878", address_with_port("::1"));
+        assert_eq!("[::1]:9000", address_with_port("[::1]:9000"));
+        assert_eq!("fridge.local:7878", address_with_port("fridge.local"));
+    }
+
+    #[test]
+    fn test_scoring() {
+        let mut lan = LanSession::host(0).unwrap();
+        assert!(lan.is_codemaker());
+        // Broken on the 5th guess
+        lan.finish_as_codemaker(5, true);
+        assert!(lan.is_game_over());
+
+        lan.start_as_codebreaker();
+        assert!(!lan.can_start_next_game());
+        // Never broken, so a bonus point for them
+        lan.finish_as_codebreaker(8, false);
+        lan.receive_reveal();
+        assert!(lan.can_start_next_game());
+        assert_eq!("Score: you 5, them 9", lan.score_text());
+    }
+
+    #[test]
+    fn test_version_check() {
+        assert!(LanSession::check_version(super::PROTOCOL_VERSION).is_ok());
+        assert!(LanSession::check_version(super::PROTOCOL_VERSION + 1).is_err());
+    }
+}
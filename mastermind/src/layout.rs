@@ -0,0 +1,172 @@
+//! Scales the board to fit the window.
+//!
+//! The board, pegs, and text around them are laid out in fixed "board units" by the draw consts,
+//! sized for a window of `BoardSizeDerivedConsts::window_size()`. When the window is any other size
+//! (e.g. the player resized it), a camera maps board units to the window: scaled evenly to fit, and
+//! centered along whichever side has room to spare. Recomputed from the window size every frame,
+//! so there's nothing to keep in sync on resize.
+//!
+//! Big boards (lots of guesses) in short windows don't shrink past [`MIN_SCALE`] to fit the height.
+//! They scroll vertically instead, by `scroll_y` board units from the top.
+//!
+//! Popups (end game text, toasts, etc.) are drawn without the camera, at their normal size.
+use better_quad::mq;
+
+/// Smallest the board gets to fit the window's height, before it scrolls instead. It still gets
+/// smaller to fit the width, since there's no scrolling sideways.
+pub(crate) const MIN_SCALE: f32 = 0.75;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct BoardLayout {
+    /// Window pixels per board unit
+    scale: f32,
+    /// Where the board's (0, 0) is in the window
+    offset: mq::Vec2,
+    window_size: mq::Vec2,
+    /// How far down it can scroll, in board units. 0 if it fits.
+    max_scroll: f32,
+}
+
+impl BoardLayout {
+    pub(crate) fn for_window(board_width: f32, board_height: f32, scroll_y: f32) -> Self {
+        Self::fit(
+            mq::vec2(board_width, board_height),
+            mq::vec2(mq::screen_width(), mq::screen_height()),
+            scroll_y,
+        )
+    }
+
+    fn fit(board_size: mq::Vec2, window_size: mq::Vec2, scroll_y: f32) -> Self {
+        let fit_width_scale = window_size.x / board_size.x;
+        let fit_height_scale = window_size.y / board_size.y;
+        let scale = fit_width_scale.min(fit_height_scale.max(MIN_SCALE));
+        // A minimized window can be 0x0
+        let scale = if scale.is_finite() && scale > 0.0 {
+            scale
+        } else {
+            1.0
+        };
+        let max_scroll = (board_size.y - window_size.y / scale).max(0.0);
+        let mut offset = (window_size - board_size * scale) / 2.0;
+        if max_scroll > 0.0 {
+            offset.y = -scroll_y.clamp(0.0, max_scroll) * scale;
+        }
+        Self {
+            scale,
+            offset,
+            window_size,
+            max_scroll,
+        }
+    }
+
+    pub(crate) fn screen_to_board(&self, x: f32, y: f32) -> (f32, f32) {
+        let board = (mq::vec2(x, y) - self.offset) / self.scale;
+        (board.x, board.y)
+    }
+
+    pub(crate) fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    pub(crate) fn max_scroll(&self) -> f32 {
+        self.max_scroll
+    }
+
+    /// How much of the board fits in the window at once, in board units.
+    pub(crate) fn visible_height(&self) -> f32 {
+        self.window_size.y / self.scale
+    }
+
+    /// Draw in board units until `mq::set_default_camera()`.
+    pub(crate) fn set_camera(&self) {
+        let top_left = -self.offset / self.scale;
+        let size = self.window_size / self.scale;
+        mq::set_camera(&mq::Camera2D::from_display_rect(mq::Rect::new(
+            top_left.x, top_left.y, size.x, size.y,
+        )));
+    }
+}
+
+/// Where to scroll to keep `pinned_top..pinned_bottom` (e.g. the working row) in view, as close to
+/// `scroll_y` as possible. If it doesn't all fit, its top stays in view.
+pub(crate) fn clamp_scroll(
+    scroll_y: f32,
+    max_scroll: f32,
+    visible_height: f32,
+    pinned: Option<(f32, f32)>,
+) -> f32 {
+    let scroll_y = match pinned {
+        Some((pinned_top, pinned_bottom)) => {
+            let lowest = (pinned_bottom - visible_height).min(pinned_top);
+            scroll_y.clamp(lowest, pinned_top)
+        }
+        None => scroll_y,
+    };
+    scroll_y.clamp(0.0, max_scroll)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clamp_scroll, BoardLayout};
+    use better_quad::mq;
+
+    #[test]
+    fn test_fit() {
+        let board_size = mq::vec2(400.0, 800.0);
+
+        // Same size
+        let layout = BoardLayout::fit(board_size, board_size, 0.0);
+        assert_eq!((10.0, 20.0), layout.screen_to_board(10.0, 20.0));
+
+        // Twice as big, with extra width split between the sides
+        let layout = BoardLayout::fit(board_size, mq::vec2(1000.0, 1600.0), 0.0);
+        assert_eq!(2.0, layout.scale);
+        assert_eq!(mq::vec2(100.0, 0.0), layout.offset);
+        assert_eq!((0.0, 0.0), layout.screen_to_board(100.0, 0.0));
+        assert_eq!((400.0, 800.0), layout.screen_to_board(900.0, 1600.0));
+
+        // A bit shorter, with extra width
+        let layout = BoardLayout::fit(board_size, mq::vec2(400.0, 700.0), 0.0);
+        assert_eq!(0.875, layout.scale);
+        assert_eq!(mq::vec2(25.0, 0.0), layout.offset);
+        assert_eq!(0.0, layout.max_scroll);
+
+        let layout = BoardLayout::fit(board_size, mq::vec2(0.0, 0.0), 0.0);
+        assert_eq!(1.0, layout.scale);
+    }
+
+    #[test]
+    fn test_fit_scrolls() {
+        let board_size = mq::vec2(400.0, 800.0);
+
+        // Half as tall only shrinks so far, then scrolls the rest
+        let layout = BoardLayout::fit(board_size, mq::vec2(400.0, 400.0), 100.0);
+        assert_eq!(0.75, layout.scale);
+        assert_eq!(mq::vec2(50.0, -75.0), layout.offset);
+        assert_eq!((0.0, 100.0), layout.screen_to_board(50.0, 0.0));
+        assert!((layout.max_scroll - 266.66666).abs() < 0.001);
+
+        // Can't scroll past the bottom
+        let layout = BoardLayout::fit(board_size, mq::vec2(400.0, 400.0), 1000.0);
+        assert_eq!(-layout.max_scroll * 0.75, layout.offset.y);
+
+        // Too narrow shrinks it anyway
+        let layout = BoardLayout::fit(board_size, mq::vec2(200.0, 400.0), 0.0);
+        assert_eq!(0.5, layout.scale);
+        assert_eq!(0.0, layout.max_scroll);
+    }
+
+    #[test]
+    fn test_clamp_scroll() {
+        assert_eq!(0.0, clamp_scroll(-10.0, 100.0, 50.0, None));
+        assert_eq!(100.0, clamp_scroll(300.0, 100.0, 50.0, None));
+
+        // Scrolls down just enough to show the bottom of the pinned part, or up to show its top
+        assert_eq!(30.0, clamp_scroll(0.0, 100.0, 50.0, Some((60.0, 80.0))));
+        assert_eq!(60.0, clamp_scroll(90.0, 100.0, 50.0, Some((60.0, 80.0))));
+        assert_eq!(45.0, clamp_scroll(45.0, 100.0, 50.0, Some((60.0, 80.0))));
+
+        // Too tall to show all of, so its top
+        assert_eq!(60.0, clamp_scroll(0.0, 100.0, 50.0, Some((60.0, 200.0))));
+    }
+}
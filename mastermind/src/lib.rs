@@ -1,45 +1,255 @@
+use crate::achievements::{Achievements, FinishedGame};
+use crate::campaign::{CampaignMap, CampaignProgress, MapAction, CAMPAIGN_LEVELS, CAMPAIGN_SLOTS};
+use crate::candidate_counter::CandidateCounter;
+use crate::candidates::Candidates;
+use crate::daily::DailyRecord;
+use crate::export::{GameExport, GameOutcome};
+use crate::heatmap::ClickHeatmap;
+use crate::lan::{LanMessage, LanSession};
+use crate::layout::{clamp_scroll, BoardLayout, MIN_SCALE};
+use crate::notes::NoteEditor;
 use crate::password::{Password, PasswordSource};
+use crate::pvp::{MatchPhase, PvpMatch};
+use crate::review::PegResult;
+use crate::rules::{DuplicateColors, RuleSet};
+use crate::scoring::{HighScore, HighScores};
+use crate::settings::{mouse_button_name, ColorHotkeys, FeedbackMode, InputMode, Settings};
+use crate::sounds::{FeedbackBeeps, SoundEffect};
+use crate::stats::{GameResult, GameStats};
 use crate::victory_mouse_animation::VictoryMouseAnimations;
-use better_quad::bq::{BetterKeyCode, TextAlignment, TextAnchorPoint};
+use better_quad::bq::{
+    AppConfigDir, AppStorage, BetterKeyCode, FrameCaptureConf, HoverTimer, KvStore, NetError,
+    PointerTracker, ResultsScreen, Rumble, SaveSlotBrowser, SeededRng, SlotBrowserAction, SlotInfo,
+    SoundBank, Stopwatch, TextAlignment, TextAnchorPoint, TextInput, TextInputEvent, ToastKind,
+    Toasts, Tutorial,
+};
 use better_quad::{
-    bq::{self, FpsCounter, TextBackground, Timestamp},
-    StatefulGui,
+    bq::{self, FpsCounter, TextBackground, TextContainer, Timestamp},
+    AppInfo, StatefulGui,
 };
 use macroquad::prelude as mq;
+use serde::Serialize;
 use std::cmp::min;
-use std::collections::HashMap;
 use std::fmt::Debug;
+use std::mem;
 use std::time::Duration;
 
+mod achievements;
+mod campaign;
+mod candidate_counter;
+mod candidates;
+mod daily;
+mod export;
+mod heatmap;
+mod help;
+mod lan;
+mod layout;
+mod notes;
+mod pvp;
+mod review;
+mod rules;
+mod scoring;
+mod settings;
+mod sounds;
+mod stats;
+mod theme;
+mod tutorial;
 mod victory_mouse_animation;
 
+/// Entry points for the fuzz targets in `fuzz/`, since they can only reach the public API.
+#[cfg(feature = "fuzzing")]
+pub mod fuzzing {
+    use crate::rules::RuleSet;
+
+    /// Small enough that every password can be listed (at most 6^4).
+    const MAX_SLOTS: u8 = 4;
+    const MAX_COLORS: u8 = 6;
+    const MAX_GUESSES: usize = 8;
+
+    /// Decodes `data` into small rules, a password, and guesses, then checks the consistency
+    /// filter against them. Panics if it's wrong.
+    pub fn consistency_filter(data: &[u8]) {
+        let [num_slots, num_colors, data @ ..] = data else {
+            return;
+        };
+        let num_slots = (2 + num_slots % (MAX_SLOTS - 1)) as usize;
+        let num_colors = (2 + num_colors % (MAX_COLORS - 1)) as usize;
+        let rules = RuleSet {
+            num_slots,
+            num_colors,
+            ..RuleSet::classic()
+        };
+        let mut rows = data.chunks_exact(num_slots).map(|chunk| {
+            chunk
+                .iter()
+                .map(|byte| rules.palette()[*byte as usize % num_colors])
+                .collect::<Vec<_>>()
+        });
+        let Some(password) = rows.next() else {
+            return;
+        };
+        let guesses = rows.take(MAX_GUESSES).collect::<Vec<_>>();
+        crate::candidates::check_consistency_filter(&rules, &password, &guesses);
+    }
+}
+
+/// Entry points for the benchmarks in `benches/`, since they can only reach the public API.
+#[cfg(feature = "benchmarks")]
+pub mod benchmarks {
+    use crate::candidates::Candidates;
+    use crate::rules::{DuplicateColors, RuleSet};
+    use crate::{evaluate_guess, Color, CompleteRow};
+
+    /// Passwords consistent with a few guesses at a fixed password, e.g. partway through a game.
+    pub struct CandidateSearch {
+        rules: RuleSet,
+        history: Vec<CompleteRow>,
+    }
+
+    impl CandidateSearch {
+        /// `num_guesses` guesses that each shift the palette by one, which narrows things down
+        /// about as much as a typical player's guesses do.
+        pub fn new(num_slots: usize, num_colors: usize, num_guesses: usize) -> Self {
+            let rules = RuleSet {
+                num_slots,
+                num_colors,
+                ..RuleSet::classic()
+            };
+            let palette = rules.palette();
+            let nth_color = |i: usize| palette[i % palette.len()];
+            let password = (0..num_slots)
+                .map(|i| nth_color(i * 2 + 1))
+                .collect::<Vec<_>>();
+            let history = (0..num_guesses)
+                .map(|guess_i| {
+                    let guess = (0..num_slots)
+                        .map(|i| nth_color(i + guess_i))
+                        .collect::<Vec<Color>>();
+                    evaluate_guess(guess, &password)
+                })
+                .collect();
+            Self { rules, history }
+        }
+
+        /// Lists every candidate in one go. Returns how many there are.
+        pub fn list_all(&self) -> usize {
+            let mut candidates = self.unlisted();
+            candidates.list_more(usize::MAX);
+            candidates.len()
+        }
+
+        /// Lists the first `max_passwords`, like a frame's worth of listing would. Returns how
+        /// many candidates there are among them.
+        pub fn list_chunk(&self, max_passwords: usize) -> usize {
+            let mut candidates = self.unlisted();
+            candidates.list_more(max_passwords);
+            candidates.len()
+        }
+
+        /// Lists every candidate, then narrows them down by a guess the way psychic mode does.
+        /// Returns how many are left.
+        pub fn narrow_adversarially(&self) -> usize {
+            let mut candidates = self.unlisted();
+            candidates.list_more(usize::MAX);
+            // A "1122" style opener
+            let num_slots = self.rules.num_slots;
+            let guess = (0..num_slots)
+                .map(|i| self.rules.palette()[i * 2 / num_slots])
+                .collect::<Vec<_>>();
+            candidates.narrow_adversarially(&guess);
+            candidates.len()
+        }
+
+        fn unlisted(&self) -> Candidates {
+            let mut candidates = Candidates::unlisted(&self.rules, DuplicateColors::Allowed)
+                .expect("benchmarked rules are small enough to list");
+            for row in &self.history {
+                candidates.retain_consistent(row);
+            }
+            candidates
+        }
+    }
+}
+
+const STORAGE: AppStorage = AppStorage::new("mastermind");
+const CONFIG_DIR: AppConfigDir = AppConfigDir::new("mastermind");
+const KV_LAST_RULE_SET: &str = "last_rule_set";
+const KV_SEEN_HELP: &str = "seen_help";
+
 // Control consts
 const KEY_SUBMIT: mq::KeyCode = mq::KeyCode::Space;
 const KEY_REPLAY_PASSWORD: mq::KeyCode = mq::KeyCode::R;
 const KEY_NEW_PASSWORD: mq::KeyCode = mq::KeyCode::Space;
 const KEY_TOGGLE_NUMBER_OVERLAY: mq::KeyCode = mq::KeyCode::N;
 const KEY_PLAYER_EDIT_PASSWORD: mq::KeyCode = mq::KeyCode::P;
-const KEY_COPY_SEED: mq::KeyCode = mq::KeyCode::S;
+const KEY_COPY_SEED: mq::KeyCode = mq::KeyCode::C;
+const KEY_ENTER_SEED: mq::KeyCode = mq::KeyCode::E;
+const KEY_TOGGLE_DAILY: mq::KeyCode = mq::KeyCode::M;
+const KEY_CYCLE_RULE_SET: mq::KeyCode = mq::KeyCode::B;
+const KEY_CYCLE_COLOR_HOTKEYS: mq::KeyCode = mq::KeyCode::Tab;
+const KEY_TOGGLE_HEATMAP: mq::KeyCode = mq::KeyCode::V;
+const KEY_TOGGLE_MUTE: mq::KeyCode = mq::KeyCode::Q;
+const KEY_TOGGLE_CASUAL: mq::KeyCode = mq::KeyCode::O;
+const KEY_UNDO_GUESS: mq::KeyCode = mq::KeyCode::U;
+// Like shell history
+const KEY_COPY_LAST_GUESS: mq::KeyCode = mq::KeyCode::Up;
+// Easter egg, so it's not in the controls text
+const KEY_TOGGLE_PSYCHIC: mq::KeyCode = mq::KeyCode::I;
+const KEY_TOGGLE_HARD_MODE: mq::KeyCode = mq::KeyCode::T;
+const KEY_CYCLE_DUPLICATE_COLORS: mq::KeyCode = mq::KeyCode::X;
+const KEY_TOGGLE_HIGH_SCORES: mq::KeyCode = mq::KeyCode::Z;
+// Yield
+const KEY_RESIGN: mq::KeyCode = mq::KeyCode::Y;
+const KEY_CYCLE_INPUT_MODE: mq::KeyCode = mq::KeyCode::W;
+// Out of letters
+const KEY_CYCLE_THEME: mq::KeyCode = mq::KeyCode::F2;
+// H is a home row color key
+const KEY_TOGGLE_HELP: mq::KeyCode = mq::KeyCode::F1;
+const KEY_REVIEW_PREVIOUS: mq::KeyCode = mq::KeyCode::Left;
+const KEY_REVIEW_NEXT: mq::KeyCode = mq::KeyCode::Right;
+const KEY_START_MATCH: mq::KeyCode = mq::KeyCode::F3;
+const KEY_TOGGLE_STREAMER_MODE: mq::KeyCode = mq::KeyCode::F4;
+const KEY_CONTINUE_MATCH: mq::KeyCode = mq::KeyCode::Enter;
+const KEY_HOST_LAN_GAME: mq::KeyCode = mq::KeyCode::F5;
+const KEY_JOIN_LAN_GAME: mq::KeyCode = mq::KeyCode::F6;
+const KEY_TOGGLE_ACHIEVEMENTS: mq::KeyCode = mq::KeyCode::F7;
+const KEY_TOGGLE_CANDIDATE_COUNTER: mq::KeyCode = mq::KeyCode::F8;
+// F9 saves a GIF
+const KEY_TOGGLE_CONFIRM_SUBMIT: mq::KeyCode = mq::KeyCode::F10;
+const KEY_EXPORT_GAME: mq::KeyCode = mq::KeyCode::F11;
+const KEY_CYCLE_FEEDBACK_MODE: mq::KeyCode = mq::KeyCode::F12;
+const KEY_PAUSE: mq::KeyCode = mq::KeyCode::Escape;
+// Only in the help overlay, so it can share a letter
+const KEY_START_TUTORIAL: mq::KeyCode = mq::KeyCode::T;
+const KEY_SKIP_TUTORIAL: mq::KeyCode = mq::KeyCode::Escape;
+// Out of letters and F keys
+const KEY_TOGGLE_MIRRORED: mq::KeyCode = mq::KeyCode::Backslash;
+const KEY_CYCLE_MOUSE_BUTTONS: mq::KeyCode = mq::KeyCode::Slash;
+const KEY_TOGGLE_COLOR_USAGE: mq::KeyCode = mq::KeyCode::Apostrophe;
+const KEY_OPEN_CAMPAIGN_MAP: mq::KeyCode = mq::KeyCode::Comma;
+const KEY_TOGGLE_FREE_REROLL: mq::KeyCode = mq::KeyCode::Semicolon;
+// Only with the free re-roll setting on, before the first guess
+const KEY_REROLL_PASSWORD: mq::KeyCode = mq::KeyCode::Period;
 
 // Game logic consts
-const COLOR_PALETTE: [Color; 6] = [
+/// A rule set's palette is the first `num_colors` of these.
+const ALL_COLORS: [Color; 9] = [
     Color::Red,
     Color::Orange,
     Color::Yellow,
-    // Color::NeonGreen,
     Color::Green,
-    // Color::LightBlue,
     Color::Blue,
     Color::Purple,
-    // Color::Pink,
+    Color::Pink,
+    Color::LightBlue,
+    Color::NeonGreen,
 ];
-const NUM_SLOTS_PER_ROW: usize = 4;
-const NUM_GUESSES: usize = 8;
+/// For arrays indexed by `Color as usize`, blank included.
+const NUM_COLOR_INDICES: usize = ALL_COLORS.len() + 1;
 
 // Draw consts
 const CURSOR_SIZE: f32 = 30.0;
 const CURSOR_RADIUS: f32 = CURSOR_SIZE / 2.0;
-const SLOTS_PER_ROW_F32: f32 = NUM_SLOTS_PER_ROW as f32;
 const BOARD_OFFSET_X: f32 = 20.0;
 const BOARD_OFFSET_Y: f32 = 20.0;
 const ROW_SEPARATOR_HEIGHT: f32 = 1.0;
@@ -49,18 +259,27 @@ const SLOT_PADDING: f32 = 5.0;
 const WORKING_BOX_THICKNESS: f32 = 7.0;
 const KEY_SIZE: f32 = 18.0;
 const KEY_RADIUS: f32 = KEY_SIZE / 2.0;
+/// Over palette pegs past the last color key.
+const CLICK_ONLY_PEG_DIM_COLOR: mq::Color = mq::Color::new(0.0, 0.0, 0.0, 0.55);
 const PEG_SIZE: f32 = 40.0;
 const PEG_RADIUS: f32 = PEG_SIZE / 2.0;
 const PEG_OUTER_PADDING: f32 = 10.0;
+const PEG_MIN_SPACING: f32 = 4.0;
 const SLOT_PEG_FONT_SIZE: u16 = 24;
-const END_GAME_FONT_SIZE: u16 = 25;
 const HOW_TO_PLAY_OFFSET_X: f32 = BOARD_OFFSET_X;
 const HOW_TO_PLAY_OFFSET_Y: f32 = BOARD_OFFSET_Y;
 const HOW_TO_PLAY_FONT_SIZE: u16 = 25;
+const TIMER_FONT_SIZE: u16 = 30;
+const TIMER_PADDING: f32 = 5.0;
+const TIMER_MARGIN_BOTTOM: f32 = 10.0;
+const TIMER_WARNING_THRESHOLD: Duration = Duration::from_secs(10);
+/// How long after the first press of the resign key a second press gives up.
+const RESIGN_CONFIRM_WINDOW: Duration = Duration::from_secs(3);
+// {num_slots}, {duplicate_colors}, and {blanks} are filled in from the rules.
 const HOW_TO_PLAY_TEXT: &str = "Try to guess the secret code!
 
-Select 4 colored pegs to make a guess.
-Duplicate colors are allowed.
+Select {num_slots} colored pegs to make a guess.
+{duplicate_colors}{blanks}
 
 The small pegs on the right side give you feedback
 on your guess.
@@ -70,9 +289,31 @@ The order of the feedback pegs does NOT correspond
 to specific pegs from your guess.";
 const SEED_FONT_SIZE: u16 = 27;
 const SEED_TEXT_PADDING: f32 = 3.0;
+const SEED_INPUT_FONT_SIZE: u16 = 30;
+// Comfortably more than a u64 in base 36 (13 chars).
+const SEED_INPUT_MAX_LEN: usize = 16;
 const VICTORY_MULTI_CURSOR_OFFSET: f32 = CURSOR_SIZE;
 const VICTORY_CURSOR_TITLE_FONT_SIZE: u16 = 50;
 const VICTORY_CURSOR_TITLE_OFFSET: f32 = 12.0;
+const STATS_PANEL_MARGIN: f32 = 10.0;
+const DAILY_BADGE_FONT_SIZE: u16 = 22;
+const DAILY_BADGE_PADDING: f32 = 5.0;
+const HEATMAP_LABEL_FONT_SIZE: u16 = 25;
+const COLOR_TOOLTIP_BACKGROUND: mq::Color = mq::Color::new(1.0, 1.0, 1.0, 0.9);
+// Blank pegs are filled in, unlike empty slots, and crossed out
+const BLANK_PEG_COLOR: mq::Color = mq::Color::new(0.85, 0.85, 0.85, 1.0);
+const BLANK_PEG_CROSS_COLOR: mq::Color = mq::Color::new(0.45, 0.45, 0.45, 1.0);
+const BLANK_PEG_CROSS_THICKNESS: f32 = 3.0;
+// Space around the board for the pegs and controls text, tuned to the classic board.
+const WINDOW_EXTRA_WIDTH: f32 = 175.0;
+const WINDOW_EXTRA_HEIGHT: f32 = 607.0;
+// Taller boards scroll rather than ask for a window taller than most screens
+const MAX_WINDOW_HEIGHT: f32 = 1000.0;
+/// Board units per notch of the mouse wheel.
+const SCROLL_STEP: f32 = 40.0;
+const SCROLLBAR_WIDTH: f32 = 6.0;
+const SUBMIT_BUTTON_MAX_FONT_SIZE: u16 = 20;
+const SCROLLBAR_COLOR: mq::Color = mq::Color::new(1.0, 1.0, 1.0, 0.4);
 
 struct BoardSizeDerivedConsts {
     row_width_guess: f32,
@@ -83,19 +324,19 @@ struct BoardSizeDerivedConsts {
 }
 
 impl BoardSizeDerivedConsts {
-    fn get() -> Self {
-        let row_width_guess =
-            SLOT_SIZE * SLOTS_PER_ROW_F32 + SLOT_PADDING * (SLOTS_PER_ROW_F32 + 1.0);
+    fn get(rules: &RuleSet) -> Self {
+        let slots_per_row = rules.num_slots as f32;
+        let num_guesses = rules.num_guesses as f32;
+        let row_width_guess = SLOT_SIZE * slots_per_row + SLOT_PADDING * (slots_per_row + 1.0);
         let row_height = SLOT_SIZE + SLOT_PADDING * 2.0;
 
         // Derive key padding such that a single guess row has 2 rows of keys.
         let key_padding = (row_height - KEY_SIZE * 2.0) / 3.0;
-        let num_keys_top_key_row = (SLOTS_PER_ROW_F32 / 2.0).ceil();
+        let num_keys_top_key_row = num_keys_top_key_row(rules.num_slots) as f32;
         let row_width_key =
             num_keys_top_key_row * KEY_SIZE + key_padding * (num_keys_top_key_row + 1.0);
 
-        let board_height =
-            row_height * (NUM_GUESSES as f32 + 1.0) + ROW_SEPARATOR_HEIGHT * NUM_GUESSES as f32;
+        let board_height = row_height * (num_guesses + 1.0) + ROW_SEPARATOR_HEIGHT * num_guesses;
 
         Self {
             row_width_guess,
@@ -105,11 +346,63 @@ impl BoardSizeDerivedConsts {
             board_height,
         }
     }
+
+    fn board_width(&self) -> f32 {
+        self.row_width_guess + self.row_width_key
+    }
+
+    /// Left edge of the guesses. Mirrored boards (see `Settings::mirrored`) swap the guesses and
+    /// feedback pegs around.
+    fn guess_x(&self, mirrored: bool) -> f32 {
+        if mirrored {
+            BOARD_OFFSET_X + self.row_width_key
+        } else {
+            BOARD_OFFSET_X
+        }
+    }
+
+    /// Left edge of the feedback pegs.
+    fn key_x(&self, mirrored: bool) -> f32 {
+        if mirrored {
+            BOARD_OFFSET_X
+        } else {
+            BOARD_OFFSET_X + self.row_width_guess
+        }
+    }
+
+    /// Between the guesses and feedback pegs.
+    fn separator_x(&self, mirrored: bool) -> f32 {
+        self.guess_x(mirrored).max(self.key_x(mirrored))
+    }
+
+    /// Board plus pegs, where clicks are tracked for the heatmap.
+    fn heatmap_area(&self) -> mq::Rect {
+        mq::Rect::new(
+            BOARD_OFFSET_X,
+            BOARD_OFFSET_Y,
+            self.board_width(),
+            self.board_height + PEG_OUTER_PADDING * 2.0 + PEG_SIZE,
+        )
+    }
+
+    /// (width, height) of a window that fits the board, pegs, and controls text.
+    fn window_size(&self) -> (f32, f32) {
+        (
+            BOARD_OFFSET_X + self.board_width() + WINDOW_EXTRA_WIDTH,
+            BOARD_OFFSET_Y + self.board_height + WINDOW_EXTRA_HEIGHT,
+        )
+    }
+
+    /// The window size to ask for. Up to `MAX_WINDOW_HEIGHT` tall, shrinking the board as much as
+    /// the layout would to fit it, and past that it scrolls.
+    fn requested_window_size(&self) -> (f32, f32) {
+        let (width, height) = self.window_size();
+        let scale = (MAX_WINDOW_HEIGHT / height).clamp(MIN_SCALE, 1.0);
+        (width * scale, (height * scale).min(MAX_WINDOW_HEIGHT))
+    }
 }
 
 // Features to do:
-// - dynamically variable game params (num colors, num slots, num guesses)
-// - pvp (https://docs.rs/gloo-net/latest/gloo_net )
 // - add ability to seed run
 pub struct MastermindGame {
     state: GameState,
@@ -119,96 +412,229 @@ pub struct MastermindGame {
     mouse_color: Color,
     // Work around annoying (0, 0) initialization issue with mq.
     mouse_moved: bool,
+    // Touchscreens get no custom cursor or hover tooltips, and an on-screen submit button
+    pointer: PointerTracker,
+    // For the color name tooltip
+    peg_hover: HoverTimer<HoveredPeg>,
+    // How far down big boards are scrolled, in board units (see `layout`)
+    board_scroll: f32,
+    // Window y of the mouse last frame, while dragging to scroll
+    scroll_drag_y: Option<f32>,
     number_overlay: NumberOverlay,
     fps_counter: FpsCounter,
+    stats: GameStats,
+    daily_record: DailyRecord,
+    settings: Settings,
+    rules: RuleSet,
+    // Built-in rule sets first, then the user's. Never empty.
+    rule_sets: Vec<RuleSet>,
+    toasts: Toasts,
+    heatmap: ClickHeatmap,
+    show_heatmap: bool,
+    high_scores: HighScores,
+    show_high_scores: bool,
+    achievements: Achievements,
+    show_achievements: bool,
+    show_color_usage: bool,
+    /// Set once an assist (see `FeedbackMode::difficulty_tag`) has been seen this game, so switching
+    /// it off before winning doesn't shake the tag off the score.
+    difficulty_tag: Option<&'static str>,
+    sounds: SoundBank<SoundEffect>,
+    feedback_beeps: FeedbackBeeps,
+    // Some in psychic mode, where the password is decided as late as possible, to be as annoying
+    // as possible. `password` is then a stand-in that fits the feedback so far.
+    psychic_candidates: Option<Candidates>,
+    kv: KvStore,
+    // Some while typing a note for a guess
+    note_editor: Option<NoteEditor>,
+    // Key of the results screen button clicked this frame, handled like pressing it
+    results_click: Option<mq::KeyCode>,
+    // When the resign key was first pressed, waiting for a second press to confirm
+    resign_requested_at: Option<Timestamp>,
+    // With confirm before submit on, the working row when submit was first pressed. Changing the
+    // row takes a fresh confirm.
+    submit_requested_for: Option<Vec<Option<Color>>>,
+    show_help: bool,
+    // Some while the tutorial's running
+    tutorial: Option<Tutorial>,
+    // Some during a hotseat match
+    pvp_match: Option<PvpMatch>,
+    // Some while hosting, joining, or playing a LAN game
+    lan: Option<LanSession>,
+    // Some while typing the address of a LAN game to join
+    lan_address_input: Option<TextInput>,
+    // Some if the setting's on
+    candidate_counter: Option<CandidateCounter>,
+    campaign: CampaignProgress,
+    // Index of the campaign level being played, if it is one. Cleared by changing the rules.
+    campaign_level: Option<usize>,
+    // Whether the last campaign win beat the level's best stars
+    campaign_new_best: bool,
+    // Some while the level select map is open
+    campaign_map: Option<CampaignMap>,
+    // Some while picking saved campaign progress to load, over the map
+    campaign_slot_browser: Option<SaveSlotBrowser>,
+    // The save slot the campaign was last saved to or loaded from, to save over
+    campaign_slot: Option<u32>,
+    // Whether this game's free re-roll (see `Settings::free_reroll`) has been used
+    password_rerolled: bool,
 }
 
 enum GameState {
     InProgress {
-        start_time: Timestamp,
-        working_row: [Option<Color>; NUM_SLOTS_PER_ROW],
+        // Game time, which stops while paused
+        clock: Stopwatch,
+        working_row: Vec<Option<Color>>,
         mouse_click_release_behavior: MouseClickReleaseBehavior,
+        // In slot first input mode, the slot the next picked color goes in
+        selected_slot: Option<usize>,
     },
     EditPassword {
         mouse_click_release_behavior: MouseClickReleaseBehavior,
+        selected_slot: Option<usize>,
+    },
+    EnterSeed {
+        seed_input: TextInput,
     },
     Victory {
         total_time: Duration,
+        is_new_best_time: bool,
+        score: u32,
+        // 1-based, if it made the high score table
+        high_score_rank: Option<usize>,
         // Put the big struct in a box
         mouse_animations: Box<VictoryMouseAnimations>,
     },
-    TooManyGuesses,
+    TooManyGuesses {
+        // For picking the timer back up after an undo in casual mode.
+        elapsed: Duration,
+    },
+    OutOfTime,
+    Resigned,
+    /// Stepping through the guesses after the game, with `end_state` to go back to.
+    Reviewing {
+        history_index: usize,
+        end_state: Box<GameState>,
+    },
 }
 
 /// Separate mod to enforce RNG state and immutability.
 mod password {
-    use crate::{Color, COLOR_PALETTE, NUM_SLOTS_PER_ROW};
-    use better_quad::bq;
+    use crate::rules::{DuplicateColors, RuleSet};
+    use crate::{daily, Color};
+    use better_quad::bq::{self, SeededRng};
 
-    #[derive(Copy, Clone)]
+    /// Keep generated seeds small so their codes are short enough to read out loud (6 chars).
+    /// Typed in seeds can be anything.
+    const MAX_RANDOM_SEED: u64 = 36u64.pow(6);
+
+    #[derive(Clone)]
     pub(super) struct Password {
-        password: [Color; NUM_SLOTS_PER_ROW],
+        password: Vec<Color>,
         source: PasswordSource,
     }
 
     #[derive(Copy, Clone)]
     pub(super) enum PasswordSource {
         Random { seed: u64 },
+        Daily { day_number: u64 },
         Player,
+        Psychic,
     }
 
     impl Password {
-        pub(super) fn random() -> Self {
-            bq::randomize_rand_seed();
+        pub(super) fn random(rules: &RuleSet, duplicate_colors: DuplicateColors) -> Self {
+            Self::from_seed(rules, duplicate_colors, bq::random_seed() % MAX_RANDOM_SEED)
+        }
+
+        /// Same seed and rules means same password, so people can race each other.
+        pub(super) fn from_seed(
+            rules: &RuleSet,
+            duplicate_colors: DuplicateColors,
+            seed: u64,
+        ) -> Self {
+            let rng = SeededRng::new(seed);
             Self {
-                password: Color::random_array(&COLOR_PALETTE),
-                source: PasswordSource::Random {
-                    seed: bq::get_last_set_rand_seed(),
-                },
+                password: duplicate_colors.random_password(rules, &rng),
+                source: PasswordSource::Random { seed },
+            }
+        }
+
+        /// Same password for everyone on the same day, always with the classic rules.
+        pub(super) fn daily(day_number: u64) -> Self {
+            let rules = RuleSet::classic();
+            let rng = SeededRng::new(daily::seed_for_day(day_number));
+            Self {
+                password: Color::random_vec(rules.palette(), rules.num_slots, &rng),
+                source: PasswordSource::Daily { day_number },
             }
         }
 
-        pub(super) fn player_specified(password: [Color; NUM_SLOTS_PER_ROW]) -> Self {
+        pub(super) fn player_specified(password: Vec<Color>) -> Self {
             Self {
                 password,
                 source: PasswordSource::Player,
             }
         }
 
-        pub(super) fn password(&self) -> &[Color; NUM_SLOTS_PER_ROW] {
+        pub(super) fn psychic(password: Vec<Color>) -> Self {
+            Self {
+                password,
+                source: PasswordSource::Psychic,
+            }
+        }
+
+        pub(super) fn password(&self) -> &[Color] {
             &self.password
         }
 
         pub(super) fn source(&self) -> PasswordSource {
             self.source
         }
+
+        pub(super) fn is_daily(&self) -> bool {
+            matches!(self.source, PasswordSource::Daily { .. })
+        }
     }
 }
 
 impl GameState {
-    fn new_game() -> Self {
-        bq::randomize_rand_seed();
+    fn new_game(rules: &RuleSet) -> Self {
         Self::InProgress {
-            start_time: Timestamp::now(),
-            working_row: [None; NUM_SLOTS_PER_ROW],
+            clock: Stopwatch::started(Timestamp::now()),
+            working_row: vec![None; rules.num_slots],
             mouse_click_release_behavior: MouseClickReleaseBehavior::None,
+            selected_slot: None,
         }
     }
 }
 
+impl AppInfo for MastermindGame {
+    const NAME: &'static str = "Mastermind";
+    const DESCRIPTION: &'static str = "Classic tabletop code-breaking game built from scratch.";
+}
+
 impl StatefulGui for MastermindGame {
     fn main_conf() -> mq::Conf {
+        // Start sized for the classic board. The window gets resized if the rules change.
+        let (window_width, window_height) =
+            BoardSizeDerivedConsts::get(&RuleSet::classic()).requested_window_size();
         mq::Conf {
             window_title: "Mastermind".to_string(),
-            // TODO less brittle const
-            window_width: 480,
-            window_height: 770,
+            window_width: window_width as i32,
+            window_height: window_height as i32,
             ..Default::default()
         }
     }
 
+    fn frame_capture_conf() -> Option<FrameCaptureConf> {
+        Some(FrameCaptureConf::default())
+    }
+
     fn update(&mut self, now: Timestamp) {
+        let progress = self.tutorial_progress();
         self.update(now);
+        self.update_tutorial(progress);
     }
 
     fn draw(&self) {
@@ -224,33 +650,608 @@ impl Default for MastermindGame {
 
 impl MastermindGame {
     fn new() -> Self {
-        Self {
-            state: GameState::new_game(),
-            password: Password::random(),
-            history: Vec::with_capacity(NUM_GUESSES),
-            mouse_color: COLOR_PALETTE[0],
+        let loaded_rule_sets = rules::load_rule_sets();
+        let mut toasts = Toasts::new();
+        for error in loaded_rule_sets.errors {
+            toasts.push(ToastKind::Error, format!("Bad rule set {error}"));
+        }
+        let rules = RuleSet::classic();
+        let heatmap = ClickHeatmap::new(BoardSizeDerivedConsts::get(&rules).heatmap_area());
+        let settings = Settings::load();
+        let mut sounds = SoundBank::new();
+        sounds.set_muted(settings.muted);
+        let mut kv = KvStore::load(STORAGE);
+        // Nothing else tells first-time players how to play
+        let show_help = !kv.get_bool(KV_SEEN_HELP).unwrap_or(false);
+        kv.set_bool(KV_SEEN_HELP, true);
+        // Pick up where the last session left off.
+        let last_rules = kv.get_string(KV_LAST_RULE_SET).and_then(|name| {
+            loaded_rule_sets
+                .configs
+                .iter()
+                .find(|rules| rules.name == name)
+                .cloned()
+        });
+
+        let mut game = Self {
+            state: GameState::new_game(&rules),
+            password: Password::random(&rules, settings.duplicate_colors),
+            history: Vec::with_capacity(rules.num_guesses),
+            mouse_color: rules.palette()[0],
             mouse_moved: false,
+            pointer: PointerTracker::new(),
+            peg_hover: HoverTimer::new(),
+            board_scroll: 0.0,
+            scroll_drag_y: None,
             number_overlay: NumberOverlay::Off,
             fps_counter: FpsCounter::new(),
+            stats: GameStats::load(),
+            daily_record: DailyRecord::load(),
+            settings,
+            rules,
+            rule_sets: loaded_rule_sets.configs,
+            toasts,
+            heatmap,
+            show_heatmap: false,
+            high_scores: HighScores::load(),
+            show_high_scores: false,
+            achievements: Achievements::load(),
+            show_achievements: false,
+            show_color_usage: false,
+            difficulty_tag: None,
+            sounds,
+            feedback_beeps: FeedbackBeeps::default(),
+            psychic_candidates: None,
+            kv,
+            note_editor: None,
+            results_click: None,
+            resign_requested_at: None,
+            submit_requested_for: None,
+            show_help,
+            tutorial: None,
+            pvp_match: None,
+            lan: None,
+            lan_address_input: None,
+            candidate_counter: None,
+            campaign: CampaignProgress::load(),
+            campaign_level: None,
+            campaign_new_best: false,
+            campaign_map: None,
+            campaign_slot_browser: None,
+            campaign_slot: None,
+            password_rerolled: false,
+        };
+        if let Some(rules) = last_rules {
+            game.set_rules(rules);
         }
+        game.candidate_counter = game.new_candidate_counter();
+        game
+    }
+
+    /// Sounds take a moment to load (and need an async context on the web), so they're opt-in.
+    pub async fn with_sounds() -> Self {
+        let mut game = Self::new();
+        game.sounds = sounds::load_sounds().await;
+        game.sounds.set_muted(game.settings.muted);
+        game
+    }
+
+    /// Starts with the password for `seed`, e.g. for reproducible screenshots. The first launch help
+    /// isn't shown, so the board is.
+    pub fn with_seed(seed: u64) -> Self {
+        let mut game = Self::new();
+        game.password = Password::from_seed(&game.rules, game.settings.duplicate_colors, seed);
+        game.show_help = false;
+        game
     }
 
     fn reset_with_same_password(&mut self) {
-        self.state = GameState::new_game();
-        self.history = Vec::with_capacity(NUM_GUESSES);
+        self.state = GameState::new_game(&self.rules);
+        self.show_heatmap = false;
+        self.show_high_scores = false;
+        self.show_achievements = false;
+        self.show_color_usage = false;
+        self.difficulty_tag = None;
+        self.campaign_new_best = false;
+        self.password_rerolled = false;
+        bq::set_controller_led(None);
+        self.note_editor = None;
+        self.resign_requested_at = None;
+        self.submit_requested_for = None;
+        self.feedback_beeps.clear();
+        self.history = Vec::with_capacity(self.rules.num_guesses);
+        // There's no same password to replay in psychic mode, only starting over.
+        if self.psychic_candidates.is_some() {
+            self.start_psychic_game();
+        }
+    }
+
+    /// Starts the tutorial on a fresh board, outside of matches and LAN games.
+    fn start_tutorial(&mut self) {
+        if self.pvp_match.is_some() || self.lan.is_some() {
+            self.toasts
+                .push(ToastKind::Error, "Finish this match to start the tutorial");
+            return;
+        }
+        self.show_help = false;
+        self.reset_with_new_password();
+        self.tutorial = Some(tutorial::new_tutorial());
+    }
+
+    /// None without a tutorial running, so there's nothing to compare.
+    fn tutorial_progress(&self) -> Option<tutorial::Progress> {
+        self.tutorial.as_ref()?;
+        let num_filled = match &self.state {
+            GameState::InProgress { working_row, .. } => {
+                working_row.iter().filter(|slot| slot.is_some()).count()
+            }
+            _ => 0,
+        };
+        Some(tutorial::Progress {
+            num_filled,
+            num_slots: self.rules.num_slots,
+            num_guesses: self.history.len(),
+        })
+    }
+
+    /// Moves the tutorial on with what happened since `before`, and ends it after the last step.
+    fn update_tutorial(&mut self, before: Option<tutorial::Progress>) {
+        let (Some(before), Some(after)) = (before, self.tutorial_progress()) else {
+            return;
+        };
+        // Color keys typed into a note don't count
+        let color_picked = matches!(self.state, GameState::InProgress { .. })
+            && self.note_editor.is_none()
+            && Self::get_mouse_color_update(
+                &self.rules,
+                &self.settings,
+                self.board_mouse_position(),
+                self.eyedropped_color(self.board_mouse_position()),
+            )
+            .is_some();
+        let actions = tutorial::actions(before, after, color_picked);
+        let Some(tutorial) = &mut self.tutorial else {
+            return;
+        };
+        tutorial.update(&actions);
+        if tutorial.is_finished() {
+            self.tutorial = None;
+            self.toasts
+                .push(ToastKind::Info, "Tutorial done, have fun!");
+        }
     }
 
     fn reset_with_new_password(&mut self) {
         self.reset_with_same_password();
-        self.password = Password::random();
+        if self.psychic_candidates.is_none() {
+            self.password = Password::random(&self.rules, self.settings.duplicate_colors);
+        }
+    }
+
+    /// Falls back to a normal game if the rules allow too many passwords to keep track of.
+    fn start_psychic_game(&mut self) {
+        match Candidates::all(&self.rules, self.settings.duplicate_colors) {
+            Some(candidates) => {
+                if let Some(password) = candidates.first() {
+                    self.password = Password::psychic(password.to_vec());
+                }
+                self.psychic_candidates = Some(candidates);
+            }
+            None => {
+                self.psychic_candidates = None;
+                self.password = Password::random(&self.rules, self.settings.duplicate_colors);
+                self.toasts.push(
+                    ToastKind::Error,
+                    format!(
+                        "Too many possible passwords in {} for psychic mode",
+                        self.rules.name
+                    ),
+                );
+            }
+        }
+    }
+
+    /// The stand-in password has to fit the feedback for every guess in `history`, so after an
+    /// undo, the candidates have to be worked out again.
+    fn rewind_psychic_candidates(
+        psychic_candidates: &mut Option<Candidates>,
+        password: &mut Password,
+        rules: &RuleSet,
+        duplicate_colors: DuplicateColors,
+        history: &[CompleteRow],
+    ) {
+        if psychic_candidates.is_none() {
+            return;
+        }
+        *psychic_candidates = Candidates::consistent_with(rules, duplicate_colors, history);
+        if let Some(stand_in) = psychic_candidates.as_ref().and_then(Candidates::first) {
+            *password = Password::psychic(stand_in.to_vec());
+        }
+    }
+
+    /// Only call this between games, since it resets the board.
+    fn set_rules(&mut self, rules: RuleSet) {
+        if rules == self.rules {
+            return;
+        }
+        self.rules = rules;
+        self.campaign_level = None;
+        self.kv.set_string(KV_LAST_RULE_SET, &self.rules.name);
+        if !self.rules.palette().contains(&self.mouse_color) {
+            self.mouse_color = self.rules.palette()[0];
+        }
+        self.reset_with_new_password();
+
+        // Clicks on a different board layout don't line up, so start over.
+        let board_size_derived_consts = BoardSizeDerivedConsts::get(&self.rules);
+        self.heatmap = ClickHeatmap::new(board_size_derived_consts.heatmap_area());
+        let (window_width, window_height) = board_size_derived_consts.requested_window_size();
+        mq::request_new_screen_size(window_width, window_height);
+        self.board_scroll = 0.0;
+    }
+
+    fn cycle_rule_set(&mut self) {
+        let next_index = self
+            .rule_sets
+            .iter()
+            .position(|rules| *rules == self.rules)
+            .map_or(0, |i| (i + 1) % self.rule_sets.len());
+        self.set_rules(self.rule_sets[next_index].clone());
+    }
+
+    /// A fresh password for the level, even if it's the one being played.
+    fn start_campaign_level(&mut self, index: usize) {
+        let level = CAMPAIGN_LEVELS[index];
+        self.set_rules(level.rules(index));
+        self.psychic_candidates = None;
+        if self.password.is_daily() {
+            self.password = Password::random(&self.rules, self.settings.duplicate_colors);
+        }
+        self.reset_with_new_password();
+        self.campaign_level = Some(index);
+        self.toasts.push(
+            ToastKind::Info,
+            format!("{}: {}", campaign::level_name(index), level.description()),
+        );
+    }
+
+    /// The daily puzzle, psychic mode, hotseat matches, and LAN games set their own passwords, so
+    /// they take a campaign level's rules without playing it.
+    fn active_campaign_level(&self) -> Option<usize> {
+        self.campaign_level.filter(|_| {
+            !self.password.is_daily()
+                && self.psychic_candidates.is_none()
+                && self.pvp_match.is_none()
+                && self.lan.is_none()
+        })
+    }
+
+    /// Into the slot it was last saved to or loaded from, or a new one named after the stars so
+    /// far, to rename later.
+    fn save_campaign_slot(&mut self, now: Timestamp) {
+        let overwritten = match self.campaign_slot {
+            Some(id) => CAMPAIGN_SLOTS.overwrite(id, &self.campaign, now),
+            None => Ok(None),
+        };
+        let result = match overwritten {
+            Ok(Some(name)) => Ok(name),
+            Ok(None) => {
+                let name = format!("Campaign, {} stars", self.campaign.total_stars());
+                CAMPAIGN_SLOTS
+                    .save_new(&name, &self.campaign, now)
+                    .map(|id| {
+                        self.campaign_slot = Some(id);
+                        name
+                    })
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(name) => self
+                .toasts
+                .push(ToastKind::Info, format!("Saved to \"{name}\"")),
+            Err(e) => self
+                .toasts
+                .push(ToastKind::Error, format!("Couldn't save the campaign: {e}")),
+        }
+    }
+
+    /// Replaces the current progress, back on the map.
+    fn load_campaign_slot(&mut self, slot: SlotInfo) {
+        match CAMPAIGN_SLOTS.load::<CampaignProgress>(slot.id) {
+            Ok(Some(progress)) => {
+                self.campaign = progress;
+                self.campaign.save();
+                self.campaign_slot = Some(slot.id);
+                self.campaign_slot_browser = None;
+                self.campaign_map = Some(CampaignMap::new(&self.campaign));
+                self.toasts
+                    .push(ToastKind::Info, format!("Loaded \"{}\"", slot.name));
+            }
+            Ok(None) => self.toasts.push(
+                ToastKind::Error,
+                format!("\"{}\" isn't there any more", slot.name),
+            ),
+            Err(e) => self.toasts.push(
+                ToastKind::Error,
+                format!("Couldn't load \"{}\": {e}", slot.name),
+            ),
+        }
+    }
+
+    fn open_campaign_map(&mut self) {
+        self.campaign_map = Some(CampaignMap::new(&self.campaign));
+        self.show_heatmap = false;
+        self.show_high_scores = false;
+        self.show_achievements = false;
+        self.show_color_usage = false;
     }
 
     fn update(&mut self, now: Timestamp) {
         self.fps_counter.tick_frame(now);
+        self.toasts.tick(now);
+        let duplicate_colors = self.active_duplicate_colors();
+        if let Some(candidate_counter) = &mut self.candidate_counter {
+            candidate_counter.update(&self.rules, duplicate_colors, &self.history);
+        }
 
         if !self.mouse_moved && mq::mouse_position() != (0.0, 0.0) {
             self.mouse_moved = true;
         }
+        self.pointer.update();
+        self.update_board_scroll();
+        let hovered_peg = self.hovered_peg();
+        self.peg_hover.update(hovered_peg, now);
+        self.feedback_beeps.play_due(&self.sounds, now);
+        if matches!(self.state, GameState::InProgress { .. }) && !self.history.is_empty() {
+            if let Some(tag) = self.settings.feedback_mode.difficulty_tag() {
+                self.difficulty_tag = Some(tag);
+            }
+        }
+
+        // Letters typed into the seed input shouldn't also trigger hotkeys.
+        if matches!(self.state, GameState::EnterSeed { .. }) {
+            self.apply_state_specific_updates(now);
+            return;
+        }
+        // Same for notes
+        if let Some(note_editor) = &mut self.note_editor {
+            match note_editor.input.update() {
+                Some(TextInputEvent::Submit(text)) => {
+                    self.history[note_editor.history_index].note = notes::clean_note(&text);
+                    self.note_editor = None;
+                }
+                Some(TextInputEvent::Cancel) => self.note_editor = None,
+                None => {}
+            }
+            return;
+        }
+        // And LAN addresses
+        if let Some(address_input) = &mut self.lan_address_input {
+            match address_input.update() {
+                Some(TextInputEvent::Submit(address)) => {
+                    self.lan_address_input = None;
+                    self.lan = Some(LanSession::join(&address));
+                }
+                Some(TextInputEvent::Cancel) => self.lan_address_input = None,
+                None => {}
+            }
+            return;
+        }
+        // And the help overlay, which covers the board
+        if self.show_help {
+            if mq::is_key_pressed(KEY_TOGGLE_HELP) || mq::is_key_pressed(mq::KeyCode::Escape) {
+                self.show_help = false;
+            } else if mq::is_key_pressed(KEY_START_TUTORIAL) {
+                self.start_tutorial();
+            }
+            return;
+        }
+        // And skipping the tutorial, before the same key pauses
+        if self.tutorial.is_some() && mq::is_key_pressed(KEY_SKIP_TUTORIAL) {
+            self.tutorial = None;
+            self.toasts.push(
+                ToastKind::Info,
+                format!(
+                    "Tutorial skipped, [{}] for help",
+                    KEY_TOGGLE_HELP.to_lowercase()
+                ),
+            );
+            return;
+        }
+        if mq::is_key_pressed(KEY_TOGGLE_HELP) {
+            self.show_help = true;
+        }
+        // And the campaign map, which covers the board too, and its saves over that
+        if let Some(slot_browser) = &mut self.campaign_slot_browser {
+            match slot_browser.update() {
+                Some(SlotBrowserAction::Load(slot)) => self.load_campaign_slot(slot),
+                Some(SlotBrowserAction::Close) => self.campaign_slot_browser = None,
+                None => {}
+            }
+            return;
+        }
+        if let Some(campaign_map) = &mut self.campaign_map {
+            match campaign_map.update(&self.campaign) {
+                Some(MapAction::Play(index)) => {
+                    self.campaign_map = None;
+                    self.start_campaign_level(index);
+                }
+                Some(MapAction::SaveSlot) => self.save_campaign_slot(now),
+                Some(MapAction::BrowseSlots) => {
+                    self.campaign_slot_browser =
+                        Some(SaveSlotBrowser::new(CAMPAIGN_SLOTS, "Saved campaigns"));
+                }
+                Some(MapAction::Close) => self.campaign_map = None,
+                None => {}
+            }
+            return;
+        }
+        // And hotseat match hand-offs, which hide the board
+        if self.pvp_match.as_ref().is_some_and(PvpMatch::covers_board) {
+            self.update_match_cover();
+            return;
+        }
+        // And LAN games, when the other end is doing the playing
+        if self.lan.as_ref().is_some_and(LanSession::takes_over_input) {
+            self.update_lan_session();
+            return;
+        }
+        // And the pause screen, which covers the board so the clock can't be cheated
+        let can_pause = self.can_pause();
+        if let GameState::InProgress { clock, .. } = &mut self.state {
+            if clock.is_paused() {
+                let any_pressed = !mq::get_keys_pressed().is_empty()
+                    || mq::is_mouse_button_pressed(mq::MouseButton::Left);
+                if any_pressed {
+                    clock.resume(now);
+                }
+                return;
+            }
+            if mq::is_key_pressed(KEY_PAUSE) && can_pause {
+                clock.pause(now);
+                self.note_editor = None;
+                return;
+            }
+        }
+
+        // Open a note for a guess if needed
+        if mq::is_mouse_button_pressed(self.settings.mouse_buttons.clear()) {
+            let (mouse_x, mouse_y) = self.board_mouse_position();
+            if let Some(history_index) = self.history_index_at(mouse_x, mouse_y) {
+                let existing_note = self.history[history_index].note.as_deref();
+                self.note_editor = Some(NoteEditor::new(history_index, existing_note));
+            }
+        }
+
+        if mq::is_key_pressed(KEY_CYCLE_COLOR_HOTKEYS) {
+            self.settings.color_hotkeys = self.settings.color_hotkeys.next();
+            self.settings.save();
+            self.toasts.push(
+                ToastKind::Info,
+                format!("Color hotkeys: {}", self.settings.color_hotkeys.name()),
+            );
+        }
+
+        if mq::is_key_pressed(KEY_CYCLE_MOUSE_BUTTONS) {
+            self.settings.mouse_buttons = self.settings.mouse_buttons.next();
+            self.settings.save();
+            self.toasts.push(
+                ToastKind::Info,
+                format!(
+                    "Mouse buttons: {} ({})",
+                    self.settings.mouse_buttons.name(),
+                    self.settings.mouse_buttons.description()
+                ),
+            );
+        }
+
+        if mq::is_key_pressed(KEY_CYCLE_INPUT_MODE) {
+            self.settings.input_mode = self.settings.input_mode.next();
+            self.settings.save();
+            self.toasts.push(
+                ToastKind::Info,
+                format!(
+                    "Input mode: {} ({})",
+                    self.settings.input_mode.name(),
+                    self.settings.input_mode.description()
+                ),
+            );
+        }
+
+        if mq::is_key_pressed(KEY_CYCLE_THEME) {
+            self.settings.theme = self.settings.theme.next();
+            self.settings.save();
+            self.toasts.push(
+                ToastKind::Info,
+                format!("Theme: {}", self.settings.theme.name()),
+            );
+        }
+
+        if mq::is_key_pressed(KEY_TOGGLE_MIRRORED) {
+            self.settings.mirrored = !self.settings.mirrored;
+            self.settings.save();
+            let text = if self.settings.mirrored {
+                "Mirrored board: feedback pegs on the left"
+            } else {
+                "Feedback pegs on the right"
+            };
+            self.toasts.push(ToastKind::Info, text);
+        }
+
+        if mq::is_key_pressed(KEY_TOGGLE_STREAMER_MODE) {
+            bq::set_streamer_mode(!bq::is_streamer_mode());
+            let text = if bq::is_streamer_mode() {
+                "Streamer mode on: guesses are hidden until submitted"
+            } else {
+                "Streamer mode off"
+            };
+            self.toasts.push(ToastKind::Info, text);
+        }
+
+        if mq::is_key_pressed(KEY_TOGGLE_CONFIRM_SUBMIT) {
+            self.settings.confirm_submit = !self.settings.confirm_submit;
+            self.settings.save();
+            self.submit_requested_for = None;
+            let text = if self.settings.confirm_submit {
+                "Confirm before submit on: press submit twice"
+            } else {
+                "Confirm before submit off"
+            };
+            self.toasts.push(ToastKind::Info, text);
+        }
+
+        if mq::is_key_pressed(KEY_CYCLE_FEEDBACK_MODE) {
+            self.settings.feedback_mode = self.settings.feedback_mode.next();
+            self.settings.save();
+            if !self.settings.feedback_mode.has_sound() {
+                self.feedback_beeps.clear();
+            }
+            let text = match self.settings.feedback_mode {
+                FeedbackMode::Pegs => "Feedback: pegs",
+                FeedbackMode::PegsAndSound => {
+                    "Feedback: pegs, and high beeps for correct, low for misplaced"
+                }
+                FeedbackMode::SoundOnly => "Feedback: sound only, pegs show when the game is over",
+                FeedbackMode::ColorCounts => {
+                    "Feedback: pegs, and how many of each guessed color are in the password \
+                     (scores count as assisted)"
+                }
+            };
+            self.toasts.push(ToastKind::Info, text);
+            if self.settings.feedback_mode.has_sound() && self.settings.muted {
+                self.toasts.push(
+                    ToastKind::Info,
+                    format!(
+                        "Sound is off, press [{}] to hear feedback",
+                        KEY_TOGGLE_MUTE.to_lowercase()
+                    ),
+                );
+            }
+        }
+
+        if mq::is_key_pressed(KEY_TOGGLE_CANDIDATE_COUNTER) {
+            self.settings.count_candidates = !self.settings.count_candidates;
+            self.settings.save();
+            self.candidate_counter = self.new_candidate_counter();
+            let text = if self.settings.count_candidates {
+                "Counting passwords left"
+            } else {
+                "Not counting passwords left"
+            };
+            self.toasts.push(ToastKind::Info, text);
+        }
+
+        if mq::is_key_pressed(KEY_TOGGLE_MUTE) {
+            self.settings.muted = self.sounds.toggle_muted();
+            self.settings.save();
+            let text = if self.settings.muted {
+                "Sound off"
+            } else {
+                "Sound on"
+            };
+            self.toasts.push(ToastKind::Info, text);
+        }
 
         if mq::is_key_pressed(KEY_TOGGLE_NUMBER_OVERLAY) {
             self.number_overlay = match self.number_overlay {
@@ -259,210 +1260,1495 @@ impl MastermindGame {
             }
         }
 
-        if mq::is_key_pressed(KEY_COPY_SEED) {
-            // freaking clipboard isn't implemented anywhere except windows. Idk if this will work.
-            mq::miniquad::window::clipboard_set(&format!("{}", bq::get_last_set_rand_seed()));
+        self.results_click = self
+            .results_screen()
+            .and_then(|results_screen| results_screen.clicked_key());
+
+        if pressed_or_clicked(KEY_COPY_SEED, self.results_click) {
+            if let PasswordSource::Random { seed } = self.password.source() {
+                // freaking clipboard isn't implemented anywhere except windows. Idk if this will work.
+                let seed_code = bq::seed_to_code(seed);
+                mq::miniquad::window::clipboard_set(&seed_code);
+                self.toasts
+                    .push(ToastKind::Info, format!("Copied seed {seed_code}"));
+            }
         }
 
+        self.update_lan_codebreaker();
         self.apply_state_specific_updates(now);
+
+        if self.show_heatmap {
+            self.heatmap.render_new_clicks();
+        }
     }
 
     fn apply_state_specific_updates(&mut self, now: Timestamp) {
+        let is_casual_game = self.is_casual_game();
+        let counts_towards_stats = self.counts_towards_stats();
+        let duplicate_colors = self.active_duplicate_colors();
+        let board_mouse_position = self.board_mouse_position();
+        let eyedropped_color = self.eyedropped_color(board_mouse_position);
+        let submit_button_tapped = self.submit_button().is_some_and(|button| button.clicked());
+        let reroll_requested = self.reroll_button().is_some_and(|button| button.clicked())
+            || (mq::is_key_pressed(KEY_REROLL_PASSWORD) && self.can_reroll_password());
         match &mut self.state {
             GameState::InProgress {
                 working_row,
-                start_time,
+                clock,
                 ref mut mouse_click_release_behavior,
+                selected_slot,
             } => {
-                // Update mouse color if needed
-                if let Some((new_color, new_release_behavior)) = Self::get_mouse_color_update() {
-                    self.mouse_color = new_color;
-                    *mouse_click_release_behavior = new_release_behavior;
+                // Lose if out of time
+                if let Some(time_limit) = self.rules.time_limit() {
+                    if clock.elapsed(now) >= time_limit {
+                        self.state = GameState::OutOfTime;
+                        self.sounds.play(SoundEffect::Loss);
+                        self.record_game_result(GameResult::Lost);
+                        return;
+                    }
                 }
 
-                // Set working row's color if needed
-                if Self::should_set_color(mouse_click_release_behavior) {
-                    let (mouse_x, mouse_y) = mq::mouse_position();
-                    if let Some((i, j)) = guess_circles_ij::get_containing_ij(mouse_x, mouse_y) {
-                        if j == NUM_GUESSES - self.history.len() {
-                            working_row[i] = Some(self.mouse_color);
-                        }
+                if mq::is_mouse_button_pressed(mq::MouseButton::Left) {
+                    let (mouse_x, mouse_y) = board_mouse_position;
+                    self.heatmap.record_click(mouse_x, mouse_y);
+                }
+
+                // Set or move working row's colors if needed
+                let filled_slots = working_row.iter().map(Option::is_some).collect::<Vec<_>>();
+                if let Some(slot_update) = Self::get_slot_update(
+                    &self.rules,
+                    &self.settings,
+                    board_mouse_position,
+                    eyedropped_color,
+                    self.rules.num_guesses - self.history.len(),
+                    &filled_slots,
+                    &mut self.mouse_color,
+                    mouse_click_release_behavior,
+                    selected_slot,
+                ) {
+                    match slot_update {
+                        SlotUpdate::Set(i, color) => working_row[i] = Some(color),
+                        SlotUpdate::Swap(from, to) => working_row.swap(from, to),
                     }
+                    self.sounds.play(SoundEffect::PegPlaced);
                 }
                 // Unset working row's color if needed
-                if mq::is_mouse_button_pressed(mq::MouseButton::Right) {
-                    let (mouse_x, mouse_y) = mq::mouse_position();
-                    if let Some((i, j)) = guess_circles_ij::get_containing_ij(mouse_x, mouse_y) {
-                        if j == NUM_GUESSES - self.history.len() {
+                if mq::is_mouse_button_pressed(self.settings.mouse_buttons.clear()) {
+                    let (mouse_x, mouse_y) = board_mouse_position;
+                    if let Some((i, j)) = guess_circles_ij::get_containing_ij(
+                        &self.rules,
+                        self.settings.mirrored,
+                        mouse_x,
+                        mouse_y,
+                    ) {
+                        if j == self.rules.num_guesses - self.history.len() {
                             working_row[i] = None;
                         }
                     }
                 }
 
-                // Apply guess if needed
-                if mq::is_key_pressed(KEY_SUBMIT) {
-                    if let Some(guess) = convert_working_row_if_completed(working_row) {
-                        let complete_row = evaluate_guess(guess, *self.password.password());
-                        self.history.push(complete_row);
-
-                        if complete_row.num_correct_hits == NUM_SLOTS_PER_ROW {
-                            self.state = GameState::Victory {
-                                total_time: now - *start_time,
-                                mouse_animations: Box::new(VictoryMouseAnimations::new(
-                                    COLOR_PALETTE.map(|c| c.as_mq()).to_vec(),
-                                    now,
-                                    VICTORY_MULTI_CURSOR_OFFSET,
-                                    win_title::get(&self.history).title.to_string(),
-                                    VICTORY_CURSOR_TITLE_FONT_SIZE,
-                                    VICTORY_CURSOR_TITLE_OFFSET,
-                                )),
-                            };
-                            return;
-                        }
+                // Copy the last guess into the working row if needed, to tweak it instead of
+                // entering it all again
+                if mq::is_key_pressed(KEY_COPY_LAST_GUESS) {
+                    if let Some(last_row) = self.history.last() {
+                        *working_row = last_row.guess.iter().copied().map(Some).collect();
+                        self.sounds.play(SoundEffect::PegPlaced);
+                    }
+                }
 
-                        if self.history.len() == NUM_GUESSES {
-                            self.state = GameState::TooManyGuesses;
-                            return;
+                // Apply guess if needed. A LAN codebreaker's guesses are scored by the host, so
+                // the feedback comes in later.
+                let mut complete_row = self.lan.as_mut().and_then(LanSession::take_feedback);
+                let extra_submit_key = self.settings.color_hotkeys.extra_submit_key();
+                if mq::is_key_pressed(KEY_SUBMIT)
+                    || extra_submit_key.is_some_and(mq::is_key_pressed)
+                    || submit_button_tapped
+                {
+                    let guess = convert_working_row_if_completed(working_row);
+                    let contradiction = guess
+                        .as_ref()
+                        .filter(|_| self.settings.hard_mode)
+                        .and_then(|guess| candidates::find_contradiction(guess, &self.history));
+                    if let Some((i, row, would_have_gotten)) = contradiction {
+                        self.toasts.push(
+                            ToastKind::Error,
+                            format!(
+                                "Hard mode: that can't be the password.\n\
+                                Guess {} got {}, but it would\n\
+                                have gotten {} if it was.",
+                                i + 1,
+                                describe_feedback((row.num_correct_hits, row.num_misplaced_hits)),
+                                describe_feedback(would_have_gotten),
+                            ),
+                        );
+                        self.sounds.play(SoundEffect::IncompleteGuess);
+                    } else if guess.is_some()
+                        && self.settings.confirm_submit
+                        && self.submit_requested_for.as_ref() != Some(working_row)
+                    {
+                        self.submit_requested_for = Some(working_row.clone());
+                        self.toasts.push(
+                            ToastKind::Info,
+                            format!("Press [{}] again to submit", KEY_SUBMIT.to_lowercase()),
+                        );
+                    } else if let Some(guess) = guess {
+                        self.submit_requested_for = None;
+                        if let Some(lan) = &mut self.lan {
+                            match lan.send_guess(guess) {
+                                Ok(true) => {}
+                                // Still waiting on the last one
+                                Ok(false) => self.sounds.play(SoundEffect::IncompleteGuess),
+                                Err(e) => self
+                                    .toasts
+                                    .push(ToastKind::Error, format!("Couldn't send guess: {e}")),
+                            }
+                        } else {
+                            // Psychic mode decides on the feedback first, then picks a password
+                            // that fits it.
+                            if let Some(candidates) = &mut self.psychic_candidates {
+                                candidates.narrow_adversarially(&guess);
+                                if let Some(stand_in) = candidates.first() {
+                                    self.password = Password::psychic(stand_in.to_vec());
+                                }
+                            }
+                            complete_row = Some(evaluate_guess(guess, self.password.password()));
                         }
-
-                        *working_row = [None; NUM_SLOTS_PER_ROW];
+                    } else {
+                        self.sounds.play(SoundEffect::IncompleteGuess);
                     }
                 }
+                if let Some(mut complete_row) = complete_row {
+                    complete_row.elapsed = Some(clock.elapsed(now));
+                    let is_win = complete_row.num_correct_hits == self.rules.num_slots;
+                    self.history.push(complete_row);
+
+                    if is_win {
+                        let total_time = clock.elapsed(now);
+                        let is_new_best_time = counts_towards_stats
+                            && self.stats.record_win_time(
+                                self.password.source(),
+                                &self.rules.name,
+                                total_time,
+                            );
+                        let score = scoring::score_win(
+                            &self.rules,
+                            duplicate_colors,
+                            self.settings.hard_mode,
+                            self.difficulty_tag.is_some(),
+                            self.history.len(),
+                            total_time,
+                        );
+                        let high_score_rank = if counts_towards_stats {
+                            self.high_scores.record(
+                                self.password.source(),
+                                HighScore {
+                                    score,
+                                    rule_set_name: self.rules.name.clone(),
+                                    num_guesses: self.history.len(),
+                                    time_millis: total_time.as_millis() as u64,
+                                    difficulty_tag: self.difficulty_tag.map(str::to_string),
+                                },
+                            )
+                        } else {
+                            None
+                        };
+                        self.state = GameState::Victory {
+                            total_time,
+                            is_new_best_time,
+                            score,
+                            high_score_rank,
+                            mouse_animations: Box::new(VictoryMouseAnimations::new(
+                                self.rules.palette().iter().map(Color::as_mq).collect(),
+                                now,
+                                VICTORY_MULTI_CURSOR_OFFSET,
+                                win_title::get(&self.history).title.to_string(),
+                                VICTORY_CURSOR_TITLE_FONT_SIZE,
+                                VICTORY_CURSOR_TITLE_OFFSET,
+                            )),
+                        };
+                        self.sounds.play(SoundEffect::Victory);
+                        self.record_game_result(GameResult::Won {
+                            num_guesses: self.history.len(),
+                        });
+                        return;
+                    }
 
-                // Change to password edit mode if needed
-                if mq::is_key_pressed(KEY_PLAYER_EDIT_PASSWORD) {
-                    let working_row_empty = !working_row.iter().any(|c| c.is_some());
-                    if self.history.is_empty() && working_row_empty {
-                        self.state = GameState::EditPassword {
-                            mouse_click_release_behavior: MouseClickReleaseBehavior::None,
+                    if self.history.len() == self.rules.num_guesses {
+                        self.state = GameState::TooManyGuesses {
+                            elapsed: clock.elapsed(now),
                         };
+                        self.sounds.play(SoundEffect::Loss);
+                        self.record_game_result(GameResult::Lost);
+                        return;
+                    }
+
+                    working_row.fill(None);
+                    *selected_slot = None;
+                    self.sounds.play(SoundEffect::GuessSubmitted);
+                    // Heads up that the next guess is the last
+                    if self.history.len() + 1 == self.rules.num_guesses {
+                        bq::rumble(Rumble::TAP);
+                    }
+                    if self.settings.feedback_mode.has_sound() {
+                        let row = &self.history[self.history.len() - 1];
+                        self.feedback_beeps.queue(
+                            row.num_correct_hits,
+                            row.num_misplaced_hits,
+                            now,
+                        );
                     }
-                }
-            }
-            GameState::EditPassword {
-                ref mut mouse_click_release_behavior,
-            } => {
-                // Update mouse color if needed
-                if let Some((new_color, new_release_behavior)) = Self::get_mouse_color_update() {
-                    self.mouse_color = new_color;
-                    *mouse_click_release_behavior = new_release_behavior;
                 }
 
-                // Set password color if needed
-                if Self::should_set_color(mouse_click_release_behavior) {
-                    let (mouse_x, mouse_y) = mq::mouse_position();
-                    if let Some((i, j)) = guess_circles_ij::get_containing_ij(mouse_x, mouse_y) {
-                        if j == 0 {
-                            let mut password = *self.password.password();
-                            password[i] = self.mouse_color;
-                            self.password = Password::player_specified(password);
-                        }
+                // Give up if needed. Takes a second press to confirm, so it can't be hit by
+                // accident.
+                if mq::is_key_pressed(KEY_RESIGN) {
+                    let confirmed = self
+                        .resign_requested_at
+                        .and_then(|requested_at| now.duration_since(requested_at))
+                        .is_some_and(|since_requested| since_requested <= RESIGN_CONFIRM_WINDOW);
+                    if confirmed {
+                        self.state = GameState::Resigned;
+                        self.resign_requested_at = None;
+                        self.sounds.play(SoundEffect::Loss);
+                        self.record_game_result(GameResult::Resigned);
+                        return;
                     }
+                    self.resign_requested_at = Some(now);
+                    self.toasts.push(
+                        ToastKind::Info,
+                        format!(
+                            "Press [{}] again to give up and see the password",
+                            KEY_RESIGN.to_lowercase()
+                        ),
+                    );
                 }
 
-                // Change to InProgress mode if needed
-                if mq::is_key_pressed(KEY_PLAYER_EDIT_PASSWORD) {
-                    self.state = GameState::new_game();
+                // Take back the last guess if needed
+                if mq::is_key_pressed(KEY_UNDO_GUESS) && is_casual_game {
+                    if let Some(last_row) = self.history.pop() {
+                        *working_row = last_row.guess.into_iter().map(Some).collect();
+                        Self::rewind_psychic_candidates(
+                            &mut self.psychic_candidates,
+                            &mut self.password,
+                            &self.rules,
+                            self.settings.duplicate_colors,
+                            &self.history,
+                        );
+                    }
                 }
-            }
-            GameState::TooManyGuesses => {
-                if mq::is_key_pressed(KEY_REPLAY_PASSWORD) {
-                    self.reset_with_same_password();
-                } else if mq::is_key_pressed(KEY_NEW_PASSWORD) {
-                    self.reset_with_new_password();
+
+                // Re-roll the password if needed. Pegs already placed stay, since they're not a
+                // guess yet.
+                if reroll_requested {
+                    self.reroll_password();
+                    return;
                 }
-            }
-            GameState::Victory {
-                mouse_animations, ..
-            } => {
-                mouse_animations.tick(now);
 
-                if mq::is_key_pressed(KEY_REPLAY_PASSWORD) {
-                    self.reset_with_same_password();
-                } else if mq::is_key_pressed(KEY_NEW_PASSWORD) {
-                    self.reset_with_new_password();
+                let working_row_empty = !working_row.iter().any(|c| c.is_some());
+                // Nothing changes the rules or password mid-match
+                let game_unstarted = self.history.is_empty()
+                    && working_row_empty
+                    && self.pvp_match.is_none()
+                    && self.lan.is_none();
+
+                // Start a hotseat match if needed
+                if mq::is_key_pressed(KEY_START_MATCH) && game_unstarted {
+                    self.psychic_candidates = None;
+                    self.pvp_match = Some(PvpMatch::new(pvp::NUM_ROUNDS));
+                    return;
                 }
-            }
-        }
-    }
 
-    fn get_mouse_color_update() -> Option<(Color, MouseClickReleaseBehavior)> {
-        if let Some(color) = Self::get_color_from_key_press() {
-            return Some((color, MouseClickReleaseBehavior::None));
-        }
+                // Host or join a LAN game if needed
+                if mq::is_key_pressed(KEY_HOST_LAN_GAME) && game_unstarted {
+                    match LanSession::host(lan::LAN_PORT) {
+                        Ok(lan) => {
+                            self.lan = Some(lan);
+                            self.psychic_candidates = None;
+                            self.password =
+                                Password::random(&self.rules, self.settings.duplicate_colors);
+                        }
+                        Err(e) => self.toasts.push(
+                            ToastKind::Error,
+                            format!("Couldn't host on port {}: {e}", lan::LAN_PORT),
+                        ),
+                    }
+                    return;
+                }
+                if mq::is_key_pressed(KEY_JOIN_LAN_GAME) && game_unstarted {
+                    self.lan_address_input = Some(TextInput::new(
+                        lan::ADDRESS_INPUT_MAX_LEN,
+                        lan::is_address_char,
+                    ));
+                    return;
+                }
 
-        if let Some(color) = Self::get_color_from_mouse_click() {
-            return Some((color, MouseClickReleaseBehavior::FillColor));
-        }
+                if mq::is_key_pressed(KEY_OPEN_CAMPAIGN_MAP) && game_unstarted {
+                    self.open_campaign_map();
+                    return;
+                }
 
-        None
-    }
+                // Change to password edit mode if needed
+                if mq::is_key_pressed(KEY_PLAYER_EDIT_PASSWORD)
+                    && game_unstarted
+                    && !self.password.is_daily()
+                    && self.psychic_candidates.is_none()
+                {
+                    self.state = GameState::EditPassword {
+                        mouse_click_release_behavior: MouseClickReleaseBehavior::None,
+                        selected_slot: None,
+                    };
+                }
 
-    fn get_color_from_key_press() -> Option<Color> {
-        let num_keys = [
-            mq::KeyCode::Key1,
-            mq::KeyCode::Key2,
-            mq::KeyCode::Key3,
-            mq::KeyCode::Key4,
-            mq::KeyCode::Key5,
-            mq::KeyCode::Key6,
-            mq::KeyCode::Key7,
-            mq::KeyCode::Key8,
-            mq::KeyCode::Key9,
-        ];
+                // Switch between daily puzzle and classic if needed. Each daily can only be played
+                // once, so there's no switching to it after it's been completed.
+                if mq::is_key_pressed(KEY_TOGGLE_DAILY) && game_unstarted {
+                    if self.password.is_daily() {
+                        self.password =
+                            Password::random(&self.rules, self.settings.duplicate_colors);
+                    } else {
+                        let today = now.utc_day_number();
+                        if self.daily_record.completion_for_day(today).is_none() {
+                            self.set_rules(RuleSet::classic());
+                            self.psychic_candidates = None;
+                            self.password = Password::daily(today);
+                        }
+                    }
+                }
 
-        let mut i = 0;
-        loop {
-            if i >= num_keys.len() || i >= COLOR_PALETTE.len() {
-                return None;
+                // Toggle psychic mode if needed
+                if mq::is_key_pressed(KEY_TOGGLE_PSYCHIC)
+                    && game_unstarted
+                    && !self.password.is_daily()
+                {
+                    if self.psychic_candidates.is_some() {
+                        self.psychic_candidates = None;
+                        self.password =
+                            Password::random(&self.rules, self.settings.duplicate_colors);
+                        self.toasts.push(ToastKind::Info, "Psychic mode off");
+                    } else {
+                        self.start_psychic_game();
+                        if self.psychic_candidates.is_some() {
+                            self.toasts.push(
+                                ToastKind::Info,
+                                "Psychic mode on. I know what you'll guess.",
+                            );
+                        }
+                    }
+                }
+
+                // Toggle hard mode if needed. Not mid-game, so earlier guesses can't break the rule.
+                if mq::is_key_pressed(KEY_TOGGLE_HARD_MODE) && game_unstarted {
+                    self.settings.hard_mode = !self.settings.hard_mode;
+                    self.settings.save();
+                    let text = if self.settings.hard_mode {
+                        "Hard mode on: every guess has to fit the feedback so far"
+                    } else {
+                        "Hard mode off"
+                    };
+                    self.toasts.push(ToastKind::Info, text);
+                }
+
+                // Change duplicate colors rule if needed. Daily puzzles always allow them.
+                if mq::is_key_pressed(KEY_CYCLE_DUPLICATE_COLORS)
+                    && game_unstarted
+                    && !self.password.is_daily()
+                {
+                    self.settings.duplicate_colors = self.settings.duplicate_colors.next();
+                    self.settings.save();
+                    self.reset_with_new_password();
+                    let duplicate_colors = self.settings.duplicate_colors;
+                    let text = if duplicate_colors.effective_for(&self.rules) == duplicate_colors {
+                        format!("Duplicate colors: {}", duplicate_colors.name())
+                    } else {
+                        format!(
+                            "Duplicate colors: {}\n(but allowed in {}, which has more slots than colors)",
+                            duplicate_colors.name(),
+                            self.rules.name
+                        )
+                    };
+                    self.toasts.push(ToastKind::Info, text);
+                }
+
+                // Toggle the free re-roll if needed
+                if mq::is_key_pressed(KEY_TOGGLE_FREE_REROLL) && game_unstarted {
+                    self.settings.free_reroll = !self.settings.free_reroll;
+                    self.settings.save();
+                    let text = if self.settings.free_reroll {
+                        format!(
+                            "Free re-roll on: [{}] gets a new password once a game, before the first guess",
+                            KEY_REROLL_PASSWORD.to_lowercase()
+                        )
+                    } else {
+                        "Free re-roll off".to_string()
+                    };
+                    self.toasts.push(ToastKind::Info, text);
+                }
+
+                // Toggle casual mode if needed. Not mid-game, so a game either counts or it doesn't.
+                if mq::is_key_pressed(KEY_TOGGLE_CASUAL) && game_unstarted {
+                    self.settings.casual = !self.settings.casual;
+                    self.settings.save();
+                    let text = if self.settings.casual {
+                        format!(
+                            "Casual mode on: [{}] undoes guesses, and games don't count towards stats",
+                            KEY_UNDO_GUESS.to_lowercase()
+                        )
+                    } else {
+                        "Casual mode off".to_string()
+                    };
+                    self.toasts.push(ToastKind::Info, text);
+                }
+
+                // Change to seed entry mode if needed
+                if mq::is_key_pressed(KEY_ENTER_SEED) && game_unstarted && !self.password.is_daily()
+                {
+                    self.state = GameState::EnterSeed {
+                        seed_input: TextInput::new(SEED_INPUT_MAX_LEN, |c| {
+                            c.is_ascii_alphanumeric()
+                        }),
+                    };
+                    return;
+                }
+
+                // Change rules if needed. Daily puzzles are always classic.
+                if mq::is_key_pressed(KEY_CYCLE_RULE_SET)
+                    && game_unstarted
+                    && !self.password.is_daily()
+                {
+                    self.cycle_rule_set();
+                }
+            }
+            GameState::EditPassword {
+                ref mut mouse_click_release_behavior,
+                selected_slot,
+            } => {
+                // Set or move password colors if needed
+                if let Some(slot_update) = Self::get_slot_update(
+                    &self.rules,
+                    &self.settings,
+                    board_mouse_position,
+                    eyedropped_color,
+                    0,
+                    &vec![true; self.rules.num_slots],
+                    &mut self.mouse_color,
+                    mouse_click_release_behavior,
+                    selected_slot,
+                ) {
+                    let mut password = self.password.password().to_vec();
+                    match slot_update {
+                        SlotUpdate::Set(i, color) => password[i] = color,
+                        SlotUpdate::Swap(from, to) => password.swap(from, to),
+                    }
+                    self.password = Password::player_specified(password);
+                    self.sounds.play(SoundEffect::PegPlaced);
+                }
+
+                // Change to InProgress mode if needed
+                if mq::is_key_pressed(KEY_PLAYER_EDIT_PASSWORD) {
+                    self.state = GameState::new_game(&self.rules);
+                    if let Some(pvp_match) = &mut self.pvp_match {
+                        pvp_match.phase = MatchPhase::HandOffToCodebreaker;
+                    }
+                }
+            }
+            GameState::EnterSeed { seed_input } => match seed_input.update() {
+                Some(TextInputEvent::Submit(code)) => match bq::seed_from_code(&code) {
+                    Some(seed) => {
+                        self.psychic_candidates = None;
+                        self.password =
+                            Password::from_seed(&self.rules, self.settings.duplicate_colors, seed);
+                        self.state = GameState::new_game(&self.rules);
+                    }
+                    None => {
+                        self.toasts
+                            .push(ToastKind::Error, format!("Invalid seed \"{code}\""));
+                    }
+                },
+                Some(TextInputEvent::Cancel) => {
+                    self.state = GameState::new_game(&self.rules);
+                }
+                None => {}
+            },
+            GameState::TooManyGuesses { elapsed } => {
+                // Un-lose by taking back the last guess if needed
+                if pressed_or_clicked(KEY_UNDO_GUESS, self.results_click) && is_casual_game {
+                    if let Some(last_row) = self.history.pop() {
+                        Self::rewind_psychic_candidates(
+                            &mut self.psychic_candidates,
+                            &mut self.password,
+                            &self.rules,
+                            self.settings.duplicate_colors,
+                            &self.history,
+                        );
+                        self.state = GameState::InProgress {
+                            clock: Stopwatch::resumed_from(*elapsed, now),
+                            working_row: last_row.guess.into_iter().map(Some).collect(),
+                            mouse_click_release_behavior: MouseClickReleaseBehavior::None,
+                            selected_slot: None,
+                        };
+                        return;
+                    }
+                }
+                self.apply_new_game_updates();
+            }
+            GameState::OutOfTime | GameState::Resigned => {
+                self.apply_new_game_updates();
+            }
+            GameState::Victory {
+                mouse_animations, ..
+            } => {
+                mouse_animations.tick(now);
+                self.apply_new_game_updates();
+            }
+            GameState::Reviewing { history_index, .. } => {
+                if mq::is_key_pressed(KEY_REVIEW_PREVIOUS) {
+                    *history_index = history_index.saturating_sub(1);
+                }
+                // Stepping past the last guess goes back too
+                let is_last = *history_index + 1 == self.history.len();
+                let next_pressed = mq::is_key_pressed(KEY_REVIEW_NEXT);
+                if next_pressed && !is_last {
+                    *history_index += 1;
+                }
+                if mq::is_key_pressed(mq::KeyCode::Escape) || (next_pressed && is_last) {
+                    if let GameState::Reviewing { end_state, .. } =
+                        mem::replace(&mut self.state, GameState::Resigned)
+                    {
+                        self.state = *end_state;
+                    }
+                }
+            }
+        }
+    }
+
+    fn apply_new_game_updates(&mut self) {
+        // Review from the last guess back
+        if pressed_or_clicked(KEY_REVIEW_PREVIOUS, self.results_click) && !self.history.is_empty() {
+            let end_state = mem::replace(&mut self.state, GameState::Resigned);
+            self.state = GameState::Reviewing {
+                history_index: self.history.len() - 1,
+                end_state: Box::new(end_state),
+            };
+            self.show_heatmap = false;
+            self.show_high_scores = false;
+            self.show_achievements = false;
+            self.show_color_usage = false;
+            return;
+        }
+
+        if pressed_or_clicked(KEY_EXPORT_GAME, self.results_click) {
+            self.export_game();
+        }
+
+        // All cover the middle of the window, so only one at a time.
+        if pressed_or_clicked(KEY_TOGGLE_HEATMAP, self.results_click) {
+            self.show_heatmap = !self.show_heatmap;
+            self.show_high_scores = false;
+            self.show_achievements = false;
+            self.show_color_usage = false;
+        }
+        if pressed_or_clicked(KEY_TOGGLE_HIGH_SCORES, self.results_click) {
+            self.show_high_scores = !self.show_high_scores;
+            self.show_heatmap = false;
+            self.show_achievements = false;
+            self.show_color_usage = false;
+        }
+        if pressed_or_clicked(KEY_TOGGLE_ACHIEVEMENTS, self.results_click) {
+            self.show_achievements = !self.show_achievements;
+            self.show_heatmap = false;
+            self.show_high_scores = false;
+            self.show_color_usage = false;
+        }
+        if pressed_or_clicked(KEY_TOGGLE_COLOR_USAGE, self.results_click) {
+            self.show_color_usage = !self.show_color_usage;
+            self.show_heatmap = false;
+            self.show_high_scores = false;
+            self.show_achievements = false;
+        }
+
+        // In a match, on to the next turn instead
+        let continue_match = pressed_or_clicked(KEY_CONTINUE_MATCH, self.results_click);
+        if let Some(pvp_match) = &mut self.pvp_match {
+            if continue_match {
+                pvp_match.next_turn();
             }
+            return;
+        }
+        // In a LAN game, the codebreaker makes the next password
+        if let Some(lan) = &self.lan {
+            if lan.can_start_next_game() && pressed_or_clicked(KEY_NEW_PASSWORD, self.results_click)
+            {
+                self.start_lan_game();
+            }
+            return;
+        }
 
-            if mq::is_key_pressed(num_keys[i]) {
-                return Some(COLOR_PALETTE[i]);
+        if pressed_or_clicked(KEY_OPEN_CAMPAIGN_MAP, self.results_click) {
+            self.open_campaign_map();
+            return;
+        }
+        // In the campaign, every game's a fresh password, so the stars can't be farmed
+        if let Some(level) = self.active_campaign_level() {
+            if pressed_or_clicked(KEY_REPLAY_PASSWORD, self.results_click) {
+                self.start_campaign_level(level);
+            } else if pressed_or_clicked(KEY_NEW_PASSWORD, self.results_click)
+                && self.campaign.is_unlocked(level + 1)
+            {
+                self.start_campaign_level(level + 1);
             }
+            return;
+        }
 
-            i += 1;
+        // No replaying the daily puzzle to improve your result.
+        if pressed_or_clicked(KEY_REPLAY_PASSWORD, self.results_click) && !self.password.is_daily()
+        {
+            self.reset_with_same_password();
+        } else if pressed_or_clicked(KEY_NEW_PASSWORD, self.results_click) {
+            self.reset_with_new_password();
+        }
+    }
+
+    /// Saves the finished game as JSON, for sharing or analyzing it elsewhere.
+    fn export_game(&mut self) {
+        let (outcome, total_time) = match &self.state {
+            GameState::Victory { total_time, .. } => (GameOutcome::Won, Some(*total_time)),
+            GameState::TooManyGuesses { elapsed } => (GameOutcome::Lost, Some(*elapsed)),
+            GameState::OutOfTime => (
+                GameOutcome::OutOfTime,
+                self.rules.time_limit_secs.map(Duration::from_secs),
+            ),
+            GameState::Resigned => (GameOutcome::GaveUp, None),
+            GameState::InProgress { .. }
+            | GameState::EditPassword { .. }
+            | GameState::EnterSeed { .. }
+            | GameState::Reviewing { .. } => return,
+        };
+        let seed = match self.password.source() {
+            PasswordSource::Random { seed } => Some(bq::seed_to_code(seed)),
+            PasswordSource::Daily { .. } | PasswordSource::Player | PasswordSource::Psychic => None,
+        };
+        let export = GameExport::new(
+            &self.rules,
+            self.active_duplicate_colors(),
+            seed,
+            outcome,
+            total_time,
+            self.password.password(),
+            &self.history,
+        );
+        let file_name = export::file_name(Timestamp::now().as_sec_f64() as u64);
+        match bq::save_json_export("mastermind", &file_name, &export) {
+            Ok(path) => self
+                .toasts
+                .push(ToastKind::Info, format!("Saved game to {}", path.display())),
+            Err(message) => self.toasts.push(ToastKind::Error, message),
+        }
+    }
+
+    /// Casual games allow undo, so they don't count towards stats. The daily puzzle, hotseat
+    /// matches, and LAN games are never casual.
+    fn is_casual_game(&self) -> bool {
+        self.settings.casual
+            && !self.password.is_daily()
+            && self.pvp_match.is_none()
+            && self.lan.is_none()
+    }
+
+    /// Covered between turns of a hotseat match, and at the end of it.
+    fn update_match_cover(&mut self) {
+        let Some(phase) = self.pvp_match.as_ref().map(|pvp_match| pvp_match.phase) else {
+            return;
+        };
+        if mq::is_key_pressed(mq::KeyCode::Escape) {
+            self.pvp_match = None;
+            self.reset_with_new_password();
+            return;
+        }
+        if !mq::is_key_pressed(KEY_CONTINUE_MATCH) {
+            return;
+        }
+        match phase {
+            MatchPhase::HandOffToCodemaker => {
+                self.reset_with_same_password();
+                self.password =
+                    Password::player_specified(vec![self.rules.palette()[0]; self.rules.num_slots]);
+                self.state = GameState::EditPassword {
+                    mouse_click_release_behavior: MouseClickReleaseBehavior::None,
+                    selected_slot: None,
+                };
+                if let Some(pvp_match) = &mut self.pvp_match {
+                    pvp_match.phase = MatchPhase::SettingPassword;
+                    self.toasts.push(
+                        ToastKind::Info,
+                        format!(
+                            "{}: set the password, then press [{}]",
+                            pvp_match.codemaker_name(),
+                            KEY_PLAYER_EDIT_PASSWORD.to_lowercase()
+                        ),
+                    );
+                }
+            }
+            MatchPhase::HandOffToCodebreaker => {
+                if let Some(pvp_match) = &mut self.pvp_match {
+                    pvp_match.phase = MatchPhase::Breaking;
+                    self.toasts.push(
+                        ToastKind::Info,
+                        format!("{}: break the password!", pvp_match.codebreaker_name()),
+                    );
+                }
+            }
+            MatchPhase::Finished => {
+                self.pvp_match = Some(PvpMatch::new(pvp::NUM_ROUNDS));
+            }
+            MatchPhase::SettingPassword | MatchPhase::Breaking => {}
+        }
+    }
+
+    /// Making the password in a LAN game, hosting, or waiting to join one.
+    fn update_lan_session(&mut self) {
+        if mq::is_key_pressed(mq::KeyCode::Escape) {
+            self.end_lan_session();
+            return;
+        }
+        let Some(lan) = &mut self.lan else {
+            return;
+        };
+        match lan.poll_connecting() {
+            Some(Ok(())) => return,
+            Some(Err(e)) => {
+                self.toasts.push(ToastKind::Error, e);
+                self.lan = None;
+                return;
+            }
+            None => {}
+        }
+        self.poll_lan_messages();
+    }
+
+    /// Breaking the password in a LAN game, on your own board.
+    fn update_lan_codebreaker(&mut self) {
+        if !self.lan.as_ref().is_some_and(LanSession::is_codebreaker) {
+            return;
+        }
+        // Esc already means going back from a review
+        if mq::is_key_pressed(mq::KeyCode::Escape)
+            && !matches!(self.state, GameState::Reviewing { .. })
+        {
+            self.end_lan_session();
+            return;
+        }
+        self.poll_lan_messages();
+    }
+
+    fn poll_lan_messages(&mut self) {
+        let Some(lan) = &mut self.lan else {
+            return;
+        };
+        match lan.poll() {
+            Ok(messages) => {
+                for message in messages {
+                    self.apply_lan_message(message);
+                }
+            }
+            Err(e) => self.lose_lan_connection(e),
+        }
+    }
+
+    /// The host goes back to waiting for someone to join, and anyone else leaves.
+    fn lose_lan_connection(&mut self, e: NetError) {
+        let Some(lan) = &self.lan else {
+            return;
+        };
+        if lan.is_host() {
+            self.toasts
+                .push(ToastKind::Error, format!("The other player left ({e})"));
+            self.reset_with_new_password();
+        } else {
+            self.toasts
+                .push(ToastKind::Error, format!("Lost the host ({e})"));
+            self.lan = None;
+            if matches!(self.state, GameState::InProgress { .. }) {
+                self.reset_with_new_password();
+            }
+        }
+    }
+
+    fn apply_lan_message(&mut self, message: LanMessage) {
+        let Some(lan) = &self.lan else {
+            return;
+        };
+        let (is_codemaker, is_game_over) = (lan.is_codemaker(), lan.is_game_over());
+        match message {
+            LanMessage::Hello { version } => match LanSession::check_version(version) {
+                Ok(()) => {
+                    self.toasts.push(ToastKind::Info, "Someone joined!");
+                    self.start_lan_game();
+                }
+                Err(reason) => {
+                    self.toasts.push(ToastKind::Error, reason.clone());
+                    if let Some(lan) = &mut self.lan {
+                        lan.reject(reason);
+                    }
+                }
+            },
+            LanMessage::Rejected { reason } => {
+                self.toasts.push(ToastKind::Error, reason);
+                self.end_lan_session();
+            }
+            // The other player's turn to make a password
+            LanMessage::NewGame { rules } => match lan::validate_rules(rules) {
+                Ok(rules) => {
+                    if let Some(lan) = &mut self.lan {
+                        lan.start_as_codebreaker();
+                    }
+                    self.psychic_candidates = None;
+                    self.set_rules(rules);
+                    self.reset_with_same_password();
+                    // Just a stand-in until it's revealed
+                    self.password = Password::player_specified(vec![
+                        self.rules.palette()[0];
+                        self.rules.num_slots
+                    ]);
+                    self.toasts
+                        .push(ToastKind::Info, "New game! Break their password.");
+                }
+                Err(e) => {
+                    self.toasts
+                        .push(ToastKind::Error, format!("Their rules are bad: {e}"));
+                    self.end_lan_session();
+                }
+            },
+            LanMessage::Guess { colors } => {
+                if !is_codemaker
+                    || is_game_over
+                    || !matches!(self.state, GameState::InProgress { .. })
+                {
+                    return;
+                }
+                let Some(guess) = lan::decode_colors(&colors, &self.rules) else {
+                    return;
+                };
+                let mut complete_row = evaluate_guess(guess, self.password.password());
+                if let GameState::InProgress { clock, .. } = &self.state {
+                    complete_row.elapsed = Some(clock.elapsed(Timestamp::now()));
+                }
+                let feedback = LanMessage::Feedback {
+                    num_correct_hits: complete_row.num_correct_hits,
+                    num_misplaced_hits: complete_row.num_misplaced_hits,
+                };
+                let is_win = complete_row.num_correct_hits == self.rules.num_slots;
+                self.history.push(complete_row);
+                self.send_lan_message(&feedback);
+                if is_win || self.history.len() == self.rules.num_guesses {
+                    self.reveal_lan_password(!is_win);
+                } else {
+                    self.sounds.play(SoundEffect::GuessSubmitted);
+                    if self.settings.feedback_mode.has_sound() {
+                        let row = &self.history[self.history.len() - 1];
+                        self.feedback_beeps.queue(
+                            row.num_correct_hits,
+                            row.num_misplaced_hits,
+                            Timestamp::now(),
+                        );
+                    }
+                }
+            }
+            LanMessage::GameOver => {
+                if is_codemaker && !is_game_over {
+                    self.reveal_lan_password(true);
+                }
+            }
+            LanMessage::Feedback {
+                num_correct_hits,
+                num_misplaced_hits,
+            } => {
+                if let Some(lan) = &mut self.lan {
+                    lan.receive_feedback(num_correct_hits, num_misplaced_hits);
+                }
+            }
+            LanMessage::Reveal { password } => {
+                if is_codemaker {
+                    return;
+                }
+                if let Some(lan) = &mut self.lan {
+                    lan.receive_reveal();
+                }
+                if let Some(password) = lan::decode_colors(&password, &self.rules) {
+                    self.password = Password::player_specified(password);
+                }
+            }
+        }
+    }
+
+    /// A new password for the other player to break.
+    fn start_lan_game(&mut self) {
+        self.reset_with_new_password();
+        if let Some(lan) = &mut self.lan {
+            lan.start_as_codemaker();
+        }
+        self.send_lan_message(&LanMessage::NewGame {
+            rules: self.rules.clone(),
+        });
+    }
+
+    /// The game's over, one way or another.
+    fn reveal_lan_password(&mut self, codemaker_won: bool) {
+        if let Some(lan) = &mut self.lan {
+            lan.finish_as_codemaker(self.history.len(), !codemaker_won);
         }
+        self.send_lan_message(&LanMessage::Reveal {
+            password: lan::encode_colors(self.password.password()),
+        });
+        self.sounds.play(if codemaker_won {
+            SoundEffect::Victory
+        } else {
+            SoundEffect::Loss
+        });
     }
 
-    fn get_color_from_mouse_click() -> Option<Color> {
-        if mq::is_mouse_button_pressed(mq::MouseButton::Left) {
-            let (mouse_x, mouse_y) = mq::mouse_position();
-            if let Some(peg_i) = pegs_ij::get_containing_i(mouse_x, mouse_y) {
-                return Some(COLOR_PALETTE[peg_i]);
+    /// The other end won't get anything after a failure, so the host waits for someone else to
+    /// join, and anyone else leaves.
+    fn send_lan_message(&mut self, message: &LanMessage) {
+        let Some(lan) = &mut self.lan else {
+            return;
+        };
+        if let Err(e) = lan.send(message) {
+            self.toasts
+                .push(ToastKind::Error, format!("Lost the connection ({e})"));
+            if lan.is_host() {
+                self.reset_with_new_password();
+            } else {
+                self.end_lan_session();
+            }
+        }
+    }
+
+    fn end_lan_session(&mut self) {
+        self.lan = None;
+        self.reset_with_new_password();
+    }
+
+    /// What passwords are generated with right now.
+    fn active_duplicate_colors(&self) -> DuplicateColors {
+        if self.password.is_daily() {
+            DuplicateColors::Allowed
+        } else {
+            self.settings.duplicate_colors.effective_for(&self.rules)
+        }
+    }
+
+    /// Some if the setting's on.
+    fn new_candidate_counter(&self) -> Option<CandidateCounter> {
+        self.settings
+            .count_candidates
+            .then(|| CandidateCounter::new(&self.rules, self.active_duplicate_colors()))
+    }
+
+    /// Casual, psychic, hotseat, and LAN games are just for fun. Campaign games are scored in
+    /// stars instead.
+    fn counts_towards_stats(&self) -> bool {
+        !self.is_casual_game()
+            && self.psychic_candidates.is_none()
+            && self.pvp_match.is_none()
+            && self.lan.is_none()
+            && self.active_campaign_level().is_none()
+    }
+
+    /// The free re-roll is for random passwords, and goes away once it's used or the first guess
+    /// is in.
+    fn can_reroll_password(&self) -> bool {
+        self.settings.free_reroll
+            && !self.password_rerolled
+            && self.history.is_empty()
+            && matches!(self.state, GameState::InProgress { .. })
+            && matches!(self.password.source(), PasswordSource::Random { .. })
+            && self.pvp_match.is_none()
+            && self.lan.is_none()
+    }
+
+    fn reroll_password(&mut self) {
+        self.password = Password::random(&self.rules, self.settings.duplicate_colors);
+        self.password_rerolled = true;
+        self.sounds.play(SoundEffect::PegPlaced);
+        self.toasts.push(
+            ToastKind::Info,
+            "Re-rolled the password. That's it for this game!",
+        );
+    }
+
+    fn record_game_result(&mut self, result: GameResult) {
+        match result {
+            GameResult::Won { .. } => {
+                bq::rumble(Rumble::CELEBRATE);
+                bq::set_controller_led(Some(mq::GREEN));
+            }
+            GameResult::Lost => {
+                bq::rumble(Rumble::PULSE);
+                bq::set_controller_led(Some(mq::RED));
+            }
+            GameResult::Resigned => {}
+        }
+        if let Some(pvp_match) = &mut self.pvp_match {
+            pvp_match.record_turn(self.history.len(), matches!(result, GameResult::Won { .. }));
+        }
+        // So the codemaker reveals the password
+        if let Some(lan) = self.lan.as_mut().filter(|lan| lan.is_codebreaker()) {
+            lan.finish_as_codebreaker(self.history.len(), matches!(result, GameResult::Won { .. }));
+            self.send_lan_message(&LanMessage::GameOver);
+        }
+        // Undo makes stars too easy
+        if let (Some(level), GameResult::Won { num_guesses }, false) =
+            (self.active_campaign_level(), result, self.is_casual_game())
+        {
+            let stars = CAMPAIGN_LEVELS[level].stars(num_guesses);
+            self.campaign_new_best = self.campaign.record_win(level, stars);
+        }
+        if !self.counts_towards_stats() {
+            return;
+        }
+        self.stats.record_game(
+            self.password.source(),
+            result,
+            self.password_rerolled,
+            &self.history,
+            self.password.password(),
+        );
+        if let PasswordSource::Daily { day_number } = self.password.source() {
+            self.daily_record
+                .record_completion(day_number, result.num_guesses_to_win());
+        }
+
+        // Same as stats: player-specified passwords are for 2-player play.
+        if let PasswordSource::Player = self.password.source() {
+            return;
+        }
+        let newly_unlocked = self.achievements.record_game(
+            &FinishedGame {
+                result,
+                history: &self.history,
+                is_daily: self.password.is_daily(),
+                hard_mode: self.settings.hard_mode,
+                timed: self.rules.time_limit().is_some(),
+                games_played: self.stats.games_played(),
+                current_streak: self.stats.current_streak(),
+            },
+            Timestamp::now().utc_day_number(),
+        );
+        for achievement in newly_unlocked {
+            self.toasts.push(
+                ToastKind::Info,
+                format!("Achievement unlocked: {}!", achievement.name()),
+            );
+        }
+    }
+
+    fn layout(&self) -> BoardLayout {
+        let (width, height) = BoardSizeDerivedConsts::get(&self.rules).window_size();
+        BoardLayout::for_window(width, height, self.board_scroll)
+    }
+
+    /// Scrolls big boards with the mouse wheel, or by dragging with the middle mouse button. The
+    /// row being filled in and the pegs below the board stay in view.
+    fn update_board_scroll(&mut self) {
+        let layout = self.layout();
+        let (_, wheel_y) = mq::mouse_wheel();
+        if wheel_y != 0.0 {
+            self.board_scroll -= wheel_y.signum() * SCROLL_STEP;
+        }
+        if mq::is_mouse_button_down(mq::MouseButton::Middle) {
+            let (_, mouse_y) = mq::mouse_position();
+            if let Some(drag_y) = self.scroll_drag_y {
+                self.board_scroll -= (mouse_y - drag_y) / layout.scale();
+            }
+            self.scroll_drag_y = Some(mouse_y);
+        } else {
+            self.scroll_drag_y = None;
+        }
+        self.board_scroll = clamp_scroll(
+            self.board_scroll,
+            layout.max_scroll(),
+            layout.visible_height(),
+            self.pinned_area(),
+        );
+    }
+
+    /// Top and bottom of what scrolling has to keep in view, in board units: from the row being
+    /// filled in down to the bottom of the pegs.
+    fn pinned_area(&self) -> Option<(f32, f32)> {
+        let j = match self.state {
+            GameState::InProgress { .. } => self.rules.num_guesses - self.history.len(),
+            GameState::EditPassword { .. } => 0,
+            _ => return None,
+        };
+        let board_size_derived_consts = BoardSizeDerivedConsts::get(&self.rules);
+        let row_top = BOARD_OFFSET_Y
+            + (board_size_derived_consts.row_height + ROW_SEPARATOR_HEIGHT) * j as f32;
+        Some((row_top, board_size_derived_consts.heatmap_area().bottom()))
+    }
+
+    /// In the working row's feedback area, for touchscreens, which have no keys to submit with.
+    fn submit_button(&self) -> Option<bq::Button> {
+        self.submit_button_rect()
+            .map(|rect| self.board_button(rect, "Submit"))
+    }
+
+    /// Over the password's feedback area, while the free re-roll can be used.
+    fn reroll_button(&self) -> Option<bq::Button> {
+        self.reroll_button_rect()
+            .map(|rect| self.board_button(rect, "Re-roll"))
+    }
+
+    /// In board units, so drawn with the board's camera.
+    fn board_button(&self, rect: mq::Rect, label: &str) -> bq::Button {
+        bq::Button::new(rect, label, self.settings.theme.theme().highlight)
+            .with_mouse_position(mq::Vec2::from(self.board_mouse_position()))
+            .max_font_size(SUBMIT_BUTTON_MAX_FONT_SIZE)
+    }
+
+    /// In board units.
+    fn submit_button_rect(&self) -> Option<mq::Rect> {
+        if !self.pointer.is_touch() || !matches!(self.state, GameState::InProgress { .. }) {
+            return None;
+        }
+        let board_size_derived_consts = BoardSizeDerivedConsts::get(&self.rules);
+        let BoardSizeDerivedConsts {
+            row_height,
+            row_width_key,
+            ..
+        } = board_size_derived_consts;
+        let j = (self.rules.num_guesses - self.history.len()) as f32;
+        Some(mq::Rect::new(
+            board_size_derived_consts.key_x(self.settings.mirrored),
+            BOARD_OFFSET_Y + (row_height + ROW_SEPARATOR_HEIGHT) * j,
+            row_width_key,
+            row_height,
+        ))
+    }
+
+    /// In board units.
+    fn reroll_button_rect(&self) -> Option<mq::Rect> {
+        if !self.can_reroll_password() {
+            return None;
+        }
+        let board_size_derived_consts = BoardSizeDerivedConsts::get(&self.rules);
+        Some(mq::Rect::new(
+            board_size_derived_consts.key_x(self.settings.mirrored),
+            BOARD_OFFSET_Y,
+            board_size_derived_consts.row_width_key,
+            board_size_derived_consts.row_height,
+        ))
+    }
+
+    /// Along the right edge of the window, when the board scrolls.
+    fn draw_scrollbar(&self) {
+        let layout = self.layout();
+        if layout.max_scroll() <= 0.0 {
+            return;
+        }
+        let content_height = layout.visible_height() + layout.max_scroll();
+        let screen_height = mq::screen_height();
+        mq::draw_rectangle(
+            mq::screen_width() - SCROLLBAR_WIDTH,
+            screen_height * self.board_scroll / content_height,
+            SCROLLBAR_WIDTH,
+            screen_height * layout.visible_height() / content_height,
+            SCROLLBAR_COLOR,
+        );
+    }
+
+    /// Where the mouse is on the board, in board units rather than window pixels.
+    fn board_mouse_position(&self) -> (f32, f32) {
+        let (mouse_x, mouse_y) = mq::mouse_position();
+        self.layout().screen_to_board(mouse_x, mouse_y)
+    }
+
+    /// The palette peg or placed peg under the mouse, for its color name tooltip. Only while the
+    /// board isn't covered, and not the working row's pegs in streamer mode, since they're hidden.
+    fn hovered_peg(&self) -> Option<HoveredPeg> {
+        if !self.mouse_moved
+            || self.pointer.is_touch()
+            || !matches!(
+                self.state,
+                GameState::InProgress { .. } | GameState::Reviewing { .. }
+            )
+        {
+            return None;
+        }
+        let (board_mouse_x, board_mouse_y) = self.board_mouse_position();
+        if let Some(i) = pegs_ij::get_containing_i(&self.rules, board_mouse_x, board_mouse_y) {
+            return Some(HoveredPeg {
+                i,
+                j: None,
+                color: self.rules.palette()[i],
+            });
+        }
+        let (i, j) = guess_circles_ij::get_containing_ij(
+            &self.rules,
+            self.settings.mirrored,
+            board_mouse_x,
+            board_mouse_y,
+        )?;
+        let color = match &self.state {
+            GameState::InProgress { working_row, .. }
+                if j == self.rules.num_guesses - self.history.len() =>
+            {
+                working_row[i].filter(|_| !bq::is_streamer_mode())?
+            }
+            // Row 0 is the password, which is past the end of the history
+            _ => self.history.get(self.rules.num_guesses - j)?.guess[i],
+        };
+        Some(HoveredPeg {
+            i,
+            j: Some(j),
+            color,
+        })
+    }
+
+    /// e.g. "Light blue [4]", or just the name for click-only colors.
+    fn color_tooltip_text(&self, color: Color) -> String {
+        let Some(label) = self
+            .rules
+            .palette()
+            .iter()
+            .position(|c| *c == color)
+            .and_then(|color_index| self.settings.color_hotkeys.label(color_index))
+        else {
+            return color.name().to_string();
+        };
+        let label = label.to_lowercase();
+        let key = match self.settings.color_hotkeys {
+            ColorHotkeys::Numpad => format!("numpad {label}"),
+            ColorHotkeys::NumberRow | ColorHotkeys::HomeRow => label,
+        };
+        format!("{} [{key}]", color.name())
+    }
+
+    /// The color of the peg being dragged onto the row, if any.
+    fn dragged_color(&self) -> Option<Color> {
+        if !mq::is_mouse_button_down(self.settings.mouse_buttons.place()) {
+            return None;
+        }
+        let (mouse_click_release_behavior, from_row) = match &self.state {
+            GameState::InProgress {
+                working_row,
+                mouse_click_release_behavior,
+                ..
+            } => (mouse_click_release_behavior, working_row.to_vec()),
+            GameState::EditPassword {
+                mouse_click_release_behavior,
+                ..
+            } => (
+                mouse_click_release_behavior,
+                self.password.password().iter().copied().map(Some).collect(),
+            ),
+            _ => return None,
+        };
+        match mouse_click_release_behavior {
+            MouseClickReleaseBehavior::None => None,
+            MouseClickReleaseBehavior::FillColor => Some(self.mouse_color),
+            MouseClickReleaseBehavior::MoveSlot { from } => from_row.get(*from).copied().flatten(),
+        }
+    }
+
+    /// If the eyedrop button was pressed over a placed peg, its color. Pegs are from earlier guesses
+    /// or the row being filled in, never the password. `board_mouse_position` is in board units.
+    fn eyedropped_color(&self, (mouse_x, mouse_y): (f32, f32)) -> Option<Color> {
+        if !mq::is_mouse_button_pressed(self.settings.mouse_buttons.eyedrop()) {
+            return None;
+        }
+        let (i, j) = guess_circles_ij::get_containing_ij(
+            &self.rules,
+            self.settings.mirrored,
+            mouse_x,
+            mouse_y,
+        )
+        .filter(|(_, j)| *j > 0)?;
+        let history_index = self.rules.num_guesses - j;
+        match (self.history.get(history_index), &self.state) {
+            (Some(row), _) => row.guess.get(i).copied(),
+            (None, GameState::InProgress { working_row, .. })
+                if history_index == self.history.len() =>
+            {
+                working_row.get(i).copied().flatten()
+            }
+            _ => None,
+        }
+    }
+
+    /// Which guess's pegs or note icon are at (x, y) in board units, if any.
+    fn history_index_at(&self, x: f32, y: f32) -> Option<usize> {
+        let num_guesses = self.rules.num_guesses;
+        let over_pegs =
+            guess_circles_ij::get_containing_ij(&self.rules, self.settings.mirrored, x, y)
+                .map(|(_, j)| j)
+                .filter(|j| *j > 0);
+        let over_note_icon = || {
+            (1..=num_guesses).find(|j| {
+                let (icon_x, icon_y) =
+                    guess_circles_ij::note_icon_center(&self.rules, self.settings.mirrored, *j);
+                notes::is_over_icon(icon_x, icon_y, x, y)
+            })
+        };
+        over_pegs
+            .or_else(over_note_icon)
+            .map(|j| num_guesses - j)
+            .filter(|history_index| *history_index < self.history.len())
+    }
+
+    /// How row `j` changes this frame, if at all. Updates the mouse color and slot selection along
+    /// the way, depending on the input mode. `filled_slots` says which slots of the row can be
+    /// dragged. `board_mouse_position` is in board units (see `layout`).
+    #[allow(clippy::too_many_arguments)]
+    fn get_slot_update(
+        rules: &RuleSet,
+        settings: &Settings,
+        board_mouse_position: (f32, f32),
+        eyedropped_color: Option<Color>,
+        j: usize,
+        filled_slots: &[bool],
+        mouse_color: &mut Color,
+        mouse_click_release_behavior: &mut MouseClickReleaseBehavior,
+        selected_slot: &mut Option<usize>,
+    ) -> Option<SlotUpdate> {
+        let color_update =
+            Self::get_mouse_color_update(rules, settings, board_mouse_position, eyedropped_color);
+        let place_button = settings.mouse_buttons.place();
+        let (mouse_x, mouse_y) = board_mouse_position;
+        let slot_at_mouse = || {
+            guess_circles_ij::get_containing_ij(rules, settings.mirrored, mouse_x, mouse_y)
+                .filter(|(_, slot_j)| *slot_j == j)
+                .map(|(i, _)| i)
+        };
+
+        match settings.input_mode {
+            InputMode::Brush => {
+                if let Some((new_color, new_release_behavior)) = color_update {
+                    *mouse_color = new_color;
+                    *mouse_click_release_behavior = new_release_behavior;
+                }
+                // Pick up a filled slot's peg, to drop on another slot
+                if mq::is_mouse_button_pressed(place_button)
+                    && *mouse_click_release_behavior == MouseClickReleaseBehavior::None
+                {
+                    if let Some(from) = slot_at_mouse().filter(|i| filled_slots[*i]) {
+                        *mouse_click_release_behavior =
+                            MouseClickReleaseBehavior::MoveSlot { from };
+                        return None;
+                    }
+                }
+                if let MouseClickReleaseBehavior::MoveSlot { from } = *mouse_click_release_behavior
+                {
+                    if !mq::is_mouse_button_released(place_button) {
+                        return None;
+                    }
+                    *mouse_click_release_behavior = MouseClickReleaseBehavior::None;
+                    return match slot_at_mouse() {
+                        Some(to) if to == from => Some(SlotUpdate::Set(from, *mouse_color)),
+                        Some(to) => Some(SlotUpdate::Swap(from, to)),
+                        // Dropped off the row
+                        None => None,
+                    };
+                }
+                if Self::should_set_color(mouse_click_release_behavior, place_button) {
+                    return slot_at_mouse().map(|i| SlotUpdate::Set(i, *mouse_color));
+                }
+                None
+            }
+            InputMode::SlotFirst => {
+                // Clicking the selected slot again deselects it
+                if mq::is_mouse_button_pressed(place_button) {
+                    if let Some(i) = slot_at_mouse() {
+                        *selected_slot = (*selected_slot != Some(i)).then_some(i);
+                    }
+                }
+                let (new_color, _) = color_update?;
+                *mouse_color = new_color;
+                let i = (*selected_slot)?;
+                *selected_slot = next_selected_slot(rules, i);
+                Some(SlotUpdate::Set(i, new_color))
+            }
+        }
+    }
+
+    /// `board_mouse_position` is in board units (see `layout`).
+    fn get_mouse_color_update(
+        rules: &RuleSet,
+        settings: &Settings,
+        board_mouse_position: (f32, f32),
+        eyedropped_color: Option<Color>,
+    ) -> Option<(Color, MouseClickReleaseBehavior)> {
+        if let Some(color) = Self::get_color_from_key_press(rules, settings.color_hotkeys) {
+            return Some((color, MouseClickReleaseBehavior::None));
+        }
+
+        if let Some(color) = eyedropped_color {
+            return Some((color, MouseClickReleaseBehavior::None));
+        }
+
+        let place_button = settings.mouse_buttons.place();
+        if let Some(color) =
+            Self::get_color_from_mouse_click(rules, board_mouse_position, place_button)
+        {
+            return Some((color, MouseClickReleaseBehavior::FillColor));
+        }
+
+        None
+    }
+
+    fn get_color_from_key_press(rules: &RuleSet, hotkeys: ColorHotkeys) -> Option<Color> {
+        palette_hotkeys(rules, hotkeys)
+            .find_map(|(color, key)| key.filter(|key| mq::is_key_pressed(*key)).map(|_| color))
+    }
+
+    fn get_color_from_mouse_click(
+        rules: &RuleSet,
+        (mouse_x, mouse_y): (f32, f32),
+        place_button: mq::MouseButton,
+    ) -> Option<Color> {
+        if mq::is_mouse_button_pressed(place_button) {
+            if let Some(peg_i) = pegs_ij::get_containing_i(rules, mouse_x, mouse_y) {
+                return Some(rules.palette()[peg_i]);
             }
         }
 
         None
     }
 
-    fn should_set_color(mouse_click_release_behavior: &mut MouseClickReleaseBehavior) -> bool {
+    fn should_set_color(
+        mouse_click_release_behavior: &mut MouseClickReleaseBehavior,
+        place_button: mq::MouseButton,
+    ) -> bool {
         // Check if mouse was released after a click and drag from a peg.
         if matches!(
             mouse_click_release_behavior,
             MouseClickReleaseBehavior::FillColor
-        ) && mq::is_mouse_button_released(mq::MouseButton::Left)
+        ) && mq::is_mouse_button_released(place_button)
         {
             *mouse_click_release_behavior = MouseClickReleaseBehavior::None;
             return true;
         }
 
         // Check if mouse is clicked.
-        mq::is_mouse_button_pressed(mq::MouseButton::Left)
+        mq::is_mouse_button_pressed(place_button)
     }
 
     fn draw(&self) {
-        mq::clear_background(mq::DARKBROWN);
-        // Between BROWN and BEIGE
-        let board_color = mq::Color::new(0.70, 0.60, 0.46, 1.0);
+        let theme = self.settings.theme.theme();
+        mq::clear_background(theme.background);
+        let layout = self.layout();
+        layout.set_camera();
 
+        let board_size_derived_consts = BoardSizeDerivedConsts::get(&self.rules);
         let BoardSizeDerivedConsts {
             row_width_guess,
             row_height,
             key_padding,
             row_width_key,
             board_height,
-        } = BoardSizeDerivedConsts::get();
+        } = board_size_derived_consts;
+        let num_guesses = self.rules.num_guesses;
+        let guess_x = board_size_derived_consts.guess_x(self.settings.mirrored);
+        let key_x = board_size_derived_consts.key_x(self.settings.mirrored);
 
         // Board
         mq::draw_rectangle(
@@ -470,39 +2756,43 @@ impl MastermindGame {
             BOARD_OFFSET_Y,
             row_width_guess + row_width_key,
             board_height,
-            board_color,
+            theme.board,
         );
 
         // Vertical separator of Guess-Key
         mq::draw_rectangle(
-            BOARD_OFFSET_X + row_width_guess,
+            board_size_derived_consts.separator_x(self.settings.mirrored),
             BOARD_OFFSET_Y,
             ROW_SEPARATOR_HEIGHT, // re-use "height" const for width :P
             board_height,
-            mq::BLACK,
+            theme.separator,
         );
 
         // Horizontal separators of Guess rows - Line goes at *bottom* of first n-1 rows
-        for j in 0..NUM_GUESSES {
+        for j in 0..num_guesses {
             let j = j as f32;
             mq::draw_rectangle(
                 BOARD_OFFSET_X,
                 BOARD_OFFSET_Y + row_height * (j + 1.0) + ROW_SEPARATOR_HEIGHT * j,
                 row_width_guess + row_width_key,
                 ROW_SEPARATOR_HEIGHT,
-                mq::BLACK,
+                theme.separator,
             );
         }
 
-        // Password - overwrite space already drawn with Board
+        // Password - overwrite space already drawn with Board. The codemaker of a LAN game sees
+        // it.
+        let is_lan_codemaker = self.lan.as_ref().is_some_and(LanSession::is_codemaker);
         let password_rectangle_color = match &self.state {
-            GameState::InProgress { .. } => mq::BLACK,
-            GameState::EditPassword { .. } => board_color,
+            GameState::InProgress { .. } if is_lan_codemaker => theme.board,
+            GameState::InProgress { .. } | GameState::EnterSeed { .. } => theme.password_cover,
+            GameState::EditPassword { .. } | GameState::Reviewing { .. } => theme.board,
             GameState::Victory { .. } => mq::GREEN,
-            GameState::TooManyGuesses => mq::RED,
+            GameState::TooManyGuesses { .. } | GameState::OutOfTime => mq::RED,
+            GameState::Resigned => mq::DARKGRAY,
         };
         mq::draw_rectangle(
-            BOARD_OFFSET_X,
+            guess_x,
             BOARD_OFFSET_Y,
             row_width_guess,
             row_height,
@@ -511,205 +2801,513 @@ impl MastermindGame {
 
         // Password colors
         match self.state {
-            GameState::InProgress { .. } => {
+            GameState::InProgress { .. } | GameState::EnterSeed { .. } if !is_lan_codemaker => {
                 for i in 0..self.password.password().len() {
-                    guess_circles_ij::draw_password_text_overlay(i, 0);
+                    guess_circles_ij::draw_password_text_overlay(
+                        &self.rules,
+                        self.settings.mirrored,
+                        i,
+                        0,
+                    );
                 }
             }
-            GameState::EditPassword { .. }
+            GameState::InProgress { .. }
+            | GameState::EnterSeed { .. }
+            | GameState::EditPassword { .. }
             | GameState::Victory { .. }
-            | GameState::TooManyGuesses => {
+            | GameState::TooManyGuesses { .. }
+            | GameState::OutOfTime
+            | GameState::Resigned
+            | GameState::Reviewing { .. } => {
                 for (i, color) in self.password.password().iter().enumerate() {
-                    guess_circles_ij::draw(i, 0, *color, self.number_overlay);
+                    guess_circles_ij::draw(
+                        &self.rules,
+                        self.settings.mirrored,
+                        i,
+                        0,
+                        *color,
+                        self.number_overlay,
+                        self.settings.color_hotkeys,
+                    );
                 }
             }
         }
 
         // Guesses - colored - history
         for (j, row) in self.history.iter().enumerate() {
-            let j = NUM_GUESSES - j;
+            let j = num_guesses - j;
             for (i, color) in row.guess.iter().enumerate() {
-                guess_circles_ij::draw(i, j, *color, self.number_overlay);
+                guess_circles_ij::draw(
+                    &self.rules,
+                    self.settings.mirrored,
+                    i,
+                    j,
+                    *color,
+                    self.number_overlay,
+                    self.settings.color_hotkeys,
+                );
             }
         }
 
         // Guesses - colored - working
         if let GameState::InProgress { working_row, .. } = &self.state {
-            let j = NUM_GUESSES - self.history.len();
+            let j = num_guesses - self.history.len();
             for (i, opt_color) in working_row.iter().enumerate() {
-                if let Some(color) = opt_color {
-                    guess_circles_ij::draw(i, j, *color, self.number_overlay);
+                // Only show which slots are filled, so viewers can guess along
+                if opt_color.is_some() && bq::is_streamer_mode() {
+                    guess_circles_ij::draw_hidden(
+                        &self.rules,
+                        self.settings.mirrored,
+                        i,
+                        j,
+                        theme.password_cover,
+                    );
+                } else if let Some(color) = opt_color {
+                    guess_circles_ij::draw(
+                        &self.rules,
+                        self.settings.mirrored,
+                        i,
+                        j,
+                        *color,
+                        self.number_overlay,
+                        self.settings.color_hotkeys,
+                    );
                 }
             }
 
-            // Gold working box
-            let j = (NUM_GUESSES - self.history.len()) as f32;
+            // Working box, which stands out while waiting for the submit to be confirmed
+            let j = (num_guesses - self.history.len()) as f32;
+            let working_box_color = if self.submit_requested_for.as_ref() == Some(working_row) {
+                mq::GOLD
+            } else {
+                theme.highlight
+            };
             mq::draw_rectangle_lines(
-                BOARD_OFFSET_X,
+                guess_x,
                 BOARD_OFFSET_Y + (row_height + ROW_SEPARATOR_HEIGHT) * j,
                 row_width_guess,
                 row_height,
                 WORKING_BOX_THICKNESS,
-                mq::GOLD,
+                working_box_color,
             );
         }
+        for button in [self.submit_button(), self.reroll_button()]
+            .into_iter()
+            .flatten()
+        {
+            button.draw();
+        }
+
+        // Reviewed guess, with which pegs the feedback was about
+        if let GameState::Reviewing { history_index, .. } = self.state {
+            let j = num_guesses - history_index;
+            mq::draw_rectangle_lines(
+                BOARD_OFFSET_X,
+                BOARD_OFFSET_Y + (row_height + ROW_SEPARATOR_HEIGHT) * j as f32,
+                row_width_guess + row_width_key,
+                row_height,
+                WORKING_BOX_THICKNESS,
+                theme.highlight,
+            );
+            let guess = &self.history[history_index].guess;
+            let results = review::annotate_guess(guess, self.password.password());
+            for (i, result) in results.into_iter().enumerate() {
+                match result {
+                    PegResult::Correct => {
+                        guess_circles_ij::draw_selection(
+                            &self.rules,
+                            self.settings.mirrored,
+                            i,
+                            j,
+                            theme.correct_key,
+                        );
+                    }
+                    PegResult::Misplaced => {
+                        guess_circles_ij::draw_selection(
+                            &self.rules,
+                            self.settings.mirrored,
+                            i,
+                            j,
+                            theme.misplaced_key,
+                        );
+                    }
+                    PegResult::Absent => {}
+                }
+            }
+        }
 
         // Guesses - outlines
-        for i in 0..NUM_SLOTS_PER_ROW {
-            for j in 0..=NUM_GUESSES {
-                guess_circles_ij::draw_outline(i, j);
+        for i in 0..self.rules.num_slots {
+            for j in 0..=num_guesses {
+                guess_circles_ij::draw_outline(
+                    &self.rules,
+                    self.settings.mirrored,
+                    i,
+                    j,
+                    theme.slot_outline,
+                );
             }
         }
 
-        // Keys - colored
-        for (j, row) in self.history.iter().enumerate() {
-            let j = (NUM_GUESSES - j) as f32;
+        // Selected slot
+        let selection = match self.state {
+            GameState::InProgress { selected_slot, .. } => {
+                selected_slot.map(|i| (i, num_guesses - self.history.len()))
+            }
+            GameState::EditPassword { selected_slot, .. } => selected_slot.map(|i| (i, 0)),
+            _ => None,
+        };
+        if let Some((i, j)) = selection.filter(|_| self.settings.input_mode == InputMode::SlotFirst)
+        {
+            guess_circles_ij::draw_selection(
+                &self.rules,
+                self.settings.mirrored,
+                i,
+                j,
+                theme.highlight,
+            );
+        }
+
+        // Keys - colored. Hidden while playing by ear.
+        let hide_keys = self.settings.feedback_mode == FeedbackMode::SoundOnly
+            && matches!(self.state, GameState::InProgress { .. });
+        for (j, row) in self.history.iter().enumerate().filter(|_| !hide_keys) {
+            let j = (num_guesses - j) as f32;
             let mut key_offset_index = 0;
             for _ in 0..row.num_correct_hits {
-                let (key_offset_x, key_offset_y) =
-                    get_key_offset(key_offset_index, NUM_SLOTS_PER_ROW, key_padding, KEY_RADIUS);
+                let (key_offset_x, key_offset_y) = get_key_offset(
+                    key_offset_index,
+                    self.rules.num_slots,
+                    key_padding,
+                    KEY_RADIUS,
+                );
                 bq::draw_circle(
-                    BOARD_OFFSET_X + row_width_guess + key_offset_x,
+                    key_x + key_offset_x,
                     BOARD_OFFSET_Y + (row_height + ROW_SEPARATOR_HEIGHT) * j + key_offset_y,
                     KEY_RADIUS,
-                    mq::WHITE,
+                    theme.correct_key,
                 );
                 key_offset_index += 1;
             }
 
             for _ in 0..row.num_misplaced_hits {
-                let (key_offset_x, key_offset_y) =
-                    get_key_offset(key_offset_index, NUM_SLOTS_PER_ROW, key_padding, KEY_RADIUS);
-                let medium_grey = mq::Color::new(0.38, 0.38, 0.38, 1.00);
+                let (key_offset_x, key_offset_y) = get_key_offset(
+                    key_offset_index,
+                    self.rules.num_slots,
+                    key_padding,
+                    KEY_RADIUS,
+                );
                 bq::draw_circle(
-                    BOARD_OFFSET_X + row_width_guess + key_offset_x,
+                    key_x + key_offset_x,
                     BOARD_OFFSET_Y + (row_height + ROW_SEPARATOR_HEIGHT) * j + key_offset_y,
                     KEY_RADIUS,
-                    medium_grey,
+                    theme.misplaced_key,
                 );
                 key_offset_index += 1;
             }
         }
 
+        // How many of each guessed color are in the password, under each peg
+        if self.settings.feedback_mode == FeedbackMode::ColorCounts {
+            for (j, row) in self.history.iter().enumerate() {
+                let counts = password_color_counts(&row.guess, self.password.password());
+                for (i, count) in counts.into_iter().enumerate() {
+                    guess_circles_ij::draw_count_badge(
+                        &self.rules,
+                        self.settings.mirrored,
+                        i,
+                        num_guesses - j,
+                        count,
+                    );
+                }
+            }
+        }
+
+        // Notes
+        for (j, row) in self.history.iter().enumerate() {
+            if row.note.is_some() {
+                let (x, y) = guess_circles_ij::note_icon_center(
+                    &self.rules,
+                    self.settings.mirrored,
+                    num_guesses - j,
+                );
+                notes::draw_icon(x, y);
+            }
+        }
+
         // Keys - outlines
-        #[allow(clippy::needless_range_loop)]
-        for i in 0..NUM_SLOTS_PER_ROW {
+        for i in 0..self.rules.num_slots {
             let (key_offset_x, key_offset_y) =
-                get_key_offset(i, NUM_SLOTS_PER_ROW, key_padding, KEY_RADIUS);
-            for j in 1..=NUM_GUESSES {
+                get_key_offset(i, self.rules.num_slots, key_padding, KEY_RADIUS);
+            for j in 1..=num_guesses {
                 let j = j as f32;
                 bq::draw_circle_outline(
-                    BOARD_OFFSET_X + row_width_guess + key_offset_x,
+                    key_x + key_offset_x,
                     BOARD_OFFSET_Y + (row_height + ROW_SEPARATOR_HEIGHT) * j + key_offset_y,
                     KEY_RADIUS,
                     1.0,
-                    mq::GOLD,
+                    theme.key_outline,
                 );
             }
         }
 
         // Pegs
-        let pegs_y = pegs_ij::compute_y_coordinate();
-        for (i, color) in COLOR_PALETTE.iter().enumerate() {
-            let x = pegs_ij::compute_x_coordinate(i);
-            bq::draw_circle(x, pegs_y, PEG_RADIUS, color.as_mq());
-            bq::draw_text(
-                format!("{}", i + 1),
-                TextAlignment::Left,
-                None,
-                SLOT_PEG_FONT_SIZE,
-                mq::BLACK,
-                TextAnchorPoint::Center { x, y: pegs_y },
-                None,
-            );
+        let pegs_y = pegs_ij::compute_y_coordinate(&self.rules);
+        let peg_radius = pegs_ij::peg_radius(&self.rules);
+        for (i, (color, key)) in
+            palette_hotkeys(&self.rules, self.settings.color_hotkeys).enumerate()
+        {
+            let x = pegs_ij::compute_x_coordinate(&self.rules, i);
+            draw_peg(x, pegs_y, peg_radius, color);
+            match (key, self.settings.color_hotkeys.label(i)) {
+                (Some(_), Some(label)) => {
+                    bq::draw_text(
+                        label,
+                        TextAlignment::Left,
+                        None,
+                        SLOT_PEG_FONT_SIZE,
+                        mq::BLACK,
+                        TextAnchorPoint::Center { x, y: pegs_y },
+                        None,
+                    );
+                }
+                // Dimmed, since it can only be clicked
+                _ => bq::draw_circle(x, pegs_y, peg_radius, CLICK_ONLY_PEG_DIM_COLOR),
+            }
         }
 
         // Text - controls
         let controls_text = format!(
-            "Press [number key] to select color\n\
-            Press [{}] to submit guess\n\
-            Press [{}] to toggle numbers display\n\
-            Press [{}] to edit password",
+            "Press [{}] to select color\n\
+            Press [{}] to change color keys, [{}] mouse\n\
+            Press [{}] to change input ({})\n\
+            Press [{}] to submit, [{}] to give up\n\
+            Press [{}] to pause timed games\n\
+            Press [{}] to confirm guesses first ({})\n\
+            Press [{}] to change feedback ({})\n\
+            Press [{}] to copy your last guess\n\
+            Press [{}] to toggle key labels, [{}] to mute\n\
+            Press [{}] to change theme ({}), [{}] to mirror\n\
+            Press [{}] for streamer mode ({})\n\
+            Press [{}] to edit password, [{}] for help\n\
+            Press [{}] for daily puzzle, [{}] for 2 players\n\
+            Press [{}] to host a LAN game, [{}] to join\n\
+            Press [{}] to count passwords left ({})\n\
+            Press [{}] to copy seed, [{}] to enter one\n\
+            Press [{}] to change rules ({})\n\
+            Press [{}] to change duplicates ({})\n\
+            Press [{}] for casual mode ({})\n\
+            Press [{}] for hard mode ({}), [{}] free re-roll ({})\n\
+            Add a note to a guess with {}-click",
+            color_keys_hint(&self.rules, self.settings.color_hotkeys),
+            KEY_CYCLE_COLOR_HOTKEYS.to_lowercase(),
+            KEY_CYCLE_MOUSE_BUTTONS.to_lowercase(),
+            KEY_CYCLE_INPUT_MODE.to_lowercase(),
+            self.settings.input_mode.name(),
             KEY_SUBMIT.to_lowercase(),
+            KEY_RESIGN.to_lowercase(),
+            KEY_PAUSE.to_lowercase(),
+            KEY_TOGGLE_CONFIRM_SUBMIT.to_lowercase(),
+            if self.settings.confirm_submit {
+                "on"
+            } else {
+                "off"
+            },
+            KEY_CYCLE_FEEDBACK_MODE.to_lowercase(),
+            self.settings.feedback_mode.name(),
+            KEY_COPY_LAST_GUESS.to_lowercase(),
             KEY_TOGGLE_NUMBER_OVERLAY.to_lowercase(),
+            KEY_TOGGLE_MUTE.to_lowercase(),
+            KEY_CYCLE_THEME.to_lowercase(),
+            self.settings.theme.name(),
+            KEY_TOGGLE_MIRRORED.to_lowercase(),
+            KEY_TOGGLE_STREAMER_MODE.to_lowercase(),
+            if bq::is_streamer_mode() { "on" } else { "off" },
             KEY_PLAYER_EDIT_PASSWORD.to_lowercase(),
+            KEY_TOGGLE_HELP.to_lowercase(),
+            KEY_TOGGLE_DAILY.to_lowercase(),
+            KEY_START_MATCH.to_lowercase(),
+            KEY_HOST_LAN_GAME.to_lowercase(),
+            KEY_JOIN_LAN_GAME.to_lowercase(),
+            KEY_TOGGLE_CANDIDATE_COUNTER.to_lowercase(),
+            if self.settings.count_candidates {
+                "on"
+            } else {
+                "off"
+            },
+            KEY_COPY_SEED.to_lowercase(),
+            KEY_ENTER_SEED.to_lowercase(),
+            KEY_CYCLE_RULE_SET.to_lowercase(),
+            self.rules.name,
+            KEY_CYCLE_DUPLICATE_COLORS.to_lowercase(),
+            self.active_duplicate_colors().name(),
+            KEY_TOGGLE_CASUAL.to_lowercase(),
+            if self.is_casual_game() {
+                format!("on, [{}] to undo", KEY_UNDO_GUESS.to_lowercase())
+            } else {
+                "off".to_string()
+            },
+            KEY_TOGGLE_HARD_MODE.to_lowercase(),
+            if self.settings.hard_mode { "on" } else { "off" },
+            KEY_TOGGLE_FREE_REROLL.to_lowercase(),
+            if self.can_reroll_password() {
+                format!("[{}] to use", KEY_REROLL_PASSWORD.to_lowercase())
+            } else if self.settings.free_reroll {
+                "on".to_string()
+            } else {
+                "off".to_string()
+            },
+            mouse_button_name(self.settings.mouse_buttons.clear()),
         );
         bq::draw_text(
             controls_text,
             TextAlignment::Left,
             None,
             25,
-            mq::BLACK,
+            theme.text,
             TextAnchorPoint::TopLeft {
                 x: BOARD_OFFSET_X,
                 y: pegs_y + PEG_RADIUS + PEG_OUTER_PADDING + 5.0,
             },
             Some(TextBackground {
-                color: mq::Color::new(1.0, 1.0, 1.0, 0.7),
+                color: theme.text_background,
                 x_padding: 2.5,
                 y_padding: 2.5,
             }),
         );
 
-        // Text - new game
-        let new_game_text = format!(
-            "Press [{}] to replay the same password.\nPress [{}] for a new password.",
-            KEY_REPLAY_PASSWORD.to_lowercase(),
-            KEY_NEW_PASSWORD.to_lowercase(),
-        );
         let info_text_background = TextBackground {
             color: mq::Color::new(0.78, 0.78, 0.78, 0.8),
             x_padding: 10.0,
             y_padding: 10.0,
         };
+        // End game popups are drawn at their normal size, on top of the scaled board.
+        mq::set_default_camera();
+        self.draw_scrollbar();
         match &self.state {
-            GameState::InProgress { .. } | GameState::EditPassword { .. } => {
+            GameState::InProgress { .. }
+            | GameState::EditPassword { .. }
+            | GameState::EnterSeed { .. } => {
+                layout.set_camera();
+                let x = BOARD_OFFSET_X + row_width_guess + row_width_key + HOW_TO_PLAY_OFFSET_X;
+                let how_to_play_y = match self.draw_timer(x, HOW_TO_PLAY_OFFSET_Y) {
+                    Some(timer_container) => {
+                        timer_container.rect_y + timer_container.rect_height + TIMER_MARGIN_BOTTOM
+                    }
+                    None => HOW_TO_PLAY_OFFSET_Y,
+                };
+                let text = match &self.lan {
+                    Some(lan) => lan.status_text(
+                        self.history.len() + 1,
+                        self.rules.num_guesses,
+                        &self.lan_result_text(),
+                    ),
+                    None => how_to_play_text(&self.rules, self.active_duplicate_colors()),
+                };
+                // Running scoreboard
+                let text = match &self.pvp_match {
+                    Some(pvp_match) => format!("{}\n\n{text}", pvp_match.status_text()),
+                    None => text,
+                };
+                let text = match &self.candidate_counter {
+                    Some(candidate_counter) => format!("{text}\n\n{}", candidate_counter.text()),
+                    None => text,
+                };
                 bq::draw_text(
-                    HOW_TO_PLAY_TEXT,
+                    text,
                     TextAlignment::Left,
                     None,
                     HOW_TO_PLAY_FONT_SIZE,
-                    mq::BLACK,
+                    theme.text,
                     TextAnchorPoint::TopLeft {
-                        x: BOARD_OFFSET_X + row_width_guess + row_width_key + HOW_TO_PLAY_OFFSET_X,
-                        y: HOW_TO_PLAY_OFFSET_Y,
+                        x,
+                        y: how_to_play_y,
                     },
-                    Some(info_text_background),
+                    Some(TextBackground {
+                        color: theme.text_background,
+                        ..info_text_background
+                    }),
                 );
+                mq::set_default_camera();
             }
-            GameState::Victory { total_time, .. } => {
-                let win_title = win_title::get(&self.history);
-                let win_title_article = match win_title.article {
-                    Some(s) => format!("{s} "),
-                    None => "".to_string(),
-                };
+            // Beside the board, like how to play, so the board isn't covered
+            GameState::Reviewing { history_index, .. } => {
+                layout.set_camera();
                 bq::draw_text(
-                    format!(
-                        "You won in {} guesses! You are {}{}!\nTime: {}\n\n{new_game_text}",
-                        self.history.len(),
-                        win_title_article,
-                        win_title.title,
-                        format_duration(*total_time)
+                    review::review_text(
+                        *history_index,
+                        &self.history,
+                        &KEY_REVIEW_PREVIOUS.to_lowercase(),
+                        &KEY_REVIEW_NEXT.to_lowercase(),
                     ),
                     TextAlignment::Left,
                     None,
-                    END_GAME_FONT_SIZE,
-                    mq::DARKGREEN,
-                    TextAnchorPoint::window_centered(),
-                    Some(info_text_background),
+                    HOW_TO_PLAY_FONT_SIZE,
+                    theme.text,
+                    TextAnchorPoint::TopLeft {
+                        x: BOARD_OFFSET_X + row_width_guess + row_width_key + HOW_TO_PLAY_OFFSET_X,
+                        y: HOW_TO_PLAY_OFFSET_Y,
+                    },
+                    Some(TextBackground {
+                        color: theme.text_background,
+                        ..info_text_background
+                    }),
                 );
+                mq::set_default_camera();
             }
-            GameState::TooManyGuesses => {
-                bq::draw_text(
-                    format!("You lose lmao\n\n{new_game_text}"),
-                    TextAlignment::Left,
-                    None,
-                    END_GAME_FONT_SIZE,
-                    mq::RED,
-                    TextAnchorPoint::window_centered(),
-                    Some(info_text_background),
+            GameState::Victory { .. }
+            | GameState::TooManyGuesses { .. }
+            | GameState::OutOfTime
+            | GameState::Resigned
+                if self.show_heatmap =>
+            {
+                self.draw_heatmap();
+            }
+            GameState::Victory {
+                high_score_rank, ..
+            } if self.show_high_scores => {
+                self.draw_high_scores(*high_score_rank);
+            }
+            GameState::TooManyGuesses { .. } | GameState::OutOfTime | GameState::Resigned
+                if self.show_high_scores =>
+            {
+                self.draw_high_scores(None);
+            }
+            GameState::Victory { .. }
+            | GameState::TooManyGuesses { .. }
+            | GameState::OutOfTime
+            | GameState::Resigned
+                if self.show_achievements =>
+            {
+                self.achievements.draw(&format!(
+                    "Press [{}] to hide",
+                    KEY_TOGGLE_ACHIEVEMENTS.to_lowercase()
+                ));
+            }
+            GameState::Victory { .. }
+            | GameState::TooManyGuesses { .. }
+            | GameState::OutOfTime
+            | GameState::Resigned
+                if self.show_color_usage =>
+            {
+                self.stats.draw_color_usage(
+                    self.rules.palette(),
+                    &format!("Press [{}] to hide", KEY_TOGGLE_COLOR_USAGE.to_lowercase()),
                 );
             }
+            GameState::Victory { .. }
+            | GameState::TooManyGuesses { .. }
+            | GameState::OutOfTime
+            | GameState::Resigned => {
+                if let Some(results_screen) = self.results_screen() {
+                    let results_rect = results_screen.draw();
+                    let highlight_guess_count = match self.state {
+                        GameState::Victory { .. } => Some(self.history.len()),
+                        _ => None,
+                    };
+                    self.draw_stats_panel(results_rect, highlight_guess_count);
+                }
+            }
         }
 
         // FPS
@@ -717,10 +3315,12 @@ impl MastermindGame {
 
         // Seed
         let seed_text = match self.password.source() {
-            PasswordSource::Random { seed } => format!("Seed: {seed}"),
+            PasswordSource::Random { seed } => format!("Seed: {}", bq::seed_to_code(seed)),
+            PasswordSource::Daily { day_number } => daily::puzzle_name(day_number),
             PasswordSource::Player => "Seed: N/A".to_string(),
+            PasswordSource::Psychic => "Psychic mode".to_string(),
         };
-        bq::draw_text(
+        let seed_text_container = bq::draw_text(
             seed_text,
             TextAlignment::Left,
             None,
@@ -737,18 +3337,69 @@ impl MastermindGame {
             }),
         );
 
+        // Daily completion badge
+        if let Some(completion) = self
+            .daily_record
+            .completion_for_day(Timestamp::now().utc_day_number())
+        {
+            bq::draw_text(
+                completion.badge_text(),
+                TextAlignment::Left,
+                None,
+                DAILY_BADGE_FONT_SIZE,
+                mq::BLACK,
+                TextAnchorPoint::BottomRight {
+                    x: mq::screen_width(),
+                    y: seed_text_container.rect_y,
+                },
+                Some(TextBackground {
+                    color: mq::GOLD,
+                    x_padding: DAILY_BADGE_PADDING,
+                    y_padding: DAILY_BADGE_PADDING,
+                }),
+            );
+        }
+
+        // Ghost of a dragged peg
+        if let Some(color) = self.dragged_color().filter(|_| self.mouse_moved) {
+            let color = if bq::is_streamer_mode() {
+                theme.password_cover
+            } else {
+                color.as_mq()
+            };
+            let (board_mouse_x, board_mouse_y) = self.board_mouse_position();
+            layout.set_camera();
+            bq::draw_circle(
+                board_mouse_x,
+                board_mouse_y,
+                SLOT_RADIUS,
+                mq::Color { a: 0.6, ..color },
+            );
+            mq::set_default_camera();
+        }
+
         // Mouse
         let (mouse_x, mouse_y) = mq::mouse_position();
         let mouse_on_screen = (0.0..=mq::screen_width()).contains(&mouse_x)
             && (0.0..=mq::screen_height()).contains(&mouse_y);
-        if mouse_on_screen && self.mouse_moved {
+        if mouse_on_screen && self.mouse_moved && !self.pointer.is_touch() {
             mq::show_mouse(false);
 
             match &self.state {
                 GameState::InProgress { .. }
                 | GameState::EditPassword { .. }
-                | GameState::TooManyGuesses => {
-                    draw_cursor(mouse_x, mouse_y, self.mouse_color.as_mq());
+                | GameState::EnterSeed { .. }
+                | GameState::TooManyGuesses { .. }
+                | GameState::OutOfTime
+                | GameState::Resigned
+                | GameState::Reviewing { .. } => {
+                    // The selected color would give away the guess
+                    let cursor_color = if bq::is_streamer_mode() {
+                        theme.password_cover
+                    } else {
+                        self.mouse_color.as_mq()
+                    };
+                    draw_cursor(mouse_x, mouse_y, cursor_color);
                 }
                 GameState::Victory {
                     mouse_animations, ..
@@ -759,11 +3410,375 @@ impl MastermindGame {
         } else {
             mq::show_mouse(true);
         }
+
+        // Note for the hovered guess, or else the hovered peg's color
+        if self.note_editor.is_none() {
+            let (board_mouse_x, board_mouse_y) = self.board_mouse_position();
+            if let Some(note) = self
+                .history_index_at(board_mouse_x, board_mouse_y)
+                .and_then(|history_index| self.history[history_index].note.as_deref())
+            {
+                notes::draw_tooltip(note, mouse_x, mouse_y);
+            } else if let Some(hovered_peg) = self.peg_hover.ready() {
+                bq::draw_tooltip(
+                    &self.color_tooltip_text(hovered_peg.color),
+                    mouse_x,
+                    mouse_y,
+                    COLOR_TOOLTIP_BACKGROUND,
+                );
+            }
+        }
+
+        // Note and seed entry, on top of everything but toasts
+        if let Some(note_editor) = &self.note_editor {
+            note_editor.draw();
+        }
+        if let GameState::EnterSeed { seed_input } = &self.state {
+            seed_input.draw(
+                "Seed ([enter] to play, [esc] to cancel):\n",
+                SEED_INPUT_FONT_SIZE,
+                TextAnchorPoint::window_centered(),
+            );
+        }
+        if let Some(address_input) = &self.lan_address_input {
+            address_input.draw(
+                "Host's address, e.g. 192.168.1.5\n([enter] to join, [esc] to cancel):\n",
+                SEED_INPUT_FONT_SIZE,
+                TextAnchorPoint::window_centered(),
+            );
+        }
+
+        if let Some(cover_text) = self
+            .pvp_match
+            .as_ref()
+            .and_then(|pvp_match| pvp_match.cover_text(&KEY_CONTINUE_MATCH.to_lowercase(), "esc"))
+        {
+            pvp::draw_cover(cover_text, theme);
+        }
+
+        if let GameState::InProgress { clock, .. } = &self.state {
+            if clock.is_paused() {
+                let time_left = self
+                    .rules
+                    .time_limit()
+                    .unwrap_or_default()
+                    .saturating_sub(clock.elapsed(Timestamp::now()));
+                pvp::draw_cover(
+                    format!(
+                        "Paused with {} left\n\nPress any key to resume",
+                        format_duration(time_left)
+                    ),
+                    theme,
+                );
+            }
+        }
+
+        if self.show_help {
+            help::draw(
+                self.rules.num_slots,
+                self.settings.input_mode.help_hint(),
+                &help::HelpKeys {
+                    colors: color_keys_hint(&self.rules, self.settings.color_hotkeys),
+                    submit: KEY_SUBMIT.to_lowercase(),
+                    toggle_help: KEY_TOGGLE_HELP.to_lowercase(),
+                    start_tutorial: KEY_START_TUTORIAL.to_lowercase(),
+                    clear_button: mouse_button_name(self.settings.mouse_buttons.clear())
+                        .to_string(),
+                },
+                theme,
+            );
+        } else if let Some(tutorial) = &self.tutorial {
+            tutorial.draw(
+                &[
+                    (
+                        "colors",
+                        &color_keys_hint(&self.rules, self.settings.color_hotkeys),
+                    ),
+                    ("placing", self.settings.input_mode.help_hint()),
+                    ("submit", &KEY_SUBMIT.to_lowercase()),
+                    ("help", &KEY_TOGGLE_HELP.to_lowercase()),
+                ],
+                &format!("[{}] to skip", KEY_SKIP_TUTORIAL.to_lowercase()),
+            );
+        }
+
+        if let Some(campaign_map) = &self.campaign_map {
+            campaign_map.draw(
+                &self.campaign,
+                &format!(
+                    "Arrows to choose, enter or click to play, esc to close. Press [{}] to open again.\n\
+                    [{}] save progress, [{}] saved campaigns",
+                    KEY_OPEN_CAMPAIGN_MAP.to_lowercase(),
+                    campaign::KEY_SAVE_SLOT.to_lowercase(),
+                    campaign::KEY_BROWSE_SLOTS.to_lowercase(),
+                ),
+            );
+        }
+        if let Some(slot_browser) = &self.campaign_slot_browser {
+            slot_browser.draw();
+        }
+
+        self.toasts.draw();
+    }
+
+    /// How the last LAN game went, from the host's side.
+    fn lan_result_text(&self) -> String {
+        let broken = self
+            .history
+            .last()
+            .is_some_and(|row| row.num_correct_hits == self.rules.num_slots);
+        if broken {
+            format!("Broken in {} guesses!", self.history.len())
+        } else {
+            "Never broken. You win!".to_string()
+        }
+    }
+
+    /// The end of game results, unless the heatmap, high scores, achievements, or color usage are
+    /// covering the middle of the window.
+    fn results_screen(&self) -> Option<ResultsScreen> {
+        if self.show_heatmap
+            || self.show_high_scores
+            || self.show_achievements
+            || self.show_color_usage
+        {
+            return None;
+        }
+        let results_screen = match &self.state {
+            GameState::Victory {
+                total_time,
+                is_new_best_time,
+                score,
+                high_score_rank,
+                ..
+            } => {
+                let win_title = win_title::get(&self.history);
+                let win_title_article = match win_title.article {
+                    Some(s) => format!("{s} "),
+                    None => "".to_string(),
+                };
+                let new_best_time_text = if *is_new_best_time { ", new best!" } else { "" };
+                let high_score_text = match high_score_rank {
+                    Some(rank) => format!(", new high score! (#{rank})"),
+                    None => "".to_string(),
+                };
+                ResultsScreen::new(
+                    format!(
+                        "You won in {} guesses! You are {win_title_article}{}!",
+                        self.history.len(),
+                        win_title.title,
+                    ),
+                    mq::DARKGREEN,
+                )
+                .stat(
+                    "Time",
+                    format!("{}{new_best_time_text}", format_duration(*total_time)),
+                )
+                .stat(
+                    "Score",
+                    match self.difficulty_tag {
+                        Some(tag) => format!("{score} ({tag}){high_score_text}"),
+                        None => format!("{score}{high_score_text}"),
+                    },
+                )
+                .message(self.psychic_reveal_text(true))
+            }
+            GameState::TooManyGuesses { .. } | GameState::OutOfTime | GameState::Resigned => {
+                let lose_text = match self.state {
+                    GameState::OutOfTime => "Out of time lmao",
+                    GameState::Resigned => "You gave up. The password is up top.",
+                    _ => "You lose lmao",
+                };
+                let results_screen =
+                    ResultsScreen::new(lose_text, mq::RED).message(self.psychic_reveal_text(false));
+                if matches!(self.state, GameState::TooManyGuesses { .. }) && self.is_casual_game() {
+                    results_screen.button(KEY_UNDO_GUESS, "Take back last guess")
+                } else {
+                    results_screen
+                }
+            }
+            GameState::InProgress { .. }
+            | GameState::EditPassword { .. }
+            | GameState::EnterSeed { .. }
+            | GameState::Reviewing { .. } => return None,
+        };
+        Some(self.add_new_game_results(results_screen))
+    }
+
+    /// What's next, which depends on the kind of game, then the buttons every game ends with.
+    fn add_new_game_results(&self, results_screen: ResultsScreen) -> ResultsScreen {
+        let mut results_screen = if let Some(lan) = &self.lan {
+            results_screen.message(lan.next_game_text(&KEY_NEW_PASSWORD.to_lowercase()))
+        } else if let Some(pvp_match) = &self.pvp_match {
+            results_screen
+                .message(pvp_match.status_text())
+                .button(KEY_CONTINUE_MATCH, "Continue match")
+        } else if let Some(level) = self.active_campaign_level() {
+            let results_screen = results_screen.message(self.campaign_result_text(level));
+            let results_screen = if self.campaign.is_unlocked(level + 1) {
+                results_screen.button(KEY_NEW_PASSWORD, "Next level")
+            } else {
+                results_screen
+            };
+            results_screen
+                .button(KEY_REPLAY_PASSWORD, "Retry")
+                .button(KEY_OPEN_CAMPAIGN_MAP, "Map")
+        } else if self.password.is_daily() {
+            results_screen
+                .message("Come back tomorrow for a new daily puzzle!")
+                .button(KEY_NEW_PASSWORD, "New password")
+        } else {
+            results_screen
+                .button(KEY_REPLAY_PASSWORD, "Replay")
+                .button(KEY_NEW_PASSWORD, "New password")
+        };
+        if let PasswordSource::Random { .. } = self.password.source() {
+            results_screen = results_screen.button(KEY_COPY_SEED, "Copy seed");
+        }
+        results_screen
+            .button(KEY_EXPORT_GAME, "Export")
+            .button(KEY_REVIEW_PREVIOUS, "Review guesses")
+            .button(KEY_TOGGLE_HEATMAP, "Heatmap")
+            .button(KEY_TOGGLE_HIGH_SCORES, "High scores")
+            .button(KEY_TOGGLE_ACHIEVEMENTS, "Achievements")
+            .button(KEY_TOGGLE_COLOR_USAGE, "Color usage")
+    }
+
+    /// How many stars the game got, and the level's best.
+    fn campaign_result_text(&self, level: usize) -> String {
+        let best = self.campaign.stars(level);
+        let name = campaign::level_name(level);
+        match self.state {
+            GameState::Victory { .. } if self.is_casual_game() => {
+                format!("{name} cleared, but casual games don't earn stars")
+            }
+            GameState::Victory { .. } => {
+                let stars = CAMPAIGN_LEVELS[level].stars(self.history.len());
+                let best_text = if self.campaign_new_best {
+                    "new best!".to_string()
+                } else {
+                    format!("best {best}")
+                };
+                format!(
+                    "{name}: {stars} of {} stars ({best_text})",
+                    campaign::MAX_STARS
+                )
+            }
+            _ if best > 0 => format!("{name}: best {best} of {} stars", campaign::MAX_STARS),
+            _ => format!("Win {name} to unlock the next level"),
+        }
+    }
+
+    /// Explains what happened at the end of a psychic game, or "" for a normal game.
+    fn psychic_reveal_text(&self, is_win: bool) -> String {
+        let Some(candidates) = &self.psychic_candidates else {
+            return String::new();
+        };
+        let ending = if is_win {
+            "passwords possible, until yours was\nthe only one left.".to_string()
+        } else {
+            format!(
+                "passwords possible. {} still fit,\nlike the one shown.",
+                candidates.len()
+            )
+        };
+        format!(
+            "Psychic mode: there never was a password!\n\
+            Each guess got the feedback that kept the most\n\
+            {ending}"
+        )
+    }
+
+    /// Stats go right below the results.
+    fn draw_stats_panel(&self, results_rect: mq::Rect, highlight_guess_count: Option<usize>) {
+        let width = BoardSizeDerivedConsts::get(&self.rules).board_width();
+        self.stats.draw_panel(
+            (mq::screen_width() - width) / 2.0,
+            results_rect.bottom() + STATS_PANEL_MARGIN,
+            width,
+            &self.rules,
+            highlight_guess_count,
+        );
+    }
+
+    fn draw_high_scores(&self, highlight_rank: Option<usize>) {
+        self.high_scores.draw(
+            highlight_rank,
+            &format!("Press [{}] to hide", KEY_TOGGLE_HIGH_SCORES.to_lowercase()),
+        );
+    }
+
+    fn draw_heatmap(&self) {
+        self.layout().set_camera();
+        self.heatmap.draw();
+        mq::set_default_camera();
+        bq::draw_text(
+            format!(
+                "Click heatmap: {} clicks this session\nPress [{}] to hide",
+                self.heatmap.num_clicks(),
+                KEY_TOGGLE_HEATMAP.to_lowercase()
+            ),
+            TextAlignment::Left,
+            None,
+            HEATMAP_LABEL_FONT_SIZE,
+            mq::WHITE,
+            TextAnchorPoint::window_centered(),
+            Some(TextBackground {
+                color: mq::Color::new(0.0, 0.0, 0.0, 0.7),
+                x_padding: 10.0,
+                y_padding: 10.0,
+            }),
+        );
+    }
+
+    /// Only timed games need pausing, and not LAN ones, since the other end's clock would keep
+    /// going.
+    fn can_pause(&self) -> bool {
+        self.rules.time_limit().is_some() && self.lan.is_none()
+    }
+
+    /// Elapsed time, or time left if there's a time limit. Only shown mid-game.
+    fn draw_timer(&self, x: f32, y: f32) -> Option<TextContainer> {
+        let GameState::InProgress { clock, .. } = &self.state else {
+            return None;
+        };
+        let elapsed = clock.elapsed(Timestamp::now());
+        let (time, text_color) = match self.rules.time_limit() {
+            Some(time_limit) => {
+                let time_left = time_limit.saturating_sub(elapsed);
+                let text_color = if time_left < TIMER_WARNING_THRESHOLD {
+                    mq::RED
+                } else {
+                    mq::WHITE
+                };
+                (time_left, text_color)
+            }
+            None => (elapsed, mq::WHITE),
+        };
+
+        Some(bq::draw_text(
+            format_duration(time),
+            TextAlignment::Left,
+            None,
+            TIMER_FONT_SIZE,
+            text_color,
+            TextAnchorPoint::TopLeft { x, y },
+            Some(TextBackground {
+                color: mq::BLACK,
+                x_padding: TIMER_PADDING,
+                y_padding: TIMER_PADDING,
+            }),
+        ))
     }
 
     #[allow(dead_code)] // for debug/test purposes
-    fn draw_ij_coordinates_on_cursor(mouse_x: f32, mouse_y: f32) {
-        if let Some((i, j)) = guess_circles_ij::get_containing_ij(mouse_x, mouse_y) {
+    fn draw_ij_coordinates_on_cursor(&self, mouse_x: f32, mouse_y: f32) {
+        if let Some((i, j)) = guess_circles_ij::get_containing_ij(
+            &self.rules,
+            self.settings.mirrored,
+            mouse_x,
+            mouse_y,
+        ) {
             mq::draw_text(
                 &format!("({i}, {j})"),
                 mouse_x - 10.0,
@@ -775,6 +3790,85 @@ impl MastermindGame {
     }
 }
 
+/// Each palette color with the key that selects it, which is None for any past the last color key.
+/// Both picking colors by key and drawing the palette go by this, so they can't disagree.
+fn palette_hotkeys(
+    rules: &RuleSet,
+    hotkeys: ColorHotkeys,
+) -> impl Iterator<Item = (Color, Option<mq::KeyCode>)> + '_ {
+    rules
+        .palette()
+        .iter()
+        .enumerate()
+        .map(move |(i, color)| (*color, hotkeys.key(i)))
+}
+
+/// e.g. "1-6" or "a-h", or "1-9, or click the rest" for palettes with more colors than keys.
+fn color_keys_hint(rules: &RuleSet, hotkeys: ColorHotkeys) -> String {
+    let num_with_keys = palette_hotkeys(rules, hotkeys)
+        .filter(|(_, key)| key.is_some())
+        .count();
+    let label = |i: usize| hotkeys.label(i).unwrap_or_default().to_lowercase();
+    let last_label = label(num_with_keys - 1);
+    let hint = match hotkeys {
+        ColorHotkeys::Numpad => format!("numpad 1-{last_label}"),
+        ColorHotkeys::NumberRow | ColorHotkeys::HomeRow => format!("{}-{last_label}", label(0)),
+    };
+    if num_with_keys < rules.palette().len() {
+        format!("{hint}, or click the rest")
+    } else {
+        hint
+    }
+}
+
+/// e.g. "2 white, 1 grey"
+fn describe_feedback((num_correct_hits, num_misplaced_hits): (usize, usize)) -> String {
+    format!("{num_correct_hits} white, {num_misplaced_hits} grey")
+}
+
+fn how_to_play_text(rules: &RuleSet, duplicate_colors: DuplicateColors) -> String {
+    HOW_TO_PLAY_TEXT
+        .replace("{num_slots}", &rules.num_slots.to_string())
+        .replace("{duplicate_colors}", duplicate_colors.description())
+        .replace(
+            "{blanks}",
+            if rules.blanks {
+                "\nBlank (the crossed out peg) counts as a color."
+            } else {
+                ""
+            },
+        )
+}
+
+/// Results screen buttons work the same as their keys.
+fn pressed_or_clicked(key: mq::KeyCode, results_click: Option<mq::KeyCode>) -> bool {
+    mq::is_key_pressed(key) || results_click == Some(key)
+}
+
+/// A palette or guess peg. Blanks get a cross, so they don't look like empty slots.
+fn draw_peg(x: f32, y: f32, radius: f32, color: Color) {
+    bq::draw_circle(x, y, radius, color.as_mq());
+    if color == Color::Blank {
+        let offset = radius * std::f32::consts::FRAC_1_SQRT_2 * 0.6;
+        mq::draw_line(
+            x - offset,
+            y - offset,
+            x + offset,
+            y + offset,
+            BLANK_PEG_CROSS_THICKNESS,
+            BLANK_PEG_CROSS_COLOR,
+        );
+        mq::draw_line(
+            x - offset,
+            y + offset,
+            x + offset,
+            y - offset,
+            BLANK_PEG_CROSS_THICKNESS,
+            BLANK_PEG_CROSS_COLOR,
+        );
+    }
+}
+
 fn draw_cursor(x: f32, y: f32, color: mq::Color) {
     bq::draw_circle(x, y, CURSOR_RADIUS, color);
     bq::draw_circle(x, y, 1.0, mq::BLACK);
@@ -812,12 +3906,14 @@ mod win_title {
 /// Helper to manage grid of circles.
 /// (x,y) = plain old pixel coordinates on display
 /// (i,j) = coordinates of circles.
-/// * i = `[0, 4)` left to right
-/// * j = `[0, 9)` bottom to top
+/// * i = `[0, num_slots)` left to right
+/// * j = `[0, num_guesses + 1)` bottom to top
 ///
 /// Other helpful indexes:
-/// * history index is `j = NUM_GUESSES - j`
-/// * working row is `j = NUM_GUESSES - history.len()`
+/// * history index is `j = num_guesses - j`
+/// * working row is `j = num_guesses - history.len()`
+///
+/// Diagram is for the classic rules (4 slots, 8 guesses).
 ///
 /// Why? It makes it easier to index into history array.
 ///
@@ -838,24 +3934,29 @@ mod win_title {
 /// ```
 mod guess_circles_ij {
     use super::{
-        Color, NumberOverlay, BOARD_OFFSET_X, BOARD_OFFSET_Y, COLOR_PALETTE, NUM_GUESSES,
-        NUM_SLOTS_PER_ROW, ROW_SEPARATOR_HEIGHT, SLOT_PADDING, SLOT_PEG_FONT_SIZE, SLOT_RADIUS,
-        SLOT_SIZE,
+        draw_peg, BoardSizeDerivedConsts, Color, NumberOverlay, BOARD_OFFSET_X, BOARD_OFFSET_Y,
+        ROW_SEPARATOR_HEIGHT, SLOT_PADDING, SLOT_PEG_FONT_SIZE, SLOT_RADIUS, SLOT_SIZE,
     };
+    use crate::rules::RuleSet;
+    use crate::settings::ColorHotkeys;
     use better_quad::bq;
     use better_quad::bq::TextAlignment;
     use macroquad::prelude as mq;
 
     const CIRCLE_OUTLINE_THICKNESS: f32 = 1.0;
+    const SELECTION_OUTLINE_THICKNESS: f32 = 3.0;
+    const COUNT_BADGE_RADIUS: f32 = 9.0;
 
-    fn compute_xy_coordinates(i: usize, j: usize) -> (f32, f32) {
+    /// `mirrored` boards have the feedback pegs left of the guesses, rather than right.
+    fn compute_xy_coordinates(rules: &RuleSet, mirrored: bool, i: usize, j: usize) -> (f32, f32) {
         // explosive way to make sure I don't mis-use this function
-        assert!(i < NUM_SLOTS_PER_ROW);
-        assert!(j < NUM_GUESSES + 1); // + 1 accounts for password row
+        assert!(i < rules.num_slots);
+        assert!(j < rules.num_guesses + 1); // + 1 accounts for password row
         let i = i as f32;
         let j = j as f32;
 
-        let x = BOARD_OFFSET_X + SLOT_RADIUS + SLOT_SIZE * i + SLOT_PADDING * (i + 1.0);
+        let guess_x = BoardSizeDerivedConsts::get(rules).guess_x(mirrored);
+        let x = guess_x + SLOT_RADIUS + SLOT_SIZE * i + SLOT_PADDING * (i + 1.0);
         let y = BOARD_OFFSET_Y
             + SLOT_RADIUS
             + SLOT_SIZE * j
@@ -865,33 +3966,100 @@ mod guess_circles_ij {
         (x, y)
     }
 
-    pub(crate) fn draw_outline(i: usize, j: usize) {
-        let (x, y) = compute_xy_coordinates(i, j);
-        bq::draw_circle_outline(x, y, SLOT_RADIUS, CIRCLE_OUTLINE_THICKNESS, mq::WHITE);
+    /// In the margin left of row `j`.
+    pub(crate) fn note_icon_center(rules: &RuleSet, mirrored: bool, j: usize) -> (f32, f32) {
+        let (_, y) = compute_xy_coordinates(rules, mirrored, 0, j);
+        (BOARD_OFFSET_X / 2.0, y)
+    }
+
+    pub(crate) fn draw_outline(
+        rules: &RuleSet,
+        mirrored: bool,
+        i: usize,
+        j: usize,
+        color: mq::Color,
+    ) {
+        let (x, y) = compute_xy_coordinates(rules, mirrored, i, j);
+        bq::draw_circle_outline(x, y, SLOT_RADIUS, CIRCLE_OUTLINE_THICKNESS, color);
+    }
+
+    /// Marks the selected slot in slot first input mode.
+    pub(crate) fn draw_selection(
+        rules: &RuleSet,
+        mirrored: bool,
+        i: usize,
+        j: usize,
+        color: mq::Color,
+    ) {
+        let (x, y) = compute_xy_coordinates(rules, mirrored, i, j);
+        bq::draw_circle_outline(
+            x,
+            y,
+            SLOT_RADIUS + SELECTION_OUTLINE_THICKNESS,
+            SELECTION_OUTLINE_THICKNESS,
+            color,
+        );
     }
 
-    pub(crate) fn draw(i: usize, j: usize, color: Color, number_overlay: NumberOverlay) {
-        let (x, y) = compute_xy_coordinates(i, j);
-        bq::draw_circle(x, y, SLOT_RADIUS, color.as_mq());
+    pub(crate) fn draw(
+        rules: &RuleSet,
+        mirrored: bool,
+        i: usize,
+        j: usize,
+        color: Color,
+        number_overlay: NumberOverlay,
+        hotkeys: ColorHotkeys,
+    ) {
+        let (x, y) = compute_xy_coordinates(rules, mirrored, i, j);
+        draw_peg(x, y, SLOT_RADIUS, color);
 
         match number_overlay {
             NumberOverlay::On => {
-                draw_text_overlay(
-                    x,
-                    y,
-                    mq::BLACK,
-                    format!(
-                        "{}",
-                        COLOR_PALETTE.iter().position(|c| *c == color).unwrap() + 1
-                    ),
-                );
+                let color_index = rules.palette().iter().position(|c| *c == color).unwrap();
+                if let Some(label) = hotkeys.label(color_index) {
+                    draw_text_overlay(x, y, mq::BLACK, label);
+                }
             }
             NumberOverlay::Off => {}
         }
     }
 
-    pub(crate) fn draw_password_text_overlay(i: usize, j: usize) {
-        let (x, y) = compute_xy_coordinates(i, j);
+    /// A filled slot without its color, for streamer mode.
+    pub(crate) fn draw_hidden(
+        rules: &RuleSet,
+        mirrored: bool,
+        i: usize,
+        j: usize,
+        color: mq::Color,
+    ) {
+        let (x, y) = compute_xy_coordinates(rules, mirrored, i, j);
+        bq::draw_circle(x, y, SLOT_RADIUS, color);
+        draw_text_overlay(x, y, mq::WHITE, "?");
+    }
+
+    /// The number of pegs of this slot's color in the password, in a badge on its lower right.
+    pub(crate) fn draw_count_badge(
+        rules: &RuleSet,
+        mirrored: bool,
+        i: usize,
+        j: usize,
+        count: usize,
+    ) {
+        let (x, y) = compute_xy_coordinates(rules, mirrored, i, j);
+        let (badge_x, badge_y) = (x + SLOT_RADIUS * 0.7, y + SLOT_RADIUS * 0.7);
+        bq::draw_circle(badge_x, badge_y, COUNT_BADGE_RADIUS, mq::BLACK);
+        bq::draw_circle_outline(
+            badge_x,
+            badge_y,
+            COUNT_BADGE_RADIUS,
+            CIRCLE_OUTLINE_THICKNESS,
+            mq::WHITE,
+        );
+        draw_text_overlay(badge_x, badge_y, mq::WHITE, count.to_string());
+    }
+
+    pub(crate) fn draw_password_text_overlay(rules: &RuleSet, mirrored: bool, i: usize, j: usize) {
+        let (x, y) = compute_xy_coordinates(rules, mirrored, i, j);
         draw_text_overlay(x, y, mq::WHITE, "?");
     }
 
@@ -907,11 +4075,16 @@ mod guess_circles_ij {
         );
     }
 
-    pub(crate) fn get_containing_ij(mut x: f32, mut y: f32) -> Option<(usize, usize)> {
-        x -= BOARD_OFFSET_X + SLOT_PADDING;
+    pub(crate) fn get_containing_ij(
+        rules: &RuleSet,
+        mirrored: bool,
+        mut x: f32,
+        mut y: f32,
+    ) -> Option<(usize, usize)> {
+        x -= BoardSizeDerivedConsts::get(rules).guess_x(mirrored) + SLOT_PADDING;
         let mut i = 0;
         loop {
-            if x < 0.0 || i >= NUM_SLOTS_PER_ROW {
+            if x < 0.0 || i >= rules.num_slots {
                 return None;
             }
             if x <= SLOT_SIZE {
@@ -925,7 +4098,7 @@ mod guess_circles_ij {
         let mut j = 0;
         loop {
             #[allow(clippy::int_plus_one)]
-            if y < 0.0 || j >= NUM_GUESSES + 1 {
+            if y < 0.0 || j >= rules.num_guesses + 1 {
                 return None;
             }
             if y <= SLOT_SIZE {
@@ -942,58 +4115,65 @@ mod guess_circles_ij {
 // I need to come up with a better re-usable method for drawing shapes and checking if mouse is within
 // the shape boundaries. I am not loving this, but it works for now.
 mod pegs_ij {
+    use crate::rules::RuleSet;
     use crate::{
-        BoardSizeDerivedConsts, BOARD_OFFSET_X, BOARD_OFFSET_Y, COLOR_PALETTE, PEG_OUTER_PADDING,
-        PEG_RADIUS, PEG_SIZE,
+        BoardSizeDerivedConsts, BOARD_OFFSET_X, BOARD_OFFSET_Y, PEG_MIN_SPACING, PEG_OUTER_PADDING,
+        PEG_RADIUS,
     };
 
-    fn intra_peg_x_padding() -> f32 {
-        let BoardSizeDerivedConsts {
-            row_width_guess,
-            row_width_key,
-            ..
-        } = BoardSizeDerivedConsts::get();
+    /// Pegs shrink if the palette is too wide to fit under the board at full size.
+    pub(crate) fn peg_radius(rules: &RuleSet) -> f32 {
+        let board_width = BoardSizeDerivedConsts::get(rules).board_width();
+        let max_peg_size = (board_width - PEG_OUTER_PADDING * 2.0) / rules.palette().len() as f32
+            - PEG_MIN_SPACING;
+        PEG_RADIUS.min(max_peg_size / 2.0)
+    }
 
+    fn intra_peg_x_padding(rules: &RuleSet) -> f32 {
         // Question for future self: Do the local vars help readability?
-        let board_width = row_width_guess + row_width_key;
+        let board_width = BoardSizeDerivedConsts::get(rules).board_width();
         let peg_outer_padding = PEG_OUTER_PADDING * 2.0;
-        let peg_total_width = COLOR_PALETTE.len() as f32 * PEG_SIZE;
-        let num_intra_peg_spaces = COLOR_PALETTE.len() as f32 - 1.0;
+        let num_pegs = rules.palette().len() as f32;
+        let peg_total_width = num_pegs * peg_radius(rules) * 2.0;
+        let num_intra_peg_spaces = num_pegs - 1.0;
 
         (board_width - (peg_outer_padding + peg_total_width)) / num_intra_peg_spaces
     }
 
-    pub(crate) fn compute_x_coordinate(i: usize) -> f32 {
+    pub(crate) fn compute_x_coordinate(rules: &RuleSet, i: usize) -> f32 {
         // explosive way to make sure I don't mis-use this function
-        assert!(i < COLOR_PALETTE.len());
+        assert!(i < rules.palette().len());
 
+        let peg_radius = peg_radius(rules);
         BOARD_OFFSET_X
             + PEG_OUTER_PADDING
-            + (PEG_RADIUS * 2.0 + intra_peg_x_padding()) * i as f32
-            + PEG_RADIUS
+            + (peg_radius * 2.0 + intra_peg_x_padding(rules)) * i as f32
+            + peg_radius
     }
 
-    pub(crate) fn compute_y_coordinate() -> f32 {
-        let derived_consts = BoardSizeDerivedConsts::get();
+    /// Pegs are vertically centered in the space for a full size peg, even if they shrink.
+    pub(crate) fn compute_y_coordinate(rules: &RuleSet) -> f32 {
+        let derived_consts = BoardSizeDerivedConsts::get(rules);
         BOARD_OFFSET_Y + derived_consts.board_height + PEG_OUTER_PADDING + PEG_RADIUS
     }
 
-    pub(crate) fn get_containing_i(mut x: f32, y: f32) -> Option<usize> {
+    pub(crate) fn get_containing_i(rules: &RuleSet, mut x: f32, y: f32) -> Option<usize> {
+        let peg_radius = peg_radius(rules);
         x -= BOARD_OFFSET_X + PEG_OUTER_PADDING;
         let mut i = 0;
         loop {
-            if x < 0.0 || i >= COLOR_PALETTE.len() {
+            if x < 0.0 || i >= rules.palette().len() {
                 return None;
             }
-            if x <= (PEG_RADIUS * 2.0) {
+            if x <= (peg_radius * 2.0) {
                 break;
             }
             i += 1;
-            x -= (PEG_RADIUS * 2.0) + intra_peg_x_padding();
+            x -= (peg_radius * 2.0) + intra_peg_x_padding(rules);
         }
 
-        let peg_y = compute_y_coordinate();
-        if y < peg_y - PEG_RADIUS || y > peg_y + PEG_RADIUS {
+        let peg_y = compute_y_coordinate(rules);
+        if y < peg_y - peg_radius || y > peg_y + peg_radius {
             return None;
         }
 
@@ -1002,17 +4182,23 @@ mod pegs_ij {
 }
 
 /// Produce (x,y) key offset, assuming 2 rows for all keys.
+/// Keys are laid out in 2 rows, with the top row taking the extra key for odd slot counts.
+///
+/// 4 -> 2
+/// 5 -> 3
+/// 6 -> 3
+/// 7 -> 4
+fn num_keys_top_key_row(num_slots_per_row: usize) -> usize {
+    num_slots_per_row.div_ceil(2)
+}
+
 fn get_key_offset(
     key_index: usize,
     num_slots_per_row: usize,
     key_padding: f32,
     key_radius: f32,
 ) -> (f32, f32) {
-    // 4 -> 2
-    // 5 -> 3
-    // 6 -> 3
-    // 7 -> 4
-    let num_keys_top_key_row = (num_slots_per_row as f32 / 2.0).ceil() as usize;
+    let num_keys_top_key_row = num_keys_top_key_row(num_slots_per_row);
     let (x_index, y_index) = if key_index < num_keys_top_key_row {
         (key_index, 0)
     } else {
@@ -1025,8 +4211,7 @@ fn get_key_offset(
     (x, y)
 }
 
-#[allow(dead_code)] // allow unused colors to be easily swapped in via const
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
 enum Color {
     // OG 6
     Red,
@@ -1035,19 +4220,21 @@ enum Color {
     Green,
     Blue,
     Purple,
-    // Additional colors for fun
+    // Additional colors for bigger palettes
     Pink,
     LightBlue,
     NeonGreen,
+    // Not in `ALL_COLORS`, but on the end of the palette with blanks on (see `RuleSet::blanks`)
+    Blank,
 }
 
 impl Color {
-    fn random_array<const N: usize>(palette: &[Self]) -> [Self; N] {
-        [(); N].map(|_| Self::random(palette))
+    fn random_vec(palette: &[Self], len: usize, rng: &SeededRng) -> Vec<Self> {
+        (0..len).map(|_| Self::random(palette, rng)).collect()
     }
 
-    fn random(palette: &[Self]) -> Self {
-        let index = mq::rand::gen_range(0, palette.len());
+    fn random(palette: &[Self], rng: &SeededRng) -> Self {
+        let index = rng.gen_range(0, palette.len());
         palette[index]
     }
 
@@ -1062,65 +4249,100 @@ impl Color {
             Self::Pink => mq::MAGENTA,
             Self::LightBlue => mq::SKYBLUE,
             Self::NeonGreen => mq::Color::from_hex(0x39FF14),
+            Self::Blank => BLANK_PEG_COLOR,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Red => "Red",
+            Self::Orange => "Orange",
+            Self::Yellow => "Yellow",
+            Self::Green => "Green",
+            Self::Blue => "Blue",
+            Self::Purple => "Purple",
+            Self::Pink => "Pink",
+            Self::LightBlue => "Light blue",
+            Self::NeonGreen => "Neon green",
+            Self::Blank => "Blank",
         }
     }
 }
 
-#[derive(Copy, Clone)]
+/// A peg under the mouse: `j` is its row on the board, or None for the palette.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+struct HoveredPeg {
+    i: usize,
+    j: Option<usize>,
+    color: Color,
+}
+
+#[derive(Clone)]
 struct CompleteRow {
-    guess: [Color; NUM_SLOTS_PER_ROW],
+    guess: Vec<Color>,
     num_correct_hits: usize,
     num_misplaced_hits: usize,
+    note: Option<String>,
+    // Time into the game it was submitted, for exports. None if there's no clock, e.g. in tests.
+    elapsed: Option<Duration>,
 }
 
 // None => Incomplete row
 // Some => Completed row
-fn convert_working_row_if_completed(
-    working_row: &[Option<Color>; NUM_SLOTS_PER_ROW],
-) -> Option<[Color; NUM_SLOTS_PER_ROW]> {
-    if working_row.contains(&None) {
-        return None;
+fn convert_working_row_if_completed(working_row: &[Option<Color>]) -> Option<Vec<Color>> {
+    working_row.iter().copied().collect()
+}
+
+fn evaluate_guess(guess: Vec<Color>, password: &[Color]) -> CompleteRow {
+    let (num_correct_hits, num_misplaced_hits) = count_hits(&guess, password);
+    CompleteRow {
+        guess,
+        num_correct_hits,
+        num_misplaced_hits,
+        note: None,
+        elapsed: None,
     }
+}
 
-    Some(working_row.map(Option::unwrap))
+/// For each peg in `guess`, how many pegs of its color are in the password, for the color counts
+/// assist.
+fn password_color_counts(guess: &[Color], password: &[Color]) -> Vec<usize> {
+    guess
+        .iter()
+        .map(|color| password.iter().filter(|c| *c == color).count())
+        .collect()
 }
 
-fn evaluate_guess(
-    guess: [Color; NUM_SLOTS_PER_ROW],
-    password: [Color; NUM_SLOTS_PER_ROW],
-) -> CompleteRow {
-    let mut guess_colors_eligible_for_misplaced_hits = HashMap::new();
-    let mut password_colors_eligible_for_misplaced_hits = HashMap::new();
+/// (correct hits, misplaced hits)
+fn count_hits(guess: &[Color], password: &[Color]) -> (usize, usize) {
+    assert_eq!(guess.len(), password.len());
+
+    // Counts per color, indexed by `Color as usize`. Arrays rather than maps, since this runs for
+    // every candidate password.
+    let mut guess_colors_eligible_for_misplaced_hits = [0usize; NUM_COLOR_INDICES];
+    let mut password_colors_eligible_for_misplaced_hits = [0usize; NUM_COLOR_INDICES];
 
     // First pass: check for correct hits
     let mut num_correct_hits = 0;
-    for i in 0..NUM_SLOTS_PER_ROW {
+    for i in 0..guess.len() {
         if guess[i] == password[i] {
             num_correct_hits += 1;
         } else {
-            *guess_colors_eligible_for_misplaced_hits
-                .entry(guess[i])
-                .or_insert(0usize) += 1;
-            *password_colors_eligible_for_misplaced_hits
-                .entry(password[i])
-                .or_insert(0usize) += 1;
+            guess_colors_eligible_for_misplaced_hits[guess[i] as usize] += 1;
+            password_colors_eligible_for_misplaced_hits[password[i] as usize] += 1;
         }
     }
 
     // Second pass: check for misplaced hits
-    let mut num_misplaced_hits = 0;
-    for (color, guess_color_count) in guess_colors_eligible_for_misplaced_hits {
-        let password_color_count = password_colors_eligible_for_misplaced_hits
-            .remove(&color)
-            .unwrap_or(0);
-        num_misplaced_hits += min(guess_color_count, password_color_count);
-    }
-
-    CompleteRow {
-        guess,
-        num_correct_hits,
-        num_misplaced_hits,
-    }
+    let num_misplaced_hits = guess_colors_eligible_for_misplaced_hits
+        .iter()
+        .zip(password_colors_eligible_for_misplaced_hits)
+        .map(|(guess_color_count, password_color_count)| {
+            min(*guess_color_count, password_color_count)
+        })
+        .sum();
+
+    (num_correct_hits, num_misplaced_hits)
 }
 
 fn format_duration(duration: Duration) -> String {
@@ -1144,23 +4366,51 @@ pub(crate) enum NumberOverlay {
     Off,
 }
 
-/// If mouse is click and dragged from pegs.
+/// The slot after `i`, or None past the last one.
+fn next_selected_slot(rules: &RuleSet, i: usize) -> Option<usize> {
+    Some(i + 1).filter(|next| *next < rules.num_slots)
+}
+
+/// If mouse is click and dragged from pegs, or from a slot.
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub(crate) enum MouseClickReleaseBehavior {
     None,
     FillColor,
+    /// Dropping it on another slot swaps them. Letting go on the same slot is a plain click.
+    MoveSlot {
+        from: usize,
+    },
+}
+
+/// What happens to the row being filled in this frame.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum SlotUpdate {
+    Set(usize, Color),
+    /// Moving into an empty slot leaves the first one empty.
+    Swap(usize, usize),
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{evaluate_guess, get_key_offset, Color, NUM_SLOTS_PER_ROW};
+    use super::{
+        color_keys_hint, evaluate_guess, get_key_offset, guess_circles_ij, next_selected_slot,
+        palette_hotkeys, password_color_counts, BoardSizeDerivedConsts, Color, BOARD_OFFSET_Y,
+        KEY_RADIUS,
+    };
+    use crate::password::Password;
+    use crate::rules::{DuplicateColors, RuleSet, NUM_SLOTS_RANGE};
+    use crate::settings::ColorHotkeys;
+    use crate::ALL_COLORS;
+    use macroquad::prelude as mq;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
 
     // Janky names for readability defining test cases
     #[derive(Debug)]
     struct EvaluateGuessTestCase {
         // inputs
-        pword: [Color; NUM_SLOTS_PER_ROW],
-        guess: [Color; NUM_SLOTS_PER_ROW],
+        pword: Vec<Color>,
+        guess: Vec<Color>,
         // (expected correct, expected misplaced)
         pins: (usize, usize),
     }
@@ -1168,7 +4418,7 @@ mod tests {
     #[test]
     fn test_evaluate_guess() {
         for tc in evaluate_guess_test_cases() {
-            let actual = evaluate_guess(tc.guess, tc.pword);
+            let actual = evaluate_guess(tc.guess.clone(), &tc.pword);
             let (expected_correct_hits, expected_misplaced_hits) = tc.pins;
             assert_eq!(
                 actual.num_correct_hits, expected_correct_hits,
@@ -1182,7 +4432,7 @@ mod tests {
             );
 
             // Algorithm is not dependent on left/right, so swap them
-            let actual = evaluate_guess(tc.pword, tc.guess);
+            let actual = evaluate_guess(tc.pword.clone(), &tc.guess);
             let (expected_correct_hits, expected_misplaced_hits) = tc.pins;
             assert_eq!(
                 actual.num_correct_hits, expected_correct_hits,
@@ -1197,51 +4447,138 @@ mod tests {
         }
     }
 
+    /// (guess, password, a shuffle of their indices), for any slot count and palette.
+    fn guess_password_and_shuffle() -> impl Strategy<Value = (Vec<Color>, Vec<Color>, Vec<usize>)> {
+        (NUM_SLOTS_RANGE, 1..=ALL_COLORS.len()).prop_flat_map(|(num_slots, num_colors)| {
+            let colors = || {
+                vec(0..num_colors, num_slots)
+                    .prop_map(|v| v.into_iter().map(|i| ALL_COLORS[i]).collect())
+            };
+            (
+                colors(),
+                colors(),
+                Just((0..num_slots).collect::<Vec<_>>()).prop_shuffle(),
+            )
+        })
+    }
+
+    fn shuffled(colors: &[Color], shuffle: &[usize]) -> Vec<Color> {
+        shuffle.iter().map(|i| colors[*i]).collect()
+    }
+
+    proptest! {
+        #[test]
+        fn test_evaluate_guess_invariants(
+            (guess, password, shuffle) in guess_password_and_shuffle()
+        ) {
+            let row = evaluate_guess(guess.clone(), &password);
+            let hits = (row.num_correct_hits, row.num_misplaced_hits);
+            prop_assert!(row.num_correct_hits + row.num_misplaced_hits <= password.len());
+
+            // Symmetric
+            let swapped = evaluate_guess(password.clone(), &guess);
+            prop_assert_eq!(hits, (swapped.num_correct_hits, swapped.num_misplaced_hits));
+
+            // Shuffling both the same way keeps every hit where it was
+            let both_shuffled =
+                evaluate_guess(shuffled(&guess, &shuffle), &shuffled(&password, &shuffle));
+            prop_assert_eq!(
+                hits,
+                (both_shuffled.num_correct_hits, both_shuffled.num_misplaced_hits)
+            );
+
+            // Shuffling just the guess can only trade correct hits for misplaced ones
+            let guess_shuffled = evaluate_guess(shuffled(&guess, &shuffle), &password);
+            prop_assert_eq!(
+                row.num_correct_hits + row.num_misplaced_hits,
+                guess_shuffled.num_correct_hits + guess_shuffled.num_misplaced_hits
+            );
+
+            let exact = evaluate_guess(password.clone(), &password);
+            prop_assert_eq!(
+                (password.len(), 0),
+                (exact.num_correct_hits, exact.num_misplaced_hits)
+            );
+        }
+    }
+
     fn evaluate_guess_test_cases() -> Vec<EvaluateGuessTestCase> {
         let a = Color::Red;
         let b = Color::Orange;
         let c = Color::Yellow;
         let d = Color::Green;
+        let blank = Color::Blank;
 
         vec![
             EvaluateGuessTestCase {
-                pword: [a, a, a, a],
-                guess: [a, a, a, a],
+                pword: vec![a, a, a, a],
+                guess: vec![a, a, a, a],
                 pins: (4, 0),
             },
             EvaluateGuessTestCase {
-                pword: [a, a, a, a],
-                guess: [a, a, a, b],
+                pword: vec![a, a, a, a],
+                guess: vec![a, a, a, b],
                 pins: (3, 0),
             },
             EvaluateGuessTestCase {
-                pword: [a, a, a, a],
-                guess: [a, b, b, b],
+                pword: vec![a, a, a, a],
+                guess: vec![a, b, b, b],
                 pins: (1, 0),
             },
             EvaluateGuessTestCase {
-                pword: [a, b, c, d],
-                guess: [a, b, b, b],
+                pword: vec![a, b, c, d],
+                guess: vec![a, b, b, b],
                 pins: (2, 0),
             },
             EvaluateGuessTestCase {
-                pword: [a, b, c, d],
-                guess: [a, c, a, b],
+                pword: vec![a, b, c, d],
+                guess: vec![a, c, a, b],
                 pins: (1, 2),
             },
             EvaluateGuessTestCase {
-                pword: [a, b, c, d],
-                guess: [d, c, a, b],
+                pword: vec![a, b, c, d],
+                guess: vec![d, c, a, b],
                 pins: (0, 4),
             },
             EvaluateGuessTestCase {
-                pword: [a, b, a, b],
-                guess: [a, b, c, d],
+                pword: vec![a, b, a, b],
+                guess: vec![a, b, c, d],
                 pins: (2, 0),
             },
+            // Blanks count like any other color
+            EvaluateGuessTestCase {
+                pword: vec![a, blank, blank, b],
+                guess: vec![blank, blank, c, a],
+                pins: (1, 2),
+            },
         ]
     }
 
+    #[test]
+    fn test_password_color_counts() {
+        use Color::*;
+        let password = [Red, Red, Blue, Green];
+        assert_eq!(
+            vec![2, 2, 0, 1],
+            password_color_counts(&[Red, Red, Yellow, Green], &password)
+        );
+        assert_eq!(
+            vec![1, 1, 1, 1],
+            password_color_counts(&[Blue, Blue, Blue, Blue], &password)
+        );
+    }
+
+    #[test]
+    fn test_password_from_seed_is_reproducible() {
+        let rules = RuleSet::classic();
+        let password = Password::from_seed(&rules, DuplicateColors::Allowed, 1234);
+        assert_eq!(rules.num_slots, password.password().len());
+        assert_eq!(
+            password.password(),
+            Password::from_seed(&rules, DuplicateColors::Allowed, 1234).password()
+        );
+    }
+
     #[test]
     fn test_get_key_offset() {
         let key_padding = 5.0;
@@ -1302,6 +4639,103 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_mirrored_board() {
+        let rules = RuleSet::classic();
+        let consts = BoardSizeDerivedConsts::get(&rules);
+        // Feedback pegs first, then the guesses
+        assert_eq!(
+            consts.key_x(true) + consts.row_width_key,
+            consts.guess_x(true)
+        );
+        assert_eq!(
+            consts.guess_x(false) + consts.row_width_guess,
+            consts.key_x(false)
+        );
+
+        // The first slot's center is under the second slot's when not mirrored
+        let y = BOARD_OFFSET_Y + consts.row_height * 1.5;
+        let x = consts.guess_x(true) + consts.row_width_guess / 8.0;
+        assert_eq!(
+            Some((0, 1)),
+            guess_circles_ij::get_containing_ij(&rules, true, x, y)
+        );
+        assert_eq!(
+            Some((1, 1)),
+            guess_circles_ij::get_containing_ij(&rules, false, x, y)
+        );
+        // Feedback pegs aren't slots
+        let x = consts.key_x(true) + consts.row_width_key / 2.0;
+        assert_eq!(
+            None,
+            guess_circles_ij::get_containing_ij(&rules, true, x, y)
+        );
+    }
+
+    #[test]
+    fn test_palette_hotkeys() {
+        let classic = RuleSet::classic();
+        let keys = palette_hotkeys(&classic, ColorHotkeys::HomeRow)
+            .map(|(_, key)| key)
+            .collect::<Vec<_>>();
+        assert_eq!(classic.palette().len(), keys.len());
+        assert!(keys.iter().all(Option::is_some));
+        assert_eq!("a-h", color_keys_hint(&classic, ColorHotkeys::HomeRow));
+
+        // Every color and blank is one more peg than there are keys
+        let rainbow = RuleSet {
+            num_colors: ALL_COLORS.len(),
+            blanks: true,
+            ..RuleSet::classic()
+        };
+        let pegs = palette_hotkeys(&rainbow, ColorHotkeys::NumberRow).collect::<Vec<_>>();
+        assert_eq!((Color::Blank, None), pegs[9]);
+        assert_eq!((Color::NeonGreen, Some(mq::KeyCode::Key9)), pegs[8]);
+        assert_eq!(
+            "numpad 1-9, or click the rest",
+            color_keys_hint(&rainbow, ColorHotkeys::Numpad)
+        );
+    }
+
+    #[test]
+    fn test_next_selected_slot() {
+        let rules = RuleSet::classic();
+        assert_eq!(Some(1), next_selected_slot(&rules, 0));
+        assert_eq!(Some(3), next_selected_slot(&rules, 2));
+        assert_eq!(None, next_selected_slot(&rules, 3));
+    }
+
+    #[test]
+    fn test_key_offsets_fit_key_area() {
+        for num_slots in NUM_SLOTS_RANGE {
+            let rules = RuleSet {
+                num_slots,
+                ..RuleSet::classic()
+            };
+            let consts = BoardSizeDerivedConsts::get(&rules);
+            let offsets = (0..num_slots)
+                .map(|i| get_key_offset(i, num_slots, consts.key_padding, KEY_RADIUS))
+                .collect::<Vec<_>>();
+            for (i, (x, y)) in offsets.iter().enumerate() {
+                assert!(
+                    *x + KEY_RADIUS + consts.key_padding <= consts.row_width_key + 0.001,
+                    "{num_slots} slots, key {i} past the right edge"
+                );
+                assert!(
+                    *y + KEY_RADIUS + consts.key_padding <= consts.row_height + 0.001,
+                    "{num_slots} slots, key {i} past the bottom edge"
+                );
+                assert!(
+                    offsets[..i]
+                        .iter()
+                        .all(|(other_x, other_y)| (x - other_x).abs() >= KEY_RADIUS * 2.0
+                            || (y - other_y).abs() >= KEY_RADIUS * 2.0),
+                    "{num_slots} slots, key {i} overlaps another"
+                );
+            }
+        }
+    }
+
     fn get_key_offset_test_case(
         key_padding: f32,
         key_radius: f32,
@@ -0,0 +1,85 @@
+//! Short notes on guesses (e.g. "testing reds"), for keeping track of what each one was for on the
+//! longer rule sets. Right-click a guess to write one; it shows as a small icon next to the row,
+//! with the note as a tooltip on hover.
+use better_quad::bq::{self, TextAnchorPoint, TextInput};
+use better_quad::mq;
+
+const NOTE_MAX_LEN: usize = 40;
+const NOTE_INPUT_FONT_SIZE: u16 = 30;
+const NOTE_ICON_SIZE: f32 = 12.0;
+// Sticky note yellow
+const NOTE_COLOR: mq::Color = mq::Color::new(1.0, 0.92, 0.45, 1.0);
+
+pub(crate) struct NoteEditor {
+    pub(crate) history_index: usize,
+    pub(crate) input: TextInput,
+}
+
+impl NoteEditor {
+    pub(crate) fn new(history_index: usize, existing_note: Option<&str>) -> Self {
+        Self {
+            history_index,
+            input: TextInput::new(NOTE_MAX_LEN, |_| true).with_text(existing_note.unwrap_or("")),
+        }
+    }
+
+    pub(crate) fn draw(&self) {
+        self.input.draw(
+            &format!(
+                "Note for guess {} ([enter] to save, [esc] to cancel):\n",
+                self.history_index + 1
+            ),
+            NOTE_INPUT_FONT_SIZE,
+            TextAnchorPoint::window_centered(),
+        );
+    }
+}
+
+/// None for a blank note, which removes it.
+pub(crate) fn clean_note(text: &str) -> Option<String> {
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// A little sticky note centered at (x, y).
+pub(crate) fn draw_icon(x: f32, y: f32) {
+    let half_size = NOTE_ICON_SIZE / 2.0;
+    mq::draw_rectangle(
+        x - half_size,
+        y - half_size,
+        NOTE_ICON_SIZE,
+        NOTE_ICON_SIZE,
+        NOTE_COLOR,
+    );
+    // Folded corner
+    mq::draw_triangle(
+        mq::vec2(x + half_size, y + half_size),
+        mq::vec2(x + half_size, y),
+        mq::vec2(x, y + half_size),
+        mq::DARKBROWN,
+    );
+}
+
+pub(crate) fn is_over_icon(icon_x: f32, icon_y: f32, x: f32, y: f32) -> bool {
+    (x - icon_x).abs() <= NOTE_ICON_SIZE && (y - icon_y).abs() <= NOTE_ICON_SIZE
+}
+
+/// Shown right away, unlike other tooltips, since the icon says there's a note.
+pub(crate) fn draw_tooltip(note: &str, mouse_x: f32, mouse_y: f32) {
+    bq::draw_tooltip(note, mouse_x, mouse_y, NOTE_COLOR);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::clean_note;
+
+    #[test]
+    fn test_clean_note() {
+        assert_eq!(
+            Some("testing reds".to_string()),
+            clean_note("  testing reds ")
+        );
+        assert_eq!(None, clean_note(""));
+        assert_eq!(None, clean_note("   "));
+    }
+}
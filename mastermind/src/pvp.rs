@@ -0,0 +1,195 @@
+//! Hotseat matches: two players take turns setting a password for the other to break, on the same
+//! computer. The codemaker scores a point for every guess the codebreaker needs, plus a bonus point
+//! if it's never broken, like the tabletop rules.
+//!
+//! Between turns the board is covered, so whoever isn't setting the password can look away.
+use crate::theme::Theme;
+use better_quad::bq::{self, TextAlignment, TextAnchorPoint, TextBackground};
+use better_quad::mq;
+
+/// Each round, both players set one password.
+pub(crate) const NUM_ROUNDS: usize = 2;
+const PLAYER_NAMES: [&str; 2] = ["Player 1", "Player 2"];
+const COVER_FONT_SIZE: u16 = 25;
+const COVER_PADDING: f32 = 15.0;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) enum MatchPhase {
+    /// Board covered until the codemaker is ready
+    HandOffToCodemaker,
+    SettingPassword,
+    /// Board covered until the codebreaker is ready
+    HandOffToCodebreaker,
+    /// Includes the end of game screen after
+    Breaking,
+    /// Final scores, covering the board
+    Finished,
+}
+
+pub(crate) struct PvpMatch {
+    num_rounds: usize,
+    /// Passwords set so far, counting the current one
+    num_turns: usize,
+    scores: [usize; 2],
+    pub(crate) phase: MatchPhase,
+}
+
+impl PvpMatch {
+    pub(crate) fn new(num_rounds: usize) -> Self {
+        Self {
+            num_rounds,
+            num_turns: 1,
+            scores: [0, 0],
+            phase: MatchPhase::HandOffToCodemaker,
+        }
+    }
+
+    /// Whether the board should be hidden.
+    pub(crate) fn covers_board(&self) -> bool {
+        matches!(
+            self.phase,
+            MatchPhase::HandOffToCodemaker
+                | MatchPhase::HandOffToCodebreaker
+                | MatchPhase::Finished
+        )
+    }
+
+    fn codemaker(&self) -> usize {
+        // Player 1 goes first
+        (self.num_turns - 1) % 2
+    }
+
+    fn codebreaker(&self) -> usize {
+        1 - self.codemaker()
+    }
+
+    pub(crate) fn codemaker_name(&self) -> &'static str {
+        PLAYER_NAMES[self.codemaker()]
+    }
+
+    pub(crate) fn codebreaker_name(&self) -> &'static str {
+        PLAYER_NAMES[self.codebreaker()]
+    }
+
+    /// Scores the current password for its codemaker. Only call once per turn.
+    pub(crate) fn record_turn(&mut self, num_guesses: usize, broken: bool) {
+        self.scores[self.codemaker()] += codemaker_points(num_guesses, broken);
+    }
+
+    /// On to the next codemaker, or the final scores after the last round.
+    pub(crate) fn next_turn(&mut self) {
+        if self.num_turns == self.num_rounds * 2 {
+            self.phase = MatchPhase::Finished;
+        } else {
+            self.num_turns += 1;
+            self.phase = MatchPhase::HandOffToCodemaker;
+        }
+    }
+
+    /// e.g. "Round 1 of 2. Player 1: 3, Player 2: 0"
+    pub(crate) fn status_text(&self) -> String {
+        format!(
+            "Round {} of {}. {}",
+            self.num_turns.div_ceil(2),
+            self.num_rounds,
+            self.scores_text(", ")
+        )
+    }
+
+    fn scores_text(&self, separator: &str) -> String {
+        PLAYER_NAMES
+            .iter()
+            .zip(self.scores)
+            .map(|(name, score)| format!("{name}: {score}"))
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    /// What covers the board, if anything.
+    pub(crate) fn cover_text(&self, continue_key: &str, leave_key: &str) -> Option<String> {
+        let text = match self.phase {
+            MatchPhase::HandOffToCodemaker => format!(
+                "{}\n\n{} sets the password.\n{}, look away!\n\n\
+                [{continue_key}] when ready, [{leave_key}] to quit",
+                self.status_text(),
+                self.codemaker_name(),
+                self.codebreaker_name(),
+            ),
+            MatchPhase::HandOffToCodebreaker => format!(
+                "{}\n\n{} breaks the password.\n\n\
+                [{continue_key}] when ready, [{leave_key}] to quit",
+                self.status_text(),
+                self.codebreaker_name(),
+            ),
+            MatchPhase::Finished => {
+                let result = match self.scores[0].cmp(&self.scores[1]) {
+                    std::cmp::Ordering::Greater => format!("{} wins!", PLAYER_NAMES[0]),
+                    std::cmp::Ordering::Less => format!("{} wins!", PLAYER_NAMES[1]),
+                    std::cmp::Ordering::Equal => "It's a tie!".to_string(),
+                };
+                format!(
+                    "Match over! {result}\n\n{}\n\n\
+                    [{continue_key}] for a rematch, [{leave_key}] to quit",
+                    self.scores_text("\n"),
+                )
+            }
+            MatchPhase::SettingPassword | MatchPhase::Breaking => return None,
+        };
+        Some(text)
+    }
+}
+
+/// A point for every guess the codebreaker needed, plus a bonus point if they never broke it.
+/// LAN games score the same way.
+pub(crate) fn codemaker_points(num_guesses: usize, broken: bool) -> usize {
+    let bonus = if broken { 0 } else { 1 };
+    num_guesses + bonus
+}
+
+/// Hides the whole window, board and all.
+pub(crate) fn draw_cover(text: String, theme: &Theme) {
+    mq::clear_background(theme.background);
+    bq::draw_text(
+        text,
+        TextAlignment::Center,
+        None,
+        COVER_FONT_SIZE,
+        theme.text,
+        TextAnchorPoint::window_centered(),
+        Some(TextBackground {
+            color: theme.text_background,
+            x_padding: COVER_PADDING,
+            y_padding: COVER_PADDING,
+        }),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MatchPhase, PvpMatch};
+
+    #[test]
+    fn test_match() {
+        let mut pvp_match = PvpMatch::new(1);
+        assert_eq!("Player 1", pvp_match.codemaker_name());
+        assert_eq!("Player 2", pvp_match.codebreaker_name());
+        // Broken on the 5th guess
+        pvp_match.record_turn(5, true);
+        pvp_match.next_turn();
+        assert_eq!(MatchPhase::HandOffToCodemaker, pvp_match.phase);
+
+        assert_eq!("Player 2", pvp_match.codemaker_name());
+        // Never broken, so a bonus point
+        pvp_match.record_turn(8, false);
+        assert_eq!(
+            "Round 1 of 1. Player 1: 5, Player 2: 9",
+            pvp_match.status_text()
+        );
+        pvp_match.next_turn();
+        assert_eq!(MatchPhase::Finished, pvp_match.phase);
+        assert!(pvp_match
+            .cover_text("enter", "esc")
+            .unwrap()
+            .starts_with("Match over! Player 2 wins!"));
+    }
+}
@@ -0,0 +1,100 @@
+//! Stepping back through a finished game's guesses, to see which pegs the feedback was about.
+use crate::{Color, CompleteRow, NUM_COLOR_INDICES};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) enum PegResult {
+    /// Right color, right spot
+    Correct,
+    /// Right color, wrong spot
+    Misplaced,
+    Absent,
+}
+
+/// Which of `guess`'s pegs the feedback counted, and how. When a color is guessed more times than
+/// it's misplaced, the leftmost ones count, so the totals always match the feedback pegs.
+pub(crate) fn annotate_guess(guess: &[Color], password: &[Color]) -> Vec<PegResult> {
+    assert_eq!(guess.len(), password.len());
+
+    let mut results = vec![PegResult::Absent; guess.len()];
+    // Password colors not already matched by a correct peg, indexed by `Color as usize`
+    let mut unmatched_password_colors = [0usize; NUM_COLOR_INDICES];
+    for i in 0..guess.len() {
+        if guess[i] == password[i] {
+            results[i] = PegResult::Correct;
+        } else {
+            unmatched_password_colors[password[i] as usize] += 1;
+        }
+    }
+    for (color, result) in guess.iter().zip(&mut results) {
+        let unmatched = &mut unmatched_password_colors[*color as usize];
+        if *result != PegResult::Correct && *unmatched > 0 {
+            *unmatched -= 1;
+            *result = PegResult::Misplaced;
+        }
+    }
+    results
+}
+
+/// `history_index` is 0-based.
+pub(crate) fn review_text(
+    history_index: usize,
+    history: &[CompleteRow],
+    previous_key: &str,
+    next_key: &str,
+) -> String {
+    let row = &history[history_index];
+    format!(
+        "Guess {} of {}\n\
+        {} right color, right spot (ringed white)\n\
+        {} right color, wrong spot (ringed grey)\n\
+        \n\
+        [{previous_key}]/[{next_key}] to step through guesses\n\
+        [esc] to go back",
+        history_index + 1,
+        history.len(),
+        row.num_correct_hits,
+        row.num_misplaced_hits,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{annotate_guess, PegResult};
+    use crate::{count_hits, Color, ALL_COLORS};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_annotate_guess() {
+        let password = [Color::Red, Color::Orange, Color::Orange, Color::Green];
+        let guess = [Color::Orange, Color::Red, Color::Orange, Color::Orange];
+        assert_eq!(
+            vec![
+                PegResult::Misplaced,
+                PegResult::Misplaced,
+                PegResult::Correct,
+                // Only one more orange in the password, and the first guessed one got it
+                PegResult::Absent,
+            ],
+            annotate_guess(&guess, &password)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_annotations_match_feedback(
+            (password, guess) in (2..=8usize).prop_flat_map(|num_slots| {
+                let colors = || vec(0..ALL_COLORS.len(), num_slots)
+                    .prop_map(|v| v.into_iter().map(|i| ALL_COLORS[i]).collect::<Vec<_>>());
+                (colors(), colors())
+            })
+        ) {
+            let results = annotate_guess(&guess, &password);
+            let count = |wanted| results.iter().filter(|result| **result == wanted).count();
+            prop_assert_eq!(
+                count_hits(&guess, &password),
+                (count(PegResult::Correct), count(PegResult::Misplaced))
+            );
+        }
+    }
+}
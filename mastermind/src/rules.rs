@@ -0,0 +1,263 @@
+//! Rule sets control the board size: how many slots per row, colors, and guesses.
+//!
+//! Built-in rule sets live in `rule-sets/*.toml` and are compiled in. Users can add their own by
+//! dropping more toml files (same format) into the app's "rule-sets" config dir (see `bq_config`).
+use crate::{Color, ALL_COLORS, CONFIG_DIR};
+use better_quad::bq::{self, ConfigFile, LoadedConfigs, SeededRng};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+const RULE_SETS_CONFIG_KIND: &str = "rule-sets";
+const BUILT_IN_RULE_SETS: [(&str, &str); 5] = [
+    (
+        "1-classic.toml",
+        include_str!("../rule-sets/1-classic.toml"),
+    ),
+    ("2-mini.toml", include_str!("../rule-sets/2-mini.toml")),
+    ("3-super.toml", include_str!("../rule-sets/3-super.toml")),
+    ("4-blitz.toml", include_str!("../rule-sets/4-blitz.toml")),
+    ("5-blanks.toml", include_str!("../rule-sets/5-blanks.toml")),
+];
+
+// Bounds are mostly about what fits in a window.
+pub(crate) const NUM_SLOTS_RANGE: RangeInclusive<usize> = 2..=8;
+const NUM_COLORS_RANGE: RangeInclusive<usize> = 2..=ALL_COLORS.len();
+const NUM_GUESSES_RANGE: RangeInclusive<usize> = 1..=12;
+
+/// Index by `num_colors` for a palette with blank on the end, so palettes can still be slices.
+static PALETTES_WITH_BLANK: [[Color; ALL_COLORS.len() + 1]; ALL_COLORS.len() + 1] =
+    palettes_with_blank();
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RuleSet {
+    pub(crate) name: String,
+    pub(crate) num_slots: usize,
+    pub(crate) num_colors: usize,
+    pub(crate) num_guesses: usize,
+    /// Lose the game if it isn't won in time.
+    pub(crate) time_limit_secs: Option<u64>,
+    /// Blank is a color too: passwords can have empty slots, and so can guesses. One more color
+    /// to rule out, and a blank looks a lot like a slot you forgot to fill.
+    #[serde(default)]
+    pub(crate) blanks: bool,
+}
+
+impl RuleSet {
+    /// The OG rules. Daily puzzles always use these so everyone plays the same puzzle.
+    pub(crate) fn classic() -> Self {
+        bq::parse_config(BUILT_IN_RULE_SETS[0].1).expect("built-in rule set is valid")
+    }
+
+    /// The first `num_colors` of `ALL_COLORS`, then blank if the rules have it.
+    pub(crate) fn palette(&self) -> &'static [Color] {
+        if self.blanks {
+            &PALETTES_WITH_BLANK[self.num_colors][..=self.num_colors]
+        } else {
+            &ALL_COLORS[..self.num_colors]
+        }
+    }
+
+    pub(crate) fn time_limit(&self) -> Option<Duration> {
+        self.time_limit_secs.map(Duration::from_secs)
+    }
+}
+
+impl ConfigFile for RuleSet {
+    fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("name must not be empty".to_string());
+        }
+        check_range("num_slots", self.num_slots, NUM_SLOTS_RANGE)?;
+        check_range("num_colors", self.num_colors, NUM_COLORS_RANGE)?;
+        check_range("num_guesses", self.num_guesses, NUM_GUESSES_RANGE)?;
+        if self.time_limit_secs == Some(0) {
+            return Err("time_limit_secs must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Whether passwords may or must repeat colors. A game option rather than part of the rule set
+/// files, so it applies to any rule set. Daily puzzles always allow duplicates.
+#[derive(Serialize, Deserialize, Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) enum DuplicateColors {
+    #[default]
+    Allowed,
+    Forbidden,
+    Required,
+}
+
+impl DuplicateColors {
+    pub(crate) fn next(self) -> Self {
+        match self {
+            Self::Allowed => Self::Forbidden,
+            Self::Forbidden => Self::Required,
+            Self::Required => Self::Allowed,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Allowed => "allowed",
+            Self::Forbidden => "forbidden",
+            Self::Required => "required",
+        }
+    }
+
+    /// For the how to play text.
+    pub(crate) fn description(self) -> &'static str {
+        match self {
+            Self::Allowed => "Duplicate colors are allowed.",
+            Self::Forbidden => "Each color is used at most once.",
+            Self::Required => "At least one color is used more than once.",
+        }
+    }
+
+    /// Forbidding duplicates needs at least as many colors as slots. Otherwise they're allowed.
+    pub(crate) fn effective_for(self, rules: &RuleSet) -> Self {
+        match self {
+            Self::Forbidden if rules.palette().len() < rules.num_slots => Self::Allowed,
+            _ => self,
+        }
+    }
+
+    pub(crate) fn allows(self, password: &[Color]) -> bool {
+        match self {
+            Self::Allowed => true,
+            Self::Forbidden => !has_duplicates(password),
+            Self::Required => has_duplicates(password),
+        }
+    }
+
+    pub(crate) fn random_password(self, rules: &RuleSet, rng: &SeededRng) -> Vec<Color> {
+        let palette = rules.palette();
+        match self.effective_for(rules) {
+            Self::Allowed => Color::random_vec(palette, rules.num_slots, rng),
+            // Sample without replacement
+            Self::Forbidden => {
+                let mut remaining = palette.to_vec();
+                (0..rules.num_slots)
+                    .map(|_| remaining.swap_remove(rng.gen_range(0, remaining.len())))
+                    .collect()
+            }
+            // Rules have at least 2 slots and 2 colors, so this doesn't take many tries.
+            Self::Required => loop {
+                let password = Color::random_vec(palette, rules.num_slots, rng);
+                if has_duplicates(&password) {
+                    return password;
+                }
+            },
+        }
+    }
+}
+
+const fn palettes_with_blank() -> [[Color; ALL_COLORS.len() + 1]; ALL_COLORS.len() + 1] {
+    let mut palettes = [[Color::Blank; ALL_COLORS.len() + 1]; ALL_COLORS.len() + 1];
+    let mut num_colors = 0;
+    while num_colors < palettes.len() {
+        let mut i = 0;
+        while i < num_colors {
+            palettes[num_colors][i] = ALL_COLORS[i];
+            i += 1;
+        }
+        num_colors += 1;
+    }
+    palettes
+}
+
+pub(crate) fn has_duplicates(password: &[Color]) -> bool {
+    (1..password.len()).any(|i| password[..i].contains(&password[i]))
+}
+
+fn check_range(field: &str, value: usize, range: RangeInclusive<usize>) -> Result<(), String> {
+    if range.contains(&value) {
+        Ok(())
+    } else {
+        Err(format!(
+            "{field} must be between {} and {}, got {value}",
+            range.start(),
+            range.end()
+        ))
+    }
+}
+
+/// Built-in rule sets first, then the user's rule sets sorted by file name.
+pub(crate) fn load_rule_sets() -> LoadedConfigs<RuleSet> {
+    CONFIG_DIR.load_all(RULE_SETS_CONFIG_KIND, &BUILT_IN_RULE_SETS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{has_duplicates, DuplicateColors, RuleSet, BUILT_IN_RULE_SETS};
+    use crate::Color;
+    use better_quad::bq::{self, SeededRng};
+
+    #[test]
+    fn test_built_in_rule_sets_parse() {
+        for (file_name, contents) in BUILT_IN_RULE_SETS {
+            let parsed = bq::parse_config::<RuleSet>(contents);
+            assert!(parsed.is_ok(), "{file_name}: {parsed:?}");
+        }
+        assert_eq!(4, RuleSet::classic().num_slots);
+    }
+
+    #[test]
+    fn test_rule_set_validation() {
+        let too_many_colors = "name = \"Rainbow\"\nnum_slots = 4\nnum_colors = 10\nnum_guesses = 8";
+        assert_eq!(
+            "num_colors must be between 2 and 9, got 10",
+            bq::parse_config::<RuleSet>(too_many_colors).unwrap_err()
+        );
+
+        // Blank is click-only then
+        let every_color_and_blank =
+            "name = \"Rainbow\"\nnum_slots = 4\nnum_colors = 9\nnum_guesses = 8\nblanks = true";
+        assert_eq!(
+            10,
+            bq::parse_config::<RuleSet>(every_color_and_blank)
+                .unwrap()
+                .palette()
+                .len()
+        );
+    }
+
+    #[test]
+    fn test_palette_with_blanks() {
+        let rules = RuleSet {
+            num_colors: 3,
+            blanks: true,
+            ..RuleSet::classic()
+        };
+        assert_eq!(
+            [Color::Red, Color::Orange, Color::Yellow, Color::Blank],
+            rules.palette()
+        );
+        assert!(!RuleSet::classic().palette().contains(&Color::Blank));
+    }
+
+    #[test]
+    fn test_duplicate_colors() {
+        let rules = RuleSet::classic();
+        let rng = SeededRng::new(42);
+        for _ in 0..50 {
+            let password = DuplicateColors::Forbidden.random_password(&rules, &rng);
+            assert_eq!(rules.num_slots, password.len());
+            assert!(!has_duplicates(&password), "{password:?}");
+
+            let password = DuplicateColors::Required.random_password(&rules, &rng);
+            assert!(has_duplicates(&password), "{password:?}");
+        }
+
+        // Can't fill 4 slots with 3 colors without repeating one.
+        let few_colors = RuleSet {
+            num_colors: 3,
+            ..RuleSet::classic()
+        };
+        assert_eq!(
+            DuplicateColors::Allowed,
+            DuplicateColors::Forbidden.effective_for(&few_colors)
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! Points for wins, so games with different rules can go in one high score table.
+//!
+//! Harder games multiply the score: more possible passwords (measured in bits, so the classic 1296
+//! passwords are ~10.3 bits), fewer guesses allowed, and hard mode. Assists (see
+//! `FeedbackMode::difficulty_tag`) divide it. Within a game, fewer guesses and a faster time score
+//! more.
+use crate::password::PasswordSource;
+use crate::rules::{DuplicateColors, RuleSet};
+use crate::{format_duration, STORAGE};
+use better_quad::bq::{self, TextAlignment, TextAnchorPoint, TextBackground};
+use better_quad::mq;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const HIGH_SCORES_FILE_NAME: &str = "high_scores";
+const MAX_HIGH_SCORES: usize = 10;
+const HIGH_SCORES_FONT_SIZE: u16 = 22;
+const HIGH_SCORES_PADDING: f32 = 10.0;
+
+const GUESS_POINTS: f32 = 1000.0;
+const TIME_POINTS: f32 = 500.0;
+/// Untimed games get time points for finishing within this long.
+const TIME_POINTS_WINDOW: Duration = Duration::from_secs(5 * 60);
+const HARD_MODE_MULTIPLIER: f32 = 1.25;
+const ASSISTED_MULTIPLIER: f32 = 0.5;
+const CLASSIC_NUM_GUESSES: f32 = 8.0;
+
+/// 1.0 for the classic rules.
+pub(crate) fn difficulty_multiplier(
+    rules: &RuleSet,
+    duplicate_colors: DuplicateColors,
+    hard_mode: bool,
+) -> f32 {
+    let classic_bits = password_bits(&RuleSet::classic(), DuplicateColors::Allowed);
+    let bits = password_bits(rules, duplicate_colors);
+    let hard_mode_multiplier = if hard_mode { HARD_MODE_MULTIPLIER } else { 1.0 };
+    (bits / classic_bits) * (CLASSIC_NUM_GUESSES / rules.num_guesses as f32) * hard_mode_multiplier
+}
+
+/// log2 of the number of possible passwords.
+fn password_bits(rules: &RuleSet, duplicate_colors: DuplicateColors) -> f32 {
+    let num_colors = rules.palette().len() as f64;
+    let all = num_colors.powi(rules.num_slots as i32);
+    let without_duplicates = (0..rules.num_slots)
+        .map(|i| (num_colors - i as f64).max(0.0))
+        .product::<f64>();
+    let num_passwords = match duplicate_colors.effective_for(rules) {
+        DuplicateColors::Allowed => all,
+        DuplicateColors::Forbidden => without_duplicates,
+        DuplicateColors::Required => all - without_duplicates,
+    };
+    num_passwords.max(1.0).log2() as f32
+}
+
+pub(crate) fn score_win(
+    rules: &RuleSet,
+    duplicate_colors: DuplicateColors,
+    hard_mode: bool,
+    assisted: bool,
+    num_guesses_used: usize,
+    time: Duration,
+) -> u32 {
+    // 1 for a first guess win, down to 1/num_guesses for a last guess win.
+    let guess_fraction =
+        (rules.num_guesses + 1).saturating_sub(num_guesses_used) as f32 / rules.num_guesses as f32;
+    let time_window = rules.time_limit().unwrap_or(TIME_POINTS_WINDOW);
+    let time_fraction = 1.0 - (time.as_secs_f32() / time_window.as_secs_f32()).min(1.0);
+
+    let points = GUESS_POINTS * guess_fraction + TIME_POINTS * time_fraction;
+    let assisted_multiplier = if assisted { ASSISTED_MULTIPLIER } else { 1.0 };
+    (points * difficulty_multiplier(rules, duplicate_colors, hard_mode) * assisted_multiplier)
+        .round() as u32
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct HighScore {
+    pub(crate) score: u32,
+    pub(crate) rule_set_name: String,
+    pub(crate) num_guesses: usize,
+    pub(crate) time_millis: u64,
+    /// e.g. "assisted" (see `FeedbackMode::difficulty_tag`).
+    #[serde(default)]
+    pub(crate) difficulty_tag: Option<String>,
+}
+
+/// Best scores first, persisted between runs.
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub(crate) struct HighScores {
+    entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    pub(crate) fn load() -> Self {
+        STORAGE.load_or_default(HIGH_SCORES_FILE_NAME)
+    }
+
+    pub(crate) fn save(&self) {
+        STORAGE.save_or_log(HIGH_SCORES_FILE_NAME, self);
+    }
+
+    /// Returns the 1-based rank if it made the table.
+    pub(crate) fn record(
+        &mut self,
+        password_source: PasswordSource,
+        entry: HighScore,
+    ) -> Option<usize> {
+        // Same as stats: player-specified passwords are for 2-player play.
+        if let PasswordSource::Player = password_source {
+            return None;
+        }
+
+        let rank = self.insert(entry);
+        if rank.is_some() {
+            self.save();
+        }
+        rank
+    }
+
+    fn insert(&mut self, entry: HighScore) -> Option<usize> {
+        // Ties go to whoever got there first.
+        let index = self
+            .entries
+            .iter()
+            .position(|existing| existing.score < entry.score)
+            .unwrap_or(self.entries.len());
+        if index >= MAX_HIGH_SCORES {
+            return None;
+        }
+        self.entries.insert(index, entry);
+        self.entries.truncate(MAX_HIGH_SCORES);
+        Some(index + 1)
+    }
+
+    /// Window centered overlay. `highlight_rank` is the game that just ended, if it made the table.
+    pub(crate) fn draw(&self, highlight_rank: Option<usize>, footer: &str) {
+        let mut text = "High scores\n\n".to_string();
+        if self.entries.is_empty() {
+            text.push_str("No wins yet!\n");
+        }
+        for (i, entry) in self.entries.iter().enumerate() {
+            let marker = if highlight_rank == Some(i + 1) {
+                ">"
+            } else {
+                " "
+            };
+            let difficulty_tag = match &entry.difficulty_tag {
+                Some(tag) => format!(" ({tag})"),
+                None => String::new(),
+            };
+            text.push_str(&format!(
+                "{marker}{: >2}. {: >5}  {} in {}, {}{difficulty_tag}\n",
+                i + 1,
+                entry.score,
+                entry.rule_set_name,
+                entry.num_guesses,
+                format_duration(Duration::from_millis(entry.time_millis)),
+            ));
+        }
+        text.push('\n');
+        text.push_str(footer);
+
+        bq::draw_text(
+            text,
+            TextAlignment::Left,
+            None,
+            HIGH_SCORES_FONT_SIZE,
+            mq::WHITE,
+            TextAnchorPoint::window_centered(),
+            Some(TextBackground {
+                color: mq::Color::new(0.0, 0.0, 0.0, 0.85),
+                x_padding: HIGH_SCORES_PADDING,
+                y_padding: HIGH_SCORES_PADDING,
+            }),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{difficulty_multiplier, score_win, HighScore, HighScores, MAX_HIGH_SCORES};
+    use crate::rules::{DuplicateColors, RuleSet};
+    use std::time::Duration;
+
+    #[test]
+    fn test_score_win() {
+        let classic = RuleSet::classic();
+        let allowed = DuplicateColors::Allowed;
+        assert!((difficulty_multiplier(&classic, allowed, false) - 1.0).abs() < 1e-6);
+
+        // First guess instantly is the max. Last guess after the time window is the min.
+        assert_eq!(
+            1500,
+            score_win(&classic, allowed, false, false, 1, Duration::ZERO)
+        );
+        let slow = Duration::from_secs(600);
+        assert_eq!(125, score_win(&classic, allowed, false, false, 8, slow));
+
+        let quick = Duration::from_secs(60);
+        let score = score_win(&classic, allowed, false, false, 4, quick);
+        assert!(score > score_win(&classic, allowed, false, false, 5, quick));
+        assert!(score > score_win(&classic, allowed, false, false, 4, slow));
+        assert!(score < score_win(&classic, allowed, true, false, 4, quick));
+        // No repeats means fewer possible passwords, so it's easier.
+        assert!(score > score_win(&classic, DuplicateColors::Forbidden, false, false, 4, quick));
+        // Assists halve it
+        assert_eq!(
+            750,
+            score_win(&classic, allowed, false, true, 1, Duration::ZERO)
+        );
+
+        let super_rules = RuleSet {
+            num_slots: 5,
+            num_colors: 8,
+            num_guesses: 10,
+            ..RuleSet::classic()
+        };
+        assert!(difficulty_multiplier(&super_rules, allowed, false) > 1.0);
+    }
+
+    #[test]
+    fn test_high_scores_keep_the_best() {
+        let entry = |score| HighScore {
+            score,
+            rule_set_name: "Classic".to_string(),
+            num_guesses: 4,
+            time_millis: 60_000,
+            difficulty_tag: None,
+        };
+        let mut high_scores = HighScores::default();
+        assert_eq!(Some(1), high_scores.insert(entry(100)));
+        assert_eq!(Some(1), high_scores.insert(entry(300)));
+        assert_eq!(Some(2), high_scores.insert(entry(200)));
+        // Ties go after existing entries
+        assert_eq!(Some(3), high_scores.insert(entry(200)));
+
+        for _ in 0..MAX_HIGH_SCORES {
+            high_scores.insert(entry(500));
+        }
+        assert_eq!(MAX_HIGH_SCORES, high_scores.entries.len());
+        assert_eq!(None, high_scores.insert(entry(400)));
+        assert!(high_scores.entries.iter().all(|entry| entry.score == 500));
+    }
+}
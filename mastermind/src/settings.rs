@@ -0,0 +1,397 @@
+//! User preferences, persisted between runs.
+use crate::rules::DuplicateColors;
+use crate::theme::ThemeName;
+use crate::STORAGE;
+use macroquad::prelude as mq;
+use serde::{Deserialize, Serialize};
+
+const SETTINGS_FILE_NAME: &str = "settings";
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub(crate) struct Settings {
+    pub(crate) color_hotkeys: ColorHotkeys,
+    pub(crate) muted: bool,
+    /// Allows undoing guesses, but games don't count towards stats.
+    pub(crate) casual: bool,
+    /// Guesses have to fit the feedback so far, i.e. could still be the password.
+    pub(crate) hard_mode: bool,
+    pub(crate) duplicate_colors: DuplicateColors,
+    pub(crate) input_mode: InputMode,
+    pub(crate) theme: ThemeName,
+    /// Shows how many passwords still fit the feedback, beside the board.
+    pub(crate) count_candidates: bool,
+    /// Submitting takes a second press, so a guess can't be sent by accident.
+    pub(crate) confirm_submit: bool,
+    pub(crate) feedback_mode: FeedbackMode,
+    /// Feedback pegs left of the guesses, for lefties, so the mouse hand doesn't cover them.
+    pub(crate) mirrored: bool,
+    pub(crate) mouse_buttons: MouseButtons,
+    /// One re-roll of the password per game, before the first guess, for a fresh seed when the
+    /// first doesn't feel right. Re-rolled games still count, and are tallied in stats.
+    pub(crate) free_reroll: bool,
+}
+
+/// Which keys select colors. The i-th key selects the i-th color of the palette.
+#[derive(Serialize, Deserialize, Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) enum ColorHotkeys {
+    #[default]
+    NumberRow,
+    /// For lefties with the mouse in their left hand.
+    Numpad,
+    /// For keeping your left hand on the home row.
+    HomeRow,
+}
+
+/// Which mouse button does what. Placing also picks colors from the palette and drags pegs, and
+/// clearing a slot's button also writes notes on earlier guesses. Eyedropping picks the color of a
+/// placed peg, as if it was picked from the palette.
+///
+/// Taps are always left clicks, so touchscreens need left to place.
+#[derive(Serialize, Deserialize, Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) enum MouseButtons {
+    #[default]
+    Standard,
+    /// For modes that want right-click for something else.
+    MiddleClear,
+    /// For mice held in the left hand, without swapping the buttons system-wide.
+    Swapped,
+}
+
+/// Which comes first when placing a peg: the color, or the slot.
+#[derive(Serialize, Deserialize, Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) enum InputMode {
+    /// Pick a color, then click (or drag to) as many slots as it goes in.
+    #[default]
+    Brush,
+    /// Click a slot, then pick its color. The next slot is selected after, so a whole guess can be
+    /// entered with color keys alone.
+    SlotFirst,
+}
+
+/// How a guess's feedback is given.
+#[derive(Serialize, Deserialize, Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) enum FeedbackMode {
+    #[default]
+    Pegs,
+    /// Also beeps: high for each correct hit, then low for each misplaced one. For players who
+    /// can't easily see the small pegs.
+    PegsAndSound,
+    /// Beeps only, with the pegs hidden until the game is over. A party variant where everyone has
+    /// to keep count by ear.
+    SoundOnly,
+    /// Pegs, plus how many of each guessed color are in the password, on every guess. A common
+    /// house rule hint, which makes games easier, so they're tagged as assisted.
+    ColorCounts,
+}
+
+impl Settings {
+    pub(crate) fn load() -> Self {
+        STORAGE.load_or_default(SETTINGS_FILE_NAME)
+    }
+
+    pub(crate) fn save(&self) {
+        STORAGE.save_or_log(SETTINGS_FILE_NAME, self);
+    }
+}
+
+impl FeedbackMode {
+    pub(crate) fn next(self) -> Self {
+        match self {
+            Self::Pegs => Self::PegsAndSound,
+            Self::PegsAndSound => Self::SoundOnly,
+            Self::SoundOnly => Self::ColorCounts,
+            Self::ColorCounts => Self::Pegs,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Pegs => "pegs",
+            Self::PegsAndSound => "pegs + sound",
+            Self::SoundOnly => "sound only",
+            Self::ColorCounts => "pegs + color counts",
+        }
+    }
+
+    /// Shown with the score of any game it was used in, for modes that change how hard it is.
+    pub(crate) fn difficulty_tag(self) -> Option<&'static str> {
+        match self {
+            Self::Pegs | Self::PegsAndSound | Self::SoundOnly => None,
+            Self::ColorCounts => Some("assisted"),
+        }
+    }
+
+    pub(crate) fn has_sound(self) -> bool {
+        matches!(self, Self::PegsAndSound | Self::SoundOnly)
+    }
+}
+
+impl ColorHotkeys {
+    pub(crate) fn next(self) -> Self {
+        match self {
+            Self::NumberRow => Self::Numpad,
+            Self::Numpad => Self::HomeRow,
+            Self::HomeRow => Self::NumberRow,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::NumberRow => "number row",
+            Self::Numpad => "numpad",
+            Self::HomeRow => "home row",
+        }
+    }
+
+    pub(crate) fn keys(self) -> [mq::KeyCode; 9] {
+        match self {
+            Self::NumberRow => [
+                mq::KeyCode::Key1,
+                mq::KeyCode::Key2,
+                mq::KeyCode::Key3,
+                mq::KeyCode::Key4,
+                mq::KeyCode::Key5,
+                mq::KeyCode::Key6,
+                mq::KeyCode::Key7,
+                mq::KeyCode::Key8,
+                mq::KeyCode::Key9,
+            ],
+            Self::Numpad => [
+                mq::KeyCode::Kp1,
+                mq::KeyCode::Kp2,
+                mq::KeyCode::Kp3,
+                mq::KeyCode::Kp4,
+                mq::KeyCode::Kp5,
+                mq::KeyCode::Kp6,
+                mq::KeyCode::Kp7,
+                mq::KeyCode::Kp8,
+                mq::KeyCode::Kp9,
+            ],
+            Self::HomeRow => [
+                mq::KeyCode::A,
+                mq::KeyCode::S,
+                mq::KeyCode::D,
+                mq::KeyCode::F,
+                mq::KeyCode::G,
+                mq::KeyCode::H,
+                mq::KeyCode::J,
+                mq::KeyCode::K,
+                mq::KeyCode::L,
+            ],
+        }
+    }
+
+    /// The key that selects the color at `color_index`. None past the 9th, which are click-only.
+    pub(crate) fn key(self, color_index: usize) -> Option<mq::KeyCode> {
+        self.keys().get(color_index).copied()
+    }
+
+    /// Short label for the key that selects the color at `color_index`, drawn on pegs. None like
+    /// [`Self::key`].
+    pub(crate) fn label(self, color_index: usize) -> Option<String> {
+        match self {
+            Self::NumberRow | Self::Numpad => self
+                .key(color_index)
+                .map(|_| format!("{}", color_index + 1)),
+            Self::HomeRow => ["A", "S", "D", "F", "G", "H", "J", "K", "L"]
+                .get(color_index)
+                .map(|label| label.to_string()),
+        }
+    }
+
+    /// Extra key for submitting a guess, so you don't have to reach over to the space bar.
+    pub(crate) fn extra_submit_key(self) -> Option<mq::KeyCode> {
+        match self {
+            Self::Numpad => Some(mq::KeyCode::KpEnter),
+            Self::NumberRow | Self::HomeRow => None,
+        }
+    }
+}
+
+impl MouseButtons {
+    pub(crate) fn next(self) -> Self {
+        match self {
+            Self::Standard => Self::MiddleClear,
+            Self::MiddleClear => Self::Swapped,
+            Self::Swapped => Self::Standard,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Standard => "standard",
+            Self::MiddleClear => "middle clears",
+            Self::Swapped => "swapped",
+        }
+    }
+
+    pub(crate) fn place(self) -> mq::MouseButton {
+        match self {
+            Self::Standard | Self::MiddleClear => mq::MouseButton::Left,
+            Self::Swapped => mq::MouseButton::Right,
+        }
+    }
+
+    pub(crate) fn clear(self) -> mq::MouseButton {
+        match self {
+            Self::Standard => mq::MouseButton::Right,
+            Self::MiddleClear => mq::MouseButton::Middle,
+            Self::Swapped => mq::MouseButton::Left,
+        }
+    }
+
+    pub(crate) fn eyedrop(self) -> mq::MouseButton {
+        match self {
+            Self::Standard | Self::Swapped => mq::MouseButton::Middle,
+            Self::MiddleClear => mq::MouseButton::Right,
+        }
+    }
+
+    /// e.g. "left places, right clears, middle eyedrops"
+    pub(crate) fn description(self) -> String {
+        format!(
+            "{} places, {} clears, {} eyedrops",
+            mouse_button_name(self.place()),
+            mouse_button_name(self.clear()),
+            mouse_button_name(self.eyedrop()),
+        )
+    }
+}
+
+/// Lowercase, e.g. "right".
+pub(crate) fn mouse_button_name(button: mq::MouseButton) -> &'static str {
+    match button {
+        mq::MouseButton::Left => "left",
+        mq::MouseButton::Right => "right",
+        mq::MouseButton::Middle => "middle",
+        mq::MouseButton::Unknown => "unknown",
+    }
+}
+
+impl InputMode {
+    pub(crate) fn next(self) -> Self {
+        match self {
+            Self::Brush => Self::SlotFirst,
+            Self::SlotFirst => Self::Brush,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Brush => "brush",
+            Self::SlotFirst => "slot first",
+        }
+    }
+
+    pub(crate) fn description(self) -> &'static str {
+        match self {
+            Self::Brush => "pick a color, then click slots",
+            Self::SlotFirst => "click a slot, then pick a color",
+        }
+    }
+
+    /// Follows "Pick colors with ... below the board." in the help overlay.
+    pub(crate) fn help_hint(self) -> &'static str {
+        match self {
+            Self::Brush => "Then click slots.",
+            Self::SlotFirst => "Click a slot first.",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColorHotkeys, MouseButtons};
+    use crate::{
+        KEY_CONTINUE_MATCH, KEY_COPY_LAST_GUESS, KEY_COPY_SEED, KEY_CYCLE_COLOR_HOTKEYS,
+        KEY_CYCLE_DUPLICATE_COLORS, KEY_CYCLE_FEEDBACK_MODE, KEY_CYCLE_INPUT_MODE,
+        KEY_CYCLE_MOUSE_BUTTONS, KEY_CYCLE_RULE_SET, KEY_CYCLE_THEME, KEY_ENTER_SEED,
+        KEY_EXPORT_GAME, KEY_HOST_LAN_GAME, KEY_JOIN_LAN_GAME, KEY_NEW_PASSWORD,
+        KEY_OPEN_CAMPAIGN_MAP, KEY_PAUSE, KEY_PLAYER_EDIT_PASSWORD, KEY_REPLAY_PASSWORD,
+        KEY_REROLL_PASSWORD, KEY_RESIGN, KEY_REVIEW_NEXT, KEY_REVIEW_PREVIOUS, KEY_START_MATCH,
+        KEY_START_TUTORIAL, KEY_SUBMIT, KEY_TOGGLE_ACHIEVEMENTS, KEY_TOGGLE_CANDIDATE_COUNTER,
+        KEY_TOGGLE_CASUAL, KEY_TOGGLE_COLOR_USAGE, KEY_TOGGLE_CONFIRM_SUBMIT, KEY_TOGGLE_DAILY,
+        KEY_TOGGLE_FREE_REROLL, KEY_TOGGLE_HARD_MODE, KEY_TOGGLE_HEATMAP, KEY_TOGGLE_HELP,
+        KEY_TOGGLE_HIGH_SCORES, KEY_TOGGLE_MIRRORED, KEY_TOGGLE_MUTE, KEY_TOGGLE_NUMBER_OVERLAY,
+        KEY_TOGGLE_PSYCHIC, KEY_TOGGLE_STREAMER_MODE, KEY_UNDO_GUESS,
+    };
+
+    #[test]
+    fn test_color_hotkeys_dont_clash_with_controls() {
+        let control_keys = [
+            KEY_SUBMIT,
+            KEY_REPLAY_PASSWORD,
+            KEY_NEW_PASSWORD,
+            KEY_TOGGLE_NUMBER_OVERLAY,
+            KEY_PLAYER_EDIT_PASSWORD,
+            KEY_COPY_SEED,
+            KEY_TOGGLE_DAILY,
+            KEY_CYCLE_RULE_SET,
+            KEY_CYCLE_COLOR_HOTKEYS,
+            KEY_ENTER_SEED,
+            KEY_TOGGLE_HEATMAP,
+            KEY_TOGGLE_MUTE,
+            KEY_TOGGLE_CASUAL,
+            KEY_UNDO_GUESS,
+            KEY_COPY_LAST_GUESS,
+            KEY_TOGGLE_PSYCHIC,
+            KEY_TOGGLE_HARD_MODE,
+            KEY_CYCLE_DUPLICATE_COLORS,
+            KEY_TOGGLE_HIGH_SCORES,
+            KEY_RESIGN,
+            KEY_CYCLE_INPUT_MODE,
+            KEY_CYCLE_THEME,
+            KEY_TOGGLE_HELP,
+            KEY_REVIEW_PREVIOUS,
+            KEY_REVIEW_NEXT,
+            KEY_START_MATCH,
+            KEY_CONTINUE_MATCH,
+            KEY_TOGGLE_STREAMER_MODE,
+            KEY_HOST_LAN_GAME,
+            KEY_JOIN_LAN_GAME,
+            KEY_TOGGLE_ACHIEVEMENTS,
+            KEY_TOGGLE_CANDIDATE_COUNTER,
+            KEY_TOGGLE_CONFIRM_SUBMIT,
+            KEY_EXPORT_GAME,
+            KEY_CYCLE_FEEDBACK_MODE,
+            KEY_PAUSE,
+            KEY_START_TUTORIAL,
+            KEY_TOGGLE_MIRRORED,
+            KEY_CYCLE_MOUSE_BUTTONS,
+            KEY_TOGGLE_COLOR_USAGE,
+            KEY_OPEN_CAMPAIGN_MAP,
+            KEY_TOGGLE_FREE_REROLL,
+            KEY_REROLL_PASSWORD,
+        ];
+        let mut hotkeys = ColorHotkeys::default();
+        loop {
+            for key in hotkeys.keys().into_iter().chain(hotkeys.extra_submit_key()) {
+                assert!(!control_keys.contains(&key), "{hotkeys:?} uses {key:?}");
+            }
+            hotkeys = hotkeys.next();
+            if hotkeys == ColorHotkeys::default() {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn test_mouse_buttons_are_all_different() {
+        let mut mouse_buttons = MouseButtons::default();
+        loop {
+            let buttons = [
+                mouse_buttons.place(),
+                mouse_buttons.clear(),
+                mouse_buttons.eyedrop(),
+            ];
+            for (i, button) in buttons.iter().enumerate() {
+                assert!(!buttons[..i].contains(button), "{mouse_buttons:?}");
+            }
+            mouse_buttons = mouse_buttons.next();
+            if mouse_buttons == MouseButtons::default() {
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Sound effects, synthesized at startup so there are no audio files to ship.
+//!
+//! Silent when built without the default `audio` feature, e.g. on Linux without ALSA dev libs.
+use better_quad::bq::{self, Note, SoundBank, Timestamp, Waveform};
+use std::time::Duration;
+
+const VOLUME: f32 = 0.3;
+
+const PEG_PLACED_NOTES: &[Note] = &[Note::new(880.0, 40)];
+const GUESS_SUBMITTED_NOTES: &[Note] = &[Note::new(523.25, 60), Note::new(659.25, 80)];
+const INCOMPLETE_GUESS_NOTES: &[Note] = &[
+    Note::new(130.81, 90),
+    Note::new(0.0, 30),
+    Note::new(130.81, 90),
+];
+// C major arpeggio up
+const VICTORY_NOTES: &[Note] = &[
+    Note::new(523.25, 100),
+    Note::new(659.25, 100),
+    Note::new(783.99, 100),
+    Note::new(1046.50, 250),
+];
+// Far apart, so they're easy to tell apart by ear
+const CORRECT_HIT_NOTES: &[Note] = &[Note::new(1318.51, 90)];
+const MISPLACED_HIT_NOTES: &[Note] = &[Note::new(329.63, 90)];
+// Sad trombone, kind of
+const LOSS_NOTES: &[Note] = &[
+    Note::new(392.00, 180),
+    Note::new(369.99, 180),
+    Note::new(349.23, 180),
+    Note::new(329.63, 400),
+];
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub(crate) enum SoundEffect {
+    PegPlaced,
+    GuessSubmitted,
+    /// Tried to submit a row with empty slots.
+    IncompleteGuess,
+    Victory,
+    Loss,
+    /// One beep of a guess's feedback, in sound feedback mode.
+    CorrectHit,
+    MisplacedHit,
+}
+
+/// Between the beeps of a guess's feedback, slow enough to count.
+const FEEDBACK_BEEP_SPACING: Duration = Duration::from_millis(250);
+/// Between the correct and misplaced beeps, so they read as two groups.
+const FEEDBACK_GROUP_SPACING: Duration = Duration::from_millis(500);
+/// After submitting, so the beeps don't run into the submit sound.
+const FEEDBACK_DELAY: Duration = Duration::from_millis(300);
+
+impl SoundEffect {
+    const ALL: [Self; 7] = [
+        Self::PegPlaced,
+        Self::GuessSubmitted,
+        Self::IncompleteGuess,
+        Self::Victory,
+        Self::Loss,
+        Self::CorrectHit,
+        Self::MisplacedHit,
+    ];
+
+    fn tune(self) -> (Waveform, &'static [Note]) {
+        match self {
+            Self::PegPlaced => (Waveform::Sine, PEG_PLACED_NOTES),
+            Self::GuessSubmitted => (Waveform::Triangle, GUESS_SUBMITTED_NOTES),
+            Self::IncompleteGuess => (Waveform::Square, INCOMPLETE_GUESS_NOTES),
+            Self::Victory => (Waveform::Triangle, VICTORY_NOTES),
+            Self::Loss => (Waveform::Square, LOSS_NOTES),
+            Self::CorrectHit => (Waveform::Sine, CORRECT_HIT_NOTES),
+            Self::MisplacedHit => (Waveform::Sine, MISPLACED_HIT_NOTES),
+        }
+    }
+}
+
+/// Feedback beeps still to play, and when.
+#[derive(Default)]
+pub(crate) struct FeedbackBeeps {
+    pending: Vec<(Timestamp, SoundEffect)>,
+}
+
+impl FeedbackBeeps {
+    /// Beeps out a guess's feedback, starting shortly after `now`.
+    pub(crate) fn queue(
+        &mut self,
+        num_correct_hits: usize,
+        num_misplaced_hits: usize,
+        now: Timestamp,
+    ) {
+        self.pending.extend(
+            feedback_beeps(num_correct_hits, num_misplaced_hits)
+                .into_iter()
+                .map(|(offset, effect)| (now + offset, effect)),
+        );
+    }
+
+    /// Call every frame.
+    pub(crate) fn play_due(&mut self, sounds: &SoundBank<SoundEffect>, now: Timestamp) {
+        self.pending.retain(|(at, effect)| {
+            let is_due = now.duration_since(*at).is_some();
+            if is_due {
+                sounds.play(*effect);
+            }
+            !is_due
+        });
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
+
+/// The beeps for a guess's feedback, and how long after submitting each one plays: a high beep per
+/// correct hit, then a low beep per misplaced hit.
+fn feedback_beeps(
+    num_correct_hits: usize,
+    num_misplaced_hits: usize,
+) -> Vec<(Duration, SoundEffect)> {
+    let mut beeps = Vec::with_capacity(num_correct_hits + num_misplaced_hits);
+    let mut offset = FEEDBACK_DELAY;
+    for _ in 0..num_correct_hits {
+        beeps.push((offset, SoundEffect::CorrectHit));
+        offset += FEEDBACK_BEEP_SPACING;
+    }
+    if num_correct_hits > 0 {
+        offset += FEEDBACK_GROUP_SPACING - FEEDBACK_BEEP_SPACING;
+    }
+    for _ in 0..num_misplaced_hits {
+        beeps.push((offset, SoundEffect::MisplacedHit));
+        offset += FEEDBACK_BEEP_SPACING;
+    }
+    beeps
+}
+
+pub(crate) async fn load_sounds() -> SoundBank<SoundEffect> {
+    let mut sounds = SoundBank::new();
+    for effect in SoundEffect::ALL {
+        let (waveform, notes) = effect.tune();
+        sounds
+            .load_bytes(effect, &bq::synth_wav(notes, waveform, VOLUME))
+            .await;
+    }
+    sounds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{feedback_beeps, SoundEffect};
+    use std::time::Duration;
+
+    #[test]
+    fn test_feedback_beeps() {
+        assert_eq!(Vec::<(Duration, SoundEffect)>::new(), feedback_beeps(0, 0));
+        assert_eq!(
+            vec![
+                (Duration::from_millis(300), SoundEffect::CorrectHit),
+                (Duration::from_millis(550), SoundEffect::CorrectHit),
+                (Duration::from_millis(1050), SoundEffect::MisplacedHit),
+            ],
+            feedback_beeps(2, 1)
+        );
+        assert_eq!(
+            vec![(Duration::from_millis(300), SoundEffect::MisplacedHit)],
+            feedback_beeps(0, 1)
+        );
+    }
+}
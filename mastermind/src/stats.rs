@@ -0,0 +1,520 @@
+use crate::password::PasswordSource;
+use crate::rules::RuleSet;
+use crate::{draw_peg, format_duration, Color, CompleteRow, ALL_COLORS, STORAGE};
+use better_quad::bq::{self, TextAlignment, TextAnchorPoint, TextContainer};
+use better_quad::mq;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+const STATS_FILE_NAME: &str = "stats";
+const STATS_FONT_SIZE: u16 = 22;
+const STATS_PADDING: f32 = 10.0;
+const STATS_BAR_FONT_SIZE: u16 = 18;
+const STATS_BAR_HEIGHT: f32 = 16.0;
+const STATS_BAR_SPACING: f32 = 4.0;
+const STATS_BAR_LABEL_WIDTH: f32 = 20.0;
+const STATS_BAR_MIN_WIDTH: f32 = 22.0;
+const COLOR_USAGE_WIDTH: f32 = 460.0;
+const COLOR_USAGE_ROW_HEIGHT: f32 = 34.0;
+const COLOR_USAGE_PEG_RADIUS: f32 = 10.0;
+const COLOR_USAGE_NAME_WIDTH: f32 = 110.0;
+const COLOR_USAGE_BAR_HEIGHT: f32 = 10.0;
+/// Percentage points between how often a color is guessed and how often it's in passwords before
+/// it's called out as a bias.
+const COLOR_BIAS_THRESHOLD: f32 = 3.0;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) enum GameResult {
+    Won {
+        num_guesses: usize,
+    },
+    Lost,
+    /// Gave up before running out of guesses or time.
+    Resigned,
+}
+
+impl GameResult {
+    pub(crate) fn num_guesses_to_win(self) -> Option<usize> {
+        match self {
+            Self::Won { num_guesses } => Some(num_guesses),
+            Self::Lost | Self::Resigned => None,
+        }
+    }
+}
+
+/// Lifetime stats across all games, persisted between runs.
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub(crate) struct GameStats {
+    games_played: u32,
+    games_won: u32,
+    // Also counted in games_played, as losses.
+    games_resigned: u32,
+    // Also counted in games_played, like any other game.
+    games_rerolled: u32,
+    // Index = number of guesses - 1. Sized lazily so changing num_guesses doesn't break old files.
+    guess_count_distribution: Vec<u32>,
+    current_streak: u32,
+    best_streak: u32,
+    // Keyed by rule set name, since times across board sizes aren't comparable.
+    best_win_times_millis: BTreeMap<String, u64>,
+    // Pegs of each color across every guess and every password, keyed by color name.
+    guessed_color_counts: BTreeMap<String, u32>,
+    password_color_counts: BTreeMap<String, u32>,
+}
+
+impl GameStats {
+    pub(crate) fn load() -> Self {
+        STORAGE.load_or_default(STATS_FILE_NAME)
+    }
+
+    pub(crate) fn save(&self) {
+        STORAGE.save_or_log(STATS_FILE_NAME, self);
+    }
+
+    pub(crate) fn record_game(
+        &mut self,
+        password_source: PasswordSource,
+        result: GameResult,
+        rerolled: bool,
+        history: &[CompleteRow],
+        password: &[Color],
+    ) {
+        // Player-specified passwords are for 2-player play, so they'd muddy solo stats.
+        if let PasswordSource::Player = password_source {
+            return;
+        }
+
+        match result {
+            GameResult::Won { num_guesses } => self.record_win(num_guesses),
+            GameResult::Lost => self.record_loss(),
+            GameResult::Resigned => self.record_resignation(),
+        }
+        if rerolled {
+            self.games_rerolled += 1;
+        }
+        self.record_color_usage(history.iter().map(|row| row.guess.as_slice()), password);
+        self.save();
+    }
+
+    fn record_color_usage<'a>(
+        &mut self,
+        guesses: impl IntoIterator<Item = &'a [Color]>,
+        password: &[Color],
+    ) {
+        for color in guesses.into_iter().flatten() {
+            *self
+                .guessed_color_counts
+                .entry(color.name().to_string())
+                .or_default() += 1;
+        }
+        for color in password {
+            *self
+                .password_color_counts
+                .entry(color.name().to_string())
+                .or_default() += 1;
+        }
+    }
+
+    /// `[0, 100]` of all guessed pegs and all password pegs that were `color`, or None before any
+    /// games.
+    fn color_usage_percents(&self, color: Color) -> Option<(f32, f32)> {
+        let percent = |counts: &BTreeMap<String, u32>| {
+            let total: u32 = counts.values().sum();
+            let count = counts.get(color.name()).copied().unwrap_or(0);
+            (total > 0).then(|| 100.0 * count as f32 / total as f32)
+        };
+        Some((
+            percent(&self.guessed_color_counts)?,
+            percent(&self.password_color_counts)?,
+        ))
+    }
+
+    /// Returns true if it's a new best time for the rule set.
+    pub(crate) fn record_win_time(
+        &mut self,
+        password_source: PasswordSource,
+        rule_set_name: &str,
+        win_time: Duration,
+    ) -> bool {
+        if let PasswordSource::Player = password_source {
+            return false;
+        }
+
+        let is_new_best = self.update_best_win_time(rule_set_name, win_time);
+        if is_new_best {
+            self.save();
+        }
+        is_new_best
+    }
+
+    fn update_best_win_time(&mut self, rule_set_name: &str, win_time: Duration) -> bool {
+        let win_time_millis = win_time.as_millis() as u64;
+        match self.best_win_times_millis.get(rule_set_name) {
+            Some(best) if *best <= win_time_millis => false,
+            _ => {
+                self.best_win_times_millis
+                    .insert(rule_set_name.to_string(), win_time_millis);
+                true
+            }
+        }
+    }
+
+    pub(crate) fn best_win_time(&self, rule_set_name: &str) -> Option<Duration> {
+        self.best_win_times_millis
+            .get(rule_set_name)
+            .map(|millis| Duration::from_millis(*millis))
+    }
+
+    fn record_win(&mut self, num_guesses: usize) {
+        self.games_played += 1;
+        self.games_won += 1;
+        self.current_streak += 1;
+        self.best_streak = self.best_streak.max(self.current_streak);
+
+        if self.guess_count_distribution.len() < num_guesses {
+            self.guess_count_distribution.resize(num_guesses, 0);
+        }
+        self.guess_count_distribution[num_guesses - 1] += 1;
+    }
+
+    fn record_loss(&mut self) {
+        self.games_played += 1;
+        self.current_streak = 0;
+    }
+
+    fn record_resignation(&mut self) {
+        self.record_loss();
+        self.games_resigned += 1;
+    }
+
+    pub(crate) fn games_played(&self) -> u32 {
+        self.games_played
+    }
+
+    pub(crate) fn current_streak(&self) -> u32 {
+        self.current_streak
+    }
+
+    /// `[0, 100]`, or None if no games have been played.
+    pub(crate) fn win_percent(&self) -> Option<f32> {
+        if self.games_played == 0 {
+            None
+        } else {
+            Some(100.0 * self.games_won as f32 / self.games_played as f32)
+        }
+    }
+
+    fn num_wins_with_guess_count(&self, num_guesses: usize) -> u32 {
+        self.guess_count_distribution
+            .get(num_guesses - 1)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Draws the stats panel with its top left at (x, y). `highlight_guess_count` is the guess
+    /// count of the game that just ended, if it was a win. Bars are shown for each possible guess
+    /// count of the rule set.
+    pub(crate) fn draw_panel(
+        &self,
+        x: f32,
+        y: f32,
+        width: f32,
+        rules: &RuleSet,
+        highlight_guess_count: Option<usize>,
+    ) {
+        let num_guesses = rules.num_guesses;
+        let win_percent = match self.win_percent() {
+            Some(p) => format!("{p:.0}%"),
+            None => "-".to_string(),
+        };
+        let best_time = match self.best_win_time(&rules.name) {
+            Some(time) => format_duration(time),
+            None => "-".to_string(),
+        };
+        let summary_text = format!(
+            "Played: {}   Win: {win_percent}   Gave up: {}\n\
+            Streak: {}    Best: {}    Re-rolled: {}\n\
+            Best time ({}): {best_time}\nGuess distribution:",
+            self.games_played,
+            self.games_resigned,
+            self.current_streak,
+            self.best_streak,
+            self.games_rerolled,
+            rules.name,
+        );
+
+        let bars_height = (STATS_BAR_HEIGHT + STATS_BAR_SPACING) * num_guesses as f32;
+        let panel_height = summary_text_height(&summary_text) + bars_height + STATS_PADDING * 3.0;
+        mq::draw_rectangle(
+            x,
+            y,
+            width,
+            panel_height,
+            mq::Color::new(0.10, 0.10, 0.10, 0.85),
+        );
+
+        let TextContainer {
+            rect_y,
+            rect_height,
+            ..
+        } = bq::draw_text(
+            summary_text,
+            TextAlignment::Left,
+            None,
+            STATS_FONT_SIZE,
+            mq::WHITE,
+            TextAnchorPoint::TopLeft {
+                x: x + STATS_PADDING,
+                y: y + STATS_PADDING,
+            },
+            None,
+        );
+
+        let max_count = (1..=num_guesses)
+            .map(|n| self.num_wins_with_guess_count(n))
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let bar_x = x + STATS_PADDING + STATS_BAR_LABEL_WIDTH;
+        let max_bar_width = width - STATS_PADDING * 2.0 - STATS_BAR_LABEL_WIDTH;
+        let mut bar_y = rect_y + rect_height + STATS_PADDING;
+        for guess_count in 1..=num_guesses {
+            let count = self.num_wins_with_guess_count(guess_count);
+            let bar_width = STATS_BAR_MIN_WIDTH
+                + (max_bar_width - STATS_BAR_MIN_WIDTH) * (count as f32 / max_count as f32);
+            let bar_color = if highlight_guess_count == Some(guess_count) {
+                mq::DARKGREEN
+            } else {
+                mq::GRAY
+            };
+
+            bq::draw_text(
+                format!("{guess_count}"),
+                TextAlignment::Left,
+                None,
+                STATS_BAR_FONT_SIZE,
+                mq::WHITE,
+                TextAnchorPoint::TopLeft {
+                    x: x + STATS_PADDING,
+                    y: bar_y,
+                },
+                None,
+            );
+            mq::draw_rectangle(bar_x, bar_y, bar_width, STATS_BAR_HEIGHT, bar_color);
+            bq::draw_text(
+                format!("{count}"),
+                TextAlignment::Left,
+                None,
+                STATS_BAR_FONT_SIZE,
+                mq::WHITE,
+                TextAnchorPoint::TopRight {
+                    x: bar_x + bar_width - 3.0,
+                    y: bar_y,
+                },
+                None,
+            );
+
+            bar_y += STATS_BAR_HEIGHT + STATS_BAR_SPACING;
+        }
+    }
+
+    /// Draws how often each color is guessed next to how often it's in the password, centered in
+    /// the window. Covers the colors of `palette` and any others from past games.
+    pub(crate) fn draw_color_usage(&self, palette: &[Color], footer: &str) {
+        let colors: Vec<Color> = ALL_COLORS
+            .iter()
+            .chain([&Color::Blank])
+            .copied()
+            .filter(|color| {
+                palette.contains(color)
+                    || self.guessed_color_counts.contains_key(color.name())
+                    || self.password_color_counts.contains_key(color.name())
+            })
+            .collect();
+        let title = "Color usage: your guesses (top) vs passwords (bottom)";
+
+        let panel_height = STATS_FONT_SIZE as f32 * 2.0
+            + COLOR_USAGE_ROW_HEIGHT * colors.len() as f32
+            + STATS_PADDING * 4.0;
+        let safe_area = bq::safe_area();
+        let x = safe_area.center().x - COLOR_USAGE_WIDTH / 2.0;
+        let y = (safe_area.center().y - panel_height / 2.0).max(safe_area.y);
+        mq::draw_rectangle(
+            x,
+            y,
+            COLOR_USAGE_WIDTH,
+            panel_height,
+            mq::Color::new(0.0, 0.0, 0.0, 0.85),
+        );
+        bq::draw_text(
+            title,
+            TextAlignment::Left,
+            None,
+            STATS_BAR_FONT_SIZE,
+            mq::WHITE,
+            TextAnchorPoint::TopLeft {
+                x: x + STATS_PADDING,
+                y: y + STATS_PADDING,
+            },
+            None,
+        );
+
+        let bar_x = x + STATS_PADDING + COLOR_USAGE_PEG_RADIUS * 3.0 + COLOR_USAGE_NAME_WIDTH;
+        let max_bar_width = x + COLOR_USAGE_WIDTH - STATS_PADDING - bar_x;
+        let mut row_y = y + STATS_PADDING * 2.0 + STATS_FONT_SIZE as f32;
+        for color in colors {
+            draw_peg(
+                x + STATS_PADDING + COLOR_USAGE_PEG_RADIUS,
+                row_y + COLOR_USAGE_ROW_HEIGHT / 2.0,
+                COLOR_USAGE_PEG_RADIUS,
+                color,
+            );
+            let (guessed_percent, password_percent) =
+                self.color_usage_percents(color).unwrap_or((0.0, 0.0));
+            let bias = match guessed_percent - password_percent {
+                d if d >= COLOR_BIAS_THRESHOLD => " (over)",
+                d if d <= -COLOR_BIAS_THRESHOLD => " (under)",
+                _ => "",
+            };
+            bq::draw_text(
+                format!("{}{bias}", color.name()),
+                TextAlignment::Left,
+                None,
+                STATS_BAR_FONT_SIZE,
+                mq::WHITE,
+                TextAnchorPoint::TopLeft {
+                    x: x + STATS_PADDING + COLOR_USAGE_PEG_RADIUS * 3.0,
+                    y: row_y + COLOR_USAGE_ROW_HEIGHT / 2.0 - STATS_BAR_FONT_SIZE as f32 / 2.0,
+                },
+                None,
+            );
+            for (i, (percent, bar_color)) in [
+                (guessed_percent, color.as_mq()),
+                (password_percent, mq::GRAY),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                let bar_y = row_y + STATS_BAR_SPACING + i as f32 * (COLOR_USAGE_BAR_HEIGHT + 4.0);
+                // Room on the end for the number
+                let bar_width = (max_bar_width - 40.0) * percent / 100.0;
+                mq::draw_rectangle(bar_x, bar_y, bar_width, COLOR_USAGE_BAR_HEIGHT, bar_color);
+                bq::draw_text(
+                    format!("{percent:.0}%"),
+                    TextAlignment::Left,
+                    None,
+                    14,
+                    mq::WHITE,
+                    TextAnchorPoint::TopLeft {
+                        x: bar_x + bar_width + 4.0,
+                        y: bar_y - 2.0,
+                    },
+                    None,
+                );
+            }
+            row_y += COLOR_USAGE_ROW_HEIGHT;
+        }
+
+        bq::draw_text(
+            footer,
+            TextAlignment::Left,
+            None,
+            STATS_BAR_FONT_SIZE,
+            mq::WHITE,
+            TextAnchorPoint::TopLeft {
+                x: x + STATS_PADDING,
+                y: row_y + STATS_PADDING,
+            },
+            None,
+        );
+    }
+}
+
+fn summary_text_height(summary_text: &str) -> f32 {
+    // Matches bq::draw_text() line spacing, which is good enough for a background rect.
+    summary_text.lines().count() as f32 * STATS_FONT_SIZE as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GameResult, GameStats};
+    use crate::Color;
+    use std::time::Duration;
+
+    #[test]
+    fn test_streaks_and_distribution() {
+        let mut stats = GameStats::default();
+        assert_eq!(None, stats.win_percent());
+
+        stats.record_win(3);
+        stats.record_win(5);
+        stats.record_loss();
+        stats.record_win(3);
+
+        assert_eq!(4, stats.games_played);
+        assert_eq!(3, stats.games_won);
+        assert_eq!(1, stats.current_streak);
+        assert_eq!(2, stats.best_streak);
+        assert_eq!(vec![0, 0, 2, 0, 1], stats.guess_count_distribution);
+        assert_eq!(Some(75.0), stats.win_percent());
+    }
+
+    #[test]
+    fn test_resignations_count_as_losses() {
+        let mut stats = GameStats::default();
+        stats.record_win(4);
+        stats.record_resignation();
+        stats.record_loss();
+
+        assert_eq!(3, stats.games_played);
+        assert_eq!(1, stats.games_won);
+        assert_eq!(1, stats.games_resigned);
+        assert_eq!(0, stats.current_streak);
+        assert_eq!(None, GameResult::Resigned.num_guesses_to_win());
+    }
+
+    #[test]
+    fn test_best_win_times() {
+        let mut stats = GameStats::default();
+        assert_eq!(None, stats.best_win_time("Classic"));
+
+        assert!(stats.update_best_win_time("Classic", Duration::from_secs(60)));
+        assert!(!stats.update_best_win_time("Classic", Duration::from_secs(90)));
+        assert!(stats.update_best_win_time("Classic", Duration::from_secs(45)));
+        assert!(stats.update_best_win_time("Mini", Duration::from_secs(100)));
+
+        assert_eq!(
+            Some(Duration::from_secs(45)),
+            stats.best_win_time("Classic")
+        );
+        assert_eq!(Some(Duration::from_secs(100)), stats.best_win_time("Mini"));
+    }
+
+    #[test]
+    fn test_color_usage() {
+        let mut stats = GameStats::default();
+        assert_eq!(None, stats.color_usage_percents(Color::Red));
+
+        stats.record_color_usage(
+            [
+                [Color::Red, Color::Red, Color::Blue, Color::Green].as_slice(),
+                [Color::Red, Color::Yellow, Color::Blue, Color::Blue].as_slice(),
+            ],
+            &[Color::Blue, Color::Green, Color::Yellow, Color::Orange],
+        );
+        stats.record_color_usage(
+            [[Color::Red, Color::Red, Color::Red, Color::Red].as_slice()],
+            &[Color::Purple, Color::Purple, Color::Red, Color::Blue],
+        );
+
+        // 7 of 12 guessed pegs, but only 1 of 8 password pegs
+        let (guessed, password) = stats.color_usage_percents(Color::Red).unwrap();
+        assert!((guessed - 100.0 * 7.0 / 12.0).abs() < 0.01);
+        assert_eq!(12.5, password);
+        // Never guessed
+        assert_eq!(Some((0.0, 25.0)), stats.color_usage_percents(Color::Purple));
+        assert_eq!(Some((0.0, 0.0)), stats.color_usage_percents(Color::Pink));
+    }
+}
@@ -0,0 +1,129 @@
+//! Colors of the board and everything around it. Peg colors aren't themed, since they're what the
+//! game is about, and the end game popups keep their own colors to stand out on any theme.
+use macroquad::prelude as mq;
+use serde::{Deserialize, Serialize};
+
+pub(crate) struct Theme {
+    pub(crate) background: mq::Color,
+    pub(crate) board: mq::Color,
+    /// Lines between rows, and between guesses and keys.
+    pub(crate) separator: mq::Color,
+    /// Over the password while it's still secret.
+    pub(crate) password_cover: mq::Color,
+    pub(crate) slot_outline: mq::Color,
+    pub(crate) key_outline: mq::Color,
+    /// Around the row being guessed, and the selected slot.
+    pub(crate) highlight: mq::Color,
+    pub(crate) correct_key: mq::Color,
+    pub(crate) misplaced_key: mq::Color,
+    /// Controls and how to play text.
+    pub(crate) text: mq::Color,
+    pub(crate) text_background: mq::Color,
+}
+
+#[derive(Serialize, Deserialize, Default, Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) enum ThemeName {
+    #[default]
+    ClassicWood,
+    Dark,
+    HighContrast,
+}
+
+const CLASSIC_WOOD: Theme = Theme {
+    background: mq::DARKBROWN,
+    // Between BROWN and BEIGE
+    board: mq::Color::new(0.70, 0.60, 0.46, 1.0),
+    separator: mq::BLACK,
+    password_cover: mq::BLACK,
+    slot_outline: mq::WHITE,
+    key_outline: mq::GOLD,
+    highlight: mq::GOLD,
+    correct_key: mq::WHITE,
+    misplaced_key: mq::Color::new(0.38, 0.38, 0.38, 1.00),
+    text: mq::BLACK,
+    text_background: mq::Color::new(1.0, 1.0, 1.0, 0.7),
+};
+
+const DARK: Theme = Theme {
+    background: mq::Color::new(0.08, 0.08, 0.10, 1.0),
+    board: mq::Color::new(0.20, 0.20, 0.23, 1.0),
+    separator: mq::Color::new(0.05, 0.05, 0.06, 1.0),
+    password_cover: mq::BLACK,
+    slot_outline: mq::Color::new(0.60, 0.60, 0.62, 1.0),
+    // Muted gold
+    key_outline: mq::Color::new(0.55, 0.47, 0.20, 1.0),
+    highlight: mq::GOLD,
+    correct_key: mq::WHITE,
+    misplaced_key: mq::Color::new(0.45, 0.45, 0.45, 1.0),
+    text: mq::Color::new(0.90, 0.90, 0.90, 1.0),
+    text_background: mq::Color::new(0.0, 0.0, 0.0, 0.6),
+};
+
+const HIGH_CONTRAST: Theme = Theme {
+    background: mq::BLACK,
+    board: mq::BLACK,
+    separator: mq::WHITE,
+    password_cover: mq::DARKGRAY,
+    slot_outline: mq::WHITE,
+    key_outline: mq::WHITE,
+    highlight: mq::YELLOW,
+    correct_key: mq::WHITE,
+    misplaced_key: mq::GRAY,
+    text: mq::WHITE,
+    text_background: mq::BLACK,
+};
+
+impl ThemeName {
+    pub(crate) fn next(self) -> Self {
+        match self {
+            Self::ClassicWood => Self::Dark,
+            Self::Dark => Self::HighContrast,
+            Self::HighContrast => Self::ClassicWood,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::ClassicWood => "classic wood",
+            Self::Dark => "dark",
+            Self::HighContrast => "high contrast",
+        }
+    }
+
+    pub(crate) fn theme(self) -> &'static Theme {
+        match self {
+            Self::ClassicWood => &CLASSIC_WOOD,
+            Self::Dark => &DARK,
+            Self::HighContrast => &HIGH_CONTRAST,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ThemeName;
+
+    #[test]
+    fn test_feedback_keys_are_distinguishable() {
+        let mut theme_name = ThemeName::default();
+        loop {
+            let theme = theme_name.theme();
+            for (key, background) in [
+                (theme.correct_key, theme.board),
+                (theme.misplaced_key, theme.board),
+                (theme.correct_key, theme.misplaced_key),
+            ] {
+                let brightness_diff = (brightness(key) - brightness(background)).abs();
+                assert!(brightness_diff > 0.1, "{theme_name:?}");
+            }
+            theme_name = theme_name.next();
+            if theme_name == ThemeName::default() {
+                break;
+            }
+        }
+    }
+
+    fn brightness(color: macroquad::prelude::Color) -> f32 {
+        (color.r + color.g + color.b) / 3.0
+    }
+}
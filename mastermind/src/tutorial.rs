@@ -0,0 +1,80 @@
+//! The step by step tutorial, offered from the help overlay. The steps live in `tutorial.toml` and
+//! are compiled in; this works out which of the actions they expect happened each frame.
+use better_quad::bq::{self, Tutorial};
+
+const TUTORIAL_SCRIPT: &str = include_str!("../tutorial.toml");
+
+pub(crate) fn new_tutorial() -> Tutorial {
+    Tutorial::new(bq::parse_config(TUTORIAL_SCRIPT).expect("built-in tutorial is valid"))
+}
+
+/// The parts of a game the tutorial watches, from before and after a frame's update.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct Progress {
+    /// Filled slots in the working row, 0 if the game's over.
+    pub(crate) num_filled: usize,
+    pub(crate) num_slots: usize,
+    pub(crate) num_guesses: usize,
+}
+
+/// What the player did between `before` and `after`. `color_picked` is whether a color was picked
+/// this frame, which doesn't always change anything (e.g. picking the color already picked).
+pub(crate) fn actions(before: Progress, after: Progress, color_picked: bool) -> Vec<&'static str> {
+    let mut actions = Vec::new();
+    let submitted = after.num_guesses > before.num_guesses;
+    // Picking a color in slot first mode places it straight away
+    let placed = !submitted && after.num_filled > before.num_filled;
+    if color_picked || placed {
+        actions.push("select_color");
+    }
+    if placed {
+        actions.push("place_peg");
+        if after.num_filled == after.num_slots {
+            actions.push("fill_row");
+        }
+    }
+    if submitted {
+        actions.push("submit_guess");
+    }
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{actions, new_tutorial, Progress};
+
+    #[test]
+    fn test_built_in_tutorial() {
+        assert!(!new_tutorial().is_finished());
+    }
+
+    #[test]
+    fn test_actions() {
+        let progress = |num_filled, num_guesses| Progress {
+            num_filled,
+            num_slots: 4,
+            num_guesses,
+        };
+        assert!(actions(progress(0, 0), progress(0, 0), false).is_empty());
+        assert_eq!(
+            vec!["select_color"],
+            actions(progress(0, 0), progress(0, 0), true)
+        );
+        assert_eq!(
+            vec!["select_color", "place_peg"],
+            actions(progress(1, 0), progress(2, 0), false)
+        );
+        assert_eq!(
+            vec!["select_color", "place_peg", "fill_row"],
+            actions(progress(3, 0), progress(4, 0), false)
+        );
+
+        // The row empties when it's submitted
+        assert_eq!(
+            vec!["submit_guess"],
+            actions(progress(4, 0), progress(0, 1), false)
+        );
+        // Clearing a slot isn't anything
+        assert!(actions(progress(2, 0), progress(1, 0), false).is_empty());
+    }
+}
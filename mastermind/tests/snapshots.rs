@@ -0,0 +1,15 @@
+//! Golden-image snapshots of the board. See `bq_snapshot` for how to run and update them.
+use better_quad::bq::{self, Snapshot, SnapshotTolerance};
+use better_quad::StatefulGui;
+use mastermind::MastermindGame;
+
+fn main() {
+    bq::run_snapshot_tests(
+        MastermindGame::main_conf(),
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots"),
+        SnapshotTolerance::default(),
+        vec![Snapshot::new("new_game", || {
+            MastermindGame::with_seed(1234)
+        })],
+    );
+}
@@ -0,0 +1,130 @@
+//! Alert for when one player's share of the total time crosses the preset's threshold, to keep
+//! slow players honest.
+//!
+//! A player is flagged once their share reaches the threshold, and only cleared once it drops
+//! [`HYSTERESIS`] below it, so hovering around the line doesn't set it off over and over.
+use std::time::Duration;
+
+/// How far under the threshold a share has to drop before the alert can go off again.
+const HYSTERESIS: f32 = 0.05;
+/// Shares are all over the place early on, e.g. the first player has 100% of the time.
+const MIN_TOTAL_TIME: Duration = Duration::from_secs(5 * 60);
+
+pub(crate) struct FairnessAlert {
+    /// Fraction of the total time, e.g. 0.4.
+    threshold: f32,
+    /// Per player, whether they're over the threshold.
+    over: Vec<bool>,
+}
+
+impl FairnessAlert {
+    pub(crate) fn new(threshold: f32, num_players: usize) -> Self {
+        Self {
+            threshold,
+            over: vec![false; num_players],
+        }
+    }
+
+    /// Call with each player's total time. Returns whether anyone newly went over the threshold.
+    /// Does nothing if the threshold is at or under an even share, since someone's always there.
+    pub(crate) fn update(&mut self, player_times: &[Duration]) -> bool {
+        let total_time = player_times.iter().sum::<Duration>();
+        let even_share = 1.0 / player_times.len() as f32;
+        if total_time < MIN_TOTAL_TIME || self.threshold <= even_share {
+            return false;
+        }
+
+        let mut newly_over = false;
+        for (over, time) in self.over.iter_mut().zip(player_times) {
+            let share = time.as_secs_f32() / total_time.as_secs_f32();
+            if !*over && share >= self.threshold {
+                *over = true;
+                newly_over = true;
+            } else if *over && share < self.threshold - HYSTERESIS {
+                *over = false;
+            }
+        }
+        newly_over
+    }
+
+    /// For a player joining mid-session, after everyone else.
+    pub(crate) fn add_player(&mut self) {
+        self.over.push(false);
+    }
+
+    pub(crate) fn remove_player(&mut self, index: usize) {
+        if index < self.over.len() {
+            self.over.remove(index);
+        }
+    }
+
+    /// For players changing seats.
+    pub(crate) fn swap_players(&mut self, a: usize, b: usize) {
+        if a < self.over.len() && b < self.over.len() {
+            self.over.swap(a, b);
+        }
+    }
+
+    /// For players moving along in the turn order.
+    pub(crate) fn move_player(&mut self, from: usize, to: usize) {
+        if from < self.over.len() && to < self.over.len() {
+            let over = self.over.remove(from);
+            self.over.insert(to, over);
+        }
+    }
+
+    /// Indices of the players over the threshold.
+    pub(crate) fn players_over(&self) -> impl Iterator<Item = usize> + '_ {
+        self.over
+            .iter()
+            .enumerate()
+            .filter(|(_, over)| **over)
+            .map(|(i, _)| i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FairnessAlert;
+    use std::time::Duration;
+
+    fn minutes(minutes: &[u64]) -> Vec<Duration> {
+        minutes
+            .iter()
+            .map(|minutes| Duration::from_secs(minutes * 60))
+            .collect()
+    }
+
+    #[test]
+    fn test_alert_hysteresis() {
+        let mut alert = FairnessAlert::new(0.4, 3);
+
+        // Too early to tell
+        assert!(!alert.update(&minutes(&[2, 1, 1])));
+        assert_eq!(0, alert.players_over().count());
+
+        // 40%
+        assert!(alert.update(&minutes(&[4, 3, 3])));
+        assert_eq!(vec![0], alert.players_over().collect::<Vec<_>>());
+        // Still over, so no new alert
+        assert!(!alert.update(&minutes(&[5, 3, 3])));
+
+        // Dipping just under the threshold doesn't clear it, so coming back doesn't alert again
+        assert!(!alert.update(&minutes(&[38, 31, 31])));
+        assert_eq!(vec![0], alert.players_over().collect::<Vec<_>>());
+        assert!(!alert.update(&minutes(&[40, 30, 30])));
+
+        // Well under clears it
+        assert!(!alert.update(&minutes(&[30, 35, 35])));
+        assert_eq!(0, alert.players_over().count());
+        assert!(alert.update(&minutes(&[40, 30, 30])));
+    }
+
+    #[test]
+    fn test_no_alert_at_even_share() {
+        // Someone always has at least half of 2 players' time
+        let mut alert = FairnessAlert::new(0.4, 2);
+        assert!(!alert.update(&minutes(&[10, 5])));
+        assert_eq!(0, alert.players_over().count());
+    }
+}
@@ -0,0 +1,265 @@
+//! Rebinding the keys used most during a session, e.g. next player on a foot pedal that sends
+//! Enter, opened with [`KEY_EDIT_KEY_BINDINGS`](crate::KEY_EDIT_KEY_BINDINGS) from preset
+//! selection. Bindings are saved with the rest of the [`Settings`](crate::settings::Settings).
+//!
+//! The other session keys stay put, so they can't be taken.
+use crate::{
+    KEYS_TOGGLE_SIMULTANEOUS_TURN, KEY_CHART_TOGGLE, KEY_CYCLE_TIME_FORMAT, KEY_CYCLE_TURN_LIMIT,
+    KEY_DETAIL_MODE_TOGGLE, KEY_EDIT_PLAYERS, KEY_END_SESSION, KEY_EXPORT_SESSION_FILE,
+    KEY_GROW_SAFE_AREA, KEY_INTERRUPT, KEY_REVERSE_TURN_ORDER, KEY_SAVE_SESSION_SLOT,
+    KEY_SHRINK_SAFE_AREA, KEY_TABLET_DISPLAY_TOGGLE, KEY_TIME_DISPLAY_TOGGLE,
+    KEY_TOGGLE_ALWAYS_ON_TOP, KEY_TURN_TREND_TOGGLE, KEY_TV_DISPLAY_TOGGLE,
+};
+use better_quad::bq::{self, BetterKeyCode, InputAction, InputMap, TextAlignment, TextAnchorPoint};
+use macroquad::prelude as mq;
+
+const KEY_SELECT_PREVIOUS: mq::KeyCode = mq::KeyCode::Up;
+const KEY_SELECT_NEXT: mq::KeyCode = mq::KeyCode::Down;
+const KEY_REBIND: mq::KeyCode = mq::KeyCode::Enter;
+const KEY_RESET: mq::KeyCode = mq::KeyCode::Backspace;
+// Also cancels waiting for a key, so it can't be bound
+const KEY_CLOSE: mq::KeyCode = mq::KeyCode::Escape;
+/// The default [`bq::FrameCaptureConf`] hotkey.
+const KEY_FRAME_CAPTURE: mq::KeyCode = mq::KeyCode::F9;
+
+const SETUP_FONT_SIZE: u16 = 30;
+const SETUP_X: f32 = 20.0;
+const SETUP_Y: f32 = 20.0;
+const ROW_HEIGHT: f32 = 40.0;
+const KEY_COLUMN_X: f32 = 300.0;
+const SELECTED_OUTLINE_THICKNESS: f32 = 3.0;
+const WAITING_COLOR: mq::Color = mq::YELLOW;
+
+/// Session keys that can be rebound.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) enum Action {
+    NextPlayer,
+    Pause,
+    NextPhase,
+    UndoTurnChange,
+    HoldTurn,
+}
+
+impl InputAction for Action {
+    const ALL: &'static [Self] = &[
+        Action::NextPlayer,
+        Action::Pause,
+        Action::NextPhase,
+        Action::UndoTurnChange,
+        Action::HoldTurn,
+    ];
+    const RESERVED_KEYS: &'static [mq::KeyCode] = &[
+        KEY_TIME_DISPLAY_TOGGLE,
+        KEY_DETAIL_MODE_TOGGLE,
+        KEY_TV_DISPLAY_TOGGLE,
+        KEY_TABLET_DISPLAY_TOGGLE,
+        KEY_CHART_TOGGLE,
+        KEY_TURN_TREND_TOGGLE,
+        KEY_CYCLE_TURN_LIMIT,
+        KEY_CYCLE_TIME_FORMAT,
+        KEY_REVERSE_TURN_ORDER,
+        KEY_INTERRUPT,
+        KEY_SHRINK_SAFE_AREA,
+        KEY_GROW_SAFE_AREA,
+        KEY_EDIT_PLAYERS,
+        KEY_END_SESSION,
+        KEY_EXPORT_SESSION_FILE,
+        KEY_SAVE_SESSION_SLOT,
+        KEY_TOGGLE_ALWAYS_ON_TOP,
+        KEY_FRAME_CAPTURE,
+        KEY_CLOSE,
+        KEYS_TOGGLE_SIMULTANEOUS_TURN[0],
+        KEYS_TOGGLE_SIMULTANEOUS_TURN[1],
+        KEYS_TOGGLE_SIMULTANEOUS_TURN[2],
+        KEYS_TOGGLE_SIMULTANEOUS_TURN[3],
+        KEYS_TOGGLE_SIMULTANEOUS_TURN[4],
+        KEYS_TOGGLE_SIMULTANEOUS_TURN[5],
+        KEYS_TOGGLE_SIMULTANEOUS_TURN[6],
+        KEYS_TOGGLE_SIMULTANEOUS_TURN[7],
+        KEYS_TOGGLE_SIMULTANEOUS_TURN[8],
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Action::NextPlayer => "next_player",
+            Action::Pause => "pause",
+            Action::NextPhase => "next_phase",
+            Action::UndoTurnChange => "undo_turn_change",
+            Action::HoldTurn => "hold_turn",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Action::NextPlayer => "Next player",
+            Action::Pause => "Pause",
+            Action::NextPhase => "Next phase",
+            Action::UndoTurnChange => "Undo turn change",
+            Action::HoldTurn => "Hold turn",
+        }
+    }
+
+    fn default_key(self) -> mq::KeyCode {
+        match self {
+            Action::NextPlayer => mq::KeyCode::Space,
+            Action::Pause => mq::KeyCode::P,
+            Action::NextPhase => mq::KeyCode::Enter,
+            Action::UndoTurnChange => mq::KeyCode::U,
+            Action::HoldTurn => mq::KeyCode::K,
+        }
+    }
+}
+
+pub(crate) struct KeyBindingSetup {
+    selected: usize,
+    /// Whether the next key pressed goes to the selected action.
+    waiting_for_key: bool,
+}
+
+pub(crate) enum BindingSetupAction {
+    Rebind { action: Action, key: mq::KeyCode },
+    Reset { action: Action },
+    Close,
+}
+
+impl KeyBindingSetup {
+    pub(crate) fn new() -> Self {
+        Self {
+            selected: 0,
+            waiting_for_key: false,
+        }
+    }
+
+    /// Call every frame while it's open, instead of the rest of the app's input.
+    pub(crate) fn update(&mut self) -> Option<BindingSetupAction> {
+        let action = Action::ALL[self.selected];
+        if self.waiting_for_key {
+            let key = mq::get_keys_pressed().into_iter().next()?;
+            self.waiting_for_key = false;
+            return (key != KEY_CLOSE).then_some(BindingSetupAction::Rebind { action, key });
+        }
+
+        if mq::is_key_pressed(KEY_CLOSE) {
+            return Some(BindingSetupAction::Close);
+        }
+        if mq::is_key_pressed(KEY_SELECT_PREVIOUS) {
+            self.selected = self.selected.saturating_sub(1);
+        } else if mq::is_key_pressed(KEY_SELECT_NEXT) {
+            self.selected = (self.selected + 1).min(Action::ALL.len() - 1);
+        } else if mq::is_key_pressed(KEY_REBIND) {
+            self.waiting_for_key = true;
+        } else if mq::is_key_pressed(KEY_RESET) {
+            return Some(BindingSetupAction::Reset { action });
+        } else if mq::is_mouse_button_pressed(mq::MouseButton::Left) {
+            // Clicking a row rebinds it
+            let mouse_position = mq::Vec2::from(mq::mouse_position());
+            if let Some(i) = (0..Action::ALL.len()).find(|i| row_rect(*i).contains(mouse_position))
+            {
+                self.selected = i;
+                self.waiting_for_key = true;
+            }
+        }
+        None
+    }
+
+    /// Covers the whole window, with the default camera.
+    pub(crate) fn draw(&self, key_bindings: &InputMap<Action>) {
+        mq::clear_background(mq::DARKGRAY);
+        draw_setup_text("Keys", mq::WHITE, SETUP_X, SETUP_Y);
+
+        for (i, action) in Action::ALL.iter().enumerate() {
+            let rect = row_rect(i);
+            let waiting = self.waiting_for_key && i == self.selected;
+            if i == self.selected {
+                let outline_color = if waiting { WAITING_COLOR } else { mq::WHITE };
+                mq::draw_rectangle_lines(
+                    rect.x,
+                    rect.y,
+                    rect.w,
+                    rect.h,
+                    SELECTED_OUTLINE_THICKNESS,
+                    outline_color,
+                );
+            }
+            let text_y = rect.y + (ROW_HEIGHT - SETUP_FONT_SIZE as f32) / 2.0;
+            draw_setup_text(action.label(), mq::WHITE, rect.x + SETUP_X / 2.0, text_y);
+            let (key_text, key_color) = if waiting {
+                ("press a key...".to_string(), WAITING_COLOR)
+            } else if key_bindings.is_default(*action) {
+                (key_bindings.key(*action).to_lowercase(), mq::LIGHTGRAY)
+            } else {
+                (
+                    format!("{} (changed)", key_bindings.key(*action).to_lowercase()),
+                    mq::WHITE,
+                )
+            };
+            draw_setup_text(&key_text, key_color, rect.x + KEY_COLUMN_X, text_y);
+        }
+
+        let help_text = if self.waiting_for_key {
+            format!("[{}] cancel", KEY_CLOSE.to_lowercase())
+        } else {
+            format!(
+                "[{}] / [{}] select\n[{}] or click to change\n[{}] back to default\n[{}] done",
+                KEY_SELECT_PREVIOUS.to_lowercase(),
+                KEY_SELECT_NEXT.to_lowercase(),
+                KEY_REBIND.to_lowercase(),
+                KEY_RESET.to_lowercase(),
+                KEY_CLOSE.to_lowercase(),
+            )
+        };
+        let below_rows = row_rect(Action::ALL.len()).y + ROW_HEIGHT / 2.0;
+        draw_setup_text(&help_text, mq::WHITE, SETUP_X, below_rows);
+    }
+}
+
+fn draw_setup_text(text: &str, color: mq::Color, x: f32, y: f32) {
+    bq::draw_text(
+        text,
+        TextAlignment::Left,
+        None,
+        SETUP_FONT_SIZE,
+        color,
+        TextAnchorPoint::TopLeft { x, y },
+        None,
+    );
+}
+
+fn row_rect(i: usize) -> mq::Rect {
+    mq::Rect::new(
+        SETUP_X,
+        SETUP_Y + ROW_HEIGHT * (i as f32 + 1.0),
+        mq::screen_width() - SETUP_X * 2.0,
+        ROW_HEIGHT,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Action;
+    use crate::KEYS_TOGGLE_SIMULTANEOUS_TURN;
+    use better_quad::bq::{InputAction, InputMap};
+    use macroquad::prelude as mq;
+
+    #[test]
+    fn test_defaults_not_reserved() {
+        for action in Action::ALL {
+            assert!(
+                !Action::RESERVED_KEYS.contains(&action.default_key()),
+                "{action:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_simultaneous_turn_keys_reserved() {
+        let mut key_bindings = InputMap::<Action>::default();
+        for key in KEYS_TOGGLE_SIMULTANEOUS_TURN {
+            assert!(
+                key_bindings.rebind(Action::NextPlayer, key).is_err(),
+                "{key:?}"
+            );
+        }
+        assert_eq!(mq::KeyCode::Space, key_bindings.key(Action::NextPlayer));
+    }
+}
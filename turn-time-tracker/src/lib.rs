@@ -1,15 +1,120 @@
+use crate::fairness::FairnessAlert;
+use crate::key_bindings::{Action, BindingSetupAction, KeyBindingSetup};
+use crate::player_setup::{PlayerSetup, SetupAction};
+use crate::player_text_layout::{fit_name, PlayerRowText, PlayerTextLayout};
+use crate::presets::{next_turn_limit_secs, ClockMode, Preset, SessionRules};
+use crate::report::ReportPeriod;
+use crate::roster_import::ImportedRoster;
+use crate::roster_templates::ROSTER_TEMPLATES;
+use crate::saved_session::{SavedPlayer, SavedSession, SESSION_SLOTS};
+use crate::session_export::SessionExport;
+use crate::session_file::SessionFile;
+use crate::session_log::{SessionLog, SessionRecord, TurnRecord};
+use crate::settings::Settings;
+use crate::sounds::SoundEffect;
+use crate::time_format::TimeFormat;
+use crate::turn_log::{TurnChange, TurnLog};
+use better_quad::bq::{
+    AppConfigDir, AppStorage, Autosave, BetterKeyCode, Confetti, FrameCaptureConf, IdleTracker,
+    InputAction, MonotonicTime, ResultsScreen, Rumble, SafeAreaMargins, SaveSlotBrowser, SeededRng,
+    SlotBrowserAction, SlotInfo, SoundBank, TextAlignment, TextAnchorPoint, TextBackground, Tick,
+    TickClock, ToastKind, Toasts,
+};
+use better_quad::utils::color_animation::gradient_color;
 use better_quad::utils::infinite_iterator::InfiniteIterator;
-use better_quad::{bq::Timestamp, StatefulGui};
+use better_quad::{
+    bq::{self, Timestamp},
+    AppInfo, StatefulGui,
+};
 use macroquad::prelude as mq;
 use std::cmp::max;
-use std::collections::BinaryHeap;
+use std::fs;
 use std::time::Duration;
 
+mod fairness;
+mod key_bindings;
+mod player_setup;
+mod player_text_layout;
+mod presets;
+mod report;
+mod roster_import;
+mod roster_templates;
+mod saved_session;
+mod session_export;
+mod session_file;
+mod session_log;
+mod settings;
+mod simultaneous_turns;
+mod sounds;
+mod tablet_display;
+mod time_format;
+mod turn_log;
+mod tv_display;
+
+const CONFIG_DIR: AppConfigDir = AppConfigDir::new("turn-time-tracker");
+/// Optional, in the config dir. The built-in font only has Latin letters, so names in other
+/// scripts need a font that has them, e.g. Noto Sans CJK.
+const NAME_FONT_FILE_NAME: &str = "names.ttf";
+const STORAGE: AppStorage = AppStorage::new("turn-time-tracker");
+
 // Control consts
-const KEY_NEXT_PLAYER: mq::KeyCode = mq::KeyCode::Space;
-const KEY_PAUSE: mq::KeyCode = mq::KeyCode::P;
 const KEY_TIME_DISPLAY_TOGGLE: mq::KeyCode = mq::KeyCode::H;
 const KEY_DETAIL_MODE_TOGGLE: mq::KeyCode = mq::KeyCode::D;
+const KEY_TV_DISPLAY_TOGGLE: mq::KeyCode = mq::KeyCode::T;
+// Only the current turn, for a tablet in the middle of the table
+const KEY_TABLET_DISPLAY_TOGGLE: mq::KeyCode = mq::KeyCode::G;
+const KEY_CHART_TOGGLE: mq::KeyCode = mq::KeyCode::B;
+const KEY_TURN_TREND_TOGGLE: mq::KeyCode = mq::KeyCode::V;
+const KEY_CYCLE_TURN_LIMIT: mq::KeyCode = mq::KeyCode::L;
+const KEY_REVERSE_TURN_ORDER: mq::KeyCode = mq::KeyCode::R;
+// Time for rules lookups, breaks, etc goes to nobody, without pausing
+const KEY_INTERRUPT: mq::KeyCode = mq::KeyCode::I;
+const KEY_CYCLE_TIME_FORMAT: mq::KeyCode = mq::KeyCode::F;
+// In the TV layout, for TVs that crop the edges
+const KEY_SHRINK_SAFE_AREA: mq::KeyCode = mq::KeyCode::RightBracket;
+const KEY_GROW_SAFE_AREA: mq::KeyCode = mq::KeyCode::LeftBracket;
+const KEY_PASTE_ROSTER: mq::KeyCode = mq::KeyCode::V;
+const KEY_IMPORT_ROSTER_FILE: mq::KeyCode = mq::KeyCode::I;
+const KEY_EDIT_PLAYERS: mq::KeyCode = mq::KeyCode::N;
+const KEY_CONFIRM_ROSTER: mq::KeyCode = mq::KeyCode::Enter;
+const KEY_CANCEL_ROSTER: mq::KeyCode = mq::KeyCode::Escape;
+const KEY_END_SESSION: mq::KeyCode = mq::KeyCode::E;
+const KEY_WEEKLY_REPORT: mq::KeyCode = mq::KeyCode::W;
+const KEY_MONTHLY_REPORT: mq::KeyCode = mq::KeyCode::M;
+const KEY_RESTART_SESSION: mq::KeyCode = mq::KeyCode::R;
+const KEY_BACK_TO_PRESETS: mq::KeyCode = mq::KeyCode::Enter;
+const KEY_SHARE_SUMMARY: mq::KeyCode = mq::KeyCode::C;
+const KEY_EXPORT_SUMMARY: mq::KeyCode = mq::KeyCode::X;
+const KEY_EXPORT_DATA: mq::KeyCode = mq::KeyCode::D;
+const KEY_SWITCH_SUMMARY_TAB: mq::KeyCode = mq::KeyCode::Tab;
+const KEY_RESUME_SESSION: mq::KeyCode = mq::KeyCode::Enter;
+const KEY_DISCARD_SESSION: mq::KeyCode = mq::KeyCode::Escape;
+// To carry on with the session on another machine
+const KEY_EXPORT_SESSION_FILE: mq::KeyCode = mq::KeyCode::S;
+const KEY_IMPORT_SESSION_FILE: mq::KeyCode = mq::KeyCode::O;
+// Next player, pause, and a few others can be rebound (see `key_bindings`)
+const KEY_EDIT_KEY_BINDINGS: mq::KeyCode = mq::KeyCode::K;
+// Named saves of sessions in progress, to keep a few games going at once
+const KEY_SAVE_SESSION_SLOT: mq::KeyCode = mq::KeyCode::F5;
+const KEY_BROWSE_SESSION_SLOTS: mq::KeyCode = mq::KeyCode::L;
+// Named sets of players, for groups that play together often
+const KEY_SAVE_ROSTER_TEMPLATE: mq::KeyCode = mq::KeyCode::S;
+const KEY_BROWSE_ROSTER_TEMPLATES: mq::KeyCode = mq::KeyCode::T;
+// On every screen, to float over a rules PDF or video call
+const KEY_TOGGLE_ALWAYS_ON_TOP: mq::KeyCode = mq::KeyCode::F8;
+const KEYS_SELECT_PRESET: [mq::KeyCode; 9] = [
+    mq::KeyCode::Key1,
+    mq::KeyCode::Key2,
+    mq::KeyCode::Key3,
+    mq::KeyCode::Key4,
+    mq::KeyCode::Key5,
+    mq::KeyCode::Key6,
+    mq::KeyCode::Key7,
+    mq::KeyCode::Key8,
+    mq::KeyCode::Key9,
+];
+// During a session, players 1-9 go at the same time as the current player, or stop
+const KEYS_TOGGLE_SIMULTANEOUS_TURN: [mq::KeyCode; 9] = KEYS_SELECT_PRESET;
 
 // Draw consts
 // TODO:3 dynamic size based on window
@@ -18,130 +123,1984 @@ const PIE_Y: f32 = 300.0;
 const PIE_THICKNESS: f32 = 230.0;
 const PIE_THICKNESS_CURRENT_TURN_MULTIPLIER: f32 = 1.2;
 
-const PAUSED_TEXT_FONT_SIZE: f32 = PLAYER_TEXT_FONT_SIZE;
+// The bar takes the pie's place.
+const BAR_X: f32 = 20.0;
+const BAR_HEIGHT: f32 = 120.0;
+const BAR_HEIGHT_CURRENT_TURN_MULTIPLIER: f32 = 1.3;
+const BAR_OUTLINE_THICKNESS: f32 = 4.0;
+const BAR_LABEL_FONT_SIZE: u16 = 26;
+// So do the turn lengths, a lane each between the top and the paused text.
+const TURN_LENGTHS_Y: f32 = 20.0;
+const TURN_LENGTHS_BOTTOM: f32 = PAUSED_TEXT_Y - PAUSED_TEXT_FONT_SIZE;
+const TURN_LENGTHS_BOX_HEIGHT_FRACTION: f32 = 0.6;
+
+const PAUSED_TEXT_FONT_SIZE: f32 = 40.0;
 const PAUSED_TEXT_X: f32 = 10.0;
 const PAUSED_TEXT_Y: f32 = PIE_Y + PIE_THICKNESS;
 
-const PLAYER_TEXT_FONT_SIZE: f32 = 40.0;
-const PLAYER_TEXT_LINE_BUFFER: f32 = 10.0;
 const PLAYER_TEXT_X: f32 = 10.0;
 const PLAYER_TEXT_Y: f32 = PIE_THICKNESS + PIE_Y + 20.0;
 const PLAYER_RECTANGLE_THICKNESS: f32 = 6.0;
+const PLAYER_NAME_MAX_WIDTH: f32 = 240.0;
+/// Each player's recent turns, as a sparkline at the end of their row.
+const TURN_TREND_WIDTH: f32 = 100.0;
+const TURN_TREND_GAP: f32 = 10.0;
+const TURN_TREND_MAX_TURNS: usize = 20;
+/// How long the current player's row flashes once they go over the turn limit, and how fast.
+const TURN_LIMIT_FLASH_DURATION: Duration = Duration::from_secs(3);
+const TURN_LIMIT_FLASH_PERIOD_SECS: f64 = 0.5;
+const TURN_LIMIT_FLASH_COLOR: mq::Color = mq::Color::new(1.0, 0.0, 0.0, 0.35);
+/// How long the window flashes in the new player's color when their turn starts, fading out, and
+/// how opaque it starts.
+const TURN_START_FLASH_DURATION: Duration = Duration::from_secs(1);
+const TURN_START_FLASH_ALPHA: f32 = 0.6;
+/// Countdown time text fades through these as time runs out, from out of time to full budget.
+const COUNTDOWN_GRADIENT: [mq::Color; 3] = [mq::RED, mq::YELLOW, mq::GREEN];
+/// Players sitting out are drawn this faded.
+const SITTING_OUT_ALPHA: f32 = 0.4;
+/// In countdown mode, the rumble hook fires when the current player gets down to this much time.
+const LOW_TIME_WARNING: Duration = Duration::from_secs(30);
+/// Going this long without a frame means the app wasn't running (e.g. the computer was asleep), so
+/// a running timer pauses rather than count it. Long enough for a backgrounded tab or a minimized
+/// window that's stopped drawing during a long turn.
+const MAX_FRAME_GAP: Duration = Duration::from_secs(10 * 60);
+
+const SESSION_INFO_FONT_SIZE: u16 = 30;
+/// With detailed stats, how many of the latest rounds' totals are listed.
+const MAX_ROUND_TOTALS_SHOWN: usize = 5;
+const SESSION_INFO_PADDING: f32 = 10.0;
+/// Across the bottom of the standard layout, under the session info, to pass the turn without a
+/// keyboard.
+const NEXT_BUTTON_HEIGHT: f32 = 90.0;
+const NEXT_BUTTON_MARGIN: f32 = 10.0;
+const NEXT_BUTTON_PAUSED_COLOR: mq::Color = mq::GRAY;
+
+const PRESET_SELECTION_FONT_SIZE: u16 = 30;
+const PRESET_SELECTION_X: f32 = 20.0;
+const PRESET_SELECTION_Y: f32 = 20.0;
+
+const FAIRNESS_BANNER_FONT_SIZE: u16 = 30;
+const FAIRNESS_BANNER_PADDING: f32 = 10.0;
+const FAIRNESS_BANNER_COLOR: mq::Color = mq::Color::new(0.7, 0.1, 0.1, 0.9);
+
+const IDLE_PAUSE_BANNER_FONT_SIZE: u16 = 40;
+const IDLE_PAUSE_BANNER_PADDING: f32 = 20.0;
+const IDLE_PAUSE_BANNER_COLOR: mq::Color = mq::Color::new(0.1, 0.2, 0.6, 0.95);
+
+const CONFETTI_PIECES: usize = 150;
+
+/// How much the safe area's margins change per key press.
+const SAFE_AREA_MARGIN_STEP: f32 = 0.01;
 
 pub struct TurnTimeTracker {
+    screen: Screen,
+    roster: Vec<(String, mq::Color)>,
     players: InfiniteIterator<Player>,
+    rules: SessionRules,
+    current_phase_index: usize,
+    // Which time round the table it is, from 0. A round ends when play passes the first player.
+    round_index: usize,
     timer: TimerState,
+    // Ticked every frame, to notice when frames stopped coming for a while.
+    frame_clock: TickClock,
+    // When there was last any input, to pause a timer that's been left running.
+    idle: IdleTracker,
+    // Some while paused for that, with how long there'd been no input.
+    idle_paused_after: Option<Duration>,
+    // Some between turns, if the preset has hand-offs.
+    handoff_remaining: Option<Duration>,
+    total_handoff_time: Duration,
+    // While interrupted, time goes to nobody rather than the current player, but the clock runs on.
+    interrupted: bool,
+    total_interrupt_time: Duration,
+    // From pauses that are over, for the session's wall time (see `paused_time`). Only while the
+    // app's open and awake.
+    total_paused_time: Duration,
+    // In draft mode, stops the current turn from passing on by itself.
+    turn_held: bool,
+    // When the current turn went over the turn limit, for flashing their row.
+    turn_limit_exceeded_at: Option<Timestamp>,
+    // When the current player's turn started, for flashing their color.
+    turn_started_at: Option<Timestamp>,
+    turn_log: TurnLog,
+    // Every counted turn so far, for exporting.
+    turns: Vec<TurnRecord>,
+    // Some if the preset has a fairness alert.
+    fairness_alert: Option<FairnessAlert>,
     time_display_mode: TimeDisplayMode,
     text_detail_mode: TextDetailMode,
+    turn_trend_mode: TurnTrendMode,
+    layout_mode: LayoutMode,
+    chart_mode: ChartMode,
+    // Some while adding, renaming, etc players, over the rest of the app.
+    player_setup: Option<PlayerSetup>,
+    key_binding_setup: Option<KeyBindingSetup>,
+    // Some while picking a saved session to load, from preset selection.
+    slot_browser: Option<SaveSlotBrowser>,
+    // Some while picking a roster template, from preset selection.
+    template_browser: Option<SaveSlotBrowser>,
+    session_log: SessionLog,
+    // When to save the session in progress, for resuming it after the app's closed.
+    autosave: Autosave,
+    // The save slot the session in progress was last saved to or loaded from, to save over.
+    session_slot: Option<u32>,
+    toasts: Toasts,
+    sounds: SoundBank<SoundEffect>,
+    settings: Settings,
+    /// For player names, if there's a [`NAME_FONT_FILE_NAME`]. Otherwise the built-in font.
+    name_font: Option<mq::Font>,
+}
+
+enum Screen {
+    /// At launch, if a session was still in progress when the app was last closed, or after
+    /// importing a session file.
+    ResumePrompt {
+        presets: Vec<Preset>,
+        saved: SavedSession,
+        /// Some for a session file, replacing the roster if it's resumed.
+        imported_roster: Option<Vec<(String, mq::Color)>>,
+    },
+    PresetSelection {
+        presets: Vec<Preset>,
+    },
+    /// Confirming an imported roster, before going back to preset selection.
+    RosterPreview {
+        presets: Vec<Preset>,
+        imported: ImportedRoster,
+    },
+    /// Presets are kept for going back to preset selection once the session ends, and the session's
+    /// preset for restarting it.
+    Tracking {
+        presets: Vec<Preset>,
+        preset: Preset,
+    },
+    /// Confetti and awards for the session that just ended, before its summary.
+    Celebration {
+        presets: Vec<Preset>,
+        preset: Preset,
+        session: SessionRecord,
+        turns: Vec<TurnRecord>,
+        confetti: Confetti,
+    },
+    /// Results of the session that just ended, or of every session that day.
+    Summary {
+        presets: Vec<Preset>,
+        preset: Preset,
+        session: SessionRecord,
+        turns: Vec<TurnRecord>,
+        tab: SummaryTab,
+    },
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum SummaryTab {
+    Session,
+    /// Totals across all of the day's sessions, for playing several back to back.
+    Day,
+}
+
+#[derive(Copy, Clone)]
+enum TimerState {
+    /// Since when, for the session's paused time.
+    Paused {
+        since: MonotonicTime,
+    },
+    Running {
+        last_tick: MonotonicTime,
+    },
+}
+
+#[derive(Copy, Clone)]
+enum TextDetailMode {
+    Concise,
+    Detailed,
+}
+
+/// Whether each player's row ends with a sparkline of their recent turns, to spot anyone slowing
+/// down.
+#[derive(Copy, Clone)]
+enum TurnTrendMode {
+    Hidden,
+    Sparklines,
+}
+
+#[derive(Copy, Clone)]
+enum LayoutMode {
+    Standard,
+    /// Just names and times, as big as possible.
+    Tv,
+    /// Only the current player and their turn's time, huge. Tapping anywhere passes the turn.
+    Tablet,
+}
+
+/// How each player's share of the total time is shown, above the text rows.
+#[derive(Copy, Clone)]
+enum ChartMode {
+    Pie,
+    /// Horizontal and stacked, with the shares labeled.
+    Bar,
+    /// Not shares, but how long each player's turns take: a box plot each, with the rows showing
+    /// average and median turns, since totals count against whoever's had more turns.
+    TurnLengths,
+}
+
+#[derive(Copy, Clone)]
+enum TimeDisplayMode {
+    Shown,
+    Hidden,
 }
 
-#[derive(Copy, Clone)]
-enum TimerState {
-    Paused,
-    Running { last_tick: Timestamp },
-}
+impl AppInfo for TurnTimeTracker {
+    const NAME: &'static str = "Tabletop Turn Time Tracker";
+    const DESCRIPTION: &'static str = "Tool to track tabletop game time per player.";
+}
+
+impl StatefulGui for TurnTimeTracker {
+    fn main_conf() -> mq::Conf {
+        mq::Conf {
+            window_title: Self::NAME.to_string(),
+            window_width: (PIE_X + PIE_THICKNESS * PIE_THICKNESS_CURRENT_TURN_MULTIPLIER + 20.0)
+                as i32,
+            window_height: 1000,
+            ..Default::default()
+        }
+    }
+
+    fn frame_capture_conf() -> Option<FrameCaptureConf> {
+        Some(FrameCaptureConf::default())
+    }
+
+    fn update(&mut self, now: Timestamp) {
+        self.toasts.tick(now);
+        let tick_at = MonotonicTime::now();
+        match self.frame_clock.tick(tick_at) {
+            Tick::Elapsed(_) => {}
+            Tick::Gap(gap) => self.pause_for_frame_gap(gap, tick_at, now),
+        }
+        bq::set_window_title(&self.window_title());
+
+        if mq::is_key_pressed(KEY_TOGGLE_ALWAYS_ON_TOP) {
+            self.toggle_always_on_top();
+        }
+
+        // A running timer isn't ticked meanwhile, and catches up once this is closed
+        if let Some(player_setup) = &mut self.player_setup {
+            match player_setup.update(&self.roster) {
+                Some(SetupAction::Edit(edit)) => self.apply_player_edit(edit),
+                Some(SetupAction::ToggleSittingOut { index }) => {
+                    self.toggle_sitting_out(index, now);
+                }
+                Some(SetupAction::Close) => self.player_setup = None,
+                None => {}
+            }
+            return;
+        }
+        if let Some(key_binding_setup) = &mut self.key_binding_setup {
+            match key_binding_setup.update() {
+                Some(BindingSetupAction::Rebind { action, key }) => {
+                    self.rebind_key(action, key);
+                }
+                Some(BindingSetupAction::Reset { action }) => {
+                    if let Some(swapped) = self.settings.key_bindings.reset(action) {
+                        self.toast_swapped_key(swapped);
+                    }
+                    self.settings.save();
+                }
+                Some(BindingSetupAction::Close) => self.key_binding_setup = None,
+                None => {}
+            }
+            return;
+        }
+        if let Some(slot_browser) = &mut self.slot_browser {
+            match slot_browser.update() {
+                Some(SlotBrowserAction::Load(slot)) => self.load_session_slot(slot, now),
+                Some(SlotBrowserAction::Close) => self.slot_browser = None,
+                None => {}
+            }
+            return;
+        }
+        if let Some(template_browser) = &mut self.template_browser {
+            match template_browser.update() {
+                Some(SlotBrowserAction::Load(slot)) => self.load_roster_template(slot),
+                Some(SlotBrowserAction::Close) => self.template_browser = None,
+                None => {}
+            }
+            return;
+        }
+        let can_edit_players = matches!(
+            self.screen,
+            Screen::PresetSelection { .. } | Screen::Tracking { .. }
+        );
+        if can_edit_players && mq::is_key_pressed(KEY_EDIT_PLAYERS) {
+            self.player_setup = Some(PlayerSetup::new());
+            return;
+        }
+
+        match &mut self.screen {
+            Screen::ResumePrompt {
+                presets,
+                saved,
+                imported_roster,
+            } => {
+                // Buttons work the same as their keys
+                let clicked_key = Self::resume_results_screen(
+                    saved,
+                    imported_roster.is_some(),
+                    self.settings.time_format,
+                )
+                .clicked_key();
+                let pressed = |key| mq::is_key_pressed(key) || clicked_key == Some(key);
+                if pressed(KEY_RESUME_SESSION) {
+                    if let Some(roster) = imported_roster.take() {
+                        self.roster = roster;
+                        player_setup::save_roster(&self.roster);
+                    }
+                    let presets = std::mem::take(presets);
+                    let saved = saved.clone();
+                    self.resume_session(saved, presets, now);
+                } else if pressed(KEY_DISCARD_SESSION) {
+                    // An import leaves the session saved on this machine alone
+                    if imported_roster.is_none() {
+                        SavedSession::delete();
+                    }
+                    self.screen = Screen::PresetSelection {
+                        presets: std::mem::take(presets),
+                    };
+                }
+            }
+            Screen::PresetSelection { presets } => {
+                if mq::is_key_pressed(KEY_EDIT_KEY_BINDINGS) {
+                    self.key_binding_setup = Some(KeyBindingSetup::new());
+                    return;
+                }
+                if mq::is_key_pressed(KEY_BROWSE_SESSION_SLOTS) {
+                    self.slot_browser = Some(SaveSlotBrowser::new(SESSION_SLOTS, "Saved sessions"));
+                    return;
+                }
+                if mq::is_key_pressed(KEY_BROWSE_ROSTER_TEMPLATES) {
+                    self.template_browser =
+                        Some(SaveSlotBrowser::new(ROSTER_TEMPLATES, "Player templates"));
+                    return;
+                }
+                if mq::is_key_pressed(KEY_SAVE_ROSTER_TEMPLATE) {
+                    self.save_roster_template(now);
+                    return;
+                }
+                if let Some(preset) = get_preset_from_key_press(presets) {
+                    let preset = preset.clone();
+                    let presets = std::mem::take(presets);
+                    self.start_session(&preset, presets);
+                    return;
+                }
+
+                // Export report if needed
+                let report_period = if mq::is_key_pressed(KEY_WEEKLY_REPORT) {
+                    Some(ReportPeriod::Week)
+                } else if mq::is_key_pressed(KEY_MONTHLY_REPORT) {
+                    Some(ReportPeriod::Month)
+                } else {
+                    None
+                };
+                if let Some(report_period) = report_period {
+                    self.export_report(report_period, now);
+                    return;
+                }
+
+                // Import a session to carry on with if needed
+                if mq::is_key_pressed(KEY_IMPORT_SESSION_FILE) {
+                    match SessionFile::import() {
+                        Ok(file) => {
+                            self.screen = Screen::ResumePrompt {
+                                presets: std::mem::take(presets),
+                                imported_roster: Some(file.roster()),
+                                saved: file.session,
+                            };
+                        }
+                        Err(message) => self.toasts.push(ToastKind::Error, message),
+                    }
+                    return;
+                }
+
+                // Import roster if needed
+                let roster_text = if mq::is_key_pressed(KEY_PASTE_ROSTER) {
+                    mq::miniquad::window::clipboard_get()
+                        .ok_or_else(|| "Nothing to paste".to_string())
+                        .map(Some)
+                } else if mq::is_key_pressed(KEY_IMPORT_ROSTER_FILE) {
+                    roster_import::read_roster_file().map(Some)
+                } else {
+                    Ok(None)
+                };
+                match roster_text {
+                    Ok(Some(roster_text)) => {
+                        let imported = roster_import::parse_roster(&roster_text);
+                        if imported.players.is_empty() {
+                            self.toasts.push(ToastKind::Error, "No players found");
+                        } else {
+                            self.screen = Screen::RosterPreview {
+                                presets: std::mem::take(presets),
+                                imported,
+                            };
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(message) => self.toasts.push(ToastKind::Error, message),
+                }
+            }
+            Screen::RosterPreview { presets, imported } => {
+                if mq::is_key_pressed(KEY_CONFIRM_ROSTER) {
+                    self.roster = std::mem::take(&mut imported.players);
+                    player_setup::save_roster(&self.roster);
+                    self.toasts.push(
+                        ToastKind::Info,
+                        format!("Roster set to {} players", self.roster.len()),
+                    );
+                } else if !mq::is_key_pressed(KEY_CANCEL_ROSTER) {
+                    return;
+                }
+                self.screen = Screen::PresetSelection {
+                    presets: std::mem::take(presets),
+                };
+            }
+            Screen::Tracking { presets, preset } => {
+                if mq::is_key_pressed(KEY_END_SESSION) {
+                    let presets = std::mem::take(presets);
+                    let preset = preset.clone();
+                    self.end_session(presets, preset, now);
+                    return;
+                }
+                if mq::is_key_pressed(KEY_EXPORT_SESSION_FILE) {
+                    self.export_session_file(now);
+                }
+                if mq::is_key_pressed(KEY_SAVE_SESSION_SLOT) {
+                    self.save_session_slot(now);
+                }
+                self.evaluate_state(now);
+                self.autosave(now);
+            }
+            Screen::Celebration {
+                presets,
+                preset,
+                session,
+                turns,
+                ..
+            } => {
+                let any_pressed = !mq::get_keys_pressed().is_empty()
+                    || mq::is_mouse_button_pressed(mq::MouseButton::Left);
+                if any_pressed {
+                    self.screen = Screen::Summary {
+                        presets: std::mem::take(presets),
+                        preset: preset.clone(),
+                        session: session.clone(),
+                        turns: std::mem::take(turns),
+                        tab: SummaryTab::Session,
+                    };
+                }
+            }
+            Screen::Summary {
+                presets,
+                preset,
+                session,
+                turns,
+                tab,
+            } => {
+                // Buttons work the same as their keys
+                let clicked_key = match tab {
+                    SummaryTab::Session => {
+                        Self::summary_results_screen(session, turns, self.settings.time_format)
+                    }
+                    SummaryTab::Day => Self::day_results_screen(
+                        &self.session_log,
+                        session,
+                        self.settings.time_format,
+                    ),
+                }
+                .clicked_key();
+                let pressed = |key| mq::is_key_pressed(key) || clicked_key == Some(key);
+                // Sharing and exporting are for the session
+                let session_pressed = |key| *tab == SummaryTab::Session && pressed(key);
+                if pressed(KEY_SWITCH_SUMMARY_TAB) {
+                    *tab = match tab {
+                        SummaryTab::Session => SummaryTab::Day,
+                        SummaryTab::Day => SummaryTab::Session,
+                    };
+                } else if pressed(KEY_RESTART_SESSION) {
+                    let preset = preset.clone();
+                    let presets = std::mem::take(presets);
+                    self.start_session(&preset, presets);
+                } else if pressed(KEY_BACK_TO_PRESETS) {
+                    self.screen = Screen::PresetSelection {
+                        presets: std::mem::take(presets),
+                    };
+                } else if session_pressed(KEY_SHARE_SUMMARY) {
+                    mq::miniquad::window::clipboard_set(&report::build_session_summary(
+                        session,
+                        self.settings.time_format,
+                    ));
+                    self.toasts.push(ToastKind::Info, "Copied summary");
+                } else if session_pressed(KEY_EXPORT_SUMMARY) {
+                    let nth_of_day = self.session_log.nth_of_day(session);
+                    let file_name = report::session_file_name(session.day_number, nth_of_day);
+                    let summary = report::build_session_summary(session, self.settings.time_format);
+                    match bq::save_export("turn-time-tracker", &file_name, &summary) {
+                        Ok(path) => self.toasts.push(
+                            ToastKind::Info,
+                            format!("Saved summary to {}", path.display()),
+                        ),
+                        Err(message) => self.toasts.push(ToastKind::Error, message),
+                    }
+                } else if session_pressed(KEY_EXPORT_DATA) {
+                    let nth_of_day = self.session_log.nth_of_day(session);
+                    match SessionExport::new(session, turns).save(session.day_number, nth_of_day) {
+                        Ok(path) => self.toasts.push(
+                            ToastKind::Info,
+                            format!(
+                                "Saved JSON and CSV data to {}",
+                                path.parent().unwrap_or(&path).display()
+                            ),
+                        ),
+                        Err(message) => self.toasts.push(ToastKind::Error, message),
+                    }
+                }
+            }
+        }
+    }
+
+    fn draw(&self) {
+        match &self.screen {
+            Screen::ResumePrompt {
+                saved,
+                imported_roster,
+                ..
+            } => {
+                mq::clear_background(mq::DARKGRAY);
+                Self::resume_results_screen(
+                    saved,
+                    imported_roster.is_some(),
+                    self.settings.time_format,
+                )
+                .draw();
+            }
+            Screen::PresetSelection { presets } => self.draw_preset_selection(presets),
+            Screen::RosterPreview { imported, .. } => Self::draw_roster_preview(imported),
+            Screen::Tracking { .. } => {
+                self.draw_state();
+                self.draw_turn_start_flash();
+                self.draw_fairness_banner();
+                self.draw_idle_pause_banner();
+            }
+            Screen::Celebration {
+                session, confetti, ..
+            } => {
+                mq::clear_background(mq::DARKGRAY);
+                Self::podium_results_screen(session, self.settings.time_format).draw();
+                confetti.draw(Timestamp::now());
+            }
+            Screen::Summary {
+                session,
+                turns,
+                tab,
+                ..
+            } => {
+                mq::clear_background(mq::DARKGRAY);
+                match tab {
+                    SummaryTab::Session => {
+                        Self::summary_results_screen(session, turns, self.settings.time_format)
+                            .draw()
+                    }
+                    SummaryTab::Day => Self::day_results_screen(
+                        &self.session_log,
+                        session,
+                        self.settings.time_format,
+                    )
+                    .draw(),
+                };
+            }
+        }
+        if let Some(player_setup) = &self.player_setup {
+            let sitting_out = match self.screen {
+                Screen::Tracking { .. } => {
+                    let (players, _) = self.players.raw();
+                    players.iter().map(|player| player.sitting_out).collect()
+                }
+                _ => Vec::new(),
+            };
+            player_setup.draw(&self.roster, &sitting_out);
+        }
+        if let Some(key_binding_setup) = &self.key_binding_setup {
+            key_binding_setup.draw(&self.settings.key_bindings);
+        }
+        if let Some(slot_browser) = &self.slot_browser {
+            slot_browser.draw();
+        }
+        if let Some(template_browser) = &self.template_browser {
+            template_browser.draw();
+        }
+        self.toasts.draw();
+    }
+
+    fn on_exit(&mut self) {
+        self.save_session(Timestamp::now());
+    }
+}
+
+impl TurnTimeTracker {
+    /// Constructor to make tracker from the players saved the last time they were set up, or a few
+    /// placeholders the first time. The session starts once a preset is selected.
+    pub fn with_saved_players() -> Self {
+        Self::with_roster(player_setup::load_roster().unwrap_or_else(player_setup::default_roster))
+    }
+
+    /// Constructor to make tracker from static player list. The session starts once a preset is
+    /// selected.
+    pub fn with_players(players: Vec<(&'static str, mq::Color)>) -> Self {
+        Self::with_roster(
+            players
+                .into_iter()
+                .map(|(player_name, player_color)| (player_name.to_string(), player_color))
+                .collect(),
+        )
+    }
+
+    /// Paused in a session of the first preset, after a turn of each of `turn_lengths` in order,
+    /// e.g. for reproducible screenshots.
+    pub fn with_players_mid_session(
+        players: Vec<(&'static str, mq::Color)>,
+        turn_lengths: &[Duration],
+    ) -> Self {
+        let mut tracker = Self::with_players(players);
+        let presets = match &mut tracker.screen {
+            Screen::PresetSelection { presets } | Screen::ResumePrompt { presets, .. } => {
+                std::mem::take(presets)
+            }
+            _ => Vec::new(),
+        };
+        let preset = presets
+            .first()
+            .cloned()
+            .expect("there are built-in presets");
+        tracker.start_session(&preset, presets);
+        let now = Timestamp::now();
+        for turn_length in turn_lengths {
+            tracker.tick_timer(*turn_length, now);
+            tracker.next_turn(now);
+        }
+        // Fades out over the first frames, so they'd all differ
+        tracker.turn_started_at = None;
+        tracker
+    }
+
+    fn with_roster(roster: Vec<(String, mq::Color)>) -> Self {
+        let loaded_presets = presets::load_presets();
+        let mut toasts = Toasts::new();
+        for error in loaded_presets.errors {
+            toasts.push(ToastKind::Error, format!("Bad preset {error}"));
+        }
+
+        let presets = loaded_presets.configs;
+        let settings = Settings::load();
+        // Only saved on once it worked, so this is the same build on the same platform
+        if settings.always_on_top && bq::is_always_on_top_supported() {
+            if let Err(message) = bq::set_always_on_top(true) {
+                toasts.push(ToastKind::Error, message);
+            }
+        }
+        let name_font = load_name_font().unwrap_or_else(|message| {
+            toasts.push(ToastKind::Error, message);
+            None
+        });
+        let screen = match SavedSession::load() {
+            Some(saved) => Screen::ResumePrompt {
+                presets,
+                saved,
+                imported_roster: None,
+            },
+            None => Screen::PresetSelection { presets },
+        };
+
+        Self {
+            screen,
+            players: Self::players_from_roster(&roster),
+            roster,
+            rules: SessionRules::default(),
+            current_phase_index: 0,
+            round_index: 0,
+            timer: TimerState::Paused {
+                since: MonotonicTime::now(),
+            },
+            frame_clock: TickClock::new(MonotonicTime::now(), MAX_FRAME_GAP),
+            idle: IdleTracker::new(MonotonicTime::now()),
+            idle_paused_after: None,
+            handoff_remaining: None,
+            total_handoff_time: Duration::ZERO,
+            interrupted: false,
+            total_interrupt_time: Duration::ZERO,
+            total_paused_time: Duration::ZERO,
+            turn_held: false,
+            turn_limit_exceeded_at: None,
+            turn_started_at: None,
+            turn_log: TurnLog::default(),
+            turns: Vec::new(),
+            fairness_alert: None,
+            time_display_mode: TimeDisplayMode::Shown,
+            text_detail_mode: TextDetailMode::Concise,
+            turn_trend_mode: TurnTrendMode::Hidden,
+            layout_mode: LayoutMode::Standard,
+            chart_mode: ChartMode::Pie,
+            player_setup: None,
+            key_binding_setup: None,
+            slot_browser: None,
+            template_browser: None,
+            session_log: SessionLog::load(),
+            autosave: Autosave::new(settings.autosave_interval()),
+            session_slot: None,
+            toasts,
+            sounds: SoundBank::new(),
+            settings,
+            name_font,
+        }
+    }
+
+    /// Loads the sound effects, which are silent until this is called.
+    pub async fn with_sounds(mut self) -> Self {
+        self.sounds = sounds::load_sounds().await;
+        self
+    }
+
+    fn players_from_roster(roster: &[(String, mq::Color)]) -> InfiniteIterator<Player> {
+        InfiniteIterator::from(
+            roster
+                .iter()
+                .map(|(player_name, player_color)| Player::new(player_name, *player_color))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn start_session(&mut self, preset: &Preset, presets: Vec<Preset>) {
+        let num_players = preset
+            .max_players
+            .unwrap_or(self.roster.len())
+            .clamp(1, self.roster.len());
+        self.players = Self::players_from_roster(&self.roster[..num_players]);
+        self.rules = preset.to_rules();
+        self.current_phase_index = 0;
+        self.round_index = 0;
+        self.timer = TimerState::Paused {
+            since: MonotonicTime::now(),
+        };
+        self.idle_paused_after = None;
+        self.handoff_remaining = None;
+        self.total_handoff_time = Duration::ZERO;
+        self.interrupted = false;
+        self.total_interrupt_time = Duration::ZERO;
+        self.total_paused_time = Duration::ZERO;
+        self.turn_held = false;
+        self.turn_limit_exceeded_at = None;
+        self.turn_started_at = None;
+        self.turn_log.clear();
+        self.turns.clear();
+        self.fairness_alert = self
+            .rules
+            .fairness_threshold
+            .map(|threshold| FairnessAlert::new(threshold, num_players));
+        self.autosave.reset();
+        self.session_slot = None;
+        self.screen = Screen::Tracking {
+            presets,
+            preset: preset.clone(),
+        };
+    }
+
+    /// Picks a saved session back up where it was left. If it was running, it carries on from
+    /// now, so the time the app was closed goes to nobody.
+    fn resume_session(&mut self, saved: SavedSession, presets: Vec<Preset>, now: Timestamp) {
+        let players = saved
+            .players
+            .iter()
+            .map(SavedPlayer::to_player)
+            .collect::<Vec<_>>();
+        let num_players = players.len();
+        self.players = InfiniteIterator::from(players);
+        for _ in 0..saved.current_player_index % num_players {
+            self.players.advance();
+        }
+        if saved.reversed {
+            self.players.reverse();
+        }
+        for index in &saved.simultaneous_player_indices {
+            self.players.set_active(*index, true);
+        }
+        self.rules = saved.preset.to_rules();
+        self.current_phase_index = saved.current_phase_index;
+        self.round_index = saved.round_index;
+        self.timer = if saved.paused {
+            TimerState::Paused {
+                since: MonotonicTime::now(),
+            }
+        } else {
+            TimerState::Running {
+                last_tick: MonotonicTime::now(),
+            }
+        };
+        self.idle.input_at(MonotonicTime::now());
+        self.idle_paused_after = None;
+        self.handoff_remaining = saved.handoff_remaining();
+        self.total_handoff_time = saved.total_handoff_time();
+        self.interrupted = saved.interrupted;
+        self.total_interrupt_time = saved.total_interrupt_time();
+        self.total_paused_time = saved.total_paused_time();
+        self.turn_held = saved.turn_held;
+        self.turn_limit_exceeded_at = None;
+        self.turn_started_at = None;
+        self.turn_log.clear();
+        self.turns = saved.turns.clone();
+        self.fairness_alert = self
+            .rules
+            .fairness_threshold
+            .map(|threshold| FairnessAlert::new(threshold, num_players));
+        self.update_fairness_alert();
+        self.autosave.saved(now);
+        self.session_slot = None;
+        self.toasts
+            .push(ToastKind::Info, format!("Resumed {}", saved.preset.name));
+        self.screen = Screen::Tracking {
+            presets,
+            preset: saved.preset,
+        };
+    }
+
+    /// Saved straight away.
+    fn rebind_key(&mut self, action: Action, key: mq::KeyCode) {
+        match self.settings.key_bindings.rebind(action, key) {
+            Ok(swapped) => {
+                if let Some(swapped) = swapped {
+                    self.toast_swapped_key(swapped);
+                }
+                self.settings.save();
+            }
+            Err(message) => self.toasts.push(ToastKind::Error, message),
+        }
+    }
+
+    /// After another action's key was taken, it gets the old one.
+    fn toast_swapped_key(&mut self, swapped: Action) {
+        self.toasts.push(
+            ToastKind::Info,
+            format!(
+                "{} moved to [{}]",
+                swapped.label(),
+                self.settings.key_bindings.key(swapped).to_lowercase()
+            ),
+        );
+    }
+
+    /// Remembered for next time, if it works here.
+    fn toggle_always_on_top(&mut self) {
+        match bq::toggle_always_on_top() {
+            Ok(always_on_top) => {
+                self.settings.always_on_top = always_on_top;
+                self.settings.save();
+                let text = if always_on_top {
+                    "Always on top: on"
+                } else {
+                    "Always on top: off"
+                };
+                self.toasts.push(ToastKind::Info, text);
+            }
+            Err(message) => self.toasts.push(ToastKind::Error, message),
+        }
+    }
+
+    /// Saves the session in progress every so often (see [`Settings::autosave_interval`]), and
+    /// after turn changes and pausing.
+    fn autosave(&mut self, now: Timestamp) {
+        if self.autosave.is_due(now) {
+            self.save_session(now);
+        }
+    }
+
+    /// Does nothing unless a session's in progress.
+    fn save_session(&mut self, now: Timestamp) {
+        if let Some(saved) = self.saved_session(now) {
+            saved.save();
+            self.autosave.saved(now);
+        }
+    }
+
+    /// Into the slot it was last saved to or loaded from, or a new one named after the preset and
+    /// when it started, to rename later.
+    fn save_session_slot(&mut self, now: Timestamp) {
+        let Some(saved) = self.saved_session(now) else {
+            return;
+        };
+        let overwritten = match self.session_slot {
+            Some(id) => SESSION_SLOTS.overwrite(id, &saved, now),
+            None => Ok(None),
+        };
+        let result = match overwritten {
+            Ok(Some(name)) => Ok(name),
+            Ok(None) => {
+                let name = format!("{} ({})", saved.preset.name, bq::format_saved_at(now));
+                SESSION_SLOTS.save_new(&name, &saved, now).map(|id| {
+                    self.session_slot = Some(id);
+                    name
+                })
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(name) => self
+                .toasts
+                .push(ToastKind::Info, format!("Saved to \"{name}\"")),
+            Err(e) => self
+                .toasts
+                .push(ToastKind::Error, format!("Couldn't save the session: {e}")),
+        }
+    }
+
+    /// Picks the session back up like resuming it after a restart, and saves over the slot next
+    /// time.
+    fn load_session_slot(&mut self, slot: SlotInfo, now: Timestamp) {
+        let Screen::PresetSelection { presets } = &mut self.screen else {
+            return;
+        };
+        match SESSION_SLOTS.load::<SavedSession>(slot.id) {
+            Ok(Some(saved)) if !saved.players.is_empty() => {
+                let presets = std::mem::take(presets);
+                self.slot_browser = None;
+                self.resume_session(saved, presets, now);
+                self.session_slot = Some(slot.id);
+            }
+            Ok(_) => self.toasts.push(
+                ToastKind::Error,
+                format!("\"{}\" doesn't have a session in it", slot.name),
+            ),
+            Err(e) => self.toasts.push(
+                ToastKind::Error,
+                format!("Couldn't load \"{}\": {e}", slot.name),
+            ),
+        }
+    }
+
+    /// None unless a session's in progress.
+    fn saved_session(&self, now: Timestamp) -> Option<SavedSession> {
+        let Screen::Tracking { preset, .. } = &self.screen else {
+            return None;
+        };
+        let (players, current_player_index) = self.players.raw();
+        Some(SavedSession {
+            preset: preset.clone(),
+            day_number: now.utc_day_number(),
+            players: players.iter().map(SavedPlayer::new).collect(),
+            current_player_index,
+            current_phase_index: self.current_phase_index,
+            paused: matches!(self.timer, TimerState::Paused { .. }),
+            handoff_remaining_millis: self
+                .handoff_remaining
+                .map(|handoff_remaining| handoff_remaining.as_millis() as u64),
+            total_handoff_millis: self.total_handoff_time.as_millis() as u64,
+            interrupted: self.interrupted,
+            total_interrupt_millis: self.total_interrupt_time.as_millis() as u64,
+            total_paused_millis: self.paused_time().as_millis() as u64,
+            turn_held: self.turn_held,
+            turns: self.turns.clone(),
+            reversed: self.players.is_reversed(),
+            round_index: self.round_index,
+            simultaneous_player_indices: self.players.also_active_indices(),
+        })
+    }
+
+    /// Exports the session in progress with the roster, to import on another machine.
+    fn export_session_file(&mut self, now: Timestamp) {
+        let Some(saved) = self.saved_session(now) else {
+            return;
+        };
+        match SessionFile::new(&self.roster, saved).export(now) {
+            Ok(path) => self.toasts.push(
+                ToastKind::Info,
+                format!("Saved session file to {}", path.display()),
+            ),
+            Err(message) => self.toasts.push(ToastKind::Error, message),
+        }
+    }
+
+    /// Imported is for a session file rather than the session saved on this machine.
+    fn resume_results_screen(
+        saved: &SavedSession,
+        imported: bool,
+        time_format: TimeFormat,
+    ) -> ResultsScreen {
+        let (title, message) = if imported {
+            (
+                "Continue imported session?",
+                "It replaces the players, and any session saved here.",
+            )
+        } else {
+            (
+                "Resume session?",
+                "It was still going when the app was closed.",
+            )
+        };
+        let mut results_screen = ResultsScreen::new(title, mq::DARKGREEN)
+            .stat("Preset", &saved.preset.name)
+            .stat(
+                "Saved",
+                bq::UtcDate::from_day_number(saved.day_number).to_string(),
+            );
+        for (i, player) in saved.players.iter().map(SavedPlayer::to_player).enumerate() {
+            let up = if i == saved.current_player_index {
+                " (up)"
+            } else {
+                ""
+            };
+            results_screen = results_screen.stat(
+                bq::truncate_with_ellipsis(&player.display_name, None, 24, PLAYER_NAME_MAX_WIDTH),
+                format!(
+                    "{}, {} turns{up}",
+                    time_format.format(player.total_time),
+                    player.stats.num_turns(),
+                ),
+            );
+        }
+        results_screen
+            .message(message)
+            .button(KEY_RESUME_SESSION, "Resume")
+            .button(KEY_DISCARD_SESSION, "Discard")
+    }
+
+    /// Logs the session for reports, and celebrates it before showing its summary. If no time was
+    /// tracked, it goes straight back to preset selection instead.
+    fn end_session(&mut self, presets: Vec<Preset>, preset: Preset, now: Timestamp) {
+        SavedSession::delete();
+        let (players, _) = self.players.raw();
+        let any_time_tracked = players.iter().any(|player| !player.total_time.is_zero());
+        if !any_time_tracked {
+            self.screen = Screen::PresetSelection { presets };
+            return;
+        }
+        // Anyone going at the same time is done, so their turns are recorded like the others'
+        self.end_simultaneous_turns();
+        let (players, _) = self.players.raw();
+        let session = SessionRecord::new(
+            &self.rules.preset_name,
+            now.utc_day_number(),
+            self.total_handoff_time,
+            self.total_interrupt_time,
+            self.paused_time(),
+            players,
+        );
+        self.session_log.record(session.clone());
+        // The turn in progress goes in too, if it's long enough to count
+        let mut turns = std::mem::take(&mut self.turns);
+        let current_player = self.players.current();
+        if current_player.stats.current_turn_counts() {
+            turns.push(self.turn_record(current_player));
+        }
+        // Nothing to celebrate without any finished turns
+        if report::podium_awards(&session, self.settings.time_format).is_empty() {
+            self.screen = Screen::Summary {
+                presets,
+                preset,
+                session,
+                turns,
+                tab: SummaryTab::Session,
+            };
+            return;
+        }
+        let colors = players
+            .iter()
+            .map(|player| player.display_color)
+            .collect::<Vec<_>>();
+        let confetti = Confetti::burst(
+            mq::screen_width() / 2.0,
+            mq::screen_height(),
+            &colors,
+            CONFETTI_PIECES,
+            now,
+            &SeededRng::new(bq::random_seed()),
+        );
+        self.screen = Screen::Celebration {
+            presets,
+            preset,
+            session,
+            turns,
+            confetti,
+        };
+    }
+
+    fn podium_results_screen(session: &SessionRecord, time_format: TimeFormat) -> ResultsScreen {
+        let mut results_screen = ResultsScreen::new("Podium", mq::DARKPURPLE);
+        for (award, winner) in report::podium_awards(session, time_format) {
+            results_screen = results_screen.stat(award, winner);
+        }
+        results_screen.message("Press any key for the summary.")
+    }
+
+    fn summary_results_screen(
+        session: &SessionRecord,
+        turns: &[TurnRecord],
+        time_format: TimeFormat,
+    ) -> ResultsScreen {
+        let players_millis = session
+            .players
+            .iter()
+            .map(|player| player.total_millis)
+            .sum::<u64>();
+        let mut results_screen =
+            ResultsScreen::new(format!("{} is over", session.preset_name), mq::DARKBLUE)
+                .stat("Total time", time_format.format(session.total_time()))
+                .stat(
+                    "Wall time",
+                    format!(
+                        "{} ({} paused)",
+                        time_format.format(session.wall_time()),
+                        time_format.format(Duration::from_millis(session.paused_millis)),
+                    ),
+                );
+        // Ranked by time, with each player's average and longest turn underneath
+        let mut ranked = session.players.iter().collect::<Vec<_>>();
+        ranked.sort_by_key(|player| std::cmp::Reverse(player.total_millis));
+        for (rank, player) in ranked.into_iter().enumerate() {
+            let total_time = Duration::from_millis(player.total_millis);
+            let longest_turn = turns
+                .iter()
+                .filter(|turn| turn.player_name == player.name)
+                .map(|turn| Duration::from_millis(turn.duration_millis))
+                .max();
+            results_screen = results_screen
+                .stat(
+                    format!(
+                        "{}. {}",
+                        rank + 1,
+                        bq::truncate_with_ellipsis(&player.name, None, 24, PLAYER_NAME_MAX_WIDTH)
+                    ),
+                    format!(
+                        "{} ({:.0}%), {} turns",
+                        time_format.format(total_time),
+                        report::share_percent(player.total_millis, players_millis),
+                        player.num_turns,
+                    ),
+                )
+                .stat(
+                    "",
+                    format!(
+                        "avg {}, longest {}",
+                        format_duration_stats(
+                            (player.num_turns > 0).then(|| total_time / player.num_turns as u32)
+                        ),
+                        format_duration_stats(longest_turn),
+                    ),
+                );
+        }
+        if let Some(longest) = turns.iter().max_by_key(|turn| turn.duration_millis) {
+            results_screen = results_screen.stat(
+                "Longest turn",
+                format!(
+                    "{}, {}",
+                    bq::truncate_with_ellipsis(
+                        &longest.player_name,
+                        None,
+                        24,
+                        PLAYER_NAME_MAX_WIDTH
+                    ),
+                    time_format.format(Duration::from_millis(longest.duration_millis)),
+                ),
+            );
+        }
+        if session.handoff_millis > 0 {
+            results_screen = results_screen.stat(
+                "Hand-offs",
+                time_format.format(Duration::from_millis(session.handoff_millis)),
+            );
+        }
+        if session.interrupt_millis > 0 {
+            results_screen = results_screen.stat(
+                "Interruptions",
+                time_format.format(Duration::from_millis(session.interrupt_millis)),
+            );
+        }
+        results_screen
+            .message("Saved for weekly and monthly reports.")
+            .button(KEY_RESTART_SESSION, "Next game")
+            .button(KEY_BACK_TO_PRESETS, "Menu")
+            .button(KEY_SWITCH_SUMMARY_TAB, "Today")
+            .button(KEY_SHARE_SUMMARY, "Share")
+            .button(KEY_EXPORT_SUMMARY, "Export")
+            .button(KEY_EXPORT_DATA, "Export data")
+    }
+
+    /// Totals across every session logged on the same day as `session`, including it.
+    fn day_results_screen(
+        session_log: &SessionLog,
+        session: &SessionRecord,
+        time_format: TimeFormat,
+    ) -> ResultsScreen {
+        let sessions = report::sessions_on_day(&session_log.sessions, session.day_number);
+        let total_time = sessions
+            .iter()
+            .map(|session| session.total_time())
+            .sum::<Duration>();
+        let players = report::player_totals(&sessions);
+        let players_millis = players
+            .iter()
+            .map(|(_, totals)| totals.total_time.as_millis() as u64)
+            .sum::<u64>();
+        let game_names = sessions
+            .iter()
+            .map(|session| session.preset_name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut results_screen = ResultsScreen::new(
+            format!(
+                "Today: {} game{}",
+                sessions.len(),
+                if sessions.len() == 1 { "" } else { "s" }
+            ),
+            mq::DARKBLUE,
+        )
+        .stat(
+            "Games",
+            bq::truncate_with_ellipsis(&game_names, None, 24, PLAYER_NAME_MAX_WIDTH * 1.5),
+        )
+        .stat("Total time", time_format.format(total_time));
+        for (name, totals) in players {
+            let total_millis = totals.total_time.as_millis() as u64;
+            results_screen = results_screen.stat(
+                bq::truncate_with_ellipsis(name, None, 24, PLAYER_NAME_MAX_WIDTH),
+                format!(
+                    "{} ({:.0}%), {} turns in {}",
+                    time_format.format(totals.total_time),
+                    report::share_percent(total_millis, players_millis),
+                    totals.num_turns,
+                    match totals.num_sessions {
+                        1 => "1 game".to_string(),
+                        n => format!("{n} games"),
+                    },
+                ),
+            );
+        }
+        results_screen
+            .message("Next game keeps the same players, with fresh clocks.")
+            .button(KEY_RESTART_SESSION, "Next game")
+            .button(KEY_BACK_TO_PRESETS, "Menu")
+            .button(KEY_SWITCH_SUMMARY_TAB, "This game")
+    }
+
+    fn export_report(&mut self, period: ReportPeriod, now: Timestamp) {
+        let today = now.utc_day_number();
+        let report = report::build_report(
+            &self.session_log.sessions,
+            period,
+            today,
+            self.settings.time_format,
+        );
+        match bq::save_export("turn-time-tracker", &period.file_name(today), &report) {
+            Ok(path) => self.toasts.push(
+                ToastKind::Info,
+                format!("Saved {} report to {}", period.name(), path.display()),
+            ),
+            Err(message) => self.toasts.push(ToastKind::Error, message),
+        }
+    }
+
+    fn evaluate_state(&mut self, now: Timestamp) {
+        self.idle.update(MonotonicTime::now());
+
+        // Toggle time display if needed
+        if mq::is_key_pressed(KEY_TIME_DISPLAY_TOGGLE) {
+            self.time_display_mode = match self.time_display_mode {
+                TimeDisplayMode::Shown => TimeDisplayMode::Hidden,
+                TimeDisplayMode::Hidden => TimeDisplayMode::Shown,
+            };
+        }
+
+        // Toggle detail mode if needed
+        if mq::is_key_pressed(KEY_DETAIL_MODE_TOGGLE) {
+            self.text_detail_mode = match self.text_detail_mode {
+                TextDetailMode::Concise => TextDetailMode::Detailed,
+                TextDetailMode::Detailed => TextDetailMode::Concise,
+            };
+        }
+
+        // Toggle turn trends if needed
+        if mq::is_key_pressed(KEY_TURN_TREND_TOGGLE) {
+            self.turn_trend_mode = match self.turn_trend_mode {
+                TurnTrendMode::Hidden => TurnTrendMode::Sparklines,
+                TurnTrendMode::Sparklines => TurnTrendMode::Hidden,
+            };
+        }
+
+        // Cycle pie/bar/turn lengths chart if needed
+        if mq::is_key_pressed(KEY_CHART_TOGGLE) {
+            self.chart_mode = match self.chart_mode {
+                ChartMode::Pie => ChartMode::Bar,
+                ChartMode::Bar => ChartMode::TurnLengths,
+                ChartMode::TurnLengths => ChartMode::Pie,
+            };
+        }
+
+        // Toggle TV layout if needed
+        if mq::is_key_pressed(KEY_TV_DISPLAY_TOGGLE) {
+            self.layout_mode = match self.layout_mode {
+                LayoutMode::Standard | LayoutMode::Tablet => LayoutMode::Tv,
+                LayoutMode::Tv => LayoutMode::Standard,
+            };
+        }
+
+        // Toggle tablet layout if needed
+        if mq::is_key_pressed(KEY_TABLET_DISPLAY_TOGGLE) {
+            self.layout_mode = match self.layout_mode {
+                LayoutMode::Standard | LayoutMode::Tv => LayoutMode::Tablet,
+                LayoutMode::Tablet => LayoutMode::Standard,
+            };
+        }
+
+        // Adjust the TV's safe area if needed
+        if let LayoutMode::Tv = self.layout_mode {
+            let step = if mq::is_key_pressed(KEY_SHRINK_SAFE_AREA) {
+                Some(SAFE_AREA_MARGIN_STEP)
+            } else if mq::is_key_pressed(KEY_GROW_SAFE_AREA) {
+                Some(-SAFE_AREA_MARGIN_STEP)
+            } else {
+                None
+            };
+            if let Some(step) = step {
+                let margins = bq::safe_area_margins();
+                bq::set_safe_area_margins(SafeAreaMargins {
+                    horizontal: margins.horizontal + step,
+                    vertical: margins.vertical + step,
+                });
+                let margins = bq::safe_area_margins();
+                self.toasts.push(
+                    ToastKind::Info,
+                    format!(
+                        "TV margins: {:.0}% x {:.0}%",
+                        margins.horizontal * 100.0,
+                        margins.vertical * 100.0,
+                    ),
+                );
+            }
+        }
+
+        // Advance phase if needed. The last phase sticks until the next player's turn.
+        if self.settings.key_bindings.is_pressed(Action::NextPhase)
+            && self.current_phase_index + 1 < self.rules.phases.len()
+        {
+            self.current_phase_index += 1;
+        }
+
+        // Undo the last turn change if needed
+        if self
+            .settings
+            .key_bindings
+            .is_pressed(Action::UndoTurnChange)
+        {
+            self.undo_turn_change(now);
+        }
+
+        // Change the turn limit if needed
+        if mq::is_key_pressed(KEY_CYCLE_TURN_LIMIT) {
+            self.cycle_turn_limit();
+        }
+
+        // Reverse the turn order if needed. Undo would go the wrong way round, so it's reset.
+        if mq::is_key_pressed(KEY_REVERSE_TURN_ORDER) {
+            self.players.reverse();
+            self.turn_log.clear();
+            let text = if self.players.is_reversed() {
+                "Turn order reversed"
+            } else {
+                "Turn order back to normal"
+            };
+            self.toasts.push(ToastKind::Info, text);
+        }
+
+        // Interrupt, or go back to the current player, if needed
+        if mq::is_key_pressed(KEY_INTERRUPT) {
+            self.toggle_interrupt();
+        }
+
+        // Change how times are shown if needed
+        if mq::is_key_pressed(KEY_CYCLE_TIME_FORMAT) {
+            self.cycle_time_format();
+        }
+
+        // Start or end someone's turn alongside the current player's if needed
+        for (index, key) in KEYS_TOGGLE_SIMULTANEOUS_TURN.into_iter().enumerate() {
+            if mq::is_key_pressed(key) {
+                self.toggle_simultaneous_turn(index);
+            }
+        }
+
+        // Hold or release the turn if needed
+        if self.settings.key_bindings.is_pressed(Action::HoldTurn)
+            && self.rules.auto_advance.is_some()
+        {
+            self.turn_held = !self.turn_held;
+        }
+
+        match &mut self.timer {
+            TimerState::Paused { .. } => {
+                // Check for unpause
+                if self.settings.key_bindings.is_pressed(Action::Pause)
+                    || self.tablet_tapped()
+                    || self.next_button_clicked()
+                {
+                    self.idle_paused_after = None;
+                    self.unpause(MonotonicTime::now());
+                    self.autosave.request();
+                } else if let Some(index) = self.clicked_player_row() {
+                    self.jump_to_player(index, now);
+                }
+            }
+            TimerState::Running { ref mut last_tick } => {
+                // Check for pause
+                if self.settings.key_bindings.is_pressed(Action::Pause) {
+                    self.pause(MonotonicTime::now());
+                    self.autosave.request();
+                    return;
+                }
+
+                let tick_at = MonotonicTime::now();
+                let elapsed_tick_time = tick_at.duration_since(*last_tick);
+                *last_tick = tick_at;
+                self.tick_timer(elapsed_tick_time, now);
+
+                // Pause if it's been left running
+                let idle_for = self.idle.idle_for(tick_at);
+                if self
+                    .settings
+                    .idle_pause_after()
+                    .is_some_and(|idle_pause_after| idle_for >= idle_pause_after)
+                {
+                    self.pause(tick_at);
+                    self.idle_paused_after = Some(idle_for);
+                    self.autosave.request();
+                    return;
+                }
+
+                // Change current player if needed. Do this AFTER ticking current player so previous
+                // player is attributed the time until we process the player change.
+                // During a hand-off, the next player is already up, so this skips the rest of it.
+                if let Some(index) = self.clicked_player_row() {
+                    self.jump_to_player(index, now);
+                } else if self.settings.key_bindings.is_pressed(Action::NextPlayer)
+                    || self.tablet_tapped()
+                    || self.next_button_clicked()
+                {
+                    if self.handoff_remaining.is_some() {
+                        self.handoff_remaining = None;
+                    } else if self.simultaneous_turns_going() {
+                        self.end_simultaneous_turns();
+                        let text = format!(
+                            "Back to one at a time, {}'s turn",
+                            self.players.current().display_name
+                        );
+                        self.toasts.push(ToastKind::Info, text);
+                        self.autosave.request();
+                    } else {
+                        self.next_turn(now);
+                    }
+                } else if self.auto_advance_remaining().is_some_and(|remaining| {
+                    remaining.is_zero() && !self.turn_held && self.handoff_remaining.is_none()
+                }) {
+                    self.next_turn(now);
+                }
+            }
+        }
+    }
+
+    /// Ticks the current player, and anyone going at the same time. Hand-off time goes to nobody,
+    /// and whatever's left of the tick after the hand-off ends goes to the players. While
+    /// interrupted, it all goes to nobody.
+    fn tick_timer(&mut self, elapsed_tick_time: Duration, now: Timestamp) {
+        if self.interrupted {
+            self.total_interrupt_time += elapsed_tick_time;
+            return;
+        }
+        let player_tick_time = match self.handoff_remaining {
+            None => elapsed_tick_time,
+            Some(handoff_remaining) => {
+                let handoff_tick_time = elapsed_tick_time.min(handoff_remaining);
+                self.total_handoff_time += handoff_tick_time;
+                self.handoff_remaining = Some(handoff_remaining - handoff_tick_time)
+                    .filter(|remaining| !remaining.is_zero());
+                elapsed_tick_time - handoff_tick_time
+            }
+        };
+        for index in self.players.active_indices() {
+            if let Some(player) = self.players.get_mut(index) {
+                player.tick_frame(player_tick_time, self.round_index);
+            }
+        }
+
+        self.update_fairness_alert();
+        self.check_low_time(player_tick_time);
+        self.check_flag_fall();
+        self.check_turn_limit(player_tick_time, now);
+    }
+
+    /// During a session, whose turn it is and for how long, e.g. "Tabletop Turn Time Tracker —
+    /// Leo 02:31", so it's in the taskbar while the window's behind others.
+    fn window_title(&self) -> String {
+        if !matches!(self.screen, Screen::Tracking { .. }) {
+            return Self::NAME.to_string();
+        }
+        let player = self.players.current();
+        let paused_suffix = match self.timer {
+            TimerState::Paused { .. } => " (paused)",
+            TimerState::Running { .. } => "",
+        };
+        format!(
+            "{} — {} {}{paused_suffix}",
+            Self::NAME,
+            player.display_name,
+            format_duration_stats(Some(player.stats.current_turn_duration)),
+        )
+    }
+
+    /// Stops the clock, with the session's paused time counting from `at`.
+    fn pause(&mut self, at: MonotonicTime) {
+        self.timer = TimerState::Paused { since: at };
+    }
+
+    /// Starts the clock again from `at`, adding the pause to the session's paused time.
+    fn unpause(&mut self, at: MonotonicTime) {
+        if let TimerState::Paused { since } = self.timer {
+            self.total_paused_time += at.duration_since(since);
+        }
+        self.timer = TimerState::Running { last_tick: at };
+    }
+
+    /// How long the session's been paused in total, including the pause it's in, if any.
+    fn paused_time(&self) -> Duration {
+        match self.timer {
+            TimerState::Paused { since } => {
+                self.total_paused_time + MonotonicTime::now().duration_since(since)
+            }
+            TimerState::Running { .. } => self.total_paused_time,
+        }
+    }
+
+    /// Everyone's time, plus hand-offs and interruptions: the session's time without pauses.
+    fn active_time(&self) -> Duration {
+        let (players, _) = self.players.raw();
+        players
+            .iter()
+            .map(|player| player.total_time)
+            .sum::<Duration>()
+            + self.total_handoff_time
+            + self.total_interrupt_time
+    }
+
+    /// After [`MAX_FRAME_GAP`] without a frame, a running timer gets the time up to the last frame,
+    /// and is paused so the gap isn't counted. A paused one doesn't count it as paused time either.
+    fn pause_for_frame_gap(&mut self, gap: Duration, tick_at: MonotonicTime, now: Timestamp) {
+        let last_tick = match self.timer {
+            TimerState::Paused { since } => {
+                self.timer = TimerState::Paused { since: since + gap };
+                return;
+            }
+            TimerState::Running { last_tick } => last_tick,
+        };
+        if !matches!(self.screen, Screen::Tracking { .. }) {
+            return;
+        }
+        let elapsed_before_gap = tick_at.duration_since(last_tick).saturating_sub(gap);
+        self.tick_timer(elapsed_before_gap, now);
+        self.pause(tick_at);
+        self.toasts.push(
+            ToastKind::Info,
+            format!(
+                "Paused after {} away (asleep?). That time wasn't counted.",
+                self.settings.time_format.format(gap)
+            ),
+        );
+    }
+
+    fn next_turn(&mut self, now: Timestamp) {
+        self.end_turn(now);
+        if self.players.advance_skipping(|player| player.sitting_out) {
+            self.round_index += 1;
+        }
+        self.start_turn(now);
+    }
+
+    /// Out of turn order, e.g. for an out-of-turn action, or to fix a mistake. The time so far is
+    /// the current player's, so it should be ticked first. It's still the same round.
+    fn jump_to_player(&mut self, index: usize, now: Timestamp) {
+        let (players, current_player_index) = self.players.raw();
+        let Some(player) = players.get(index) else {
+            return;
+        };
+        if index == current_player_index {
+            return;
+        }
+        if player.sitting_out {
+            let text = format!("{} is sitting out", player.display_name);
+            self.toasts.push(ToastKind::Error, text);
+            return;
+        }
+        self.end_turn(now);
+        self.players.set_current(index);
+        self.start_turn(now);
+    }
+
+    /// Records the current player's turn as over, for [`Self::next_turn`] and
+    /// [`Self::jump_to_player`]. Anyone going at the same time is done too.
+    fn end_turn(&mut self, now: Timestamp) {
+        self.end_simultaneous_turns();
+        let mut time_bonus_earned = Duration::ZERO;
+        if let ClockMode::Countdown {
+            increment: Some(increment),
+            ..
+        } = self.rules.clock_mode
+        {
+            let player = self.players.current_mut();
+            // Too late once the flag's fallen
+            if !player.flagged {
+                time_bonus_earned = increment.earned(player.stats.current_turn_duration);
+                player.time_bonus += time_bonus_earned;
+            }
+        }
+        let (players, current_player_index) = self.players.raw();
+        let current_player = &players[current_player_index];
+        let turn_counts = current_player.stats.current_turn_counts();
+        if turn_counts {
+            self.turns.push(self.turn_record(current_player));
+        }
+        self.turn_log.record(TurnChange {
+            at: now,
+            ended_player_index: current_player_index,
+            ended_turn_duration: current_player.stats.current_turn_duration,
+            time_bonus_earned,
+            phase_index: self.current_phase_index,
+            round_index: self.round_index,
+            turn_held: self.turn_held,
+            total_handoff_time: self.total_handoff_time,
+            turn_recorded: turn_counts,
+        });
+        self.players.current_mut().stats.end_turn();
+        self.autosave.request();
+    }
+
+    /// Stops the current player's time for a rules lookup, break, etc, with the time going to
+    /// nobody, or goes back to them. The clock keeps running either way.
+    fn toggle_interrupt(&mut self) {
+        self.interrupted = !self.interrupted;
+        let text = if self.interrupted {
+            format!(
+                "Interrupted, time goes to nobody. [{}] to go back to {}",
+                KEY_INTERRUPT.to_lowercase(),
+                self.players.current().display_name
+            )
+        } else {
+            format!("Back to {}", self.players.current().display_name)
+        };
+        self.toasts.push(ToastKind::Info, text);
+        self.autosave.request();
+    }
 
-#[derive(Copy, Clone)]
-enum TextDetailMode {
-    Concise,
-    Detailed,
-}
+    /// For whoever's up after [`Self::end_turn`]. Passing the turn on means play's back on, so it
+    /// ends any interruption.
+    fn start_turn(&mut self, now: Timestamp) {
+        self.announce_turn_start(now);
+        bq::set_controller_led(None);
+        self.interrupted = false;
+        self.current_phase_index = 0;
+        self.handoff_remaining = self.rules.handoff;
+        self.turn_held = false;
+        self.turn_limit_exceeded_at = None;
+    }
 
-#[derive(Copy, Clone)]
-enum TimeDisplayMode {
-    Shown,
-    Hidden,
-}
+    /// Flashes whoever's up now's color (and plays a tone, if the preset wants one), so everyone at
+    /// the table notices it's someone else's turn.
+    fn announce_turn_start(&mut self, now: Timestamp) {
+        self.turn_started_at = Some(now);
+        if self.rules.turn_start_sound {
+            self.sounds.play(SoundEffect::TurnStart);
+        }
+    }
 
-impl StatefulGui for TurnTimeTracker {
-    fn main_conf() -> mq::Conf {
-        mq::Conf {
-            window_title: "Tabletop Turn Time Tracker".to_string(),
-            window_width: (PIE_X + PIE_THICKNESS * PIE_THICKNESS_CURRENT_TURN_MULTIPLIER + 20.0)
-                as i32,
-            window_height: 1000,
-            ..Default::default()
+    /// `player`'s current turn, as of now.
+    fn turn_record(&self, player: &Player) -> TurnRecord {
+        TurnRecord {
+            player_name: player.display_name.clone(),
+            duration_millis: player.stats.current_turn_duration.as_millis() as u64,
+            ended_at_millis: self.active_time().as_millis() as u64,
         }
     }
 
-    fn update(&mut self, now: Timestamp) {
-        self.evaluate_state(now);
+    /// Back to the turn that last ended (usually the previous player's), as if it never ended. The
+    /// time since (hand-off included) was really theirs, so it's taken off the player who's up now
+    /// and given back.
+    fn undo_turn_change(&mut self, now: Timestamp) {
+        let Some(change) = self.turn_log.undo() else {
+            self.toasts.push(ToastKind::Error, "No turn change to undo");
+            return;
+        };
+        self.end_simultaneous_turns();
+        if change.turn_recorded {
+            self.turns.pop();
+        }
+        let handoff_time = self
+            .total_handoff_time
+            .saturating_sub(change.total_handoff_time);
+        self.total_handoff_time = change.total_handoff_time;
+
+        let wrong_player = self.players.current_mut();
+        let wrong_turn_duration = wrong_player.stats.current_turn_duration;
+        wrong_player.total_time = wrong_player.total_time.saturating_sub(wrong_turn_duration);
+        if let Some(round_time) = wrong_player.round_times.get_mut(self.round_index) {
+            *round_time = round_time.saturating_sub(wrong_turn_duration);
+        }
+        wrong_player.stats.current_turn_duration = Duration::ZERO;
+        let wrong_player = self.players.current();
+        // Their flag might only have fallen because of the time that wasn't theirs
+        if wrong_player.flagged && !self.displayed_time(wrong_player).is_zero() {
+            self.players.current_mut().flagged = false;
+        }
+
+        self.players.set_current(change.ended_player_index);
+        self.round_index = change.round_index;
+        let player = self.players.current_mut();
+        player.time_bonus = player.time_bonus.saturating_sub(change.time_bonus_earned);
+        player.stats.resume_turn(change.ended_turn_duration);
+        player.tick_frame(wrong_turn_duration + handoff_time, change.round_index);
+        let name = player.display_name.clone();
+        self.current_phase_index = change.phase_index;
+        self.handoff_remaining = None;
+        self.turn_limit_exceeded_at = None;
+        self.announce_turn_start(now);
+        self.turn_held = change.turn_held;
+        // Otherwise draft mode would pass the turn straight back on
+        if self
+            .auto_advance_remaining()
+            .is_some_and(|remaining| remaining.is_zero())
+        {
+            self.turn_held = true;
+        }
+
+        self.autosave.request();
+
+        let ago = now.duration_since(change.at).unwrap_or_default();
+        self.toasts.push(
+            ToastKind::Info,
+            format!(
+                "Undid turn change from {} ago, back to {name}",
+                self.settings.time_format.format(ago)
+            ),
+        );
     }
 
-    fn draw(&self) {
-        self.draw_state();
+    /// In draft mode, how long until the current turn passes on by itself. Releasing a held turn
+    /// that's already run out passes it on right away.
+    fn auto_advance_remaining(&self) -> Option<Duration> {
+        let auto_advance = self.rules.auto_advance?;
+        Some(auto_advance.saturating_sub(self.players.current().stats.current_turn_duration))
     }
-}
 
-impl TurnTimeTracker {
-    /// Constructor to make tracker from static player list.
-    pub fn with_players(players: Vec<(&'static str, mq::Color)>) -> Self {
-        let players = players
-            .into_iter()
-            .map(|(player_name, player_color)| Player::new(player_name, player_color))
+    fn update_fairness_alert(&mut self) {
+        let Some(fairness_alert) = &mut self.fairness_alert else {
+            return;
+        };
+        let (players, _) = self.players.raw();
+        let player_times = players
+            .iter()
+            .map(|player| player.total_time)
             .collect::<Vec<_>>();
-        Self {
-            players: InfiniteIterator::from(players),
-            timer: TimerState::Paused,
-            time_display_mode: TimeDisplayMode::Shown,
-            text_detail_mode: TextDetailMode::Concise,
+        if fairness_alert.update(&player_times) && self.rules.fairness_alert_sound {
+            self.sounds.play(SoundEffect::FairnessAlert);
         }
     }
 
-    fn evaluate_state(&mut self, now: Timestamp) {
-        // Toggle time display if needed
-        if mq::is_key_pressed(KEY_TIME_DISPLAY_TOGGLE) {
-            self.time_display_mode = match self.time_display_mode {
-                TimeDisplayMode::Shown => TimeDisplayMode::Hidden,
-                TimeDisplayMode::Hidden => TimeDisplayMode::Shown,
-            };
+    /// In countdown mode, flags whoever's going once they're out of time. The session carries on,
+    /// e.g. for the others to finish the game.
+    fn check_flag_fall(&mut self) {
+        if !matches!(self.rules.clock_mode, ClockMode::Countdown { .. }) {
+            return;
+        }
+        for index in self.players.active_indices() {
+            let (players, _) = self.players.raw();
+            let player = &players[index];
+            if player.flagged || !self.displayed_time(player).is_zero() {
+                continue;
+            }
+            let name = player.display_name.clone();
+            if let Some(player) = self.players.get_mut(index) {
+                player.flagged = true;
+            }
+            self.toasts
+                .push(ToastKind::Error, format!("{name}'s flag fell: out of time"));
+            self.sounds.play(SoundEffect::FlagFall);
+            bq::rumble(Rumble::PULSE);
+            bq::set_controller_led(Some(mq::RED));
         }
+    }
 
-        // Toggle detail mode if needed
-        if mq::is_key_pressed(KEY_DETAIL_MODE_TOGGLE) {
-            self.text_detail_mode = match self.text_detail_mode {
-                TextDetailMode::Concise => TextDetailMode::Detailed,
-                TextDetailMode::Detailed => TextDetailMode::Concise,
-            };
+    /// In countdown mode, a nudge on the tick anyone going's time gets low, so it only goes off
+    /// once per crossing.
+    fn check_low_time(&self, player_tick_time: Duration) {
+        if !matches!(self.rules.clock_mode, ClockMode::Countdown { .. }) {
+            return;
+        }
+        let (players, _) = self.players.raw();
+        let crossed = self.players.active_indices().into_iter().any(|index| {
+            let time_left = self.displayed_time(&players[index]);
+            time_left <= LOW_TIME_WARNING && time_left + player_tick_time > LOW_TIME_WARNING
+        });
+        if crossed {
+            bq::rumble(Rumble::TAP);
         }
+    }
 
-        match &mut self.timer {
-            TimerState::Paused => {
-                // Check for unpause
-                if mq::is_key_pressed(KEY_PAUSE) {
-                    self.timer = TimerState::Running { last_tick: now };
-                }
-            }
-            TimerState::Running { ref mut last_tick } => {
-                // Check for pause
-                if mq::is_key_pressed(KEY_PAUSE) {
-                    self.timer = TimerState::Paused;
-                    return;
-                }
+    /// Steps through the ways of showing times, remembering it for next time.
+    fn cycle_time_format(&mut self) {
+        let units = self.settings.time_format.units.next();
+        self.settings.time_format.units = units;
+        self.settings.save();
+        self.toasts
+            .push(ToastKind::Info, format!("Times shown as {}", units.name()));
+    }
+
+    /// Steps the soft turn limit up, and back to off after the longest. It's kept in the session's
+    /// preset, so it sticks for the next game and when resuming.
+    fn cycle_turn_limit(&mut self) {
+        let Screen::Tracking { preset, .. } = &mut self.screen else {
+            return;
+        };
+        preset.turn_limit_secs = next_turn_limit_secs(preset.turn_limit_secs);
+        self.rules.turn_limit = preset.turn_limit_secs.map(Duration::from_secs);
+        self.turn_limit_exceeded_at = None;
+        let text = match self.rules.turn_limit {
+            Some(turn_limit) => format!("Turn limit: {}", format_duration_stats(Some(turn_limit))),
+            None => "Turn limit: off".to_string(),
+        };
+        self.toasts.push(ToastKind::Info, text);
+    }
 
-                // Tick current player
-                let elapsed_tick_time = now
-                    .duration_since(*last_tick)
-                    .expect("Elapsed tick time underflow");
-                self.players.current_mut().tick_frame(elapsed_tick_time);
+    /// Chimes and starts flashing the current player's row on the tick their turn goes over the
+    /// turn limit, so it only goes off once per turn.
+    fn check_turn_limit(&mut self, player_tick_time: Duration, now: Timestamp) {
+        let Some(turn_limit) = self.rules.turn_limit else {
+            return;
+        };
+        let turn_duration = self.players.current().stats.current_turn_duration;
+        let crossed = turn_duration > turn_limit
+            && turn_duration.saturating_sub(player_tick_time) <= turn_limit;
+        if crossed {
+            self.turn_limit_exceeded_at = Some(now);
+            self.sounds.play(SoundEffect::TurnLimit);
+            bq::rumble(Rumble::TAP);
+        }
+    }
 
-                *last_tick = now;
+    /// Whether the current player's row is lit, blinking for a bit after they go over the limit.
+    fn turn_limit_flash_on(&self, now: Timestamp) -> bool {
+        self.turn_limit_exceeded_at
+            .and_then(|exceeded_at| now.duration_since(exceeded_at))
+            .is_some_and(|since| {
+                since < TURN_LIMIT_FLASH_DURATION
+                    && (since.as_secs_f64() / TURN_LIMIT_FLASH_PERIOD_SECS) % 1.0 < 0.5
+            })
+    }
 
-                // Change current player if needed. Do this AFTER ticking current player so previous
-                // player is attributed the time until we process the player change.
-                if mq::is_key_pressed(KEY_NEXT_PLAYER) {
-                    self.players.current_mut().stats.end_turn();
-                    self.players.advance();
-                }
+    /// Over the whole window in the current player's color, fading out, for a moment after their
+    /// turn starts.
+    fn draw_turn_start_flash(&self) {
+        let since = self
+            .turn_started_at
+            .and_then(|started_at| Timestamp::now().duration_since(started_at));
+        let alpha = turn_start_flash_alpha(since);
+        if alpha <= 0.0 {
+            return;
+        }
+        let mut color = self.players.current().display_color;
+        color.a = alpha;
+        mq::draw_rectangle(0.0, 0.0, mq::screen_width(), mq::screen_height(), color);
+    }
 
-                // TODO:2 press 1-9 to fastswap to player turn
-            }
+    /// Banner across the top while anyone's over the fairness threshold.
+    fn draw_fairness_banner(&self) {
+        let Some(fairness_alert) = &self.fairness_alert else {
+            return;
+        };
+        let (players, _) = self.players.raw();
+        let all_total_time = players
+            .iter()
+            .map(|player| player.total_time)
+            .sum::<Duration>();
+        let lines = fairness_alert
+            .players_over()
+            .map(|i| {
+                let player = &players[i];
+                format!(
+                    "{} has taken {:.0}% of the time",
+                    player.display_name,
+                    100.0 * player.total_time.as_secs_f32() / all_total_time.as_secs_f32(),
+                )
+            })
+            .collect::<Vec<_>>();
+        if lines.is_empty() {
+            return;
         }
+        bq::draw_text(
+            lines.join("\n"),
+            TextAlignment::Center,
+            None,
+            FAIRNESS_BANNER_FONT_SIZE,
+            mq::WHITE,
+            TextAnchorPoint::TopCenter {
+                x: mq::screen_width() / 2.0,
+                y: 0.0,
+            },
+            Some(TextBackground {
+                color: FAIRNESS_BANNER_COLOR,
+                x_padding: FAIRNESS_BANNER_PADDING,
+                y_padding: FAIRNESS_BANNER_PADDING,
+            }),
+        );
+    }
+
+    /// Across the middle while paused for having no input, so it's seen from across the room.
+    fn draw_idle_pause_banner(&self) {
+        let Some(idle_paused_after) = self.idle_paused_after else {
+            return;
+        };
+        bq::draw_text(
+            format!(
+                "Auto-paused due to inactivity\nNo input for {} - [{}] to carry on",
+                self.settings.time_format.format(idle_paused_after),
+                self.settings.key_bindings.key(Action::Pause).to_lowercase(),
+            ),
+            TextAlignment::Center,
+            None,
+            IDLE_PAUSE_BANNER_FONT_SIZE,
+            mq::WHITE,
+            TextAnchorPoint::window_centered(),
+            Some(TextBackground {
+                color: IDLE_PAUSE_BANNER_COLOR,
+                x_padding: IDLE_PAUSE_BANNER_PADDING,
+                y_padding: IDLE_PAUSE_BANNER_PADDING,
+            }),
+        );
     }
 
     fn draw_state(&self) {
+        match self.layout_mode {
+            LayoutMode::Standard => {}
+            LayoutMode::Tv => {
+                self.draw_tv_display();
+                return;
+            }
+            LayoutMode::Tablet => {
+                self.draw_tablet_display();
+                return;
+            }
+        }
+
         let bg_color = match self.timer {
-            TimerState::Paused => mq::DARKGRAY,
+            TimerState::Paused { .. } => mq::DARKGRAY,
             TimerState::Running { .. } => mq::LIGHTGRAY,
         };
         mq::clear_background(bg_color);
@@ -153,101 +2112,519 @@ impl TurnTimeTracker {
         }
 
         match self.time_display_mode {
-            TimeDisplayMode::Shown => Self::draw_pie(players, current_player_index, all_total_time),
+            TimeDisplayMode::Shown => match self.chart_mode {
+                ChartMode::Pie => Self::draw_pie(players, current_player_index, all_total_time),
+                ChartMode::Bar => Self::draw_bar(players, current_player_index, all_total_time),
+                ChartMode::TurnLengths => Self::draw_turn_lengths(players, current_player_index),
+            },
             TimeDisplayMode::Hidden => {}
         }
-        self.draw_player_text(players, current_player_index, all_total_time);
+        self.draw_player_text(players, current_player_index);
 
-        if let TimerState::Paused = self.timer {
+        let paused_text = match self.timer {
+            TimerState::Paused { .. } => Some("PAUSED"),
+            TimerState::Running { .. } if self.interrupted => Some("INTERRUPTED"),
+            TimerState::Running { .. } => None,
+        };
+        if let Some(paused_text) = paused_text {
             mq::draw_text(
-                "PAUSED",
+                paused_text,
                 PAUSED_TEXT_X,
                 PAUSED_TEXT_Y,
                 PAUSED_TEXT_FONT_SIZE,
                 mq::WHITE,
             );
         }
+
+        self.draw_session_info();
+        if let Some(next_button) = self.next_button() {
+            next_button.draw();
+        }
     }
 
-    fn draw_player_text(
-        &self,
-        players: &[Player],
-        current_player_index: usize,
-        all_total_time: Duration,
-    ) {
-        for (i, player) in players.iter().enumerate() {
-            let text_line_name = format!(
-                // Names longer than 8 chars will push the line out a little bit :P oh well
-                "{} {: <8}",
-                if i == current_player_index {
-                    "[X]"
+    fn draw_session_info(&self) {
+        bq::draw_text(
+            self.session_info_text(),
+            TextAlignment::Left,
+            None,
+            SESSION_INFO_FONT_SIZE,
+            mq::WHITE,
+            TextAnchorPoint::BottomLeft {
+                x: SESSION_INFO_PADDING,
+                y: mq::screen_height() - self.next_button_space() - SESSION_INFO_PADDING,
+            },
+            None,
+        );
+    }
+
+    /// In the standard layout, a button along the bottom that does what the next player key does:
+    /// passes the turn, skips the rest of the hand-off, or ends simultaneous turns. While paused,
+    /// it starts the clock instead, like a tap in the tablet layout.
+    fn next_button(&self) -> Option<bq::Button> {
+        if !matches!(self.layout_mode, LayoutMode::Standard)
+            || !matches!(self.screen, Screen::Tracking { .. })
+        {
+            return None;
+        }
+        let (label, color) = match self.timer {
+            TimerState::Paused { .. } => ("Start".to_string(), NEXT_BUTTON_PAUSED_COLOR),
+            TimerState::Running { .. } => {
+                let label = if self.handoff_remaining.is_some() {
+                    "Skip hand-off".to_string()
+                } else if self.simultaneous_turns_going() {
+                    "Everyone's done".to_string()
                 } else {
-                    "[ ]"
-                },
-                player.display_name
-            );
+                    format!("{} is done", self.players.current().display_name)
+                };
+                (label, self.players.current().display_color)
+            }
+        };
+        let rect = mq::Rect::new(
+            NEXT_BUTTON_MARGIN,
+            mq::screen_height() - NEXT_BUTTON_MARGIN - NEXT_BUTTON_HEIGHT,
+            mq::screen_width() - NEXT_BUTTON_MARGIN * 2.0,
+            NEXT_BUTTON_HEIGHT,
+        );
+        Some(bq::Button::new(rect, label, color))
+    }
 
-            let text_line_info = match (self.time_display_mode, self.text_detail_mode) {
-                (TimeDisplayMode::Hidden, _) => {
-                    if i == current_player_index {
-                        format_duration_concise(player.stats.current_turn_duration)
-                    } else {
-                        "".to_string()
-                    }
-                }
-                (TimeDisplayMode::Shown, TextDetailMode::Concise) => format!(
-                    "{} ({: >2.0}%)",
-                    format_duration_concise(player.total_time),
-                    100.0 * (player.total_time.as_secs_f32() / all_total_time.as_secs_f32()),
-                ),
-                (TimeDisplayMode::Shown, TextDetailMode::Detailed) => format!(
-                    "{} ({: >2.0}%) -- ({} turns; avg: {}, max: {}, median: {})",
-                    format_duration_detailed(player.total_time),
-                    100.0 * (player.total_time.as_secs_f32() / all_total_time.as_secs_f32()),
-                    player.stats.num_turns(),
-                    format_duration_stats(if player.stats.num_turns() == 0 {
-                        None
-                    } else {
-                        Some(player.total_time / player.stats.num_turns() as u32)
-                    }),
-                    format_duration_stats(player.stats.max_turn()),
-                    format_duration_stats(player.stats.median_turn())
-                ),
-            };
+    fn next_button_clicked(&self) -> bool {
+        self.next_button().is_some_and(|button| button.clicked())
+    }
+
+    /// How much of the bottom of the window the next button takes up, if it's shown.
+    fn next_button_space(&self) -> f32 {
+        if self.next_button().is_some() {
+            NEXT_BUTTON_HEIGHT + NEXT_BUTTON_MARGIN
+        } else {
+            0.0
+        }
+    }
 
-            let text_line = if text_line_info.is_empty() {
-                text_line_name
+    /// Preset name, round, current phase if the preset has phases, and hand-off info if it has
+    /// those. With detailed stats, each round's total time too.
+    fn session_info_text(&self) -> String {
+        let mut session_info_text = match self.rules.phases.get(self.current_phase_index) {
+            None => format!(
+                "{} - Round {}",
+                self.rules.preset_name,
+                self.round_index + 1
+            ),
+            Some(phase) => format!(
+                "{} - Round {} - Phase: {phase} ({}/{}) [{}]",
+                self.rules.preset_name,
+                self.round_index + 1,
+                self.current_phase_index + 1,
+                self.rules.phases.len(),
+                self.settings
+                    .key_bindings
+                    .key(Action::NextPhase)
+                    .to_lowercase(),
+            ),
+        };
+        if let Some(handoff_remaining) = self.handoff_remaining {
+            session_info_text.push_str(&format!(
+                " - Hand-off: {} [{}]",
+                format_seconds_remaining(handoff_remaining),
+                self.settings
+                    .key_bindings
+                    .key(Action::NextPlayer)
+                    .to_lowercase(),
+            ));
+        }
+        if let Some(auto_advance_remaining) = self
+            .auto_advance_remaining()
+            .filter(|_| self.handoff_remaining.is_none())
+        {
+            let auto_advance_text = if self.turn_held {
+                format!(
+                    " - Turn held [{}]",
+                    self.settings
+                        .key_bindings
+                        .key(Action::HoldTurn)
+                        .to_lowercase()
+                )
             } else {
-                format!("{text_line_name}: {text_line_info}")
+                format!(
+                    " - Next in {} ([{}] to hold)",
+                    format_seconds_remaining(auto_advance_remaining),
+                    self.settings
+                        .key_bindings
+                        .key(Action::HoldTurn)
+                        .to_lowercase(),
+                )
             };
+            session_info_text.push_str(&auto_advance_text);
+        }
+        if self.players.is_reversed() {
+            session_info_text.push_str(&format!(
+                " - Reversed [{}]",
+                KEY_REVERSE_TURN_ORDER.to_lowercase()
+            ));
+        }
+        if self.interrupted {
+            session_info_text.push_str(&format!(
+                " - Interrupted [{}]",
+                KEY_INTERRUPT.to_lowercase()
+            ));
+        }
+        if self.simultaneous_turns_going() {
+            let (players, _) = self.players.raw();
+            let names = self
+                .players
+                .also_active_indices()
+                .into_iter()
+                .map(|index| players[index].display_name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            session_info_text.push_str(&format!(
+                "\nAlso going: {names} ([{}] when done)",
+                self.settings
+                    .key_bindings
+                    .key(Action::NextPlayer)
+                    .to_lowercase()
+            ));
+        }
+        if self.rules.handoff.is_some() {
+            session_info_text.push_str(&format!(
+                "\nHand-offs total: {}",
+                self.settings.time_format.format(self.total_handoff_time)
+            ));
+        }
+        if !self.total_interrupt_time.is_zero() {
+            session_info_text.push_str(&format!(
+                "\nInterruptions total: {}",
+                self.settings.time_format.format(self.total_interrupt_time)
+            ));
+        }
+        let paused_time = self.paused_time();
+        session_info_text.push_str(&format!(
+            "\nActive: {} - Wall: {} ({} paused)",
+            self.settings.time_format.format(self.active_time()),
+            self.settings
+                .time_format
+                .format(self.active_time() + paused_time),
+            self.settings.time_format.format(paused_time),
+        ));
+        if matches!(self.text_detail_mode, TextDetailMode::Detailed) {
+            session_info_text.push_str(&format!("\nRounds: {}", self.round_totals_text()));
+        }
+        session_info_text
+    }
+
+    /// Each round's time so far, summed over the players, e.g. "... 3: 04:12, 4: 03:50, 5: 00:41".
+    /// Only the last few fit.
+    fn round_totals_text(&self) -> String {
+        let (players, _) = self.players.raw();
+        let first_shown = (self.round_index + 1).saturating_sub(MAX_ROUND_TOTALS_SHOWN);
+        let round_totals = (first_shown..=self.round_index)
+            .map(|round_index| {
+                let round_total = players
+                    .iter()
+                    .map(|player| player.round_time(round_index))
+                    .sum::<Duration>();
+                format!(
+                    "{}: {}",
+                    round_index + 1,
+                    format_duration_stats(Some(round_total))
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        if first_shown > 0 {
+            format!("... {round_totals}")
+        } else {
+            round_totals
+        }
+    }
+
+    fn draw_preset_selection(&self, presets: &[Preset]) {
+        mq::clear_background(mq::DARKGRAY);
+
+        let player_names = self
+            .roster
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let players_text = format!("Players ({}): {player_names}", self.roster.len());
+        let mut text = format!(
+            "{}\n[{}] edit players\n[{}] paste players from clipboard\n\
+            [{}] import players from {}\n\
+            [{}] continue a session from {}\n\
+            [{}] load a saved session\n\
+            [{}] / [{}] save players as a template / pick a template\n\
+            [{}] / [{}] export weekly / monthly report\n\
+            [{}] change keys\n\n\
+            Select a game preset:\n",
+            bq::truncate_with_ellipsis(
+                &players_text,
+                None,
+                PRESET_SELECTION_FONT_SIZE,
+                mq::screen_width() - PRESET_SELECTION_X * 2.0,
+            ),
+            KEY_EDIT_PLAYERS.to_lowercase(),
+            KEY_PASTE_ROSTER.to_lowercase(),
+            KEY_IMPORT_ROSTER_FILE.to_lowercase(),
+            roster_import::ROSTER_FILE_NAME,
+            KEY_IMPORT_SESSION_FILE.to_lowercase(),
+            session_file::IMPORT_FILE_NAME,
+            KEY_BROWSE_SESSION_SLOTS.to_lowercase(),
+            KEY_SAVE_ROSTER_TEMPLATE.to_lowercase(),
+            KEY_BROWSE_ROSTER_TEMPLATES.to_lowercase(),
+            KEY_WEEKLY_REPORT.to_lowercase(),
+            KEY_MONTHLY_REPORT.to_lowercase(),
+            KEY_EDIT_KEY_BINDINGS.to_lowercase(),
+        );
+        for (i, preset) in presets.iter().take(KEYS_SELECT_PRESET.len()).enumerate() {
+            text.push_str(&format!("\n[{}] {}\n", i + 1, preset.name));
+            if !preset.description.is_empty() {
+                text.push_str(&format!("    {}\n", preset.description));
+            }
+        }
+
+        bq::draw_text(
+            text,
+            TextAlignment::Left,
+            None,
+            PRESET_SELECTION_FONT_SIZE,
+            mq::WHITE,
+            TextAnchorPoint::TopLeft {
+                x: PRESET_SELECTION_X,
+                y: PRESET_SELECTION_Y,
+            },
+            None,
+        );
+    }
+
+    /// What's displayed as the player's time, depending on clock mode.
+    fn displayed_time(&self, player: &Player) -> Duration {
+        match self.rules.clock_mode {
+            ClockMode::CountUp => player.total_time,
+            ClockMode::Countdown { budget, .. } => {
+                (budget + player.time_bonus).saturating_sub(player.total_time)
+            }
+        }
+    }
+
+    /// In countdown mode, time text is shaded by how much time is left. Otherwise it's None, and
+    /// the whole line is the player's color.
+    fn countdown_time_color(&self, player: &Player) -> Option<mq::Color> {
+        match self.rules.clock_mode {
+            ClockMode::CountUp => None,
+            ClockMode::Countdown { budget, .. } => {
+                let fraction_left =
+                    self.displayed_time(player).as_secs_f32() / budget.as_secs_f32();
+                Some(gradient_color(&COUNTDOWN_GRADIENT, fraction_left))
+            }
+        }
+    }
+
+    /// Each player's row in the standard layout, with names in full.
+    fn player_rows(&self) -> Vec<PlayerRowText> {
+        let (players, _) = self.players.raw();
+        let all_total_time: Duration = players.iter().map(|player| player.total_time).sum();
+        players
+            .iter()
+            .enumerate()
+            .map(|(i, player)| {
+                let marker = if self.players.is_active(i) {
+                    "[X] "
+                } else {
+                    "[ ] "
+                };
+
+                let text_line_info = match (self.time_display_mode, self.text_detail_mode) {
+                    (TimeDisplayMode::Hidden, _) => {
+                        if self.players.is_active(i) {
+                            self.settings.time_format.format(player.stats.current_turn_duration)
+                        } else {
+                            "".to_string()
+                        }
+                    }
+                    (TimeDisplayMode::Shown, TextDetailMode::Concise)
+                        if matches!(self.chart_mode, ChartMode::TurnLengths) =>
+                    {
+                        format!(
+                            "avg {}, median {} ({} turns)",
+                            format_duration_stats(player.average_turn()),
+                            format_duration_stats(player.stats.median_turn()),
+                            player.stats.num_turns(),
+                        )
+                    }
+                    (TimeDisplayMode::Shown, TextDetailMode::Concise) => format!(
+                        "{} ({: >2.0}%)",
+                        self.settings.time_format.format(self.displayed_time(player)),
+                        100.0 * (player.total_time.as_secs_f32() / all_total_time.as_secs_f32()),
+                    ),
+                    (TimeDisplayMode::Shown, TextDetailMode::Detailed) => format!(
+                        "{} ({: >2.0}%) -- ({} turns; avg: {}, max: {}, median: {}, last: {}; this round: {})",
+                        self.settings.time_format.format_detailed(self.displayed_time(player)),
+                        100.0 * (player.total_time.as_secs_f32() / all_total_time.as_secs_f32()),
+                        player.stats.num_turns(),
+                        format_duration_stats(player.average_turn()),
+                        format_duration_stats(player.stats.max_turn()),
+                        format_duration_stats(player.stats.median_turn()),
+                        format_duration_stats(player.stats.last_turn()),
+                        format_duration_stats(Some(player.round_time(self.round_index))),
+                    ),
+                };
+
+                let text_line_info = if player.flagged && !text_line_info.is_empty() {
+                    format!("{text_line_info} FLAG")
+                } else {
+                    text_line_info
+                };
+                let text_line_info = if player.sitting_out {
+                    format!("{text_line_info} OUT").trim_start().to_string()
+                } else {
+                    text_line_info
+                };
+
+                PlayerRowText {
+                    marker,
+                    name: player.display_name.clone(),
+                    info: text_line_info,
+                }
+            })
+            .collect()
+    }
+
+    /// The player rows go below the chart, down to the session info.
+    fn player_text_layout(&self, rows: &[PlayerRowText]) -> PlayerTextLayout {
+        let session_info_height = self.session_info_text().lines().count() as f32
+            * f32::from(SESSION_INFO_FONT_SIZE)
+            + SESSION_INFO_PADDING * 2.0
+            + self.next_button_space();
+        let turn_trend_width = if self.turn_trends_shown() {
+            TURN_TREND_WIDTH + TURN_TREND_GAP
+        } else {
+            0.0
+        };
+        let area = mq::Rect::new(
+            PLAYER_TEXT_X,
+            PLAYER_TEXT_Y,
+            mq::screen_width() - PLAYER_TEXT_X * 2.0 - turn_trend_width,
+            (mq::screen_height() - session_info_height - PLAYER_TEXT_Y).max(0.0),
+        );
+        PlayerTextLayout::new(rows, self.name_font.as_ref(), area)
+    }
+
+    /// Only with times shown, since they'd give the times away.
+    fn turn_trends_shown(&self) -> bool {
+        matches!(self.turn_trend_mode, TurnTrendMode::Sparklines)
+            && matches!(self.time_display_mode, TimeDisplayMode::Shown)
+    }
 
-            // TODO:3 use friendlier monospace font
-            let player_text_y = PLAYER_TEXT_Y
-                + ((PLAYER_TEXT_LINE_BUFFER + PLAYER_TEXT_FONT_SIZE) * (i as f32 + 1.0));
+    /// Each player's last few turns, oldest first, all on the same scale so slower players stand
+    /// out.
+    fn draw_turn_trends(&self, players: &[Player], layout: &PlayerTextLayout) {
+        let recent_turns = players
+            .iter()
+            .map(|player| {
+                let turns = player.stats.turn_durations();
+                turns[turns.len().saturating_sub(TURN_TREND_MAX_TURNS)..]
+                    .iter()
+                    .map(Duration::as_secs_f32)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let scale_max = recent_turns.iter().flatten().copied().fold(0.0, f32::max);
+        let font_size = f32::from(layout.font_size);
+        for (i, (player, turns)) in players.iter().zip(&recent_turns).enumerate() {
+            let rect = mq::Rect::new(
+                mq::screen_width() - PLAYER_TEXT_X - TURN_TREND_WIDTH,
+                layout.baseline_y(i) - font_size,
+                TURN_TREND_WIDTH,
+                font_size,
+            );
+            bq::draw_sparkline(turns, scale_max, rect, player.text_color());
+        }
+    }
+
+    fn draw_player_text(&self, players: &[Player], current_player_index: usize) {
+        let rows = self.player_rows();
+        let layout = self.player_text_layout(&rows);
+        if self.turn_trends_shown() {
+            self.draw_turn_trends(players, &layout);
+        }
+        let font_size = f32::from(layout.font_size);
+        for (i, (player, row)) in players.iter().zip(&rows).enumerate() {
+            // The name's in its own font, since it can be in any script, and everyone's info
+            // lines up after the longest name.
+            let y = layout.baseline_y(i);
             mq::draw_text(
-                &text_line,
-                PLAYER_TEXT_X,
-                player_text_y,
-                PLAYER_TEXT_FONT_SIZE,
-                player.display_color,
+                row.marker,
+                layout.marker_x(),
+                y,
+                font_size,
+                player.text_color(),
             );
+            let name_width = mq::draw_text_ex(
+                &fit_name(&row.name, self.name_font.as_ref(), &layout),
+                layout.name_x,
+                y,
+                mq::TextParams {
+                    font: self.name_font.as_ref(),
+                    font_size: layout.font_size,
+                    color: player.text_color(),
+                    ..Default::default()
+                },
+            )
+            .width;
+            let mut line_end_x = layout.name_x + name_width;
+            if !row.info.is_empty() {
+                // TODO:3 use friendlier monospace font
+                mq::draw_text(": ", layout.separator_x, y, font_size, player.text_color());
+                // Name in the player's color, and in countdown mode, time shaded by how much is
+                // left.
+                let info_color = self
+                    .countdown_time_color(player)
+                    .unwrap_or(player.text_color());
+                line_end_x = layout.info_x
+                    + mq::draw_text(&row.info, layout.info_x, y, font_size, info_color).width;
+            }
 
-            if i == current_player_index {
-                let text_dimension =
-                    mq::measure_text(&text_line, None, PLAYER_TEXT_FONT_SIZE as u16, 1.0);
-                // Magic numbers are rectangle padding, which just "looks right".
+            if self.players.is_active(i) {
+                let over_turn_limit = self
+                    .rules
+                    .turn_limit
+                    .is_some_and(|turn_limit| player.stats.current_turn_duration > turn_limit);
+                let rectangle_color = if over_turn_limit { mq::RED } else { mq::WHITE };
+                let rect = layout.highlight_rect(i, line_end_x);
+                if i == current_player_index && self.turn_limit_flash_on(Timestamp::now()) {
+                    mq::draw_rectangle(
+                        0.0,
+                        rect.y,
+                        mq::screen_width(),
+                        rect.h,
+                        TURN_LIMIT_FLASH_COLOR,
+                    );
+                }
                 mq::draw_rectangle_lines(
-                    PLAYER_TEXT_X - 5.0,
-                    player_text_y - text_dimension.height - 4.0,
-                    text_dimension.width + 10.0,
-                    text_dimension.height + 18.0,
+                    rect.x,
+                    rect.y,
+                    rect.w,
+                    rect.h,
                     PLAYER_RECTANGLE_THICKNESS,
-                    mq::WHITE,
+                    rectangle_color,
                 );
             }
         }
     }
 
+    /// The player whose row was just clicked, in the standard layout.
+    fn clicked_player_row(&self) -> Option<usize> {
+        if !matches!(self.layout_mode, LayoutMode::Standard)
+            || !mq::is_mouse_button_pressed(mq::MouseButton::Left)
+        {
+            return None;
+        }
+        let (_, mouse_y) = mq::mouse_position();
+        let rows = self.player_rows();
+        self.player_text_layout(&rows).row_at(mouse_y, rows.len())
+    }
+
     fn draw_pie(players: &[Player], current_player_index: usize, all_total_time: Duration) {
         let circle_sides = 100;
         let radius = 0.0;
@@ -279,25 +2656,166 @@ impl TurnTimeTracker {
             current_start_degree += player_slice_degrees;
         }
     }
-}
 
-fn format_duration_concise(duration: Duration) -> String {
-    let total_seconds = duration.as_secs();
-    let hours = total_seconds / 3600;
-    let minutes = (total_seconds % 3600) / 60;
-    let seconds = total_seconds % 60;
+    /// Like the pie, but a stacked bar, with each share labeled if there's room.
+    fn draw_bar(players: &[Player], current_player_index: usize, all_total_time: Duration) {
+        let bar_width = mq::screen_width() - BAR_X * 2.0;
+        let mut x = BAR_X;
+        let mut current_segment = None;
+        for (i, player) in players.iter().enumerate() {
+            let portion = if all_total_time.is_zero() {
+                0.0
+            } else {
+                player.total_time.as_secs_f32() / all_total_time.as_secs_f32()
+            };
+            let segment_width = bar_width * portion;
+            let segment_height = if i == current_player_index {
+                BAR_HEIGHT * BAR_HEIGHT_CURRENT_TURN_MULTIPLIER
+            } else {
+                BAR_HEIGHT
+            };
+            let segment = mq::Rect::new(
+                x,
+                PIE_Y - segment_height / 2.0,
+                segment_width,
+                segment_height,
+            );
+            mq::draw_rectangle(
+                segment.x,
+                segment.y,
+                segment.w,
+                segment.h,
+                player.display_color,
+            );
+
+            let label = format!("{:.0}%", 100.0 * portion);
+            let label_width = mq::measure_text(&label, None, BAR_LABEL_FONT_SIZE, 1.0).width;
+            if label_width < segment_width {
+                bq::draw_text(
+                    label,
+                    TextAlignment::Left,
+                    None,
+                    BAR_LABEL_FONT_SIZE,
+                    bq::contrasting_text_color(player.display_color),
+                    TextAnchorPoint::Center {
+                        x: segment.center().x,
+                        y: segment.center().y,
+                    },
+                    None,
+                );
+            }
+            if i == current_player_index {
+                current_segment = Some(segment);
+            }
+            x += segment_width;
+        }
+
+        // Outlined last, so the next segment doesn't cover it.
+        if let Some(segment) = current_segment.filter(|segment| segment.w > 0.0) {
+            mq::draw_rectangle_lines(
+                segment.x,
+                segment.y,
+                segment.w,
+                segment.h,
+                BAR_OUTLINE_THICKNESS,
+                mq::WHITE,
+            );
+        }
+    }
+
+    /// A box plot of each player's turn lengths, all on the same scale, so they can be compared
+    /// however many turns each has had.
+    fn draw_turn_lengths(players: &[Player], current_player_index: usize) {
+        let turn_secs: Vec<Vec<f32>> = players
+            .iter()
+            .map(|player| {
+                player
+                    .stats
+                    .turn_durations()
+                    .iter()
+                    .map(Duration::as_secs_f32)
+                    .collect()
+            })
+            .collect();
+        let scale_max = turn_secs.iter().flatten().copied().fold(0.0, f32::max);
+        let label_height = bq::draw_text(
+            format!(
+                "Turn lengths, up to {}",
+                format_duration_stats(Some(Duration::from_secs_f32(scale_max)))
+            ),
+            TextAlignment::Left,
+            None,
+            BAR_LABEL_FONT_SIZE,
+            mq::WHITE,
+            TextAnchorPoint::TopLeft {
+                x: BAR_X,
+                y: TURN_LENGTHS_Y,
+            },
+            None,
+        )
+        .rect_height;
+
+        let lanes_y = TURN_LENGTHS_Y + label_height;
+        let lane_height = (TURN_LENGTHS_BOTTOM - lanes_y) / players.len().max(1) as f32;
+        let box_height = (lane_height * TURN_LENGTHS_BOX_HEIGHT_FRACTION).min(BAR_HEIGHT / 2.0);
+        for (i, (player, secs)) in players.iter().zip(&turn_secs).enumerate() {
+            let lane = mq::Rect::new(
+                BAR_X,
+                lanes_y + lane_height * i as f32,
+                mq::screen_width() - BAR_X * 2.0,
+                lane_height,
+            );
+            if i == current_player_index {
+                mq::draw_rectangle_lines(
+                    lane.x,
+                    lane.y,
+                    lane.w,
+                    lane.h,
+                    BAR_OUTLINE_THICKNESS,
+                    mq::WHITE,
+                );
+            }
+            // Nothing to plot until they've had a turn
+            let Some(stats) = bq::BoxPlotStats::from_values(secs) else {
+                continue;
+            };
+            let plot = mq::Rect::new(
+                lane.x + BAR_OUTLINE_THICKNESS * 2.0,
+                lane.center().y - box_height / 2.0,
+                lane.w - BAR_OUTLINE_THICKNESS * 4.0,
+                box_height,
+            );
+            bq::draw_box_plot(&stats, scale_max, plot, player.display_color);
+        }
+    }
+}
 
-    format!("{hours:02}:{minutes:02}:{seconds:02}")
+/// None if there's no [`NAME_FONT_FILE_NAME`], or nowhere to look for one.
+fn load_name_font() -> Result<Option<mq::Font>, String> {
+    let Some(path) = CONFIG_DIR
+        .app_dir()
+        .map(|dir| dir.join(NAME_FONT_FILE_NAME))
+        .filter(|path| path.exists())
+    else {
+        return Ok(None);
+    };
+    let bytes = fs::read(&path).map_err(|e| format!("Couldn't read {}: {e}", path.display()))?;
+    mq::load_ttf_font_from_bytes(&bytes)
+        .map(Some)
+        .map_err(|e| format!("Couldn't load font {}: {e}", path.display()))
 }
 
-fn format_duration_detailed(duration: Duration) -> String {
-    let total_seconds = duration.as_secs();
-    let hours = total_seconds / 3600;
-    let minutes = (total_seconds % 3600) / 60;
-    let seconds = total_seconds % 60;
-    let hundredths = (100.0 * (duration.as_secs_f32() % 1.0)) as u32;
+fn get_preset_from_key_press(presets: &[Preset]) -> Option<&Preset> {
+    KEYS_SELECT_PRESET
+        .iter()
+        .zip(presets)
+        .find(|(key, _)| mq::is_key_pressed(**key))
+        .map(|(_, preset)| preset)
+}
 
-    format!("{hours:02}:{minutes:02}:{seconds:02}.{hundredths:02.0}")
+/// e.g. "7s", rounded up so it doesn't show 0s before it's over.
+fn format_seconds_remaining(remaining: Duration) -> String {
+    format!("{}s", remaining.as_secs_f32().ceil())
 }
 
 fn format_duration_stats(duration: Option<Duration>) -> String {
@@ -313,6 +2831,15 @@ struct Player {
     display_color: mq::Color,
     total_time: Duration,
     stats: PlayerTurnDurationStats,
+    /// In countdown mode, time given back by increments so far, on top of the budget.
+    time_bonus: Duration,
+    /// In countdown mode, whether they've run out of time.
+    flagged: bool,
+    /// Skipped when the turn passes on, e.g. eliminated or sitting out a round. Their times and
+    /// stats stay on screen.
+    sitting_out: bool,
+    /// Their time in each round, by round index. Rounds after their last turn aren't in it yet.
+    round_times: Vec<Duration>,
 }
 
 impl Player {
@@ -322,18 +2849,52 @@ impl Player {
             display_color,
             total_time: Duration::ZERO,
             stats: PlayerTurnDurationStats::new(),
+            time_bonus: Duration::ZERO,
+            flagged: false,
+            sitting_out: false,
+            round_times: Vec::new(),
         }
     }
 
-    pub(crate) fn tick_frame(&mut self, elapsed_tick_time: Duration) {
+    /// Faded while they're sitting out.
+    pub(crate) fn text_color(&self) -> mq::Color {
+        if self.sitting_out {
+            mq::Color {
+                a: SITTING_OUT_ALPHA,
+                ..self.display_color
+            }
+        } else {
+            self.display_color
+        }
+    }
+
+    pub(crate) fn tick_frame(&mut self, elapsed_tick_time: Duration, round_index: usize) {
         self.total_time += elapsed_tick_time;
         self.stats.tick_frame(elapsed_tick_time);
+        if self.round_times.len() <= round_index {
+            self.round_times.resize(round_index + 1, Duration::ZERO);
+        }
+        self.round_times[round_index] += elapsed_tick_time;
+    }
+
+    /// None until they've had a turn.
+    pub(crate) fn average_turn(&self) -> Option<Duration> {
+        let num_turns = self.stats.num_turns();
+        (num_turns > 0).then(|| self.total_time / num_turns as u32)
+    }
+
+    pub(crate) fn round_time(&self, round_index: usize) -> Duration {
+        self.round_times
+            .get(round_index)
+            .copied()
+            .unwrap_or_default()
     }
 }
 
 struct PlayerTurnDurationStats {
     current_turn_duration: Duration,
-    completed_turn_durations: BinaryHeap<Duration>,
+    /// In the order they were taken.
+    completed_turn_durations: Vec<Duration>,
 }
 
 impl PlayerTurnDurationStats {
@@ -343,18 +2904,34 @@ impl PlayerTurnDurationStats {
     pub(crate) fn new() -> Self {
         Self {
             current_turn_duration: Duration::ZERO,
-            completed_turn_durations: BinaryHeap::new(),
+            completed_turn_durations: Vec::new(),
         }
     }
 
+    /// Whether the turn so far is long enough to count, rather than a quick press to skip it.
+    pub(crate) fn current_turn_counts(&self) -> bool {
+        self.current_turn_duration >= Self::DONT_COUNT_TURN_THRESHOLD
+    }
+
     pub(crate) fn end_turn(&mut self) {
-        if self.current_turn_duration >= Self::DONT_COUNT_TURN_THRESHOLD {
+        if self.current_turn_counts() {
             self.completed_turn_durations
                 .push(self.current_turn_duration);
         }
         self.current_turn_duration = Duration::ZERO;
     }
 
+    /// Undoes [`Self::end_turn`] for a turn that lasted `turn_duration`.
+    pub(crate) fn resume_turn(&mut self, turn_duration: Duration) {
+        if turn_duration >= Self::DONT_COUNT_TURN_THRESHOLD {
+            let turns = &mut self.completed_turn_durations;
+            if let Some(index) = turns.iter().rposition(|turn| *turn == turn_duration) {
+                turns.remove(index);
+            }
+        }
+        self.current_turn_duration = turn_duration;
+    }
+
     pub(crate) fn tick_frame(&mut self, elapsed_tick_time: Duration) {
         self.current_turn_duration += elapsed_tick_time;
     }
@@ -370,7 +2947,7 @@ impl PlayerTurnDurationStats {
     }
 
     pub(crate) fn max_turn(&self) -> Option<Duration> {
-        let opt_max_completed = self.completed_turn_durations.peek();
+        let opt_max_completed = self.completed_turn_durations.iter().max();
         let opt_current = if self.current_turn_duration.is_zero() {
             None
         } else {
@@ -385,19 +2962,30 @@ impl PlayerTurnDurationStats {
         }
     }
 
-    // Sub-optimal, but whatever
-    pub(crate) fn median_turn(&self) -> Option<Duration> {
-        let mut sorted_turns = self.completed_turn_durations.clone();
+    /// The most recently completed one, not counting the one in progress.
+    pub(crate) fn last_turn(&self) -> Option<Duration> {
+        self.completed_turn_durations.last().copied()
+    }
+
+    /// Every turn counted so far, including the one in progress.
+    pub(crate) fn turn_durations(&self) -> Vec<Duration> {
+        let mut turns = self.completed_turn_durations.clone();
         if !self.current_turn_duration.is_zero() {
-            sorted_turns.push(self.current_turn_duration);
+            turns.push(self.current_turn_duration);
         }
-        let sorted_turns_vec = sorted_turns.into_sorted_vec();
+        turns
+    }
+
+    // Sub-optimal, but whatever
+    pub(crate) fn median_turn(&self) -> Option<Duration> {
+        let mut sorted_turns_vec = self.turn_durations();
+        sorted_turns_vec.sort();
 
         if sorted_turns_vec.is_empty() {
             return None;
         }
 
-        if 0 == sorted_turns_vec.len() % 2 {
+        if sorted_turns_vec.len().is_multiple_of(2) {
             // even length
             let median_index_1 = sorted_turns_vec.len() / 2 - 1;
             let median_index_2 = sorted_turns_vec.len() / 2;
@@ -410,22 +2998,83 @@ impl PlayerTurnDurationStats {
     }
 }
 
+/// How opaque the turn start flash is `since` the turn started, fading out evenly. None if it
+/// started in the future, e.g. after the clock was changed.
+fn turn_start_flash_alpha(since: Option<Duration>) -> f32 {
+    let Some(since) = since else {
+        return 0.0;
+    };
+    let left = TURN_START_FLASH_DURATION.saturating_sub(since);
+    TURN_START_FLASH_ALPHA * left.as_secs_f32() / TURN_START_FLASH_DURATION.as_secs_f32()
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
 
     #[test]
-    fn test_format_duration_detailed() {
-        let test_cases = [
-            // (input seconds, expected format)
-            (2.99999, "00:00:02.99"),
-            (3.00000, "00:00:03.00"),
-        ];
-
-        for (input_seconds, expected_output) in test_cases {
-            let input = Duration::from_secs_f64(input_seconds);
-            let actual_output = super::format_duration_detailed(input);
-            assert_eq!(expected_output, &actual_output);
+    fn test_turn_history() {
+        let mut stats = super::PlayerTurnDurationStats::new();
+        assert_eq!(None, stats.last_turn());
+        for seconds in [30, 10, 50, 20] {
+            stats.tick_frame(Duration::from_secs(seconds));
+            stats.end_turn();
         }
+        stats.tick_frame(Duration::from_secs(5));
+
+        // In the order they were taken, without the one in progress
+        assert_eq!(
+            vec![30, 10, 50, 20],
+            stats
+                .completed_turn_durations
+                .iter()
+                .map(Duration::as_secs)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Some(Duration::from_secs(20)), stats.last_turn());
+        assert_eq!(Some(Duration::from_secs(50)), stats.max_turn());
+        assert_eq!(Some(Duration::from_secs(20)), stats.median_turn());
+        assert_eq!(5, stats.num_turns());
+    }
+
+    #[test]
+    fn test_resume_turn() {
+        let mut stats = super::PlayerTurnDurationStats::new();
+        stats.tick_frame(Duration::from_secs(20));
+        stats.end_turn();
+        stats.tick_frame(Duration::from_secs(40));
+        stats.end_turn();
+        assert_eq!(2, stats.num_turns());
+
+        stats.resume_turn(Duration::from_secs(40));
+        assert_eq!(2, stats.num_turns());
+        assert_eq!(
+            vec![Duration::from_secs(20)],
+            stats.completed_turn_durations
+        );
+        stats.tick_frame(Duration::from_secs(5));
+        assert_eq!(Some(Duration::from_secs(45)), stats.max_turn());
+
+        // Too quick to have counted, so there's nothing to take back out
+        stats.end_turn();
+        stats.tick_frame(Duration::from_millis(100));
+        stats.end_turn();
+        stats.resume_turn(Duration::from_millis(100));
+        assert_eq!(3, stats.num_turns());
+    }
+
+    #[test]
+    fn test_turn_start_flash_alpha() {
+        use super::{turn_start_flash_alpha, TURN_START_FLASH_ALPHA};
+        assert_eq!(
+            TURN_START_FLASH_ALPHA,
+            turn_start_flash_alpha(Some(Duration::ZERO))
+        );
+        assert_eq!(
+            TURN_START_FLASH_ALPHA / 2.0,
+            turn_start_flash_alpha(Some(Duration::from_millis(500)))
+        );
+        assert_eq!(0.0, turn_start_flash_alpha(Some(Duration::from_secs(5))));
+        assert_eq!(0.0, turn_start_flash_alpha(None));
     }
 }
@@ -0,0 +1,728 @@
+//! Adding, renaming, recoloring, reordering, and removing players in the app, opened with
+//! [`KEY_EDIT_PLAYERS`](crate::KEY_EDIT_PLAYERS) from preset selection or during a session. During
+//! a session, players can also sit out (e.g. once eliminated) and come back.
+//!
+//! The roster is saved whenever it changes, and loaded on startup. During a session, changes also
+//! apply to the players in it, who keep their times.
+use crate::roster_import::DEFAULT_COLORS;
+use crate::{Player, Screen, TurnTimeTracker, STORAGE};
+use better_quad::bq::{
+    self, BetterKeyCode, TextAlignment, TextAnchorPoint, TextInput, TextInputEvent, Timestamp,
+    ToastKind,
+};
+use macroquad::prelude as mq;
+use serde::{Deserialize, Serialize};
+
+const ROSTER_FILE_NAME: &str = "roster";
+const NUM_DEFAULT_PLAYERS: usize = 4;
+const NAME_MAX_LEN: usize = 24;
+
+// With shift held, these move the selected player instead
+const KEY_SELECT_PREVIOUS: mq::KeyCode = mq::KeyCode::Up;
+const KEY_SELECT_NEXT: mq::KeyCode = mq::KeyCode::Down;
+const KEY_ADD: mq::KeyCode = mq::KeyCode::A;
+const KEY_RENAME: mq::KeyCode = mq::KeyCode::Enter;
+const KEY_PREVIOUS_COLOR: mq::KeyCode = mq::KeyCode::Left;
+const KEY_NEXT_COLOR: mq::KeyCode = mq::KeyCode::Right;
+const KEY_REMOVE: mq::KeyCode = mq::KeyCode::Delete;
+// Press on one player, then another, for when people change seats
+const KEY_SWAP: mq::KeyCode = mq::KeyCode::S;
+const KEY_SIT_OUT: mq::KeyCode = mq::KeyCode::O;
+const KEY_CLOSE: mq::KeyCode = mq::KeyCode::Escape;
+
+const SETUP_FONT_SIZE: u16 = 30;
+const SETUP_X: f32 = 20.0;
+const SETUP_Y: f32 = 20.0;
+const ROW_HEIGHT: f32 = 40.0;
+const ROW_SWATCH_SIZE: f32 = 24.0;
+const SWATCH_SIZE: f32 = 36.0;
+const SWATCH_GAP: f32 = 8.0;
+const SELECTED_OUTLINE_THICKNESS: f32 = 3.0;
+const SWAP_OUTLINE_COLOR: mq::Color = mq::YELLOW;
+const DROP_LINE_THICKNESS: f32 = 4.0;
+
+pub(crate) struct PlayerSetup {
+    selected: usize,
+    /// Some while typing a name.
+    name_input: Option<(NameTarget, TextInput)>,
+    /// Some once the first of two players to swap has been picked.
+    swap_from: Option<usize>,
+    /// Some while a row's being dragged to somewhere else in the order.
+    drag_from: Option<usize>,
+}
+
+#[derive(Copy, Clone)]
+enum NameTarget {
+    NewPlayer,
+    Rename(usize),
+}
+
+pub(crate) enum SetupAction {
+    Edit(PlayerEdit),
+    /// Only for the session, so it's not a roster edit.
+    ToggleSittingOut {
+        index: usize,
+    },
+    Close,
+}
+
+/// Indices are into the roster.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PlayerEdit {
+    Add {
+        name: String,
+        color: mq::Color,
+    },
+    Rename {
+        index: usize,
+        name: String,
+    },
+    Recolor {
+        index: usize,
+        color: mq::Color,
+    },
+    Remove {
+        index: usize,
+    },
+    Swap {
+        a: usize,
+        b: usize,
+    },
+    /// Shifts the ones in between along.
+    Move {
+        from: usize,
+        to: usize,
+    },
+}
+
+/// Saved file format, for the roster and its templates (see `roster_templates`). Colors are RGBA
+/// bytes.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub(crate) struct SavedRoster {
+    players: Vec<(String, [u8; 4])>,
+}
+
+impl SavedRoster {
+    pub(crate) fn new(roster: &[(String, mq::Color)]) -> Self {
+        Self {
+            players: roster
+                .iter()
+                .map(|(name, color)| (name.clone(), (*color).into()))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn into_roster(self) -> Vec<(String, mq::Color)> {
+        self.players
+            .into_iter()
+            .map(|(name, color)| (name, color.into()))
+            .collect()
+    }
+}
+
+impl PlayerSetup {
+    pub(crate) fn new() -> Self {
+        Self {
+            selected: 0,
+            name_input: None,
+            swap_from: None,
+            drag_from: None,
+        }
+    }
+
+    /// Call every frame while it's open, instead of the rest of the app's input.
+    pub(crate) fn update(&mut self, roster: &[(String, mq::Color)]) -> Option<SetupAction> {
+        self.selected = self.selected.min(roster.len().saturating_sub(1));
+
+        if let Some((target, name_input)) = &mut self.name_input {
+            let target = *target;
+            let event = name_input.update()?;
+            self.name_input = None;
+            let TextInputEvent::Submit(name) = event else {
+                return None;
+            };
+            let name = name.trim().to_string();
+            if name.is_empty() {
+                return None;
+            }
+            return Some(SetupAction::Edit(match target {
+                NameTarget::NewPlayer => {
+                    self.selected = roster.len();
+                    PlayerEdit::Add {
+                        name,
+                        color: unused_color(roster),
+                    }
+                }
+                NameTarget::Rename(index) => PlayerEdit::Rename { index, name },
+            }));
+        }
+
+        if mq::is_key_pressed(KEY_CLOSE) {
+            return Some(SetupAction::Close);
+        }
+        if mq::is_key_pressed(KEY_ADD) {
+            self.name_input = Some((NameTarget::NewPlayer, name_input("")));
+            return None;
+        }
+        let last_index = roster.len().saturating_sub(1);
+        let step = if mq::is_key_pressed(KEY_SELECT_PREVIOUS) {
+            Some(self.selected.saturating_sub(1))
+        } else if mq::is_key_pressed(KEY_SELECT_NEXT) {
+            Some((self.selected + 1).min(last_index))
+        } else {
+            None
+        };
+        if let Some(to) = step {
+            let from = self.selected;
+            self.selected = to;
+            if is_shift_down() && from != to {
+                self.swap_from = None;
+                return Some(SetupAction::Edit(PlayerEdit::Move { from, to }));
+            }
+        }
+
+        let mouse_clicked = mq::is_mouse_button_pressed(mq::MouseButton::Left);
+        let mouse_position = mq::Vec2::from(mq::mouse_position());
+        let hovered_row = row_at(roster.len(), mouse_position);
+        if mouse_clicked {
+            if let Some(i) = hovered_row {
+                self.selected = i;
+                self.drag_from = Some(i);
+            }
+        }
+        if mq::is_mouse_button_released(mq::MouseButton::Left) {
+            if let (Some(from), Some(to)) = (self.drag_from.take(), hovered_row) {
+                if from != to {
+                    self.selected = to;
+                    self.swap_from = None;
+                    return Some(SetupAction::Edit(PlayerEdit::Move { from, to }));
+                }
+            }
+        }
+
+        let (name, color) = roster.get(self.selected)?;
+        let index = self.selected;
+        if mq::is_key_pressed(KEY_RENAME) {
+            self.name_input = Some((NameTarget::Rename(index), name_input(name)));
+            None
+        } else if mq::is_key_pressed(KEY_SWAP) {
+            // Picking the same one again cancels
+            match self.swap_from.take() {
+                None => self.swap_from = Some(index),
+                Some(a) if a == index => {}
+                Some(a) => return Some(SetupAction::Edit(PlayerEdit::Swap { a, b: index })),
+            }
+            None
+        } else if mq::is_key_pressed(KEY_SIT_OUT) {
+            Some(SetupAction::ToggleSittingOut { index })
+        } else if mq::is_key_pressed(KEY_REMOVE) {
+            self.swap_from = None;
+            Some(SetupAction::Edit(PlayerEdit::Remove { index }))
+        } else if mq::is_key_pressed(KEY_PREVIOUS_COLOR) || mq::is_key_pressed(KEY_NEXT_COLOR) {
+            let step = if mq::is_key_pressed(KEY_NEXT_COLOR) {
+                1
+            } else {
+                -1
+            };
+            let color = cycle_color(*color, step);
+            Some(SetupAction::Edit(PlayerEdit::Recolor { index, color }))
+        } else if mouse_clicked {
+            // The color picker
+            let color = DEFAULT_COLORS
+                .iter()
+                .enumerate()
+                .find(|(i, _)| swatch_rect(roster.len(), *i).contains(mouse_position))
+                .map(|(_, color)| *color)?;
+            Some(SetupAction::Edit(PlayerEdit::Recolor { index, color }))
+        } else {
+            None
+        }
+    }
+
+    /// Covers the whole window, with the default camera. `sitting_out` is by roster index, and
+    /// empty outside a session.
+    pub(crate) fn draw(&self, roster: &[(String, mq::Color)], sitting_out: &[bool]) {
+        mq::clear_background(mq::DARKGRAY);
+        bq::draw_text(
+            format!("Players ({})", roster.len()),
+            TextAlignment::Left,
+            None,
+            SETUP_FONT_SIZE,
+            mq::WHITE,
+            TextAnchorPoint::TopLeft {
+                x: SETUP_X,
+                y: SETUP_Y,
+            },
+            None,
+        );
+
+        let selected = self.selected.min(roster.len().saturating_sub(1));
+        // Where a dragged row would land: above the hovered row if it's moving up, otherwise below
+        let drop_target = self
+            .drag_from
+            .zip(row_at(roster.len(), mq::Vec2::from(mq::mouse_position())))
+            .filter(|(from, to)| from != to);
+        if let Some((from, to)) = drop_target {
+            let rect = row_rect(to);
+            let y = if to < from { rect.top() } else { rect.bottom() };
+            mq::draw_line(
+                rect.left(),
+                y,
+                rect.right(),
+                y,
+                DROP_LINE_THICKNESS,
+                mq::WHITE,
+            );
+        }
+        for (i, (name, color)) in roster.iter().enumerate() {
+            let rect = row_rect(i);
+            if self.swap_from == Some(i) {
+                mq::draw_rectangle_lines(
+                    rect.x - SELECTED_OUTLINE_THICKNESS,
+                    rect.y - SELECTED_OUTLINE_THICKNESS,
+                    rect.w + SELECTED_OUTLINE_THICKNESS * 2.0,
+                    rect.h + SELECTED_OUTLINE_THICKNESS * 2.0,
+                    SELECTED_OUTLINE_THICKNESS,
+                    SWAP_OUTLINE_COLOR,
+                );
+            }
+            if i == selected {
+                mq::draw_rectangle_lines(
+                    rect.x,
+                    rect.y,
+                    rect.w,
+                    rect.h,
+                    SELECTED_OUTLINE_THICKNESS,
+                    mq::WHITE,
+                );
+            }
+            let swatch_margin = (ROW_HEIGHT - ROW_SWATCH_SIZE) / 2.0;
+            mq::draw_rectangle(
+                rect.x + swatch_margin,
+                rect.y + swatch_margin,
+                ROW_SWATCH_SIZE,
+                ROW_SWATCH_SIZE,
+                *color,
+            );
+            let name = if sitting_out.get(i).copied().unwrap_or(false) {
+                format!("{name} (out)")
+            } else {
+                name.clone()
+            };
+            bq::draw_text(
+                bq::truncate_with_ellipsis(
+                    &name,
+                    None,
+                    SETUP_FONT_SIZE,
+                    rect.w - ROW_HEIGHT - swatch_margin,
+                ),
+                TextAlignment::Left,
+                None,
+                SETUP_FONT_SIZE,
+                *color,
+                TextAnchorPoint::TopLeft {
+                    x: rect.x + ROW_HEIGHT,
+                    y: rect.y + (ROW_HEIGHT - SETUP_FONT_SIZE as f32) / 2.0,
+                },
+                None,
+            );
+        }
+
+        let selected_color = roster.get(selected).map(|(_, color)| *color);
+        for (i, color) in DEFAULT_COLORS.iter().enumerate() {
+            let rect = swatch_rect(roster.len(), i);
+            mq::draw_rectangle(rect.x, rect.y, rect.w, rect.h, *color);
+            if selected_color == Some(*color) {
+                mq::draw_rectangle_lines(
+                    rect.x,
+                    rect.y,
+                    rect.w,
+                    rect.h,
+                    SELECTED_OUTLINE_THICKNESS,
+                    mq::WHITE,
+                );
+            }
+        }
+
+        let below_swatches = swatch_rect(roster.len(), 0).bottom() + ROW_HEIGHT / 2.0;
+        match &self.name_input {
+            Some((_, name_input)) => {
+                name_input.draw(
+                    "Name: ",
+                    SETUP_FONT_SIZE,
+                    TextAnchorPoint::TopLeft {
+                        x: SETUP_X,
+                        y: below_swatches,
+                    },
+                );
+            }
+            None => {
+                let swap_text = match self.swap_from.and_then(|i| roster.get(i)) {
+                    Some((name, _)) => format!(
+                        "[{}] swap seats with {name} (again to cancel)",
+                        KEY_SWAP.to_lowercase()
+                    ),
+                    None => format!("[{}] swap seats", KEY_SWAP.to_lowercase()),
+                };
+                let sit_out_text = if sitting_out.is_empty() {
+                    String::new()
+                } else {
+                    format!("\n[{}] sit out / back in", KEY_SIT_OUT.to_lowercase())
+                };
+                bq::draw_text(
+                    format!(
+                        "[{}] / [{}] select (with shift or drag to move), [{}] add\n\
+                        [{}] rename, [{}] remove\n\
+                        [{}] / [{}] or click to recolor\n{swap_text}{sit_out_text}\n[{}] done",
+                        KEY_SELECT_PREVIOUS.to_lowercase(),
+                        KEY_SELECT_NEXT.to_lowercase(),
+                        KEY_ADD.to_lowercase(),
+                        KEY_RENAME.to_lowercase(),
+                        KEY_REMOVE.to_lowercase(),
+                        KEY_PREVIOUS_COLOR.to_lowercase(),
+                        KEY_NEXT_COLOR.to_lowercase(),
+                        KEY_CLOSE.to_lowercase(),
+                    ),
+                    TextAlignment::Left,
+                    None,
+                    SETUP_FONT_SIZE,
+                    mq::WHITE,
+                    TextAnchorPoint::TopLeft {
+                        x: SETUP_X,
+                        y: below_swatches,
+                    },
+                    None,
+                );
+            }
+        }
+    }
+}
+
+fn name_input(name: &str) -> TextInput {
+    TextInput::new(NAME_MAX_LEN, |_| true).with_text(name)
+}
+
+fn is_shift_down() -> bool {
+    mq::is_key_down(mq::KeyCode::LeftShift) || mq::is_key_down(mq::KeyCode::RightShift)
+}
+
+fn row_at(num_players: usize, position: mq::Vec2) -> Option<usize> {
+    (0..num_players).find(|i| row_rect(*i).contains(position))
+}
+
+fn row_rect(i: usize) -> mq::Rect {
+    mq::Rect::new(
+        SETUP_X,
+        SETUP_Y + ROW_HEIGHT * (i as f32 + 1.0),
+        mq::screen_width() - SETUP_X * 2.0,
+        ROW_HEIGHT,
+    )
+}
+
+/// Swatches of the color picker, in a row under the players.
+fn swatch_rect(num_players: usize, i: usize) -> mq::Rect {
+    mq::Rect::new(
+        SETUP_X + (SWATCH_SIZE + SWATCH_GAP) * i as f32,
+        row_rect(num_players).y + ROW_HEIGHT / 2.0,
+        SWATCH_SIZE,
+        SWATCH_SIZE,
+    )
+}
+
+/// The first default color nobody has, or the first one if they're all taken.
+fn unused_color(roster: &[(String, mq::Color)]) -> mq::Color {
+    DEFAULT_COLORS
+        .into_iter()
+        .find(|color| roster.iter().all(|(_, taken)| taken != color))
+        .unwrap_or(DEFAULT_COLORS[0])
+}
+
+/// `step` default colors on from `color`, wrapping around. Colors that aren't one of them (e.g.
+/// imported) go to the first.
+fn cycle_color(color: mq::Color, step: isize) -> mq::Color {
+    let Some(i) = DEFAULT_COLORS.iter().position(|default| *default == color) else {
+        return DEFAULT_COLORS[0];
+    };
+    DEFAULT_COLORS[(i as isize + step).rem_euclid(DEFAULT_COLORS.len() as isize) as usize]
+}
+
+/// Err if the edit would leave nobody.
+pub(crate) fn edit_roster(
+    roster: &mut Vec<(String, mq::Color)>,
+    edit: &PlayerEdit,
+) -> Result<(), String> {
+    match edit {
+        PlayerEdit::Add { name, color } => roster.push((name.clone(), *color)),
+        PlayerEdit::Rename { index, name } => {
+            if let Some((old_name, _)) = roster.get_mut(*index) {
+                *old_name = name.clone();
+            }
+        }
+        PlayerEdit::Recolor { index, color } => {
+            if let Some((_, old_color)) = roster.get_mut(*index) {
+                *old_color = *color;
+            }
+        }
+        PlayerEdit::Remove { index } => {
+            if roster.len() == 1 {
+                return Err("There has to be at least one player".to_string());
+            }
+            if *index < roster.len() {
+                roster.remove(*index);
+            }
+        }
+        PlayerEdit::Swap { a, b } => {
+            if *a < roster.len() && *b < roster.len() {
+                roster.swap(*a, *b);
+            }
+        }
+        PlayerEdit::Move { from, to } => {
+            if *from < roster.len() && *to < roster.len() {
+                let player = roster.remove(*from);
+                roster.insert(*to, player);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// None if nobody's been saved yet.
+pub(crate) fn load_roster() -> Option<Vec<(String, mq::Color)>> {
+    let roster = STORAGE
+        .load_or_default::<SavedRoster>(ROSTER_FILE_NAME)
+        .into_roster();
+    (!roster.is_empty()).then_some(roster)
+}
+
+pub(crate) fn save_roster(roster: &[(String, mq::Color)]) {
+    STORAGE.save_or_log(ROSTER_FILE_NAME, &SavedRoster::new(roster));
+}
+
+/// Until some players are set up.
+pub(crate) fn default_roster() -> Vec<(String, mq::Color)> {
+    (0..NUM_DEFAULT_PLAYERS)
+        .map(|i| (format!("Player {}", i + 1), DEFAULT_COLORS[i]))
+        .collect()
+}
+
+impl TurnTimeTracker {
+    /// Applies `edit` to the roster and saves it. During a session, the players in it are the
+    /// start of the roster (see `start_session`), so they get the same edit.
+    pub(crate) fn apply_player_edit(&mut self, edit: PlayerEdit) {
+        let in_session = matches!(self.screen, Screen::Tracking { .. });
+        let (players, current_player_index) = self.players.raw();
+        let num_players = players.len();
+        if let PlayerEdit::Remove { index } = edit {
+            if in_session && index < num_players && num_players == 1 {
+                self.toasts.push(
+                    ToastKind::Error,
+                    "Can't remove the only player in the session",
+                );
+                return;
+            }
+        }
+        // Otherwise the session's players wouldn't be the start of the roster any more
+        if let PlayerEdit::Swap { a, b } = edit {
+            if in_session && (a < num_players) != (b < num_players) {
+                self.toasts.push(
+                    ToastKind::Error,
+                    "Only players in the session can swap seats with each other",
+                );
+                return;
+            }
+        }
+        if let PlayerEdit::Move { from, to } = edit {
+            if in_session && (from < num_players) != (to < num_players) {
+                self.toasts.push(
+                    ToastKind::Error,
+                    "Only players in the session can move around in it",
+                );
+                return;
+            }
+        }
+        if let Err(message) = edit_roster(&mut self.roster, &edit) {
+            self.toasts.push(ToastKind::Error, message);
+            return;
+        }
+        save_roster(&self.roster);
+        if !in_session {
+            return;
+        }
+
+        match edit {
+            PlayerEdit::Add { name, color } => {
+                // Only if everyone on the roster is playing, otherwise they'd be out of order
+                if num_players + 1 == self.roster.len() {
+                    self.players.push(Player::new(name, color));
+                    self.turn_log.clear();
+                    if let Some(fairness_alert) = &mut self.fairness_alert {
+                        fairness_alert.add_player();
+                    }
+                }
+            }
+            PlayerEdit::Rename { index, name } => {
+                if let Some(player) = self.players.get_mut(index) {
+                    player.display_name = name;
+                }
+            }
+            PlayerEdit::Recolor { index, color } => {
+                if let Some(player) = self.players.get_mut(index) {
+                    player.display_color = color;
+                }
+            }
+            PlayerEdit::Remove { index } => {
+                if self.players.remove(index).is_none() {
+                    return;
+                }
+                self.turn_log.clear();
+                if let Some(fairness_alert) = &mut self.fairness_alert {
+                    fairness_alert.remove_player(index);
+                }
+                // The next player's turn starts fresh
+                if index == current_player_index {
+                    self.current_phase_index = 0;
+                    self.handoff_remaining = None;
+                    self.turn_held = false;
+                }
+            }
+            // Times and turns go with them, and whoever's turn it is keeps it
+            PlayerEdit::Swap { a, b } => {
+                self.players.swap(a, b);
+                self.turn_log.clear();
+                if let Some(fairness_alert) = &mut self.fairness_alert {
+                    fairness_alert.swap_players(a, b);
+                }
+            }
+            PlayerEdit::Move { from, to } => {
+                self.players.move_item(from, to);
+                self.turn_log.clear();
+                if let Some(fairness_alert) = &mut self.fairness_alert {
+                    fairness_alert.move_player(from, to);
+                }
+            }
+        }
+    }
+}
+
+impl TurnTimeTracker {
+    /// Sitting out is only for the session. If it's their turn, it passes on, and the turn log is
+    /// cleared since undoing would skip differently now.
+    pub(crate) fn toggle_sitting_out(&mut self, index: usize, now: Timestamp) {
+        if !matches!(self.screen, Screen::Tracking { .. }) {
+            self.toasts.push(
+                ToastKind::Error,
+                "Players can only sit out during a session",
+            );
+            return;
+        }
+        let (players, current_player_index) = self.players.raw();
+        let Some(player) = players.get(index) else {
+            self.toasts
+                .push(ToastKind::Error, "Only players in the session can sit out");
+            return;
+        };
+        let sitting_out = !player.sitting_out;
+        let num_playing = players.iter().filter(|player| !player.sitting_out).count();
+        if sitting_out && num_playing == 1 {
+            self.toasts
+                .push(ToastKind::Error, "Someone has to keep playing");
+            return;
+        }
+        let name = player.display_name.clone();
+        if let Some(player) = self.players.get_mut(index) {
+            player.sitting_out = sitting_out;
+        }
+        if sitting_out && index == current_player_index {
+            self.next_turn(now);
+        } else if sitting_out && self.players.is_active(index) {
+            self.end_simultaneous_turn(index);
+        }
+        self.turn_log.clear();
+        let text = if sitting_out {
+            format!("{name} is sitting out")
+        } else {
+            format!("{name} is back in")
+        };
+        self.toasts.push(ToastKind::Info, text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cycle_color, edit_roster, unused_color, PlayerEdit, DEFAULT_COLORS};
+    use macroquad::prelude as mq;
+
+    #[test]
+    fn test_edit_roster() {
+        let mut roster = vec![("Marceline".to_string(), mq::YELLOW)];
+        let edits = [
+            PlayerEdit::Add {
+                name: "Bonnibel".to_string(),
+                color: mq::PINK,
+            },
+            PlayerEdit::Rename {
+                index: 0,
+                name: "Marcy".to_string(),
+            },
+            PlayerEdit::Recolor {
+                index: 1,
+                color: mq::SKYBLUE,
+            },
+            PlayerEdit::Swap { a: 1, b: 0 },
+            PlayerEdit::Add {
+                name: "Finn".to_string(),
+                color: mq::GREEN,
+            },
+            PlayerEdit::Move { from: 2, to: 0 },
+        ];
+        for edit in &edits {
+            assert_eq!(Ok(()), edit_roster(&mut roster, edit));
+        }
+        assert_eq!(
+            vec![
+                ("Finn".to_string(), mq::GREEN),
+                ("Bonnibel".to_string(), mq::SKYBLUE),
+                ("Marcy".to_string(), mq::YELLOW),
+            ],
+            roster
+        );
+
+        assert_eq!(
+            Ok(()),
+            edit_roster(&mut roster, &PlayerEdit::Remove { index: 0 })
+        );
+        assert_eq!(
+            Ok(()),
+            edit_roster(&mut roster, &PlayerEdit::Remove { index: 0 })
+        );
+        assert_eq!(vec![("Marcy".to_string(), mq::YELLOW)], roster);
+        // Out of bounds does nothing
+        assert_eq!(
+            Ok(()),
+            edit_roster(&mut roster, &PlayerEdit::Swap { a: 0, b: 1 })
+        );
+        assert_eq!(vec![("Marcy".to_string(), mq::YELLOW)], roster);
+        // Not the last one
+        assert!(edit_roster(&mut roster, &PlayerEdit::Remove { index: 0 }).is_err());
+        assert_eq!(1, roster.len());
+    }
+
+    #[test]
+    fn test_colors() {
+        let roster = vec![
+            ("Marceline".to_string(), DEFAULT_COLORS[0]),
+            ("Bonnibel".to_string(), DEFAULT_COLORS[2]),
+        ];
+        assert_eq!(DEFAULT_COLORS[1], unused_color(&roster));
+
+        assert_eq!(DEFAULT_COLORS[1], cycle_color(DEFAULT_COLORS[0], 1));
+        assert_eq!(DEFAULT_COLORS[11], cycle_color(DEFAULT_COLORS[0], -1));
+        assert_eq!(DEFAULT_COLORS[0], cycle_color(DEFAULT_COLORS[11], 1));
+        // Imported colors start over
+        assert_eq!(
+            DEFAULT_COLORS[0],
+            cycle_color(mq::Color::from_rgba(1, 2, 3, 255), 1)
+        );
+    }
+}
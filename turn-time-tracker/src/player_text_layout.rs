@@ -0,0 +1,182 @@
+//! Where the player rows go in the standard layout, sized to the window: the font is as big as
+//! fits every row below the chart and the longest row across, and the times line up in a column
+//! after the longest name.
+//!
+//! Text is measured once at [`REFERENCE_FONT_SIZE`], and assumed to grow in proportion to the font
+//! size, which is close enough for fitting.
+use better_quad::bq;
+use macroquad::prelude as mq;
+
+const REFERENCE_FONT_SIZE: u16 = 40;
+/// Bigger than this just looks silly on a big window with a couple of players.
+const MAX_FONT_SIZE: u16 = 60;
+/// Past this, rows overflow the window rather than get unreadable.
+const MIN_FONT_SIZE: u16 = 14;
+/// Space between rows, as a share of the font size.
+const LINE_SPACING_FRACTION: f32 = 0.25;
+/// Names longer than this share of the width are cut short, rather than shrink everyone's text.
+const NAME_MAX_WIDTH_FRACTION: f32 = 0.4;
+const SEPARATOR: &str = ": ";
+/// The outline around the current player's row, as a share of the font size.
+const HIGHLIGHT_PADDING_FRACTION: f32 = 0.15;
+
+/// One row's text, before it's laid out.
+pub(crate) struct PlayerRowText {
+    pub(crate) marker: &'static str,
+    pub(crate) name: String,
+    /// Empty for nothing after the name.
+    pub(crate) info: String,
+}
+
+/// How wide the widest of each column is, at [`REFERENCE_FONT_SIZE`].
+#[derive(Copy, Clone, Debug)]
+struct ColumnWidths {
+    marker: f32,
+    name: f32,
+    separator: f32,
+    info: f32,
+}
+
+pub(crate) struct PlayerTextLayout {
+    pub(crate) font_size: u16,
+    area: mq::Rect,
+    row_height: f32,
+    /// Where names start, after the turn markers.
+    pub(crate) name_x: f32,
+    /// Longer names are cut short to this.
+    pub(crate) name_max_width: f32,
+    /// Where the ": " goes, after the widest name.
+    pub(crate) separator_x: f32,
+    /// Where everyone's info starts, lined up.
+    pub(crate) info_x: f32,
+}
+
+impl PlayerTextLayout {
+    /// Rows from the top of `area` down, in `name_font` for names (or the built-in font).
+    pub(crate) fn new(
+        rows: &[PlayerRowText],
+        name_font: Option<&mq::Font>,
+        area: mq::Rect,
+    ) -> Self {
+        let measure =
+            |text: &str, font| mq::measure_text(text, font, REFERENCE_FONT_SIZE, 1.0).width;
+        let widest = |widths: &mut dyn Iterator<Item = f32>| widths.fold(0.0, f32::max);
+        let widths = ColumnWidths {
+            marker: widest(&mut rows.iter().map(|row| measure(row.marker, None))),
+            name: widest(&mut rows.iter().map(|row| measure(&row.name, name_font))),
+            separator: measure(SEPARATOR, None),
+            info: widest(&mut rows.iter().map(|row| measure(&row.info, None))),
+        };
+        let font_size = fit_font_size(widths, rows.len(), area.w, area.h);
+
+        let scale = f32::from(font_size) / f32::from(REFERENCE_FONT_SIZE);
+        let name_max_width = area.w * NAME_MAX_WIDTH_FRACTION;
+        let name_x = area.x + widths.marker * scale;
+        let separator_x = name_x + (widths.name * scale).min(name_max_width);
+        Self {
+            font_size,
+            area,
+            row_height: f32::from(font_size) * (1.0 + LINE_SPACING_FRACTION),
+            name_x,
+            name_max_width,
+            separator_x,
+            info_x: separator_x + widths.separator * scale,
+        }
+    }
+
+    pub(crate) fn marker_x(&self) -> f32 {
+        self.area.x
+    }
+
+    /// Where row `i`'s text sits.
+    pub(crate) fn baseline_y(&self, i: usize) -> f32 {
+        self.row_top(i) + f32::from(self.font_size)
+    }
+
+    fn row_top(&self, i: usize) -> f32 {
+        self.area.y + self.row_height * i as f32
+    }
+
+    /// Around row `i`'s text, which ends at `line_end_x`, for outlining the current player.
+    pub(crate) fn highlight_rect(&self, i: usize, line_end_x: f32) -> mq::Rect {
+        let padding = f32::from(self.font_size) * HIGHLIGHT_PADDING_FRACTION;
+        mq::Rect::new(
+            self.area.x - padding,
+            self.row_top(i) - padding,
+            line_end_x - self.area.x + padding * 2.0,
+            self.row_height + padding,
+        )
+    }
+
+    /// The row at `y`, if any, with the spacing below each row counting as part of it.
+    pub(crate) fn row_at(&self, y: f32, num_rows: usize) -> Option<usize> {
+        if y < self.area.y {
+            return None;
+        }
+        let i = ((y - self.area.y) / self.row_height) as usize;
+        (i < num_rows).then_some(i)
+    }
+}
+
+/// As big as fits `num_rows` rows of text as wide as `widths` in `width` by `height`, with names
+/// over [`NAME_MAX_WIDTH_FRACTION`] of the width cut short rather than fitted.
+fn fit_font_size(widths: ColumnWidths, num_rows: usize, width: f32, height: f32) -> u16 {
+    let reference = f32::from(REFERENCE_FONT_SIZE);
+    let rest_width = widths.marker + widths.separator + widths.info;
+    let everything_fits = reference * width / (rest_width + widths.name);
+    let by_width = if widths.name * everything_fits / reference > width * NAME_MAX_WIDTH_FRACTION {
+        // The name column's capped, so the rest gets the remaining width
+        reference * width * (1.0 - NAME_MAX_WIDTH_FRACTION) / rest_width
+    } else {
+        everything_fits
+    };
+    let by_height = height / (num_rows.max(1) as f32 * (1.0 + LINE_SPACING_FRACTION));
+    let font_size = by_width.min(by_height).min(f32::from(MAX_FONT_SIZE));
+    (font_size.max(0.0) as u16).max(MIN_FONT_SIZE)
+}
+
+/// `name` cut short to fit `layout`'s name column.
+pub(crate) fn fit_name(
+    name: &str,
+    name_font: Option<&mq::Font>,
+    layout: &PlayerTextLayout,
+) -> String {
+    bq::truncate_with_ellipsis(name, name_font, layout.font_size, layout.name_max_width)
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fit_font_size, ColumnWidths, MAX_FONT_SIZE, MIN_FONT_SIZE};
+
+    /// Roughly "[X] ", "Marceline", ": ", and "00:12:34 (25%)" at the reference size.
+    const WIDTHS: ColumnWidths = ColumnWidths {
+        marker: 60.0,
+        name: 180.0,
+        separator: 20.0,
+        info: 240.0,
+    };
+
+    #[test]
+    fn test_fit_font_size() {
+        // Lots of room, so it's capped
+        assert_eq!(MAX_FONT_SIZE, fit_font_size(WIDTHS, 2, 2000.0, 1000.0));
+        // Half as wide as the text at the reference size
+        assert_eq!(20, fit_font_size(WIDTHS, 2, 250.0, 1000.0));
+        // 8 rows in 400 tall, with line spacing
+        assert_eq!(40, fit_font_size(WIDTHS, 8, 2000.0, 400.0));
+        // Never unreadable, even with no room
+        assert_eq!(MIN_FONT_SIZE, fit_font_size(WIDTHS, 20, 100.0, 0.0));
+    }
+
+    #[test]
+    fn test_long_names_cut_short() {
+        let widths = ColumnWidths {
+            name: 2000.0,
+            ..WIDTHS
+        };
+        // The rest (320 wide at the reference size) gets 60% of the width, so the long name
+        // doesn't shrink everything
+        assert_eq!(30, fit_font_size(widths, 2, 400.0, 1000.0));
+    }
+}
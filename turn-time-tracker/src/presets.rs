@@ -0,0 +1,254 @@
+//! Game presets that configure the clock mode, turn limits, and phases in one go.
+//!
+//! Built-in presets live in `presets/*.toml` and are compiled in. Users can add their own by
+//! dropping more toml files (same format) into the app's "presets" config dir (see `bq_config`).
+use crate::CONFIG_DIR;
+use better_quad::bq::{ConfigFile, LoadedConfigs};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const PRESETS_CONFIG_KIND: &str = "presets";
+const BUILT_IN_PRESETS: [(&str, &str); 6] = [
+    (
+        "1-free-play.toml",
+        include_str!("../presets/1-free-play.toml"),
+    ),
+    ("2-chess.toml", include_str!("../presets/2-chess.toml")),
+    ("3-dnd.toml", include_str!("../presets/3-dnd.toml")),
+    ("4-catan.toml", include_str!("../presets/4-catan.toml")),
+    ("5-draft.toml", include_str!("../presets/5-draft.toml")),
+    ("6-blitz.toml", include_str!("../presets/6-blitz.toml")),
+];
+
+/// Raw preset file format.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Preset {
+    pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) description: String,
+    /// Only the first N players of the roster play.
+    pub(crate) max_players: Option<usize>,
+    /// Each player gets this much time in total, counting down (chess clock style).
+    pub(crate) countdown_secs: Option<u64>,
+    /// With a countdown, time given back at the end of each turn (see `increment_style`).
+    pub(crate) increment_secs: Option<u64>,
+    #[serde(default)]
+    pub(crate) increment_style: IncrementStyle,
+    /// Soft limit for a single turn.
+    pub(crate) turn_limit_secs: Option<u64>,
+    /// Buffer between turns that counts towards nobody, for passing dice, drawing cards, etc.
+    pub(crate) handoff_secs: Option<u64>,
+    /// Draft mode: each turn passes to the next player by itself after this long, unless held.
+    pub(crate) auto_advance_secs: Option<u64>,
+    #[serde(default)]
+    pub(crate) phases: Vec<String>,
+    /// Shows a banner when a player's share of the total time reaches this percent.
+    pub(crate) fairness_alert_percent: Option<u8>,
+    /// Also plays a chime when the fairness alert goes off.
+    #[serde(default)]
+    pub(crate) fairness_alert_sound: bool,
+    /// Plays a short tone whenever the turn passes to someone else.
+    #[serde(default)]
+    pub(crate) turn_start_sound: bool,
+}
+
+/// What the soft turn limit can be set to during a session, in seconds, besides off.
+const TURN_LIMIT_STEPS_SECS: [u64; 6] = [30, 60, 90, 120, 180, 300];
+
+/// The next soft turn limit up from `turn_limit_secs`, going back to off after the longest.
+pub(crate) fn next_turn_limit_secs(turn_limit_secs: Option<u64>) -> Option<u64> {
+    let current = turn_limit_secs.unwrap_or(0);
+    TURN_LIMIT_STEPS_SECS
+        .into_iter()
+        .find(|step| *step > current)
+}
+
+/// Rules for the session, derived from the selected preset.
+pub(crate) struct SessionRules {
+    pub(crate) preset_name: String,
+    pub(crate) clock_mode: ClockMode,
+    pub(crate) turn_limit: Option<Duration>,
+    pub(crate) handoff: Option<Duration>,
+    pub(crate) auto_advance: Option<Duration>,
+    pub(crate) phases: Vec<String>,
+    /// Fraction of the total time, e.g. 0.4.
+    pub(crate) fairness_threshold: Option<f32>,
+    pub(crate) fairness_alert_sound: bool,
+    pub(crate) turn_start_sound: bool,
+}
+
+#[derive(Copy, Clone)]
+pub(crate) enum ClockMode {
+    CountUp,
+    Countdown {
+        budget: Duration,
+        increment: Option<Increment>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Default, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum IncrementStyle {
+    /// The whole increment after every turn, so quick turns bank time.
+    #[default]
+    Fischer,
+    /// Up to the increment, but no more than the turn took, so time can't be banked.
+    Bronstein,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct Increment {
+    pub(crate) style: IncrementStyle,
+    pub(crate) amount: Duration,
+}
+
+impl Increment {
+    /// Time given back for a turn that took `turn_duration`.
+    pub(crate) fn earned(self, turn_duration: Duration) -> Duration {
+        match self.style {
+            IncrementStyle::Fischer => self.amount,
+            IncrementStyle::Bronstein => self.amount.min(turn_duration),
+        }
+    }
+}
+
+impl Default for SessionRules {
+    fn default() -> Self {
+        Self {
+            preset_name: "Free play".to_string(),
+            clock_mode: ClockMode::CountUp,
+            turn_limit: None,
+            handoff: None,
+            auto_advance: None,
+            phases: Vec::new(),
+            fairness_threshold: None,
+            fairness_alert_sound: false,
+            turn_start_sound: false,
+        }
+    }
+}
+
+impl Preset {
+    pub(crate) fn to_rules(&self) -> SessionRules {
+        SessionRules {
+            preset_name: self.name.clone(),
+            clock_mode: match self.countdown_secs {
+                None => ClockMode::CountUp,
+                Some(secs) => ClockMode::Countdown {
+                    budget: Duration::from_secs(secs),
+                    increment: self.increment_secs.map(|increment_secs| Increment {
+                        style: self.increment_style,
+                        amount: Duration::from_secs(increment_secs),
+                    }),
+                },
+            },
+            turn_limit: self.turn_limit_secs.map(Duration::from_secs),
+            handoff: self.handoff_secs.map(Duration::from_secs),
+            auto_advance: self.auto_advance_secs.map(Duration::from_secs),
+            phases: self.phases.clone(),
+            fairness_threshold: self
+                .fairness_alert_percent
+                .map(|percent| f32::from(percent) / 100.0),
+            fairness_alert_sound: self.fairness_alert_sound,
+            turn_start_sound: self.turn_start_sound,
+        }
+    }
+}
+
+impl ConfigFile for Preset {
+    fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("name must not be empty".to_string());
+        }
+        if self.max_players == Some(0) {
+            return Err("max_players must be at least 1".to_string());
+        }
+        if self.countdown_secs == Some(0) {
+            return Err("countdown_secs must be positive".to_string());
+        }
+        if self.increment_secs.is_some() && self.countdown_secs.is_none() {
+            return Err("increment_secs needs countdown_secs".to_string());
+        }
+        if self.increment_secs == Some(0) {
+            return Err("increment_secs must be positive".to_string());
+        }
+        if self.turn_limit_secs == Some(0) {
+            return Err("turn_limit_secs must be positive".to_string());
+        }
+        if self.handoff_secs == Some(0) {
+            return Err("handoff_secs must be positive".to_string());
+        }
+        if self.auto_advance_secs == Some(0) {
+            return Err("auto_advance_secs must be positive".to_string());
+        }
+        if self
+            .fairness_alert_percent
+            .is_some_and(|percent| !(1..100).contains(&percent))
+        {
+            return Err("fairness_alert_percent must be from 1 to 99".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Built-in presets first, then the user's presets sorted by file name.
+pub(crate) fn load_presets() -> LoadedConfigs<Preset> {
+    CONFIG_DIR.load_all(PRESETS_CONFIG_KIND, &BUILT_IN_PRESETS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{next_turn_limit_secs, Increment, IncrementStyle, Preset, BUILT_IN_PRESETS};
+    use better_quad::bq;
+    use std::time::Duration;
+
+    #[test]
+    fn test_next_turn_limit_secs() {
+        assert_eq!(Some(30), next_turn_limit_secs(None));
+        assert_eq!(Some(60), next_turn_limit_secs(Some(30)));
+        // Presets can set limits between the steps
+        assert_eq!(Some(60), next_turn_limit_secs(Some(45)));
+        assert_eq!(None, next_turn_limit_secs(Some(300)));
+        assert_eq!(None, next_turn_limit_secs(Some(600)));
+    }
+
+    #[test]
+    fn test_built_in_presets_parse() {
+        for (file_name, contents) in BUILT_IN_PRESETS {
+            let parsed = bq::parse_config::<Preset>(contents);
+            assert!(parsed.is_ok(), "{file_name}: {parsed:?}");
+        }
+    }
+
+    #[test]
+    fn test_increment() {
+        let preset = bq::parse_config::<Preset>(
+            "name = \"Blitz\"\n\
+            countdown_secs = 180\n\
+            increment_secs = 2\n\
+            increment_style = \"bronstein\"\n",
+        );
+        assert_eq!(
+            Ok(IncrementStyle::Bronstein),
+            preset.map(|p| p.increment_style)
+        );
+        assert!(bq::parse_config::<Preset>("name = \"Blitz\"\nincrement_secs = 2").is_err());
+
+        let amount = Duration::from_secs(2);
+        let fischer = Increment {
+            style: IncrementStyle::Fischer,
+            amount,
+        };
+        let bronstein = Increment {
+            style: IncrementStyle::Bronstein,
+            amount,
+        };
+        let quick_turn = Duration::from_millis(500);
+        let slow_turn = Duration::from_secs(30);
+        assert_eq!(amount, fischer.earned(quick_turn));
+        assert_eq!(amount, fischer.earned(slow_turn));
+        assert_eq!(quick_turn, bronstein.earned(quick_turn));
+        assert_eq!(amount, bronstein.earned(slow_turn));
+    }
+}
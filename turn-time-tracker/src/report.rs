@@ -0,0 +1,456 @@
+//! Markdown reports on the sessions logged over the last week or month: total time, per-player
+//! stats, and the most played games. Saved as exports, for pasting into a group chat or email.
+//!
+//! There's also a summary of a single session, for sharing it right after it ends, the awards
+//! shown on its podium, and totals for the day it was played on, for back-to-back games.
+use crate::format_duration_stats;
+use crate::session_log::{PlayerRecord, SessionRecord};
+use crate::time_format::TimeFormat;
+use better_quad::bq::UtcDate;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) enum ReportPeriod {
+    Week,
+    Month,
+}
+
+impl ReportPeriod {
+    fn num_days(self) -> u64 {
+        match self {
+            Self::Week => 7,
+            Self::Month => 30,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Week => "weekly",
+            Self::Month => "monthly",
+        }
+    }
+
+    /// e.g. "turn-time-weekly-2025-08-07.md"
+    pub(crate) fn file_name(self, today: u64) -> String {
+        format!(
+            "turn-time-{}-{}.md",
+            self.name(),
+            UtcDate::from_day_number(today)
+        )
+    }
+}
+
+#[derive(Default, Debug, PartialEq)]
+pub(crate) struct PlayerTotals {
+    pub(crate) num_sessions: usize,
+    pub(crate) num_turns: usize,
+    pub(crate) total_time: Duration,
+}
+
+#[derive(Default)]
+struct GameTotals {
+    num_sessions: usize,
+    total_time: Duration,
+}
+
+/// Covers the `period` ending on `today` (a UTC day number), inclusive.
+pub(crate) fn build_report(
+    sessions: &[SessionRecord],
+    period: ReportPeriod,
+    today: u64,
+    time_format: TimeFormat,
+) -> String {
+    let first_day = (today + 1).saturating_sub(period.num_days());
+    let sessions = sessions
+        .iter()
+        .filter(|session| (first_day..=today).contains(&session.day_number))
+        .collect::<Vec<_>>();
+
+    let mut report = format!(
+        "# Turn time report: last {} days\n\n{} to {}\n\n",
+        period.num_days(),
+        UtcDate::from_day_number(first_day),
+        UtcDate::from_day_number(today),
+    );
+    if sessions.is_empty() {
+        report.push_str("No sessions.\n");
+        return report;
+    }
+    let total_time = sessions
+        .iter()
+        .map(|session| session.total_time())
+        .sum::<Duration>();
+    report.push_str(&format!(
+        "Sessions: {}\nTotal time: {}\n",
+        sessions.len(),
+        time_format.format(total_time)
+    ));
+
+    let mut games = BTreeMap::<&str, GameTotals>::new();
+    for session in &sessions {
+        let totals = games.entry(&session.preset_name).or_default();
+        totals.num_sessions += 1;
+        totals.total_time += session.total_time();
+    }
+
+    let players = player_totals(&sessions);
+    report.push_str(
+        "\n## Players\n\n\
+        | Player | Sessions | Turns | Total time | Average turn |\n\
+        | --- | --- | --- | --- | --- |\n",
+    );
+    for (name, totals) in players {
+        let average_turn =
+            (totals.num_turns > 0).then(|| totals.total_time / totals.num_turns as u32);
+        report.push_str(&format!(
+            "| {name} | {} | {} | {} | {} |\n",
+            totals.num_sessions,
+            totals.num_turns,
+            time_format.format(totals.total_time),
+            format_duration_stats(average_turn),
+        ));
+    }
+
+    // Most sessions first, then most time
+    let mut games = games.into_iter().collect::<Vec<_>>();
+    games.sort_by(|(_, a), (_, b)| {
+        (b.num_sessions, b.total_time).cmp(&(a.num_sessions, a.total_time))
+    });
+    report.push_str(
+        "\n## Most played games\n\n\
+        | Game | Sessions | Total time |\n\
+        | --- | --- | --- |\n",
+    );
+    for (name, totals) in games {
+        report.push_str(&format!(
+            "| {name} | {} | {} |\n",
+            totals.num_sessions,
+            time_format.format(totals.total_time),
+        ));
+    }
+    report
+}
+
+/// Each player's totals across `sessions`, most time first. Keyed by name, since that's all that
+/// identifies a player across sessions.
+pub(crate) fn player_totals<'a>(sessions: &[&'a SessionRecord]) -> Vec<(&'a str, PlayerTotals)> {
+    let mut players = BTreeMap::<&str, PlayerTotals>::new();
+    for session in sessions {
+        for player in &session.players {
+            let totals = players.entry(&player.name).or_default();
+            totals.num_sessions += 1;
+            totals.num_turns += player.num_turns;
+            totals.total_time += Duration::from_millis(player.total_millis);
+        }
+    }
+    let mut players = players.into_iter().collect::<Vec<_>>();
+    players.sort_by_key(|(_, totals)| Reverse(totals.total_time));
+    players
+}
+
+/// The sessions logged on `day_number`, oldest first.
+pub(crate) fn sessions_on_day(sessions: &[SessionRecord], day_number: u64) -> Vec<&SessionRecord> {
+    sessions
+        .iter()
+        .filter(|session| session.day_number == day_number)
+        .collect()
+}
+
+/// One session's total time and each player's share of it.
+pub(crate) fn build_session_summary(session: &SessionRecord, time_format: TimeFormat) -> String {
+    let players_millis = session
+        .players
+        .iter()
+        .map(|player| player.total_millis)
+        .sum::<u64>();
+    let mut summary = format!(
+        "# {}: {}\n\nTotal time: {}\n",
+        session.preset_name,
+        UtcDate::from_day_number(session.day_number),
+        time_format.format(session.total_time()),
+    );
+    if session.handoff_millis > 0 {
+        summary.push_str(&format!(
+            "Hand-offs: {}\n",
+            time_format.format(Duration::from_millis(session.handoff_millis))
+        ));
+    }
+    if session.interrupt_millis > 0 {
+        summary.push_str(&format!(
+            "Interruptions: {}\n",
+            time_format.format(Duration::from_millis(session.interrupt_millis))
+        ));
+    }
+    if session.paused_millis > 0 {
+        summary.push_str(&format!(
+            "Paused: {} (wall time {})\n",
+            time_format.format(Duration::from_millis(session.paused_millis)),
+            time_format.format(session.wall_time()),
+        ));
+    }
+    summary.push_str(
+        "\n| Player | Turns | Total time | Share | Average turn |\n\
+        | --- | --- | --- | --- | --- |\n",
+    );
+    for player in &session.players {
+        let total_time = Duration::from_millis(player.total_millis);
+        let average_turn = (player.num_turns > 0).then(|| total_time / player.num_turns as u32);
+        summary.push_str(&format!(
+            "| {} | {} | {} | {:.0}% | {} |\n",
+            player.name,
+            player.num_turns,
+            time_format.format(total_time),
+            share_percent(player.total_millis, players_millis),
+            format_duration_stats(average_turn),
+        ));
+    }
+    summary
+}
+
+/// Superlatives for the podium after a session: (award, "winner (value)"). Players without turns
+/// don't win anything, and ties go to whoever went first.
+pub(crate) fn podium_awards(
+    session: &SessionRecord,
+    time_format: TimeFormat,
+) -> Vec<(&'static str, String)> {
+    let players = session
+        .players
+        .iter()
+        .filter(|player| player.num_turns > 0)
+        .collect::<Vec<_>>();
+    let average_turn = |player: &PlayerRecord| {
+        Duration::from_millis(player.total_millis) / player.num_turns as u32
+    };
+    // `min_by_key` keeps the first of equals, `max_by_key` the last
+    let fastest = players.iter().min_by_key(|player| average_turn(player));
+    let slowest = players
+        .iter()
+        .min_by_key(|player| Reverse(average_turn(player)));
+    let most_turns = players
+        .iter()
+        .min_by_key(|player| Reverse(player.num_turns));
+    let most_time = players
+        .iter()
+        .min_by_key(|player| Reverse(player.total_millis));
+    [
+        fastest.map(|player| {
+            (
+                "Fastest average turn",
+                format!(
+                    "{} ({})",
+                    player.name,
+                    format_duration_stats(Some(average_turn(player)))
+                ),
+            )
+        }),
+        slowest.map(|player| {
+            (
+                "Deepest thinker",
+                format!(
+                    "{} ({})",
+                    player.name,
+                    format_duration_stats(Some(average_turn(player)))
+                ),
+            )
+        }),
+        most_turns.map(|player| {
+            (
+                "Most turns",
+                format!("{} ({})", player.name, player.num_turns),
+            )
+        }),
+        most_time.map(|player| {
+            (
+                "Most time at the table",
+                format!(
+                    "{} ({})",
+                    player.name,
+                    time_format.format(Duration::from_millis(player.total_millis))
+                ),
+            )
+        }),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// e.g. "turn-time-session-2025-08-07-2.md" for the 2nd session that day.
+pub(crate) fn session_file_name(day_number: u64, nth_of_day: usize) -> String {
+    format!("{}.md", session_file_stem(day_number, nth_of_day))
+}
+
+/// e.g. "turn-time-session-2025-08-07-2", for the summary and the data exports.
+pub(crate) fn session_file_stem(day_number: u64, nth_of_day: usize) -> String {
+    format!(
+        "turn-time-session-{}-{nth_of_day}",
+        UtcDate::from_day_number(day_number)
+    )
+}
+
+/// Of all players' time, not counting hand-offs. 0 if there's no time at all.
+pub(crate) fn share_percent(player_millis: u64, players_millis: u64) -> f32 {
+    if players_millis == 0 {
+        0.0
+    } else {
+        100.0 * player_millis as f32 / players_millis as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        build_report, build_session_summary, player_totals, podium_awards, sessions_on_day,
+        PlayerTotals, ReportPeriod, TimeFormat,
+    };
+    use crate::session_log::{PlayerRecord, SessionRecord};
+    use std::time::Duration;
+
+    fn session(
+        preset_name: &str,
+        day_number: u64,
+        players: &[(&str, u64, usize)],
+    ) -> SessionRecord {
+        SessionRecord {
+            preset_name: preset_name.to_string(),
+            day_number,
+            handoff_millis: 0,
+            interrupt_millis: 0,
+            paused_millis: 0,
+            players: players
+                .iter()
+                .map(|(name, total_secs, num_turns)| PlayerRecord {
+                    name: name.to_string(),
+                    total_millis: total_secs * 1000,
+                    num_turns: *num_turns,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_build_report() {
+        let today = 20_310;
+        let sessions = [
+            // Too long ago for a weekly report
+            session("Catan", today - 7, &[("Leo", 3600, 10)]),
+            session("Catan", today - 6, &[("Leo", 600, 10), ("Tiger", 1200, 10)]),
+            session("Chess", today, &[("Leo", 60, 2)]),
+            session("Catan", today, &[("Tiger", 60, 1)]),
+        ];
+        let report = build_report(&sessions, ReportPeriod::Week, today, TimeFormat::default());
+        assert_eq!(
+            "# Turn time report: last 7 days\n\
+            \n\
+            2025-08-04 to 2025-08-10\n\
+            \n\
+            Sessions: 3\n\
+            Total time: 00:32:00\n\
+            \n\
+            ## Players\n\
+            \n\
+            | Player | Sessions | Turns | Total time | Average turn |\n\
+            | --- | --- | --- | --- | --- |\n\
+            | Tiger | 2 | 11 | 00:21:00 | 01:54 |\n\
+            | Leo | 2 | 12 | 00:11:00 | 00:55 |\n\
+            \n\
+            ## Most played games\n\
+            \n\
+            | Game | Sessions | Total time |\n\
+            | --- | --- | --- |\n\
+            | Catan | 2 | 00:31:00 |\n\
+            | Chess | 1 | 00:01:00 |\n",
+            report
+        );
+
+        let report = build_report(&sessions, ReportPeriod::Month, today, TimeFormat::default());
+        assert!(report.contains("Sessions: 4\n"));
+        assert!(
+            build_report(&[], ReportPeriod::Month, today, TimeFormat::default())
+                .ends_with("No sessions.\n")
+        );
+    }
+
+    #[test]
+    fn test_day_player_totals() {
+        let today = 20_310;
+        let sessions = [
+            session("Catan", today - 1, &[("Leo", 3600, 10)]),
+            session("Catan", today, &[("Leo", 600, 10), ("Tiger", 1200, 8)]),
+            session("Azul", today, &[("Leo", 900, 5)]),
+        ];
+        let day = sessions_on_day(&sessions, today);
+        assert_eq!(2, day.len());
+        assert_eq!(
+            vec![
+                (
+                    "Leo",
+                    PlayerTotals {
+                        num_sessions: 2,
+                        num_turns: 15,
+                        total_time: Duration::from_secs(1500),
+                    }
+                ),
+                (
+                    "Tiger",
+                    PlayerTotals {
+                        num_sessions: 1,
+                        num_turns: 8,
+                        total_time: Duration::from_secs(1200),
+                    }
+                ),
+            ],
+            player_totals(&day)
+        );
+    }
+
+    #[test]
+    fn test_build_session_summary() {
+        let mut session = session("Catan", 20_310, &[("Leo", 600, 4), ("Tiger", 1800, 0)]);
+        session.handoff_millis = 30_000;
+        session.interrupt_millis = 90_000;
+        session.paused_millis = 600_000;
+        assert_eq!(
+            "# Catan: 2025-08-10\n\
+            \n\
+            Total time: 00:42:00\n\
+            Hand-offs: 00:00:30\n\
+            Interruptions: 00:01:30\n\
+            Paused: 00:10:00 (wall time 00:52:00)\n\
+            \n\
+            | Player | Turns | Total time | Share | Average turn |\n\
+            | --- | --- | --- | --- | --- |\n\
+            | Leo | 4 | 00:10:00 | 25% | 02:30 |\n\
+            | Tiger | 0 | 00:30:00 | 75% | 00:00 |\n",
+            build_session_summary(&session, TimeFormat::default())
+        );
+    }
+
+    #[test]
+    fn test_podium_awards() {
+        let catan = session(
+            "Catan",
+            20_310,
+            &[
+                ("Leo", 600, 4),
+                ("Tiger", 900, 3),
+                ("Dorian", 300, 4),
+                ("Bo", 0, 0),
+            ],
+        );
+        assert_eq!(
+            vec![
+                ("Fastest average turn", "Dorian (01:15)".to_string()),
+                ("Deepest thinker", "Tiger (05:00)".to_string()),
+                // Tied with Dorian, but went first
+                ("Most turns", "Leo (4)".to_string()),
+                ("Most time at the table", "Tiger (00:15:00)".to_string()),
+            ],
+            podium_awards(&catan, TimeFormat::default())
+        );
+        let no_turns = session("Catan", 20_310, &[("Bo", 0, 0)]);
+        assert!(podium_awards(&no_turns, TimeFormat::default()).is_empty());
+    }
+}
@@ -0,0 +1,215 @@
+//! Setting up the roster from a list of names, pasted from the clipboard or read from
+//! `roster.csv` in the app's config dir. One player per line, with an optional hex color after a
+//! comma or tab (e.g. `Marceline, #ffff00`), or as the last word (`Marceline #ff0`).
+//!
+//! Imports are shown for confirmation before they replace the roster.
+use crate::{TurnTimeTracker, CONFIG_DIR};
+use better_quad::bq::{self, TextAlignment, TextAnchorPoint};
+use macroquad::prelude as mq;
+use std::fs;
+
+pub(crate) const ROSTER_FILE_NAME: &str = "roster.csv";
+const PREVIEW_FONT_SIZE: u16 = 30;
+const PREVIEW_X: f32 = 20.0;
+const PREVIEW_Y: f32 = 20.0;
+/// Players without a color get these, in order.
+pub(crate) const DEFAULT_COLORS: [mq::Color; 12] = [
+    mq::YELLOW,
+    mq::SKYBLUE,
+    mq::PINK,
+    mq::GREEN,
+    mq::ORANGE,
+    mq::VIOLET,
+    mq::RED,
+    mq::LIME,
+    mq::GOLD,
+    mq::MAGENTA,
+    mq::BEIGE,
+    mq::WHITE,
+];
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct ImportedRoster {
+    pub(crate) players: Vec<(String, mq::Color)>,
+    /// Lines that couldn't be read, with why.
+    pub(crate) skipped_lines: Vec<String>,
+}
+
+pub(crate) fn parse_roster(text: &str) -> ImportedRoster {
+    let mut imported = ImportedRoster {
+        players: Vec::new(),
+        skipped_lines: Vec::new(),
+    };
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        // Spreadsheets like to add a header
+        let is_header = i == 0 && ["name", "name,color"].contains(&line.to_lowercase().as_str());
+        if line.is_empty() || is_header {
+            continue;
+        }
+
+        let (name, color) = split_name_and_color(line);
+        let name = name.trim().trim_matches('"').trim();
+        if name.is_empty() {
+            imported
+                .skipped_lines
+                .push(format!("line {}: missing name", i + 1));
+            continue;
+        }
+        let color = match color.map(str::trim).filter(|color| !color.is_empty()) {
+            None => DEFAULT_COLORS[imported.players.len() % DEFAULT_COLORS.len()],
+            Some(color) => match bq::parse_hex_color(color) {
+                Some(color) => color,
+                None => {
+                    imported
+                        .skipped_lines
+                        .push(format!("line {}: bad color \"{color}\"", i + 1));
+                    continue;
+                }
+            },
+        };
+        imported.players.push((name.to_string(), color));
+    }
+    imported
+}
+
+fn split_name_and_color(line: &str) -> (&str, Option<&str>) {
+    if let Some((name, color)) = line.split_once([',', '\t']) {
+        return (name, Some(color));
+    }
+    match line.rsplit_once(char::is_whitespace) {
+        Some((name, color)) if color.starts_with('#') => (name, Some(color)),
+        _ => (line, None),
+    }
+}
+
+/// Contents of `roster.csv`, or why it couldn't be read.
+pub(crate) fn read_roster_file() -> Result<String, String> {
+    let path = CONFIG_DIR
+        .app_dir()
+        .ok_or("No config dir on this platform")?
+        .join(ROSTER_FILE_NAME);
+    fs::read_to_string(&path).map_err(|e| format!("Couldn't read {}: {e}", path.display()))
+}
+
+impl TurnTimeTracker {
+    pub(crate) fn draw_roster_preview(imported: &ImportedRoster) {
+        mq::clear_background(mq::DARKGRAY);
+
+        let mut header = format!("Import {} players?\n", imported.players.len());
+        for skipped_line in &imported.skipped_lines {
+            header.push_str(&format!("Skipped {skipped_line}\n"));
+        }
+        let header_container = bq::draw_text(
+            header,
+            TextAlignment::Left,
+            None,
+            PREVIEW_FONT_SIZE,
+            mq::WHITE,
+            TextAnchorPoint::TopLeft {
+                x: PREVIEW_X,
+                y: PREVIEW_Y,
+            },
+            None,
+        );
+
+        // One line per player, in their color
+        let mut y = header_container.rect_y + header_container.rect_height;
+        for (i, (name, color)) in imported.players.iter().enumerate() {
+            let name_container = bq::draw_text(
+                format!("{}. {name}", i + 1),
+                TextAlignment::Left,
+                None,
+                PREVIEW_FONT_SIZE,
+                *color,
+                TextAnchorPoint::TopLeft { x: PREVIEW_X, y },
+                None,
+            );
+            y += name_container.rect_height.max(PREVIEW_FONT_SIZE as f32);
+        }
+
+        bq::draw_text(
+            "[enter] to use these players, [esc] to cancel",
+            TextAlignment::Left,
+            None,
+            PREVIEW_FONT_SIZE,
+            mq::WHITE,
+            TextAnchorPoint::BottomLeft {
+                x: PREVIEW_X,
+                y: mq::screen_height() - PREVIEW_Y,
+            },
+            None,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_roster, DEFAULT_COLORS};
+    use macroquad::prelude as mq;
+
+    #[test]
+    fn test_parse_roster() {
+        let imported = parse_roster(
+            "Name,Color\n\
+            Marceline, #ffff00\n\
+            \n\
+            Bonnibel\t#f0f\n\
+            Dorian #00ff00\n\
+            Leo\n\
+            \"Tiger\",\n\
+            Russet, orange\n\
+            , #ffffff\n",
+        );
+        assert_eq!(
+            vec![
+                (
+                    "Marceline".to_string(),
+                    mq::Color::from_rgba(255, 255, 0, 255)
+                ),
+                (
+                    "Bonnibel".to_string(),
+                    mq::Color::from_rgba(255, 0, 255, 255)
+                ),
+                ("Dorian".to_string(), mq::Color::from_rgba(0, 255, 0, 255)),
+                ("Leo".to_string(), DEFAULT_COLORS[3]),
+                ("Tiger".to_string(), DEFAULT_COLORS[4]),
+            ],
+            imported.players
+        );
+        assert_eq!(
+            vec![
+                "line 8: bad color \"orange\"".to_string(),
+                "line 9: missing name".to_string(),
+            ],
+            imported.skipped_lines
+        );
+    }
+
+    #[test]
+    fn test_names_with_spaces() {
+        let imported = parse_roster("Dr Zoidberg\nPrincess Bubblegum #f0f");
+        assert_eq!("Dr Zoidberg", imported.players[0].0);
+        assert_eq!("Princess Bubblegum", imported.players[1].0);
+    }
+
+    #[test]
+    fn test_non_ascii_names() {
+        let imported = parse_roster(
+            "山田\u{3000}花子, #ff0000\n\
+            Zoe\u{308}\t#00ff00\n\
+            👩\u{200D}🚀 Ada\n\
+            أحمد #0000ff\n",
+        );
+        let names = imported
+            .players
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec!["山田\u{3000}花子", "Zoe\u{308}", "👩\u{200D}🚀 Ada", "أحمد"],
+            names
+        );
+        assert!(imported.skipped_lines.is_empty());
+    }
+}
@@ -0,0 +1,116 @@
+//! Named sets of players (names and colors), for groups that play together often. Saved from the
+//! preset screen with [`KEY_SAVE_ROSTER_TEMPLATE`](crate::KEY_SAVE_ROSTER_TEMPLATE), and picked with
+//! [`KEY_BROWSE_ROSTER_TEMPLATES`](crate::KEY_BROWSE_ROSTER_TEMPLATES) to replace the roster before
+//! starting a session.
+use crate::player_setup::{self, SavedRoster};
+use crate::{Screen, TurnTimeTracker, STORAGE};
+use better_quad::bq::{self, SaveSlots, SlotInfo, Timestamp, ToastKind};
+use macroquad::prelude as mq;
+
+pub(crate) const ROSTER_TEMPLATES: SaveSlots = SaveSlots::new(STORAGE, "roster-template");
+/// New templates are named after their players, cut short to this many characters. They can be
+/// renamed in the picker.
+const TEMPLATE_NAME_MAX_LEN: usize = 32;
+
+impl TurnTimeTracker {
+    /// The roster as it is, as a new template.
+    pub(crate) fn save_roster_template(&mut self, now: Timestamp) {
+        let name = template_name(&self.roster);
+        match ROSTER_TEMPLATES.save_new(&name, &SavedRoster::new(&self.roster), now) {
+            Ok(_) => self
+                .toasts
+                .push(ToastKind::Info, format!("Saved players as \"{name}\"")),
+            Err(e) => self
+                .toasts
+                .push(ToastKind::Error, format!("Couldn't save the players: {e}")),
+        }
+    }
+
+    /// Replaces the roster with the template's players, for the next session.
+    pub(crate) fn load_roster_template(&mut self, slot: SlotInfo) {
+        if !matches!(self.screen, Screen::PresetSelection { .. }) {
+            return;
+        }
+        let roster = ROSTER_TEMPLATES
+            .load::<SavedRoster>(slot.id)
+            .map(|saved| saved.map(SavedRoster::into_roster));
+        match roster {
+            Ok(Some(roster)) if !roster.is_empty() => {
+                self.roster = roster;
+                player_setup::save_roster(&self.roster);
+                self.template_browser = None;
+                self.toasts
+                    .push(ToastKind::Info, format!("Players set to \"{}\"", slot.name));
+            }
+            Ok(_) => self.toasts.push(
+                ToastKind::Error,
+                format!("\"{}\" doesn't have any players in it", slot.name),
+            ),
+            Err(e) => self.toasts.push(
+                ToastKind::Error,
+                format!("Couldn't load \"{}\": {e}", slot.name),
+            ),
+        }
+    }
+}
+
+/// Everyone's names, e.g. "Leo, Tiger, Marceline", or as many as fit followed by ", ...".
+fn template_name(roster: &[(String, mq::Color)]) -> String {
+    const MORE: &str = ", ...";
+    let names = roster
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if bq::grapheme_count(&names) <= TEMPLATE_NAME_MAX_LEN {
+        return names;
+    }
+    let mut fitted = String::new();
+    for (name, _) in roster {
+        let with_name = if fitted.is_empty() {
+            name.clone()
+        } else {
+            format!("{fitted}, {name}")
+        };
+        if bq::grapheme_count(&with_name) + MORE.len() > TEMPLATE_NAME_MAX_LEN {
+            break;
+        }
+        fitted = with_name;
+    }
+    if fitted.is_empty() {
+        // Even the first name's too long, so it's cut short
+        let first_name = roster.first().map(|(name, _)| name.as_str()).unwrap_or("");
+        let mut cut = bq::graphemes(first_name)
+            .take(TEMPLATE_NAME_MAX_LEN - 3)
+            .collect::<String>();
+        cut.push_str("...");
+        cut
+    } else {
+        fitted + MORE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{template_name, TEMPLATE_NAME_MAX_LEN};
+    use better_quad::bq;
+    use macroquad::prelude as mq;
+
+    #[test]
+    fn test_template_name() {
+        let roster = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| (name.to_string(), mq::RED))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!("Leo, Tiger", template_name(&roster(&["Leo", "Tiger"])));
+
+        let long = template_name(&roster(&["Marceline", "Bonnibel", "Finn", "Jake", "Lumpy"]));
+        assert_eq!("Marceline, Bonnibel, Finn, ...", long);
+        assert!(bq::grapheme_count(&long) <= TEMPLATE_NAME_MAX_LEN);
+
+        let longest = template_name(&roster(&["Princess Bubblegum of the Candy Kingdom"]));
+        assert_eq!("Princess Bubblegum of the Can...", longest);
+    }
+}
@@ -0,0 +1,187 @@
+//! The session in progress, saved every so often and when the app closes, so it can be picked back
+//! up on the next launch (see the resume prompt). It's deleted once the session ends.
+use crate::presets::Preset;
+use crate::session_log::TurnRecord;
+use crate::{Player, PlayerTurnDurationStats, STORAGE};
+use better_quad::bq::{SaveSlots, StorageError};
+use better_quad::mq;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const SAVED_SESSION_FILE_NAME: &str = "session";
+/// Sessions saved on purpose, to load from preset selection. Unlike the one saved to resume, they
+/// stay until they're deleted.
+pub(crate) const SESSION_SLOTS: SaveSlots = SaveSlots::new(STORAGE, "session");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub(crate) struct SavedSession {
+    pub(crate) preset: Preset,
+    /// UTC day number it was saved on.
+    pub(crate) day_number: u64,
+    pub(crate) players: Vec<SavedPlayer>,
+    pub(crate) current_player_index: usize,
+    pub(crate) current_phase_index: usize,
+    pub(crate) paused: bool,
+    pub(crate) handoff_remaining_millis: Option<u64>,
+    pub(crate) total_handoff_millis: u64,
+    #[serde(default)]
+    pub(crate) interrupted: bool,
+    #[serde(default)]
+    pub(crate) total_interrupt_millis: u64,
+    #[serde(default)]
+    pub(crate) total_paused_millis: u64,
+    pub(crate) turn_held: bool,
+    #[serde(default)]
+    pub(crate) turns: Vec<TurnRecord>,
+    /// Whether the turn order's been reversed.
+    #[serde(default)]
+    pub(crate) reversed: bool,
+    #[serde(default)]
+    pub(crate) round_index: usize,
+    /// Anyone going at the same time as the current player.
+    #[serde(default)]
+    pub(crate) simultaneous_player_indices: Vec<usize>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub(crate) struct SavedPlayer {
+    pub(crate) name: String,
+    pub(crate) color: [u8; 4],
+    pub(crate) total_millis: u64,
+    pub(crate) current_turn_millis: u64,
+    pub(crate) completed_turn_millis: Vec<u64>,
+    pub(crate) time_bonus_millis: u64,
+    pub(crate) flagged: bool,
+    #[serde(default)]
+    pub(crate) sitting_out: bool,
+    /// Their time in each round.
+    #[serde(default)]
+    pub(crate) round_millis: Vec<u64>,
+}
+
+impl SavedSession {
+    /// None if there's no session to resume, or it can't be read (which is logged).
+    pub(crate) fn load() -> Option<Self> {
+        match STORAGE.load(SAVED_SESSION_FILE_NAME) {
+            // Something's gone wrong if there's nobody in it
+            Ok(saved) => saved.filter(|saved: &Self| !saved.players.is_empty()),
+            Err(StorageError::Unavailable) => None,
+            Err(e) => {
+                mq::warn!("Failed to load the saved session: {e}");
+                None
+            }
+        }
+    }
+
+    pub(crate) fn save(&self) {
+        STORAGE.save_or_log(SAVED_SESSION_FILE_NAME, self);
+    }
+
+    /// Once it's been ended or discarded, so it's not offered again.
+    pub(crate) fn delete() {
+        STORAGE.remove_or_log(SAVED_SESSION_FILE_NAME);
+    }
+
+    pub(crate) fn handoff_remaining(&self) -> Option<Duration> {
+        self.handoff_remaining_millis.map(Duration::from_millis)
+    }
+
+    pub(crate) fn total_handoff_time(&self) -> Duration {
+        Duration::from_millis(self.total_handoff_millis)
+    }
+
+    pub(crate) fn total_interrupt_time(&self) -> Duration {
+        Duration::from_millis(self.total_interrupt_millis)
+    }
+
+    pub(crate) fn total_paused_time(&self) -> Duration {
+        Duration::from_millis(self.total_paused_millis)
+    }
+}
+
+impl SavedPlayer {
+    pub(crate) fn new(player: &Player) -> Self {
+        Self {
+            name: player.display_name.clone(),
+            color: player.display_color.into(),
+            total_millis: player.total_time.as_millis() as u64,
+            current_turn_millis: player.stats.current_turn_duration.as_millis() as u64,
+            completed_turn_millis: player
+                .stats
+                .completed_turn_durations
+                .iter()
+                .map(|turn| turn.as_millis() as u64)
+                .collect(),
+            time_bonus_millis: player.time_bonus.as_millis() as u64,
+            flagged: player.flagged,
+            sitting_out: player.sitting_out,
+            round_millis: player
+                .round_times
+                .iter()
+                .map(|round_time| round_time.as_millis() as u64)
+                .collect(),
+        }
+    }
+
+    pub(crate) fn to_player(&self) -> Player {
+        Player {
+            display_name: self.name.clone(),
+            display_color: self.color.into(),
+            total_time: Duration::from_millis(self.total_millis),
+            stats: PlayerTurnDurationStats {
+                current_turn_duration: Duration::from_millis(self.current_turn_millis),
+                completed_turn_durations: self
+                    .completed_turn_millis
+                    .iter()
+                    .map(|millis| Duration::from_millis(*millis))
+                    .collect(),
+            },
+            time_bonus: Duration::from_millis(self.time_bonus_millis),
+            flagged: self.flagged,
+            sitting_out: self.sitting_out,
+            round_times: self
+                .round_millis
+                .iter()
+                .map(|millis| Duration::from_millis(*millis))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SavedPlayer;
+    use crate::Player;
+    use better_quad::mq;
+    use std::time::Duration;
+
+    #[test]
+    fn test_saved_player() {
+        let mut player = Player::new("Alice", mq::Color::from_rgba(255, 0, 0, 255));
+        player.tick_frame(Duration::from_secs(30), 0);
+        player.stats.end_turn();
+        player.tick_frame(Duration::from_millis(12_345), 1);
+        player.time_bonus = Duration::from_secs(2);
+        player.sitting_out = true;
+
+        let saved = SavedPlayer::new(&player);
+        assert_eq!([255, 0, 0, 255], saved.color);
+        assert_eq!(42_345, saved.total_millis);
+        assert_eq!(vec![30_000], saved.completed_turn_millis);
+        assert_eq!(vec![30_000, 12_345], saved.round_millis);
+
+        let restored = saved.to_player();
+        assert_eq!(player.display_name, restored.display_name);
+        assert_eq!(player.total_time, restored.total_time);
+        assert_eq!(2, restored.stats.num_turns());
+        assert_eq!(
+            Duration::from_millis(12_345),
+            restored.stats.current_turn_duration
+        );
+        assert_eq!(player.time_bonus, restored.time_bonus);
+        assert_eq!(Duration::from_millis(12_345), restored.round_time(1));
+        assert_eq!(Duration::ZERO, restored.round_time(2));
+        assert!(!restored.flagged);
+        assert!(restored.sitting_out);
+    }
+}
@@ -0,0 +1,206 @@
+//! A session's raw numbers for spreadsheets and other analysis: per-player totals, turn counts,
+//! and average turns, plus every turn in order. Saved as JSON, and as CSV with one file per table.
+//!
+//! Times are in seconds with millisecond precision, which spreadsheets handle better than
+//! formatted durations.
+use crate::report;
+use crate::session_log::{SessionRecord, TurnRecord};
+use better_quad::bq::{self, UtcDate};
+use serde::Serialize;
+use std::path::PathBuf;
+
+const APP_EXPORT_DIR: &str = "turn-time-tracker";
+
+#[derive(Serialize, Debug, PartialEq)]
+pub(crate) struct SessionExport {
+    preset: String,
+    date: String,
+    total_secs: f64,
+    handoff_secs: f64,
+    interrupt_secs: f64,
+    /// Not part of `total_secs`.
+    paused_secs: f64,
+    /// `total_secs` and `paused_secs` together.
+    wall_secs: f64,
+    players: Vec<PlayerExport>,
+    turns: Vec<TurnExport>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+struct PlayerExport {
+    name: String,
+    total_secs: f64,
+    turns: usize,
+    /// None without any turns.
+    average_turn_secs: Option<f64>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+struct TurnExport {
+    /// From 1
+    turn: usize,
+    player: String,
+    duration_secs: f64,
+    ended_at_secs: f64,
+}
+
+impl SessionExport {
+    pub(crate) fn new(session: &SessionRecord, turns: &[TurnRecord]) -> Self {
+        Self {
+            preset: session.preset_name.clone(),
+            date: UtcDate::from_day_number(session.day_number).to_string(),
+            total_secs: session.total_time().as_secs_f64(),
+            handoff_secs: secs(session.handoff_millis),
+            interrupt_secs: secs(session.interrupt_millis),
+            paused_secs: secs(session.paused_millis),
+            wall_secs: session.wall_time().as_secs_f64(),
+            players: session
+                .players
+                .iter()
+                .map(|player| PlayerExport {
+                    name: player.name.clone(),
+                    total_secs: secs(player.total_millis),
+                    turns: player.num_turns,
+                    average_turn_secs: (player.num_turns > 0)
+                        .then(|| secs(player.total_millis) / player.num_turns as f64),
+                })
+                .collect(),
+            turns: turns
+                .iter()
+                .enumerate()
+                .map(|(i, turn)| TurnExport {
+                    turn: i + 1,
+                    player: turn.player_name.clone(),
+                    duration_secs: secs(turn.duration_millis),
+                    ended_at_secs: secs(turn.ended_at_millis),
+                })
+                .collect(),
+        }
+    }
+
+    fn players_csv(&self) -> String {
+        let mut csv = "player,total_secs,turns,average_turn_secs\n".to_string();
+        for player in &self.players {
+            let average_turn_secs = match player.average_turn_secs {
+                Some(average) => format!("{average:.3}"),
+                None => String::new(),
+            };
+            csv.push_str(&format!(
+                "{},{:.3},{},{average_turn_secs}\n",
+                csv_field(&player.name),
+                player.total_secs,
+                player.turns,
+            ));
+        }
+        csv
+    }
+
+    fn turns_csv(&self) -> String {
+        let mut csv = "turn,player,duration_secs,ended_at_secs\n".to_string();
+        for turn in &self.turns {
+            csv.push_str(&format!(
+                "{},{},{:.3},{:.3}\n",
+                turn.turn,
+                csv_field(&turn.player),
+                turn.duration_secs,
+                turn.ended_at_secs,
+            ));
+        }
+        csv
+    }
+
+    /// Writes `<stem>.json`, `<stem>-players.csv`, and `<stem>-turns.csv` (see
+    /// [`report::session_file_stem`]). Returns the JSON's path.
+    pub(crate) fn save(&self, day_number: u64, nth_of_day: usize) -> Result<PathBuf, String> {
+        let stem = report::session_file_stem(day_number, nth_of_day);
+        bq::save_export(
+            APP_EXPORT_DIR,
+            &format!("{stem}-players.csv"),
+            &self.players_csv(),
+        )?;
+        bq::save_export(
+            APP_EXPORT_DIR,
+            &format!("{stem}-turns.csv"),
+            &self.turns_csv(),
+        )?;
+        bq::save_json_export(APP_EXPORT_DIR, &format!("{stem}.json"), self)
+    }
+}
+
+fn secs(millis: u64) -> f64 {
+    millis as f64 / 1000.0
+}
+
+/// Quoted if it has anything that would break the row, e.g. a comma in a name.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{csv_field, SessionExport};
+    use crate::session_log::{PlayerRecord, SessionRecord, TurnRecord};
+
+    #[test]
+    fn test_csv() {
+        let session = SessionRecord {
+            preset_name: "Catan".to_string(),
+            day_number: 20_310,
+            handoff_millis: 1500,
+            interrupt_millis: 1000,
+            paused_millis: 60_000,
+            players: vec![
+                PlayerRecord {
+                    name: "Leo".to_string(),
+                    total_millis: 90_000,
+                    num_turns: 2,
+                },
+                PlayerRecord {
+                    name: "Tiger, Jr.".to_string(),
+                    total_millis: 1_234,
+                    num_turns: 0,
+                },
+            ],
+        };
+        let turns = [
+            TurnRecord {
+                player_name: "Leo".to_string(),
+                duration_millis: 60_000,
+                ended_at_millis: 60_000,
+            },
+            TurnRecord {
+                player_name: "Leo".to_string(),
+                duration_millis: 30_000,
+                ended_at_millis: 92_734,
+            },
+        ];
+        let export = SessionExport::new(&session, &turns);
+        assert_eq!("2025-08-10", export.date);
+        assert_eq!(93.734, export.total_secs);
+        assert_eq!(1.0, export.interrupt_secs);
+        assert_eq!(60.0, export.paused_secs);
+        assert_eq!(153.734, export.wall_secs);
+        assert_eq!(
+            "player,total_secs,turns,average_turn_secs\n\
+            Leo,90.000,2,45.000\n\
+            \"Tiger, Jr.\",1.234,0,\n",
+            export.players_csv()
+        );
+        assert_eq!(
+            "turn,player,duration_secs,ended_at_secs\n\
+            1,Leo,60.000,60.000\n\
+            2,Leo,30.000,92.734\n",
+            export.turns_csv()
+        );
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!("Leo", csv_field("Leo"));
+        assert_eq!("\"Leo \"\"The Lion\"\"\"", csv_field("Leo \"The Lion\""));
+    }
+}
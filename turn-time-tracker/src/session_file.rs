@@ -0,0 +1,178 @@
+//! A session in progress as a file, to carry to another machine and carry on there, e.g. when the
+//! game moves to someone else's place. It has the roster, the session as it's saved for resuming
+//! (players, turns, whose turn it is, and the preset with its modes), and a format version, so a
+//! file from a newer version of the app is turned away instead of half read.
+//!
+//! Exports go in the documents folder like reports. To import one, it's copied into the app's
+//! config dir as [`IMPORT_FILE_NAME`].
+use crate::saved_session::SavedSession;
+use crate::CONFIG_DIR;
+use better_quad::bq::{self, Timestamp};
+use macroquad::prelude as mq;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const SESSION_FILE_FORMAT: &str = "turn-time-tracker-session";
+/// Bump when older versions couldn't read it any more.
+const SESSION_FILE_VERSION: u32 = 1;
+const APP_EXPORT_DIR: &str = "turn-time-tracker";
+pub(crate) const IMPORT_FILE_NAME: &str = "session.json";
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub(crate) struct SessionFile {
+    /// Colors are RGBA bytes. Starts with the session's players.
+    roster: Vec<(String, [u8; 4])>,
+    pub(crate) session: SavedSession,
+}
+
+impl SessionFile {
+    pub(crate) fn new(roster: &[(String, mq::Color)], session: SavedSession) -> Self {
+        Self {
+            roster: roster
+                .iter()
+                .map(|(name, color)| (name.clone(), (*color).into()))
+                .collect(),
+            session,
+        }
+    }
+
+    /// The roster to carry on with. Hand-edited files might not start with the session's players,
+    /// which player setup relies on, so then it's just them.
+    pub(crate) fn roster(&self) -> Vec<(String, mq::Color)> {
+        let starts_with_players = self.roster.len() >= self.session.players.len()
+            && self
+                .session
+                .players
+                .iter()
+                .zip(&self.roster)
+                .all(|(player, (name, _))| player.name == *name);
+        if starts_with_players {
+            self.roster
+                .iter()
+                .map(|(name, color)| (name.clone(), (*color).into()))
+                .collect()
+        } else {
+            self.session
+                .players
+                .iter()
+                .map(|player| (player.name.clone(), player.color.into()))
+                .collect()
+        }
+    }
+
+    fn to_json(&self) -> Result<String, String> {
+        bq::to_versioned_json(SESSION_FILE_FORMAT, SESSION_FILE_VERSION, self)
+            .map_err(|e| format!("Couldn't write the session file: {e}"))
+    }
+
+    fn from_json(contents: &str) -> Result<Self, String> {
+        let file: Self =
+            bq::from_versioned_json(contents, SESSION_FILE_FORMAT, SESSION_FILE_VERSION)
+                .map_err(|e| format!("Couldn't read the session file: {e}"))?;
+        if file.session.players.is_empty() {
+            return Err("The session file has no players".to_string());
+        }
+        Ok(file)
+    }
+
+    /// e.g. "turn-time-carry-over-2025-08-07-213015.json", with the UTC time so several exports
+    /// the same day don't overwrite each other. Returns where it went.
+    pub(crate) fn export(&self, now: Timestamp) -> Result<PathBuf, String> {
+        let secs_of_day = now.as_sec_f64() as u64 % (24 * 60 * 60);
+        let file_name = format!(
+            "turn-time-carry-over-{}-{:02}{:02}{:02}.json",
+            now.utc_date(),
+            secs_of_day / 3600,
+            secs_of_day / 60 % 60,
+            secs_of_day % 60,
+        );
+        bq::save_export(APP_EXPORT_DIR, &file_name, &self.to_json()?)
+    }
+
+    /// From [`IMPORT_FILE_NAME`] in the config dir.
+    pub(crate) fn import() -> Result<Self, String> {
+        let path = CONFIG_DIR
+            .app_dir()
+            .ok_or("No config dir on this platform")?
+            .join(IMPORT_FILE_NAME);
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| format!("Couldn't read {}: {e}", path.display()))?;
+        Self::from_json(&contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SessionFile, SESSION_FILE_VERSION};
+    use crate::presets::Preset;
+    use crate::saved_session::{SavedPlayer, SavedSession};
+    use crate::Player;
+    use better_quad::bq;
+    use macroquad::prelude as mq;
+
+    fn session(names: &[&str]) -> SavedSession {
+        SavedSession {
+            preset: bq::parse_config::<Preset>("name = \"Blitz\"\ncountdown_secs = 180").unwrap(),
+            day_number: 20_000,
+            players: names
+                .iter()
+                .map(|name| SavedPlayer::new(&Player::new(*name, mq::RED)))
+                .collect(),
+            current_player_index: 1,
+            current_phase_index: 0,
+            paused: true,
+            handoff_remaining_millis: None,
+            total_handoff_millis: 0,
+            interrupted: false,
+            total_interrupt_millis: 0,
+            total_paused_millis: 0,
+            turn_held: false,
+            turns: Vec::new(),
+            reversed: true,
+            round_index: 0,
+            simultaneous_player_indices: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        // Colors that survive the trip to bytes exactly
+        let roster = vec![
+            ("Marceline".to_string(), mq::WHITE),
+            ("Bonnibel".to_string(), mq::BLACK),
+            ("Finn".to_string(), mq::BLANK),
+        ];
+        let file = SessionFile::new(&roster, session(&["Marceline", "Bonnibel"]));
+        let read = SessionFile::from_json(&file.to_json().unwrap()).unwrap();
+        assert_eq!(file, read);
+        assert_eq!(roster, read.roster());
+    }
+
+    #[test]
+    fn test_roster_starts_with_players() {
+        let roster = vec![("Finn".to_string(), mq::GREEN)];
+        let file = SessionFile::new(&roster, session(&["Marceline", "Bonnibel"]));
+        let names = file
+            .roster()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["Marceline", "Bonnibel"], names);
+    }
+
+    #[test]
+    fn test_version_check() {
+        let file = SessionFile::new(&[], session(&["Marceline"]));
+        let too_new =
+            bq::to_versioned_json("turn-time-tracker-session", SESSION_FILE_VERSION + 1, &file)
+                .unwrap();
+        let error = SessionFile::from_json(&too_new).unwrap_err();
+        assert!(error.contains("newer version"), "{error}");
+
+        let other = bq::to_versioned_json("mastermind-game", 1, &file).unwrap();
+        assert!(SessionFile::from_json(&other).is_err());
+        let nobody = SessionFile::new(&[], session(&[]));
+        assert!(SessionFile::from_json(&nobody.to_json().unwrap()).is_err());
+    }
+}
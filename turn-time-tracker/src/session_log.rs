@@ -0,0 +1,107 @@
+//! Finished sessions, persisted between runs for reports (see `report`). A session is logged when
+//! it's ended with [`KEY_END_SESSION`](crate::KEY_END_SESSION).
+use crate::{Player, STORAGE};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const SESSION_LOG_FILE_NAME: &str = "sessions";
+
+/// Oldest first.
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub(crate) struct SessionLog {
+    pub(crate) sessions: Vec<SessionRecord>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub(crate) struct SessionRecord {
+    pub(crate) preset_name: String,
+    /// UTC day number the session ended on.
+    pub(crate) day_number: u64,
+    pub(crate) handoff_millis: u64,
+    /// Time that went to nobody, for rules lookups, breaks, etc.
+    #[serde(default)]
+    pub(crate) interrupt_millis: u64,
+    /// How long it was paused for, which isn't in [`Self::total_time`].
+    #[serde(default)]
+    pub(crate) paused_millis: u64,
+    pub(crate) players: Vec<PlayerRecord>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub(crate) struct PlayerRecord {
+    pub(crate) name: String,
+    pub(crate) total_millis: u64,
+    pub(crate) num_turns: usize,
+}
+
+/// One turn of the session in progress, for exporting turn by turn. Not kept in the log, which only
+/// needs totals. Turns too quick to count (see `PlayerTurnDurationStats`) aren't recorded.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub(crate) struct TurnRecord {
+    pub(crate) player_name: String,
+    pub(crate) duration_millis: u64,
+    /// Session time (everyone's turns plus hand-offs and interruptions) when it ended.
+    pub(crate) ended_at_millis: u64,
+}
+
+impl SessionLog {
+    pub(crate) fn load() -> Self {
+        STORAGE.load_or_default(SESSION_LOG_FILE_NAME)
+    }
+
+    /// Which of its day's sessions `session` was, from 1, for telling their exports apart.
+    pub(crate) fn nth_of_day(&self, session: &SessionRecord) -> usize {
+        self.sessions
+            .iter()
+            .filter(|logged| logged.day_number == session.day_number)
+            .count()
+    }
+
+    pub(crate) fn record(&mut self, session: SessionRecord) {
+        self.sessions.push(session);
+        STORAGE.save_or_log(SESSION_LOG_FILE_NAME, self);
+    }
+}
+
+impl SessionRecord {
+    pub(crate) fn new(
+        preset_name: &str,
+        day_number: u64,
+        handoff_time: Duration,
+        interrupt_time: Duration,
+        paused_time: Duration,
+        players: &[Player],
+    ) -> Self {
+        Self {
+            preset_name: preset_name.to_string(),
+            day_number,
+            handoff_millis: handoff_time.as_millis() as u64,
+            interrupt_millis: interrupt_time.as_millis() as u64,
+            paused_millis: paused_time.as_millis() as u64,
+            players: players
+                .iter()
+                .map(|player| PlayerRecord {
+                    name: player.display_name.clone(),
+                    total_millis: player.total_time.as_millis() as u64,
+                    num_turns: player.stats.num_turns(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Everyone's turns plus hand-offs and interruptions.
+    pub(crate) fn total_time(&self) -> Duration {
+        let players_millis = self
+            .players
+            .iter()
+            .map(|player| player.total_millis)
+            .sum::<u64>();
+        Duration::from_millis(players_millis + self.handoff_millis + self.interrupt_millis)
+    }
+
+    /// From start to end, pauses included (but not time with the app closed).
+    pub(crate) fn wall_time(&self) -> Duration {
+        self.total_time() + Duration::from_millis(self.paused_millis)
+    }
+}
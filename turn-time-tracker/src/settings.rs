@@ -0,0 +1,63 @@
+//! User preferences, persisted between runs.
+use crate::key_bindings::Action;
+use crate::time_format::TimeFormat;
+use crate::STORAGE;
+use better_quad::bq::InputMap;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const SETTINGS_FILE_NAME: &str = "settings";
+
+/// How often a session in progress is saved by default, on top of turn changes and when the app
+/// closes, in case it crashes.
+const DEFAULT_AUTOSAVE_INTERVAL_SECS: u64 = 10;
+/// How long a running timer goes without any input before it pauses itself, e.g. when the app's
+/// left running overnight.
+const DEFAULT_IDLE_PAUSE_SECS: u64 = 10 * 60;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub(crate) struct Settings {
+    /// Keeps the window over others, e.g. a rules PDF or a video call (see
+    /// `bq::set_always_on_top`).
+    pub(crate) always_on_top: bool,
+    /// Only the ones changed from their defaults.
+    pub(crate) key_bindings: InputMap<Action>,
+    /// Only in the file, e.g. longer for a slow disk.
+    pub(crate) autosave_interval_secs: u64,
+    /// Only in the file. 0 never pauses.
+    pub(crate) idle_pause_secs: u64,
+    /// How times are shown in rows, the summary, and reports.
+    pub(crate) time_format: TimeFormat,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            always_on_top: false,
+            key_bindings: InputMap::default(),
+            autosave_interval_secs: DEFAULT_AUTOSAVE_INTERVAL_SECS,
+            idle_pause_secs: DEFAULT_IDLE_PAUSE_SECS,
+            time_format: TimeFormat::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub(crate) fn load() -> Self {
+        STORAGE.load_or_default(SETTINGS_FILE_NAME)
+    }
+
+    pub(crate) fn save(&self) {
+        STORAGE.save_or_log(SETTINGS_FILE_NAME, self);
+    }
+
+    pub(crate) fn autosave_interval(&self) -> Duration {
+        Duration::from_secs(self.autosave_interval_secs)
+    }
+
+    /// None to never pause.
+    pub(crate) fn idle_pause_after(&self) -> Option<Duration> {
+        (self.idle_pause_secs > 0).then(|| Duration::from_secs(self.idle_pause_secs))
+    }
+}
@@ -0,0 +1,69 @@
+//! Simultaneous turns, for games where several players go at once, e.g. everyone picking a card in
+//! a drafting round. During a session, a player's number
+//! ([`KEYS_TOGGLE_SIMULTANEOUS_TURN`](crate::KEYS_TOGGLE_SIMULTANEOUS_TURN)) starts their turn
+//! alongside the current player's, and pressing it again ends it. Everyone going is ticked
+//! together, and passing the turn ends all the others' turns first, back to one at a time.
+use crate::TurnTimeTracker;
+use better_quad::bq::ToastKind;
+
+impl TurnTimeTracker {
+    pub(crate) fn toggle_simultaneous_turn(&mut self, index: usize) {
+        let (players, current_player_index) = self.players.raw();
+        let Some(player) = players.get(index) else {
+            return;
+        };
+        let name = player.display_name.clone();
+        if index == current_player_index {
+            self.toasts
+                .push(ToastKind::Error, format!("It's already {name}'s turn"));
+            return;
+        }
+        if player.sitting_out {
+            self.toasts
+                .push(ToastKind::Error, format!("{name} is sitting out"));
+            return;
+        }
+        let text = if self.players.is_active(index) {
+            self.end_simultaneous_turn(index);
+            if self.simultaneous_turns_going() {
+                format!("{name} is done")
+            } else {
+                format!("{name} is done, back to one at a time")
+            }
+        } else {
+            self.players.set_active(index, true);
+            format!("{name} is going at the same time")
+        };
+        self.toasts.push(ToastKind::Info, text);
+        self.autosave.request();
+    }
+
+    /// Whether anyone's going alongside the current player.
+    pub(crate) fn simultaneous_turns_going(&self) -> bool {
+        !self.players.also_active_indices().is_empty()
+    }
+
+    /// Ends everyone's turn but the current player's, e.g. before the turn passes on.
+    pub(crate) fn end_simultaneous_turns(&mut self) {
+        for index in self.players.also_active_indices() {
+            self.end_simultaneous_turn(index);
+        }
+    }
+
+    /// Their turn counts like any other, but the turn order hasn't moved, so there's nothing to
+    /// undo.
+    pub(crate) fn end_simultaneous_turn(&mut self, index: usize) {
+        let (players, _) = self.players.raw();
+        let Some(player) = players.get(index) else {
+            return;
+        };
+        if player.stats.current_turn_counts() {
+            let turn = self.turn_record(player);
+            self.turns.push(turn);
+        }
+        if let Some(player) = self.players.get_mut(index) {
+            player.stats.end_turn();
+        }
+        self.players.set_active(index, false);
+    }
+}
@@ -0,0 +1,64 @@
+//! Sound effects, synthesized at startup so there are no audio files to ship.
+//!
+//! Silent when built without the default `audio` feature, e.g. on Linux without ALSA dev libs.
+use better_quad::bq::{self, Note, SoundBank, Waveform};
+
+const VOLUME: f32 = 0.3;
+
+// Two-tone doorbell, noticeable over table talk without being alarming
+const FAIRNESS_ALERT_NOTES: &[Note] = &[Note::new(659.25, 200), Note::new(523.25, 350)];
+// Falling buzz, like a game show's wrong answer
+const FLAG_FALL_NOTES: &[Note] = &[
+    Note::new(392.0, 150),
+    Note::new(329.63, 150),
+    Note::new(261.63, 400),
+];
+// Quick high chime, a nudge rather than an alarm
+const TURN_LIMIT_NOTES: &[Note] = &[
+    Note::new(880.0, 120),
+    Note::new(1046.5, 120),
+    Note::new(1318.51, 250),
+];
+// Soft rising pair, since it goes off every turn
+const TURN_START_NOTES: &[Note] = &[Note::new(587.33, 80), Note::new(783.99, 160)];
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub(crate) enum SoundEffect {
+    /// A player's share of the time crossed the preset's threshold.
+    FairnessAlert,
+    /// A player ran out of time on a countdown clock.
+    FlagFall,
+    /// The current turn went over the soft turn limit.
+    TurnLimit,
+    /// The turn passed to another player.
+    TurnStart,
+}
+
+impl SoundEffect {
+    const ALL: [Self; 4] = [
+        Self::FairnessAlert,
+        Self::FlagFall,
+        Self::TurnLimit,
+        Self::TurnStart,
+    ];
+
+    fn tune(self) -> (Waveform, &'static [Note]) {
+        match self {
+            Self::FairnessAlert => (Waveform::Sine, FAIRNESS_ALERT_NOTES),
+            Self::FlagFall => (Waveform::Square, FLAG_FALL_NOTES),
+            Self::TurnLimit => (Waveform::Sine, TURN_LIMIT_NOTES),
+            Self::TurnStart => (Waveform::Sine, TURN_START_NOTES),
+        }
+    }
+}
+
+pub(crate) async fn load_sounds() -> SoundBank<SoundEffect> {
+    let mut sounds = SoundBank::new();
+    for effect in SoundEffect::ALL {
+        let (waveform, notes) = effect.tune();
+        sounds
+            .load_bytes(effect, &bq::synth_wav(notes, waveform, VOLUME))
+            .await;
+    }
+    sounds
+}
@@ -0,0 +1,117 @@
+//! Huge text layout for a tablet in the middle of the table: only whose turn it is and how long
+//! it's been going, with a tap anywhere passing the turn on. Everything scales with the window, so
+//! it reads from across the table on a phone too.
+use crate::{format_seconds_remaining, ClockMode, LayoutMode, TimerState, TurnTimeTracker};
+use better_quad::bq;
+use macroquad::prelude as mq;
+
+/// Shares of the window height, top to bottom: the name, the turn's time, and a line for the
+/// player's total time and what a tap does.
+const NAME_ROW_HEIGHT_FRACTION: f32 = 0.3;
+const TIME_ROW_HEIGHT_FRACTION: f32 = 0.5;
+const PADDING_FRACTION: f32 = 0.04;
+/// Text is as big as fits, but long names get an ellipsis rather than shrinking past this.
+const MIN_FONT_SIZE: u16 = 24;
+/// The bottom line is only a reminder, so it stays small on big screens.
+const MAX_FOOTER_FONT_SIZE: u16 = 60;
+const FOOTER_COLOR: mq::Color = mq::LIGHTGRAY;
+
+impl TurnTimeTracker {
+    pub(crate) fn draw_tablet_display(&self) {
+        let bg_color = match self.timer {
+            TimerState::Paused { .. } => mq::DARKGRAY,
+            TimerState::Running { .. } => mq::BLACK,
+        };
+        mq::clear_background(bg_color);
+        let player = self.players.current();
+
+        let padding = mq::screen_width().min(mq::screen_height()) * PADDING_FRACTION;
+        let area = mq::Rect::new(
+            padding,
+            padding,
+            mq::screen_width() - padding * 2.0,
+            mq::screen_height() - padding * 2.0,
+        );
+        let name_rect = mq::Rect::new(area.x, area.y, area.w, area.h * NAME_ROW_HEIGHT_FRACTION);
+        let time_rect = mq::Rect::new(
+            area.x,
+            name_rect.bottom(),
+            area.w,
+            area.h * TIME_ROW_HEIGHT_FRACTION,
+        );
+        let footer_rect = mq::Rect::new(
+            area.x,
+            time_rect.bottom(),
+            area.w,
+            area.bottom() - time_rect.bottom(),
+        );
+
+        bq::draw_text_fitted(
+            &player.display_name,
+            self.name_font.as_ref(),
+            MIN_FONT_SIZE,
+            u16::MAX,
+            player.display_color,
+            name_rect,
+        );
+
+        let over_turn_limit = self
+            .rules
+            .turn_limit
+            .is_some_and(|turn_limit| player.stats.current_turn_duration > turn_limit);
+        let (time_text, time_color) = match self.handoff_remaining {
+            Some(handoff_remaining) => (
+                format!("Get ready: {}", format_seconds_remaining(handoff_remaining)),
+                mq::LIGHTGRAY,
+            ),
+            None => (
+                self.settings
+                    .time_format
+                    .format(player.stats.current_turn_duration),
+                if over_turn_limit { mq::RED } else { mq::WHITE },
+            ),
+        };
+        bq::draw_text_fitted(
+            &time_text,
+            None,
+            MIN_FONT_SIZE,
+            u16::MAX,
+            time_color,
+            time_rect,
+        );
+
+        let total_text = match self.rules.clock_mode {
+            _ if player.flagged => "Out of time".to_string(),
+            ClockMode::CountUp => format!(
+                "Total {}",
+                self.settings.time_format.format(player.total_time)
+            ),
+            ClockMode::Countdown { .. } => format!(
+                "{} left",
+                self.settings
+                    .time_format
+                    .format(self.displayed_time(player))
+            ),
+        };
+        let tap_text = match (self.timer, self.handoff_remaining) {
+            (TimerState::Paused { .. }, _) => "Paused - tap to start",
+            (TimerState::Running { .. }, _) if self.interrupted => "Interrupted - tap to pass",
+            (TimerState::Running { .. }, Some(_)) => "Tap to skip",
+            (TimerState::Running { .. }, None) => "Tap to pass",
+        };
+        bq::draw_text_fitted(
+            &format!("{total_text} - {tap_text}"),
+            None,
+            MIN_FONT_SIZE,
+            MAX_FOOTER_FONT_SIZE,
+            self.countdown_time_color(player).unwrap_or(FOOTER_COLOR),
+            footer_rect,
+        );
+    }
+
+    /// A tap or click anywhere in the tablet layout, which starts the clock or passes the turn.
+    pub(crate) fn tablet_tapped(&self) -> bool {
+        matches!(self.layout_mode, LayoutMode::Tablet)
+            && mq::is_mouse_button_pressed(mq::MouseButton::Left)
+    }
+}
@@ -0,0 +1,166 @@
+//! How times are written out in player rows, the summary, and reports, picked in the settings.
+//! Cycled with [`KEY_CYCLE_TIME_FORMAT`](crate::KEY_CYCLE_TIME_FORMAT) during a session, and the
+//! fraction of a second in detailed stats is only in the file.
+//!
+//! Turn lengths (averages, longest turns, etc) are always minutes and seconds, since they're
+//! short.
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Serialize, Deserialize, Copy, Clone, Default, Debug, PartialEq)]
+#[serde(default)]
+pub(crate) struct TimeFormat {
+    pub(crate) units: TimeUnits,
+    /// Only in detailed stats.
+    pub(crate) fraction: SecondFraction,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum TimeUnits {
+    /// e.g. "00:02:03"
+    #[default]
+    HoursMinutesSeconds,
+    /// e.g. "02:03", or "01:02:03" once there are hours
+    HoursIfAny,
+    /// e.g. "62:03", without hours however long it gets
+    MinutesSeconds,
+    /// e.g. "62m", rounded down
+    TotalMinutes,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SecondFraction {
+    Tenths,
+    #[default]
+    Hundredths,
+}
+
+impl TimeUnits {
+    pub(crate) fn next(self) -> Self {
+        match self {
+            Self::HoursMinutesSeconds => Self::HoursIfAny,
+            Self::HoursIfAny => Self::MinutesSeconds,
+            Self::MinutesSeconds => Self::TotalMinutes,
+            Self::TotalMinutes => Self::HoursMinutesSeconds,
+        }
+    }
+
+    /// For the toast when they're changed.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::HoursMinutesSeconds => "hh:mm:ss",
+            Self::HoursIfAny => "mm:ss, with hours once there are some",
+            Self::MinutesSeconds => "mm:ss",
+            Self::TotalMinutes => "minutes",
+        }
+    }
+}
+
+impl SecondFraction {
+    fn digits(self) -> usize {
+        match self {
+            Self::Tenths => 1,
+            Self::Hundredths => 2,
+        }
+    }
+}
+
+impl TimeFormat {
+    pub(crate) fn format(self, duration: Duration) -> String {
+        let total_seconds = duration.as_secs();
+        let hours = total_seconds / 3600;
+        let minutes = (total_seconds % 3600) / 60;
+        let seconds = total_seconds % 60;
+        match self.units {
+            TimeUnits::HoursMinutesSeconds => format!("{hours:02}:{minutes:02}:{seconds:02}"),
+            TimeUnits::HoursIfAny if hours > 0 => format!("{hours:02}:{minutes:02}:{seconds:02}"),
+            TimeUnits::HoursIfAny => format!("{minutes:02}:{seconds:02}"),
+            TimeUnits::MinutesSeconds => format!("{:02}:{seconds:02}", total_seconds / 60),
+            TimeUnits::TotalMinutes => format!("{}m", total_seconds / 60),
+        }
+    }
+
+    /// With tenths or hundredths of a second, or of a minute for total minutes. Rounded down, like
+    /// [`Self::format`].
+    pub(crate) fn format_detailed(self, duration: Duration) -> String {
+        let digits = self.fraction.digits();
+        let scale = 10_u128.pow(digits as u32);
+        match self.units {
+            TimeUnits::TotalMinutes => {
+                let fraction = duration.as_millis() * scale / 60_000 % scale;
+                let minutes = duration.as_secs() / 60;
+                format!("{minutes}.{fraction:0digits$}m")
+            }
+            _ => {
+                let fraction = u128::from(duration.subsec_millis()) * scale / 1000;
+                format!("{}.{fraction:0digits$}", self.format(duration))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SecondFraction, TimeFormat, TimeUnits};
+    use std::time::Duration;
+
+    #[test]
+    fn test_format_detailed() {
+        let test_cases = [
+            // (input seconds, expected format)
+            (2.99999, "00:00:02.99"),
+            (3.00000, "00:00:03.00"),
+        ];
+
+        for (input_seconds, expected_output) in test_cases {
+            let input = Duration::from_secs_f64(input_seconds);
+            let actual_output = TimeFormat::default().format_detailed(input);
+            assert_eq!(expected_output, &actual_output);
+        }
+    }
+
+    #[test]
+    fn test_time_units() {
+        let short = Duration::from_millis(2 * 60_000 + 3_456);
+        let long = Duration::from_millis(62 * 60_000 + 3_456);
+        let test_cases = [
+            (TimeUnits::HoursMinutesSeconds, "00:02:03", "01:02:03"),
+            (TimeUnits::HoursIfAny, "02:03", "01:02:03"),
+            (TimeUnits::MinutesSeconds, "02:03", "62:03"),
+            (TimeUnits::TotalMinutes, "2m", "62m"),
+        ];
+        for (units, expected_short, expected_long) in test_cases {
+            let format = TimeFormat {
+                units,
+                ..TimeFormat::default()
+            };
+            assert_eq!(expected_short, format.format(short), "{units:?}");
+            assert_eq!(expected_long, format.format(long), "{units:?}");
+        }
+    }
+
+    #[test]
+    fn test_second_fraction() {
+        let duration = Duration::from_millis(62 * 60_000 + 3_456);
+        let format = |units, fraction| TimeFormat { units, fraction }.format_detailed(duration);
+        assert_eq!(
+            "62:03.4",
+            format(TimeUnits::MinutesSeconds, SecondFraction::Tenths)
+        );
+        assert_eq!(
+            "62:03.45",
+            format(TimeUnits::MinutesSeconds, SecondFraction::Hundredths)
+        );
+        // 3.456s is 0.0576 of a minute
+        assert_eq!(
+            "62.0m",
+            format(TimeUnits::TotalMinutes, SecondFraction::Tenths)
+        );
+        assert_eq!(
+            "62.05m",
+            format(TimeUnits::TotalMinutes, SecondFraction::Hundredths)
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! Recent turn changes, so one made by accident (e.g. a stray tap on space) can be undone.
+//!
+//! Each change remembers what the turn it ended looked like, so undoing it can pick that turn back
+//! up where it left off, and give it the time the wrong player was charged for since.
+use better_quad::bq::Timestamp;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Only the last few changes can be undone. Anything older has probably been noticed by now.
+const MAX_TURN_CHANGES: usize = 10;
+
+/// A turn passing from one player to the next.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct TurnChange {
+    pub(crate) at: Timestamp,
+    /// Whose turn ended, to go back to. Not always the one before, since play can jump around.
+    pub(crate) ended_player_index: usize,
+    /// How long the turn that ended had run.
+    pub(crate) ended_turn_duration: Duration,
+    /// In countdown mode, time the player who ended the turn got back for it.
+    pub(crate) time_bonus_earned: Duration,
+    pub(crate) phase_index: usize,
+    /// The round the turn that ended was in, since passing the first player starts a new one.
+    pub(crate) round_index: usize,
+    pub(crate) turn_held: bool,
+    /// Total hand-off time so far, so the hand-off that followed can be given back too.
+    pub(crate) total_handoff_time: Duration,
+    /// Whether the turn that ended was long enough to go in the session's turn records.
+    pub(crate) turn_recorded: bool,
+}
+
+#[derive(Default)]
+pub(crate) struct TurnLog {
+    changes: VecDeque<TurnChange>,
+}
+
+impl TurnLog {
+    pub(crate) fn record(&mut self, change: TurnChange) {
+        if self.changes.len() == MAX_TURN_CHANGES {
+            self.changes.pop_front();
+        }
+        self.changes.push_back(change);
+    }
+
+    /// The most recent change, which is forgotten.
+    pub(crate) fn undo(&mut self) -> Option<TurnChange> {
+        self.changes.pop_back()
+    }
+
+    /// For when the players change, since the changes are about who was next to who.
+    pub(crate) fn clear(&mut self) {
+        self.changes.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TurnChange, TurnLog, MAX_TURN_CHANGES};
+    use better_quad::bq::Timestamp;
+    use std::time::Duration;
+
+    fn change(phase_index: usize) -> TurnChange {
+        TurnChange {
+            at: Timestamp::now(),
+            ended_player_index: 0,
+            ended_turn_duration: Duration::from_secs(30),
+            time_bonus_earned: Duration::ZERO,
+            phase_index,
+            round_index: 0,
+            turn_held: false,
+            total_handoff_time: Duration::ZERO,
+            turn_recorded: true,
+        }
+    }
+
+    #[test]
+    fn test_undo_most_recent_first() {
+        let mut turn_log = TurnLog::default();
+        assert_eq!(None, turn_log.undo());
+
+        for phase_index in 0..MAX_TURN_CHANGES + 2 {
+            turn_log.record(change(phase_index));
+        }
+        let undone = std::iter::from_fn(|| turn_log.undo())
+            .map(|change| change.phase_index)
+            .collect::<Vec<_>>();
+        // The oldest 2 were dropped
+        assert_eq!((2..MAX_TURN_CHANGES + 2).rev().collect::<Vec<_>>(), undone);
+
+        turn_log.record(change(0));
+        turn_log.clear();
+        assert_eq!(None, turn_log.undo());
+    }
+}
@@ -0,0 +1,144 @@
+//! Big text layout for a TV across the room. Top row is whose turn it is and how long it's been
+//! going; bottom row is everyone's total. Everything scales with the window, inside its safe area
+//! (see `bq_safe_area`) for TVs that crop the edges.
+use crate::{format_seconds_remaining, TimerState, TurnTimeTracker};
+use better_quad::bq;
+use macroquad::prelude as mq;
+
+/// Share of the window height for the current turn row. The rest is for totals.
+const CURRENT_TURN_ROW_HEIGHT_FRACTION: f32 = 0.55;
+const CELL_PADDING_FRACTION: f32 = 0.08;
+/// Text is as big as fits, but long names get an ellipsis rather than shrinking past this.
+const MIN_FONT_SIZE: u16 = 24;
+const CURRENT_PLAYER_HIGHLIGHT_THICKNESS: f32 = 8.0;
+
+impl TurnTimeTracker {
+    pub(crate) fn draw_tv_display(&self) {
+        let bg_color = match self.timer {
+            TimerState::Paused { .. } => mq::DARKGRAY,
+            TimerState::Running { .. } => mq::BLACK,
+        };
+        mq::clear_background(bg_color);
+        let area = bq::safe_area();
+        let (players, current_player_index) = self.players.raw();
+        let current_player = &players[current_player_index];
+
+        // Current turn
+        let current_turn_row = mq::Rect::new(
+            area.x,
+            area.y,
+            area.w,
+            area.h * CURRENT_TURN_ROW_HEIGHT_FRACTION,
+        );
+        let (name_rect, time_rect) = split_rows(current_turn_row);
+        draw_name_fitted(
+            &current_player.display_name,
+            self.name_font.as_ref(),
+            name_rect,
+            current_player.display_color,
+        );
+        let over_turn_limit = self
+            .rules
+            .turn_limit
+            .is_some_and(|turn_limit| current_player.stats.current_turn_duration > turn_limit);
+        let paused_suffix = match self.timer {
+            TimerState::Paused { .. } => " (paused)",
+            TimerState::Running { .. } if self.interrupted => " (interrupted)",
+            TimerState::Running { .. } => "",
+        };
+        let (time_text, time_color) = match self.handoff_remaining {
+            Some(handoff_remaining) => (
+                format!("Get ready: {}", format_seconds_remaining(handoff_remaining)),
+                mq::LIGHTGRAY,
+            ),
+            None => match self.auto_advance_remaining() {
+                Some(remaining) if !self.turn_held => (
+                    format!("Next in {}", format_seconds_remaining(remaining)),
+                    if over_turn_limit { mq::RED } else { mq::WHITE },
+                ),
+                _ => (
+                    self.settings
+                        .time_format
+                        .format(current_player.stats.current_turn_duration),
+                    if over_turn_limit { mq::RED } else { mq::WHITE },
+                ),
+            },
+        };
+        draw_text_fitted(
+            &format!("{time_text}{paused_suffix}"),
+            time_rect,
+            time_color,
+        );
+
+        // Totals, one column per player
+        let totals_row_y = current_turn_row.bottom();
+        let column_width = area.w / players.len() as f32;
+        for (i, player) in players.iter().enumerate() {
+            let cell = mq::Rect::new(
+                area.x + column_width * i as f32,
+                totals_row_y,
+                column_width,
+                area.bottom() - totals_row_y,
+            );
+            if i == current_player_index {
+                mq::draw_rectangle_lines(
+                    cell.x,
+                    cell.y,
+                    cell.w,
+                    cell.h,
+                    CURRENT_PLAYER_HIGHLIGHT_THICKNESS,
+                    player.display_color,
+                );
+            }
+            let (name_rect, time_rect) = split_rows(cell);
+            draw_name_fitted(
+                &player.display_name,
+                self.name_font.as_ref(),
+                name_rect,
+                player.text_color(),
+            );
+            // Out of time reads as 00:00:00 anyway
+            let time_text = if player.sitting_out {
+                "OUT".to_string()
+            } else if player.flagged {
+                "FLAG".to_string()
+            } else {
+                self.settings
+                    .time_format
+                    .format(self.displayed_time(player))
+            };
+            draw_text_fitted(
+                &time_text,
+                time_rect,
+                self.countdown_time_color(player)
+                    .unwrap_or(player.display_color),
+            );
+        }
+    }
+}
+
+/// (top half, bottom half), with padding around the edges.
+fn split_rows(rect: mq::Rect) -> (mq::Rect, mq::Rect) {
+    let padding = rect.w.min(rect.h) * CELL_PADDING_FRACTION;
+    let inner = mq::Rect::new(
+        rect.x + padding,
+        rect.y + padding,
+        rect.w - padding * 2.0,
+        rect.h - padding * 2.0,
+    );
+    let half_height = inner.h / 2.0;
+    (
+        mq::Rect::new(inner.x, inner.y, inner.w, half_height),
+        mq::Rect::new(inner.x, inner.y + half_height, inner.w, half_height),
+    )
+}
+
+/// Draws `text` as big as it fits in `rect`, centered.
+fn draw_text_fitted(text: &str, rect: mq::Rect, color: mq::Color) {
+    bq::draw_text_fitted(text, None, MIN_FONT_SIZE, u16::MAX, color, rect);
+}
+
+/// Like [`draw_text_fitted`], in the name font if there is one.
+fn draw_name_fitted(name: &str, font: Option<&mq::Font>, rect: mq::Rect, color: mq::Color) {
+    bq::draw_text_fitted(name, font, MIN_FONT_SIZE, u16::MAX, color, rect);
+}
@@ -0,0 +1,31 @@
+//! Golden-image snapshots of the tracker. See `bq_snapshot` for how to run and update them.
+use better_quad::bq::{self, Snapshot, SnapshotTolerance};
+use better_quad::StatefulGui;
+use macroquad::prelude as mq;
+use std::time::Duration;
+use turn_time_tracker::TurnTimeTracker;
+
+const PLAYERS: [(&str, mq::Color); 4] = [
+    ("Marceline", mq::YELLOW),
+    ("Bonnibel", mq::SKYBLUE),
+    ("Dorian", mq::PINK),
+    ("Leo", mq::GREEN),
+];
+
+fn main() {
+    // Two rounds, with everyone's turns taking a different time
+    let turn_lengths = [45, 80, 20, 130, 60, 35, 95, 50].map(Duration::from_secs);
+    bq::run_snapshot_tests(
+        TurnTimeTracker::main_conf(),
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots"),
+        SnapshotTolerance::default(),
+        vec![
+            Snapshot::new("preset_selection", || {
+                TurnTimeTracker::with_players(PLAYERS.to_vec())
+            }),
+            Snapshot::new("mid_session", move || {
+                TurnTimeTracker::with_players_mid_session(PLAYERS.to_vec(), &turn_lengths)
+            }),
+        ],
+    );
+}
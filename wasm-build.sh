@@ -2,7 +2,8 @@
 
 set -euxo pipefail
 
-cargo build --target wasm32-unknown-unknown --release
+# No ALSA on the web, so sound can always be on there.
+cargo build --target wasm32-unknown-unknown --release --features better-quad/audio
 
 cp ./target/wasm32-unknown-unknown/release/caterpillar.wasm ./docs/
 cp ./target/wasm32-unknown-unknown/release/mastermind.wasm ./docs/